@@ -0,0 +1,43 @@
+use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
+use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
+use crate::constant_pool::ConstantPool;
+use crate::runtime_visible_annotations_attribute::ElementValue;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// the default value of an annotation type element
+pub struct AnnotationDefaultAttribute {
+    cp: Rc<RefCell<ConstantPool>>,
+    default_value: Option<ElementValue>,
+}
+
+impl AnnotationDefaultAttribute {
+    #[inline]
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> Self {
+        return AnnotationDefaultAttribute {
+            cp,
+            default_value: None,
+        };
+    }
+
+    #[inline]
+    pub fn default_value(&self) -> &ElementValue {
+        return self.default_value.as_ref().expect("attribute isn't read");
+    }
+}
+
+impl AttributeInfo for AnnotationDefaultAttribute {
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let pool = (*self.cp).borrow();
+        let default_value = ElementValue::read(reader, &pool)?;
+        drop(pool);
+        self.default_value = Some(default_value);
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        self.default_value().write(writer);
+    }
+}
@@ -1,5 +1,9 @@
+use crate::annotation_default_attribute::AnnotationDefaultAttribute;
 use crate::attribute_info::Attribute::*;
+use crate::bootstrap_methods_attribute::BootstrapMethodsAttribute;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::code_attribute::CodeAttribute;
 use crate::constant_pool::ConstantPool;
 use crate::constant_value_attribute::ConstantValueAttribute;
@@ -10,40 +14,96 @@ use crate::line_number_table_attribute::LineNumberTableAttribute;
 use crate::local_variable_table_attribute::LocalVariableTableAttribute;
 use crate::local_variable_type_table_attribute::LocalVariableTypeTableAttribute;
 use crate::makers_attribute::{DeprecatedAttribute, SyntheticAttribute};
+use crate::method_parameters_attribute::MethodParametersAttribute;
+use crate::parameter_annotations_attribute::ParameterAnnotationsAttribute;
 use crate::runtime_visible_annotations_attribute::AnnotationsAttribute;
 use crate::signature_attribute::SignatureAttribute;
+use crate::source_debug_extension_attribute::SourceDebugExtensionAttribute;
 use crate::source_file_attribute::SourceFileAttribute;
 use crate::stack_map_table_attribute::StackMapAttribute;
+use crate::type_annotations_attribute::TypeAnnotationsAttribute;
 use crate::unparsed_attribute::UnparsedAttribute;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 pub trait AttributeInfo {
-    fn read_info(&mut self, reader: &mut ClassReader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError>;
+
+    /// writes what `read_info` reads, the name and the length are written
+    /// by `write_attribute`
+    fn write_info(&self, writer: &mut ClassWriter);
 }
 
-pub fn read_attributes(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> Vec<Attribute> {
-    let clone = reader.data.clone();
-    let attr_count = reader.read_u16();
+pub fn read_attributes(
+    reader: &mut ClassReader,
+    cp: Rc<RefCell<ConstantPool>>,
+) -> Result<Vec<Attribute>, ClassFormatError> {
+    let attr_count = reader.read_u16()?;
     let mut attributes = Vec::new();
     for _i in 0..attr_count {
-        attributes.push(read_attribute(reader, cp.clone()));
+        attributes.push(read_attribute(reader, cp.clone())?);
     }
-    return attributes;
+    return Ok(attributes);
 }
 
-pub fn read_attribute(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> Attribute {
-    let attr_name_index = reader.read_u16();
-    let clone = cp.clone();
-    let borrow_clone = (*clone).borrow();
-    let attr_name = borrow_clone.get_utf8(attr_name_index as usize);
-    let attr_len = reader.read_u32();
-    let mut info = new(attr_name, attr_len, cp);
+/// an attribute has to be read up to the length it declares, not further
+pub fn read_attribute(
+    reader: &mut ClassReader,
+    cp: Rc<RefCell<ConstantPool>>,
+) -> Result<Attribute, ClassFormatError> {
+    let attr_name_index = reader.read_u16()?;
+    let attr_name = (*cp).borrow().utf8(attr_name_index)?.to_string();
+    let attr_len = reader.read_u32()?;
+    let mut info = new(attr_name.as_str(), attr_len, cp);
 
-    info.read_info(reader);
-    return info;
+    let outer_limit = reader.limit_to(attr_len as usize)?;
+    let rs = info.read_info(reader);
+    let rs = match rs {
+        Ok(()) if !reader.is_empty() => {
+            Err(ClassFormatError::BadAttributeLength { name: attr_name })
+        }
+        // the content ran past the declared length
+        Err(ClassFormatError::Truncated { .. }) => {
+            Err(ClassFormatError::BadAttributeLength { name: attr_name })
+        }
+        rs => rs,
+    };
+    rs?;
+    reader.restore_limit(outer_limit);
+    return Ok(info);
 }
 
+pub fn write_attributes(
+    writer: &mut ClassWriter,
+    attributes: &[Attribute],
+    cp: &Rc<RefCell<ConstantPool>>,
+) {
+    writer.write_u16(attributes.len() as u16);
+    for attribute in attributes {
+        write_attribute(writer, attribute, cp);
+    }
+}
+
+/// the attribute name is looked up in the constant pool, it has to be there
+pub fn write_attribute(
+    writer: &mut ClassWriter,
+    attribute: &Attribute,
+    cp: &Rc<RefCell<ConstantPool>>,
+) {
+    let name = attribute.name();
+    let name_index = (*cp)
+        .borrow()
+        .utf8_index(name)
+        .unwrap_or_else(|| panic!("no CONSTANT_Utf8 for the attribute name {}", name));
+    writer.write_u16(name_index);
+    let length_offset = writer.position();
+    writer.write_u32(0);
+    attribute.write_info(writer);
+    let length = writer.position() - length_offset - 4;
+    writer.patch_u32(length_offset, length as u32);
+}
+
+/// the attributes of Java SE 8, JVMS §4.7, anything else is kept unparsed
 pub fn new(attr_name: &str, attr_len: u32, cp: Rc<RefCell<ConstantPool>>) -> Attribute {
     let info: Attribute = match attr_name {
         "Code" => Code(CodeAttribute::with_cp(cp)),
@@ -52,11 +112,36 @@ pub fn new(attr_name: &str, attr_len: u32, cp: Rc<RefCell<ConstantPool>>) -> Att
         "Exceptions" => Exceptions(ExceptionsAttribute::new()),
         "LineNumberTable" => LineNumberTable(LineNumberTableAttribute::new()),
         "LocalVariableTable" => LocalVariableTable(LocalVariableTableAttribute::new()),
+        "LocalVariableTypeTable" => LocalVariableTypeTable(LocalVariableTypeTableAttribute::new()),
         "SourceFile" => SourceFile(SourceFileAttribute::with_cp(cp)),
+        "SourceDebugExtension" => {
+            SourceDebugExtension(SourceDebugExtensionAttribute::new(attr_len))
+        }
         "Synthetic" => Synthetic(SyntheticAttribute::new()),
-        //        "StackMapTable" => StackMap(StackMapAttribute::new(attr_len)),
+        "Signature" => Signature(SignatureAttribute::with_cp(cp)),
+        "InnerClasses" => InnerClasses(InnerClassesAttribute::new()),
+        "EnclosingMethod" => EnclosingMethod(EnclosingMethodAttribute::with_cp(cp)),
+        "StackMapTable" => StackMap(StackMapAttribute::new()),
         "RuntimeVisibleAnnotations" => RuntimeVisibleAnnotations(AnnotationsAttribute::with_cp(cp)),
-        _ => Unparsed(UnparsedAttribute::new(attr_len)),
+        "RuntimeInvisibleAnnotations" => {
+            RuntimeInvisibleAnnotations(AnnotationsAttribute::with_cp(cp))
+        }
+        "RuntimeVisibleParameterAnnotations" => {
+            RuntimeVisibleParameterAnnotations(ParameterAnnotationsAttribute::with_cp(cp))
+        }
+        "RuntimeInvisibleParameterAnnotations" => {
+            RuntimeInvisibleParameterAnnotations(ParameterAnnotationsAttribute::with_cp(cp))
+        }
+        "RuntimeVisibleTypeAnnotations" => {
+            RuntimeVisibleTypeAnnotations(TypeAnnotationsAttribute::with_cp(cp))
+        }
+        "RuntimeInvisibleTypeAnnotations" => {
+            RuntimeInvisibleTypeAnnotations(TypeAnnotationsAttribute::with_cp(cp))
+        }
+        "AnnotationDefault" => AnnotationDefault(AnnotationDefaultAttribute::with_cp(cp)),
+        "BootstrapMethods" => BootstrapMethods(BootstrapMethodsAttribute::new()),
+        "MethodParameters" => MethodParameters(MethodParametersAttribute::with_cp(cp)),
+        _ => Unparsed(UnparsedAttribute::new(attr_name, attr_len)),
     };
     return info;
 }
@@ -77,12 +162,21 @@ pub enum Attribute {
     ConstantValue(ConstantValueAttribute),
     Code(CodeAttribute),
     RuntimeVisibleAnnotations(AnnotationsAttribute),
+    RuntimeInvisibleAnnotations(AnnotationsAttribute),
+    RuntimeVisibleParameterAnnotations(ParameterAnnotationsAttribute),
+    RuntimeInvisibleParameterAnnotations(ParameterAnnotationsAttribute),
+    RuntimeVisibleTypeAnnotations(TypeAnnotationsAttribute),
+    RuntimeInvisibleTypeAnnotations(TypeAnnotationsAttribute),
+    AnnotationDefault(AnnotationDefaultAttribute),
+    BootstrapMethods(BootstrapMethodsAttribute),
+    MethodParameters(MethodParametersAttribute),
+    SourceDebugExtension(SourceDebugExtensionAttribute),
 }
 
 impl Attribute {
     #[inline]
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        match self {
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return match self {
             Code(attr) => attr.read_info(reader),
             ConstantValue(attr) => attr.read_info(reader),
             EnclosingMethod(attr) => attr.read_info(reader),
@@ -98,6 +192,267 @@ impl Attribute {
             SourceFile(attr) => attr.read_info(reader),
             Unparsed(attr) => attr.read_info(reader),
             RuntimeVisibleAnnotations(attr) => attr.read_info(reader),
+            RuntimeInvisibleAnnotations(attr) => attr.read_info(reader),
+            RuntimeVisibleParameterAnnotations(attr) => attr.read_info(reader),
+            RuntimeInvisibleParameterAnnotations(attr) => attr.read_info(reader),
+            RuntimeVisibleTypeAnnotations(attr) => attr.read_info(reader),
+            RuntimeInvisibleTypeAnnotations(attr) => attr.read_info(reader),
+            AnnotationDefault(attr) => attr.read_info(reader),
+            BootstrapMethods(attr) => attr.read_info(reader),
+            MethodParameters(attr) => attr.read_info(reader),
+            SourceDebugExtension(attr) => attr.read_info(reader),
+        };
+    }
+
+    #[inline]
+    pub fn write_info(&self, writer: &mut ClassWriter) {
+        match self {
+            Code(attr) => attr.write_info(writer),
+            ConstantValue(attr) => attr.write_info(writer),
+            EnclosingMethod(attr) => attr.write_info(writer),
+            Exceptions(attr) => attr.write_info(writer),
+            InnerClasses(attr) => attr.write_info(writer),
+            LineNumberTable(attr) => attr.write_info(writer),
+            LocalVariableTable(attr) => attr.write_info(writer),
+            LocalVariableTypeTable(attr) => attr.write_info(writer),
+            Synthetic(attr) => attr.write_info(writer),
+            Deprecated(attr) => attr.write_info(writer),
+            Signature(attr) => attr.write_info(writer),
+            StackMap(attr) => attr.write_info(writer),
+            SourceFile(attr) => attr.write_info(writer),
+            Unparsed(attr) => attr.write_info(writer),
+            RuntimeVisibleAnnotations(attr) => attr.write_info(writer),
+            RuntimeInvisibleAnnotations(attr) => attr.write_info(writer),
+            RuntimeVisibleParameterAnnotations(attr) => attr.write_info(writer),
+            RuntimeInvisibleParameterAnnotations(attr) => attr.write_info(writer),
+            RuntimeVisibleTypeAnnotations(attr) => attr.write_info(writer),
+            RuntimeInvisibleTypeAnnotations(attr) => attr.write_info(writer),
+            AnnotationDefault(attr) => attr.write_info(writer),
+            BootstrapMethods(attr) => attr.write_info(writer),
+            MethodParameters(attr) => attr.write_info(writer),
+            SourceDebugExtension(attr) => attr.write_info(writer),
         }
     }
+
+    /// the name the attribute has in the class file
+    pub fn name(&self) -> &str {
+        return match self {
+            Code(_) => "Code",
+            ConstantValue(_) => "ConstantValue",
+            EnclosingMethod(_) => "EnclosingMethod",
+            Exceptions(_) => "Exceptions",
+            InnerClasses(_) => "InnerClasses",
+            LineNumberTable(_) => "LineNumberTable",
+            LocalVariableTable(_) => "LocalVariableTable",
+            LocalVariableTypeTable(_) => "LocalVariableTypeTable",
+            Synthetic(_) => "Synthetic",
+            Deprecated(_) => "Deprecated",
+            Signature(_) => "Signature",
+            StackMap(_) => "StackMapTable",
+            SourceFile(_) => "SourceFile",
+            Unparsed(attr) => attr.name(),
+            RuntimeVisibleAnnotations(_) => "RuntimeVisibleAnnotations",
+            RuntimeInvisibleAnnotations(_) => "RuntimeInvisibleAnnotations",
+            RuntimeVisibleParameterAnnotations(_) => "RuntimeVisibleParameterAnnotations",
+            RuntimeInvisibleParameterAnnotations(_) => "RuntimeInvisibleParameterAnnotations",
+            RuntimeVisibleTypeAnnotations(_) => "RuntimeVisibleTypeAnnotations",
+            RuntimeInvisibleTypeAnnotations(_) => "RuntimeInvisibleTypeAnnotations",
+            AnnotationDefault(_) => "AnnotationDefault",
+            BootstrapMethods(_) => "BootstrapMethods",
+            MethodParameters(_) => "MethodParameters",
+            SourceDebugExtension(_) => "SourceDebugExtension",
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::attribute_info::Attribute::*;
+    use crate::attribute_info::{read_attributes, write_attributes};
+    use crate::class_format_error::ClassFormatError;
+    use crate::class_reader::ClassReader;
+    use crate::class_writer::ClassWriter;
+    use crate::constant_pool::ConstantPool;
+    use crate::runtime_visible_annotations_attribute::ElementValue;
+    use crate::type_annotations_attribute::TargetInfo;
+
+    fn utf8(bytes: &mut Vec<u8>, s: &str) {
+        bytes.push(1);
+        bytes.extend_from_slice(&(s.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(s.as_bytes());
+    }
+
+    fn attribute(bytes: &mut Vec<u8>, name_index: u8, info: &[u8]) {
+        bytes.extend_from_slice(&[0, name_index]);
+        bytes.extend_from_slice(&(info.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(info);
+    }
+
+    /// a constant pool followed by the attributes of `attributes`
+    fn attributes_data(attributes: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut bytes = vec![0, 13];
+        for name in &[
+            "Signature",
+            "TT;",
+            "RuntimeInvisibleAnnotations",
+            "LFoo;",
+            "value",
+            "RuntimeVisibleParameterAnnotations",
+            "RuntimeVisibleTypeAnnotations",
+            "AnnotationDefault",
+            "MethodParameters",
+            "SourceDebugExtension",
+            "LocalVariableTypeTable",
+        ] {
+            utf8(&mut bytes, name);
+        }
+        // #12
+        bytes.extend_from_slice(&[3, 0, 0, 0, 7]);
+        let mut info = vec![0, attributes.len() as u8];
+        for (name_index, data) in attributes {
+            attribute(&mut info, *name_index, data);
+        }
+        bytes.extend(info);
+        return bytes;
+    }
+
+    fn attributes_reader(attributes: &[(u8, &[u8])]) -> ClassReader {
+        return ClassReader::new(attributes_data(attributes));
+    }
+
+    const JAVA8_ATTRIBUTES: [(u8, &[u8]); 8] = [
+        (1, &[0, 2]),
+        // @Foo(value = 7)
+        (3, &[0, 1, 0, 4, 0, 1, 0, 5, b'I', 0, 12]),
+        // the second of two parameters has @Foo
+        (6, &[2, 0, 0, 0, 1, 0, 4, 0, 0]),
+        // @Foo on the type of local variable 1 at pc 0..10
+        (
+            7,
+            &[0, 1, 0x40, 0, 1, 0, 0, 0, 10, 0, 1, 1, 3, 0, 0, 4, 0, 0],
+        ),
+        // default {@Foo}
+        (8, &[b'[', 0, 1, b'@', 0, 4, 0, 0]),
+        // one final parameter named "value" and a nameless one
+        (9, &[2, 0, 5, 0x00, 0x10, 0, 0, 0, 0]),
+        (10, b"SMAP"),
+        (11, &[0, 1, 0, 0, 0, 4, 0, 5, 0, 2, 0, 1]),
+    ];
+
+    #[test]
+    fn read_java8_attributes() {
+        let mut reader = attributes_reader(&JAVA8_ATTRIBUTES);
+        let cp = ConstantPool::read_constant_pool(&mut reader).unwrap();
+        let attributes = read_attributes(&mut reader, cp).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(attributes.len(), 8);
+        match &attributes[0] {
+            Signature(attr) => assert_eq!(attr.signature(), "TT;"),
+            _ => panic!("not a Signature"),
+        }
+        match &attributes[1] {
+            RuntimeInvisibleAnnotations(attr) => {
+                let annotation = &attr.annotations()[0];
+                assert_eq!(annotation.name(), "LFoo;");
+                let pair = &annotation.element_value_pairs()[0];
+                assert_eq!(pair.element_name_index(), 5);
+                match pair.element_value() {
+                    ElementValue::Const {
+                        tag: b'I',
+                        const_value_index: 12,
+                    } => {}
+                    value => panic!("unexpected {:?}", value),
+                }
+            }
+            _ => panic!("not a RuntimeInvisibleAnnotations"),
+        }
+        match &attributes[2] {
+            RuntimeVisibleParameterAnnotations(attr) => {
+                let parameters = attr.parameter_annotations();
+                assert_eq!(parameters.len(), 2);
+                assert!(parameters[0].is_empty());
+                assert_eq!(parameters[1][0].name(), "LFoo;");
+            }
+            _ => panic!("not a RuntimeVisibleParameterAnnotations"),
+        }
+        match &attributes[3] {
+            RuntimeVisibleTypeAnnotations(attr) => {
+                let annotation = &attr.annotations()[0];
+                assert_eq!(annotation.target_type(), 0x40);
+                match annotation.target_info() {
+                    TargetInfo::LocalVar(table) => {
+                        assert_eq!(
+                            (table[0].start_pc, table[0].length, table[0].index),
+                            (0, 10, 1)
+                        )
+                    }
+                    info => panic!("unexpected {:?}", info),
+                }
+                assert_eq!(annotation.target_path()[0].type_path_kind, 3);
+                assert_eq!(annotation.annotation().name(), "LFoo;");
+            }
+            _ => panic!("not a RuntimeVisibleTypeAnnotations"),
+        }
+        match &attributes[4] {
+            AnnotationDefault(attr) => match attr.default_value() {
+                ElementValue::Array(values) => match &values[0] {
+                    ElementValue::Annotation(annotation) => assert_eq!(annotation.name(), "LFoo;"),
+                    value => panic!("unexpected {:?}", value),
+                },
+                value => panic!("unexpected {:?}", value),
+            },
+            _ => panic!("not an AnnotationDefault"),
+        }
+        match &attributes[5] {
+            MethodParameters(attr) => {
+                assert_eq!(attr.parameter_name(0), Some("value".to_string()));
+                assert_eq!(attr.parameters()[0].access_flags(), 0x10);
+                assert_eq!(attr.parameter_name(1), None);
+            }
+            _ => panic!("not a MethodParameters"),
+        }
+        match &attributes[6] {
+            SourceDebugExtension(attr) => assert_eq!(attr.debug_extension(), b"SMAP"),
+            _ => panic!("not a SourceDebugExtension"),
+        }
+        match &attributes[7] {
+            LocalVariableTypeTable(attr) => {
+                let entry = &attr.local_variable_type_table()[0];
+                assert_eq!((entry.start_pc(), entry.length(), entry.index()), (0, 4, 1));
+                assert_eq!(entry.signature_index(), 2);
+            }
+            _ => panic!("not a LocalVariableTypeTable"),
+        }
+    }
+
+    #[test]
+    fn write_java8_attributes() {
+        let data = attributes_data(&JAVA8_ATTRIBUTES);
+        let mut reader = ClassReader::new(data.clone());
+        let cp = ConstantPool::read_constant_pool(&mut reader).unwrap();
+        let attributes = read_attributes(&mut reader, cp.clone()).unwrap();
+        let mut writer = ClassWriter::new();
+        (*cp).borrow().write_constant_pool(&mut writer);
+        write_attributes(&mut writer, &attributes, &cp);
+        assert_eq!(writer.into_bytes(), data);
+    }
+
+    #[test]
+    fn read_bad_attributes() {
+        // the annotation is one byte longer than the attribute
+        let mut reader = attributes_reader(&[(3, &[0, 1, 0, 4, 0])]);
+        let cp = ConstantPool::read_constant_pool(&mut reader).unwrap();
+        let error = read_attributes(&mut reader, cp).err().unwrap();
+        assert_eq!(
+            error,
+            ClassFormatError::BadAttributeLength {
+                name: "RuntimeInvisibleAnnotations".to_string()
+            }
+        );
+
+        let mut reader = attributes_reader(&[(7, &[0, 1, 0x30, 0])]);
+        let cp = ConstantPool::read_constant_pool(&mut reader).unwrap();
+        let error = read_attributes(&mut reader, cp).err().unwrap();
+        assert_eq!(error, ClassFormatError::BadTargetType(0x30));
+    }
 }
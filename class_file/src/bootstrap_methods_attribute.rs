@@ -1,25 +1,86 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct BootstrapMethodsAttribute {
     bootstrap_methods: Vec<BootstrapMethod>,
 }
 
-struct BootstrapMethod {
+impl BootstrapMethodsAttribute {
+    #[inline]
+    pub fn new() -> BootstrapMethodsAttribute {
+        return BootstrapMethodsAttribute {
+            bootstrap_methods: vec![],
+        };
+    }
+
+    #[inline]
+    pub fn bootstrap_methods(&self) -> &Vec<BootstrapMethod> {
+        return &self.bootstrap_methods;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BootstrapMethod {
     bootstrap_method_ref: u16,
     bootstrap_arguments: Vec<u16>,
 }
 
+impl BootstrapMethod {
+    /// index of a CONSTANT_MethodHandle_info
+    #[inline]
+    pub fn bootstrap_method_ref(&self) -> u16 {
+        return self.bootstrap_method_ref;
+    }
+
+    /// indexes of loadable constants passed as static arguments
+    #[inline]
+    pub fn bootstrap_arguments(&self) -> &Vec<u16> {
+        return &self.bootstrap_arguments;
+    }
+}
+
 impl AttributeInfo for BootstrapMethodsAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let num_bootstrap_methods = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let num_bootstrap_methods = reader.read_u16()?;
         let mut bootstrap_methods = Vec::new();
         for _ in 0..num_bootstrap_methods {
             bootstrap_methods.push(BootstrapMethod {
-                bootstrap_method_ref: reader.read_u16(),
-                bootstrap_arguments: reader.read_u16_table(),
+                bootstrap_method_ref: reader.read_u16()?,
+                bootstrap_arguments: reader.read_u16_table()?,
             })
         }
         self.bootstrap_methods = bootstrap_methods;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.bootstrap_methods.len() as u16);
+        for method in &self.bootstrap_methods {
+            writer.write_u16(method.bootstrap_method_ref);
+            writer.write_u16_table(&method.bootstrap_arguments);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::attribute_info::AttributeInfo;
+    use crate::bootstrap_methods_attribute::BootstrapMethodsAttribute;
+    use crate::class_reader::ClassReader;
+
+    #[test]
+    fn read_bootstrap_methods() {
+        // one LambdaMetafactory.metafactory entry with three static arguments
+        let data = vec![0, 1, 0, 30, 0, 3, 0, 31, 0, 32, 0, 33];
+        let mut reader = ClassReader::new(data);
+        let mut attr = BootstrapMethodsAttribute::new();
+        attr.read_info(&mut reader).unwrap();
+        assert_eq!(1, attr.bootstrap_methods().len());
+        let method = &attr.bootstrap_methods()[0];
+        assert_eq!(30, method.bootstrap_method_ref());
+        assert_eq!(&vec![31, 32, 33], method.bootstrap_arguments());
+        assert!(reader.is_empty());
     }
 }
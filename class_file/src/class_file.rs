@@ -1,6 +1,9 @@
-use crate::attribute_info::Attribute::SourceFile;
-use crate::attribute_info::{read_attributes, Attribute, AttributeInfo};
+use crate::attribute_info::Attribute::{BootstrapMethods, SourceFile};
+use crate::attribute_info::{read_attributes, write_attributes, Attribute};
+use crate::bootstrap_methods_attribute::BootstrapMethodsAttribute;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
 use crate::member_info::MemberInfo;
 use crate::source_file_attribute::SourceFileAttribute;
@@ -22,7 +25,9 @@ pub struct ClassFile {
 }
 
 impl ClassFile {
-    pub fn parse(class_data: Vec<u8>) -> ClassFile {
+    /// parses a whole class file, the class loader turns the error into a
+    /// java.lang.ClassFormatError or UnsupportedClassVersionError
+    pub fn parse(class_data: Vec<u8>) -> Result<ClassFile, ClassFormatError> {
         let mut class_reader = ClassReader::new(class_data);
         let mut class_file = ClassFile {
             minor_version: 0,
@@ -36,43 +41,89 @@ impl ClassFile {
             methods: vec![],
             attributes: vec![],
         };
-        class_file.read(&mut class_reader);
-        return class_file;
+        class_file.read(&mut class_reader)?;
+        return Ok(class_file);
     }
 
-    fn read(&mut self, reader: &mut ClassReader) {
-        self.read_and_check_magic(reader);
-        self.read_and_check_version(reader);
-        self.constant_pool = ConstantPool::read_constant_pool(reader);
-        self.access_flags = reader.read_u16();
-        self.this_class = reader.read_u16();
-        self.super_class = reader.read_u16();
-        self.interfaces = reader.read_u16_table();
-        self.fields = MemberInfo::read_members(reader, self.constant_pool.clone());
-        self.methods = MemberInfo::read_members(reader, self.constant_pool.clone());
-        self.attributes = read_attributes(reader, self.constant_pool.clone())
+    /// serializes the class file, what `parse` read comes out byte for byte
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = ClassWriter::new();
+        self.write(&mut writer);
+        return writer.into_bytes();
     }
 
-    fn read_and_check_magic(&mut self, reader: &mut ClassReader) {
-        let magic = reader.read_u32();
+    fn write(&self, writer: &mut ClassWriter) {
+        writer.write_u32(0xCAFEBABE);
+        writer.write_u16(self.minor_version);
+        writer.write_u16(self.major_version);
+        (*self.constant_pool).borrow().write_constant_pool(writer);
+        writer.write_u16(self.access_flags);
+        writer.write_u16(self.this_class);
+        writer.write_u16(self.super_class);
+        writer.write_u16_table(&self.interfaces);
+        MemberInfo::write_members(writer, &self.fields);
+        MemberInfo::write_members(writer, &self.methods);
+        write_attributes(writer, &self.attributes, &self.constant_pool);
+    }
+
+    fn read(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.read_and_check_magic(reader)?;
+        self.read_and_check_version(reader)?;
+        self.constant_pool = ConstantPool::read_constant_pool(reader)?;
+        self.access_flags = reader.read_u16()?;
+        self.this_class = reader.read_u16()?;
+        self.super_class = reader.read_u16()?;
+        self.interfaces = reader.read_u16_table()?;
+        self.check_class_indices()?;
+        self.fields = MemberInfo::read_members(reader, self.constant_pool.clone())?;
+        self.methods = MemberInfo::read_members(reader, self.constant_pool.clone())?;
+        self.attributes = read_attributes(reader, self.constant_pool.clone())?;
+        if !reader.is_empty() {
+            return Err(ClassFormatError::ExtraBytes {
+                offset: reader.position(),
+            });
+        }
+        return Ok(());
+    }
+
+    fn read_and_check_magic(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let magic = reader.read_u32()?;
         if magic != 0xCAFEBABE {
-            panic!("java.lang.ClassFormatError: magic!")
+            return Err(ClassFormatError::BadMagic(magic));
         }
+        return Ok(());
     }
 
-    fn read_and_check_version(&mut self, reader: &mut ClassReader) {
-        self.minor_version = reader.read_u16();
-        self.major_version = reader.read_u16();
+    fn read_and_check_version(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.minor_version = reader.read_u16()?;
+        self.major_version = reader.read_u16()?;
         match self.major_version {
-            45 => return,
+            45 => return Ok(()),
             46..=52 => {
                 if self.minor_version == 0 {
-                    return;
+                    return Ok(());
                 }
             }
             _ => {}
         }
-        panic!("java.lang.UnsupportedClassVersionError!")
+        return Err(ClassFormatError::UnsupportedVersion {
+            major: self.major_version,
+            minor: self.minor_version,
+        });
+    }
+
+    /// this_class and the interfaces have to be classes, super_class too
+    /// unless it is 0
+    fn check_class_indices(&self) -> Result<(), ClassFormatError> {
+        let pool = (*self.constant_pool).borrow();
+        pool.check_class(self.this_class)?;
+        if self.super_class != 0 {
+            pool.check_class(self.super_class)?;
+        }
+        for index in &self.interfaces {
+            pool.check_class(*index)?;
+        }
+        return Ok(());
     }
 
     pub fn minor_version(&self) -> u16 {
@@ -136,6 +187,16 @@ impl ClassFile {
         return None;
     }
 
+    pub fn bootstrap_methods_attribute(&self) -> Option<&BootstrapMethodsAttribute> {
+        for attr in &self.attributes {
+            match attr {
+                BootstrapMethods(r) => return Some(r),
+                _ => {}
+            }
+        }
+        return None;
+    }
+
     #[inline]
     pub fn attributes(&self) -> &Vec<Attribute> {
         return self.attributes.as_ref();
@@ -173,10 +234,124 @@ impl ClassFile {
 
 #[cfg(test)]
 mod test {
+    use crate::class_file::ClassFile;
+    use crate::class_format_error::ClassFormatError;
     use core::mem;
     use std::cell::RefCell;
     use std::rc::Rc;
 
+    /// `class A` without members, #1 is the class and #2 its name
+    fn class_bytes() -> Vec<u8> {
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52, 0, 3];
+        bytes.extend_from_slice(&[7, 0, 2]);
+        bytes.extend_from_slice(&[1, 0, 1, b'A']);
+        bytes.extend_from_slice(&[0, 0x21, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        return bytes;
+    }
+
+    fn parse_error(bytes: Vec<u8>) -> ClassFormatError {
+        return ClassFile::parse(bytes)
+            .err()
+            .expect("parsed a broken class file");
+    }
+
+    #[test]
+    fn test_parse() {
+        let class_file = ClassFile::parse(class_bytes()).unwrap();
+        assert_eq!(class_file.class_name(), "A");
+        assert_eq!(class_file.super_class_name(), None);
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let bytes = class_bytes();
+        assert_eq!(ClassFile::parse(bytes.clone()).unwrap().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut bytes = class_bytes();
+        bytes.truncate(15);
+        assert_eq!(
+            parse_error(bytes),
+            ClassFormatError::Truncated { offset: 14 }
+        );
+        assert_eq!(
+            parse_error(vec![0xCA, 0xFE]),
+            ClassFormatError::Truncated { offset: 0 }
+        );
+    }
+
+    #[test]
+    fn test_bad_magic() {
+        let mut bytes = class_bytes();
+        bytes[3] = 0xBF;
+        assert_eq!(parse_error(bytes), ClassFormatError::BadMagic(0xCAFEBABF));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut bytes = class_bytes();
+        bytes[7] = 53;
+        let error = parse_error(bytes);
+        assert_eq!(
+            error,
+            ClassFormatError::UnsupportedVersion {
+                major: 53,
+                minor: 0
+            }
+        );
+        assert_eq!(error.to_string(), "Unsupported major.minor version 53.0");
+    }
+
+    #[test]
+    fn test_bad_constant_pool_tag() {
+        let mut bytes = class_bytes();
+        bytes[13] = 2;
+        assert_eq!(
+            parse_error(bytes),
+            ClassFormatError::BadConstantPoolTag { index: 2, tag: 2 }
+        );
+    }
+
+    #[test]
+    fn test_bad_index() {
+        // the class entry names itself instead of the Utf8
+        let mut bytes = class_bytes();
+        bytes[12] = 1;
+        assert!(matches!(
+            parse_error(bytes),
+            ClassFormatError::BadIndex { index: 1, .. }
+        ));
+        // this_class is out of the constant pool
+        let mut bytes = class_bytes();
+        bytes[20] = 3;
+        assert!(matches!(
+            parse_error(bytes),
+            ClassFormatError::BadIndex { index: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut bytes = class_bytes();
+        bytes[16] = 0xFF;
+        assert_eq!(
+            parse_error(bytes),
+            ClassFormatError::InvalidUtf8 { index: 2 }
+        );
+    }
+
+    #[test]
+    fn test_extra_bytes() {
+        let mut bytes = class_bytes();
+        bytes.push(0);
+        assert_eq!(
+            parse_error(bytes),
+            ClassFormatError::ExtraBytes { offset: 31 }
+        );
+    }
+
     #[test]
     fn test_swap() {
         let mut rc_1 = Rc::new("123");
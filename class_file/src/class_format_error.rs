@@ -0,0 +1,109 @@
+use std::error::Error;
+use std::fmt;
+
+/// why a class file was rejected, the VM throws java.lang.ClassFormatError
+/// for all of them but `UnsupportedVersion`, which is an
+/// UnsupportedClassVersionError
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassFormatError {
+    /// the data ended in the middle of a structure starting at `offset`
+    Truncated {
+        offset: usize,
+    },
+    BadMagic(u32),
+    UnsupportedVersion {
+        major: u16,
+        minor: u16,
+    },
+    /// the constant at `index` has an unknown tag
+    BadConstantPoolTag {
+        index: u16,
+        tag: u8,
+    },
+    /// `index` is out of the constant pool or refers to a constant of
+    /// another kind than `expected`
+    BadIndex {
+        index: u16,
+        expected: &'static str,
+    },
+    /// a CONSTANT_MethodHandle with a kind outside of 1 to 9
+    BadReferenceKind(u8),
+    /// the CONSTANT_Utf8 at `index` is not modified UTF-8
+    InvalidUtf8 {
+        index: u16,
+    },
+    /// an attribute the parser knows is longer or shorter than its content
+    BadAttributeLength {
+        name: String,
+    },
+    BadStackMapFrameType(u8),
+    BadVerificationType(u8),
+    BadElementValueTag(u8),
+    BadTargetType(u8),
+    /// there are bytes after the last attribute of the class
+    ExtraBytes {
+        offset: usize,
+    },
+}
+
+impl fmt::Display for ClassFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ClassFormatError::Truncated { offset } => {
+                write!(f, "Truncated class file at offset {}", offset)
+            }
+            ClassFormatError::BadMagic(magic) => write!(f, "Incompatible magic value {}", magic),
+            ClassFormatError::UnsupportedVersion { major, minor } => {
+                write!(f, "Unsupported major.minor version {}.{}", major, minor)
+            }
+            ClassFormatError::BadConstantPoolTag { index, tag } => {
+                write!(
+                    f,
+                    "Unknown constant tag {} at constant pool index {}",
+                    tag, index
+                )
+            }
+            ClassFormatError::BadIndex { index, expected } => {
+                write!(
+                    f,
+                    "Invalid constant pool index {}, expected {}",
+                    index, expected
+                )
+            }
+            ClassFormatError::BadReferenceKind(kind) => {
+                write!(f, "Bad method handle kind {} in constant pool", kind)
+            }
+            ClassFormatError::InvalidUtf8 { index } => {
+                write!(f, "Illegal UTF8 string in constant pool at index {}", index)
+            }
+            ClassFormatError::BadAttributeLength { name } => {
+                write!(f, "Wrong length of {} attribute", name)
+            }
+            ClassFormatError::BadStackMapFrameType(frame_type) => {
+                write!(f, "Unknown frame type {} in StackMapTable", frame_type)
+            }
+            ClassFormatError::BadVerificationType(tag) => {
+                write!(f, "Unknown verification type {} in StackMapTable", tag)
+            }
+            ClassFormatError::BadElementValueTag(tag) => {
+                write!(
+                    f,
+                    "Unknown element value tag '{}' in annotation",
+                    *tag as char
+                )
+            }
+            ClassFormatError::BadTargetType(target_type) => {
+                write!(f, "Unknown target type {} in type annotation", target_type)
+            }
+            ClassFormatError::ExtraBytes { offset } => {
+                write!(
+                    f,
+                    "Extra bytes at the end of class file at offset {}",
+                    offset
+                )
+            }
+        };
+    }
+}
+
+impl Error for ClassFormatError {}
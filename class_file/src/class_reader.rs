@@ -1,69 +1,127 @@
+use crate::class_format_error::ClassFormatError;
+
+/// reads the big-endian items of a class file, running past the end of
+/// the data is a `ClassFormatError::Truncated`
 pub struct ClassReader {
-    pub data: Vec<u8>,
+    data: Vec<u8>,
+    pos: usize,
+    /// reading stops here, the end of the attribute being parsed
+    limit: usize,
 }
 
 impl ClassReader {
     pub fn new(data: Vec<u8>) -> ClassReader {
-        return ClassReader { data };
+        let limit = data.len();
+        return ClassReader {
+            data,
+            pos: 0,
+            limit,
+        };
     }
 
+    /// the offset of the next byte in the data
     #[inline]
-    pub fn read_u8(&mut self) -> u8 {
-        let val = self.data.remove(0usize);
-        return val;
+    pub fn position(&self) -> usize {
+        return self.pos;
     }
 
     #[inline]
-    pub fn read_char(&mut self) -> char {
-        return self.read_u8() as char;
+    pub fn is_empty(&self) -> bool {
+        return self.pos >= self.limit;
     }
 
     #[inline]
-    pub fn read_u16(&mut self) -> u16 {
-        let mut head = self.data.remove(0usize) as u16;
-        let tail = self.data.remove(0usize) as u16;
-        head = head << 8;
-        return head | tail;
+    fn take(&mut self, n: usize) -> Result<&[u8], ClassFormatError> {
+        if self.limit - self.pos < n {
+            return Err(ClassFormatError::Truncated { offset: self.pos });
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        return Ok(bytes);
     }
 
     #[inline]
-    pub fn read_u32(&mut self) -> u32 {
-        let mut result = 0u32;
-        for i in 0..4usize {
-            let mut head = self.data.remove(0usize) as u32;
-            head = head << (24 - (i as u32 * 8));
-            result = result | head;
-        }
-        return result;
+    pub fn read_u8(&mut self) -> Result<u8, ClassFormatError> {
+        return Ok(self.take(1)?[0]);
     }
 
     #[inline]
-    pub fn read_u64(&mut self) -> u64 {
-        let mut result = 0u64;
-        for i in 0..8usize {
-            let mut head = self.data.remove(0usize) as u64;
-            head = head << (56 - (i as u64 * 8));
-            result = result | head;
-        }
-        return result;
+    pub fn read_u16(&mut self) -> Result<u16, ClassFormatError> {
+        let bytes = self.take(2)?;
+        return Ok(u16::from_be_bytes([bytes[0], bytes[1]]));
+    }
+
+    #[inline]
+    pub fn read_u32(&mut self) -> Result<u32, ClassFormatError> {
+        let bytes = self.take(4)?;
+        return Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    }
+
+    #[inline]
+    pub fn read_u64(&mut self) -> Result<u64, ClassFormatError> {
+        let high = self.read_u32()? as u64;
+        let low = self.read_u32()? as u64;
+        return Ok(high << 32 | low);
     }
 
     #[inline]
-    pub fn read_u16_table(&mut self) -> Vec<u16> {
-        let n = self.read_u16();
-        let mut table = Vec::new();
+    pub fn read_u16_table(&mut self) -> Result<Vec<u16>, ClassFormatError> {
+        let n = self.read_u16()?;
+        let mut table = Vec::with_capacity(n as usize);
         for _i in 0..n {
-            table.push(self.read_u16());
+            table.push(self.read_u16()?);
         }
-        return table;
+        return Ok(table);
     }
 
     #[inline]
-    pub fn read_bytes(&mut self, n: usize) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        for _i in 0..n {
-            bytes.push(self.data.remove(0usize));
+    pub fn read_bytes(&mut self, n: usize) -> Result<Vec<u8>, ClassFormatError> {
+        return Ok(self.take(n)?.to_vec());
+    }
+
+    /// makes the next `n` bytes all there is to read, returns the limit
+    /// `restore_limit` has to put back
+    pub fn limit_to(&mut self, n: usize) -> Result<usize, ClassFormatError> {
+        if self.limit - self.pos < n {
+            return Err(ClassFormatError::Truncated { offset: self.pos });
         }
-        return bytes;
+        let outer = self.limit;
+        self.limit = self.pos + n;
+        return Ok(outer);
+    }
+
+    #[inline]
+    pub fn restore_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::class_format_error::ClassFormatError;
+    use crate::class_reader::ClassReader;
+
+    #[test]
+    fn test_read() {
+        let mut reader = ClassReader::new(vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 1, 0, 2, 7]);
+        assert_eq!(reader.read_u32(), Ok(0xCAFEBABE));
+        let outer = reader.limit_to(4).unwrap();
+        assert_eq!(reader.read_u16_table(), Ok(vec![2]));
+        assert!(reader.is_empty());
+        assert_eq!(
+            reader.read_u8(),
+            Err(ClassFormatError::Truncated { offset: 8 })
+        );
+        reader.restore_limit(outer);
+        assert_eq!(
+            reader.read_u16(),
+            Err(ClassFormatError::Truncated { offset: 8 })
+        );
+        assert_eq!(reader.read_u8(), Ok(7));
+        assert!(reader.is_empty());
+        assert_eq!(
+            reader.read_u8(),
+            Err(ClassFormatError::Truncated { offset: 9 })
+        );
     }
 }
@@ -0,0 +1,88 @@
+/// writes the big-endian items of a class file, the counterpart of
+/// `ClassReader`
+pub struct ClassWriter {
+    data: Vec<u8>,
+}
+
+impl ClassWriter {
+    pub fn new() -> ClassWriter {
+        return ClassWriter { data: Vec::new() };
+    }
+
+    /// the offset the next byte is written at
+    #[inline]
+    pub fn position(&self) -> usize {
+        return self.data.len();
+    }
+
+    #[inline]
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    #[inline]
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_be_bytes());
+    }
+
+    #[inline]
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_be_bytes());
+    }
+
+    #[inline]
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// a u16 count followed by the items, like `ClassReader::read_u16_table`
+    #[inline]
+    pub fn write_u16_table(&mut self, table: &[u16]) {
+        self.write_u16(table.len() as u16);
+        for value in table {
+            self.write_u16(*value);
+        }
+    }
+
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// overwrites the u32 at `offset`, used for lengths only known after the
+    /// content is written
+    pub fn patch_u32(&mut self, offset: usize, value: u32) {
+        self.data[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        return self.data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::class_reader::ClassReader;
+    use crate::class_writer::ClassWriter;
+
+    #[test]
+    fn test_write() {
+        let mut writer = ClassWriter::new();
+        writer.write_u32(0xCAFEBABE);
+        let length = writer.position();
+        writer.write_u32(0);
+        writer.write_u16_table(&[2, 3]);
+        writer.write_u64(u64::MAX - 1);
+        writer.patch_u32(length, 6);
+        let bytes = writer.into_bytes();
+        assert_eq!(&bytes[..10], &[0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 6, 0, 2]);
+
+        let mut reader = ClassReader::new(bytes);
+        assert_eq!(reader.read_u32(), Ok(0xCAFEBABE));
+        assert_eq!(reader.read_u32(), Ok(6));
+        assert_eq!(reader.read_u16_table(), Ok(vec![2, 3]));
+        assert_eq!(reader.read_u64(), Ok(u64::MAX - 1));
+        assert!(reader.is_empty());
+    }
+}
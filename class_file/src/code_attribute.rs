@@ -1,7 +1,10 @@
-use crate::attribute_info::{read_attributes, Attribute, AttributeInfo};
+use crate::attribute_info::{read_attributes, write_attributes, Attribute, AttributeInfo};
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
 use crate::line_number_table_attribute::LineNumberTableAttribute;
+use crate::stack_map_table_attribute::StackMapAttribute;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -57,6 +60,12 @@ impl CodeAttribute {
         return &self.exception_table;
     }
 
+    /// LineNumberTable, LocalVariableTable, StackMapTable and the like
+    #[inline]
+    pub fn attributes(&self) -> &Vec<Attribute> {
+        return &self.attributes;
+    }
+
     pub fn line_number_table_attribute(&self) -> Option<LineNumberTableAttribute> {
         for attribute in &self.attributes {
             match attribute {
@@ -67,6 +76,16 @@ impl CodeAttribute {
         return None;
     }
 
+    pub fn stack_map_table_attribute(&self) -> Option<StackMapAttribute> {
+        for attribute in &self.attributes {
+            match attribute {
+                Attribute::StackMap(attr) => return Some(attr.clone()),
+                _ => {}
+            }
+        }
+        return None;
+    }
+
     pub fn display(&self) {
         println!("CodeAttribute:");
         println!("  max_stack:{}", self.max_stack);
@@ -78,13 +97,29 @@ impl CodeAttribute {
 }
 
 impl AttributeInfo for CodeAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.max_stack = reader.read_u16();
-        self.max_locals = reader.read_u16();
-        let code_len = reader.read_u32();
-        self.code = reader.read_bytes(code_len as usize);
-        self.exception_table = ExceptionTableEntry::read_exception_table(reader);
-        self.attributes = read_attributes(reader, self.cp.clone());
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.max_stack = reader.read_u16()?;
+        self.max_locals = reader.read_u16()?;
+        let code_len = reader.read_u32()?;
+        self.code = reader.read_bytes(code_len as usize)?;
+        self.exception_table = ExceptionTableEntry::read_exception_table(reader)?;
+        self.attributes = read_attributes(reader, self.cp.clone())?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.max_stack);
+        writer.write_u16(self.max_locals);
+        writer.write_u32(self.code.len() as u32);
+        writer.write_bytes(&self.code);
+        writer.write_u16(self.exception_table.len() as u16);
+        for entry in &self.exception_table {
+            writer.write_u16(entry.start_pc);
+            writer.write_u16(entry.end_pc);
+            writer.write_u16(entry.handler_pc);
+            writer.write_u16(entry.catch_type);
+        }
+        write_attributes(writer, &self.attributes, &self.cp);
     }
 }
 
@@ -96,18 +131,20 @@ pub struct ExceptionTableEntry {
 }
 
 impl ExceptionTableEntry {
-    pub fn read_exception_table(reader: &mut ClassReader) -> Vec<ExceptionTableEntry> {
-        let exception_table_len = reader.read_u16();
+    pub fn read_exception_table(
+        reader: &mut ClassReader,
+    ) -> Result<Vec<ExceptionTableEntry>, ClassFormatError> {
+        let exception_table_len = reader.read_u16()?;
         let mut exception_table = Vec::new();
         for _ in 0..exception_table_len {
             exception_table.push(ExceptionTableEntry {
-                start_pc: reader.read_u16(),
-                end_pc: reader.read_u16(),
-                handler_pc: reader.read_u16(),
-                catch_type: reader.read_u16(),
+                start_pc: reader.read_u16()?,
+                end_pc: reader.read_u16()?,
+                handler_pc: reader.read_u16()?,
+                catch_type: reader.read_u16()?,
             })
         }
-        return exception_table;
+        return Ok(exception_table);
     }
 
     #[inline]
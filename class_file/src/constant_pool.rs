@@ -1,6 +1,7 @@
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantInfoEnum::*;
-use crate::makers_attribute::DeprecatedAttribute;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -15,21 +16,17 @@ impl ConstantPool {
         return ConstantPool { vec: vec![] };
     }
 
-    pub fn read_constant_pool(reader: &mut ClassReader) -> Rc<RefCell<ConstantPool>> {
-        let cp_count = reader.read_u16();
+    pub fn read_constant_pool(
+        reader: &mut ClassReader,
+    ) -> Result<Rc<RefCell<ConstantPool>>, ClassFormatError> {
+        let cp_count = reader.read_u16()?;
         let cp = Rc::new(RefCell::new(ConstantPool::new()));
         let mut vec: Vec<ConstantInfoEnum> = Vec::new();
         let mut i = 1;
         while i < cp_count {
-            let constant_info = read_constant_info(reader, cp.clone());
+            let constant_info = read_constant_info(reader, cp.clone(), i)?;
             match &constant_info {
-                Long(info) => {
-                    i = i + 2;
-                    vec.push(constant_info);
-                    vec.push(None);
-                    continue;
-                }
-                Double(info) => {
+                Long(_) | Double(_) => {
                     i = i + 2;
                     vec.push(constant_info);
                     vec.push(None);
@@ -40,9 +37,168 @@ impl ConstantPool {
             vec.push(constant_info);
             i += 1;
         }
-        let c = Rc::new(RefCell::new(ConstantPool { vec }));
+        let pool = ConstantPool { vec };
+        pool.check_references()?;
+        let c = Rc::new(RefCell::new(pool));
         ConstantPool::post_constant_pool(c.clone());
-        return c;
+        return Ok(c);
+    }
+
+    /// writes the constant_pool_count and the constants
+    pub fn write_constant_pool(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.vec.len() as u16 + 1);
+        for info in &self.vec {
+            info.write_info(writer);
+        }
+    }
+
+    /// every index a constant holds has to refer to a constant of the kind
+    /// JVMS §4.4 asks for
+    fn check_references(&self) -> Result<(), ClassFormatError> {
+        for info in &self.vec {
+            match info {
+                Class(class) => self.check(class.name_index, "CONSTANT_Utf8", is_utf8)?,
+                Str(string) => self.check(string.string_index, "CONSTANT_Utf8", is_utf8)?,
+                FieldRef(ConstantFieldRefInfo(member))
+                | MethodRef(ConstantMethodRefInfo(member))
+                | InterfaceMethodRef(ConstantInterfaceMethodRefInfo(member)) => {
+                    self.check(member.class_index, "CONSTANT_Class", is_class)?;
+                    self.check(
+                        member.name_and_type_index,
+                        "CONSTANT_NameAndType",
+                        is_name_and_type,
+                    )?;
+                }
+                NameAndType(name_and_type) => {
+                    self.check(name_and_type.name_index, "CONSTANT_Utf8", is_utf8)?;
+                    self.check(name_and_type.desc_index, "CONSTANT_Utf8", is_utf8)?;
+                }
+                MethodHandle(handle) => {
+                    let index = handle.reference_index;
+                    match handle.reference_kind {
+                        1..=4 => self.check(index, "CONSTANT_Fieldref", |info| {
+                            matches!(info, FieldRef(_))
+                        })?,
+                        5 | 8 => self.check(index, "CONSTANT_Methodref", |info| {
+                            matches!(info, MethodRef(_))
+                        })?,
+                        6 | 7 => self.check(
+                            index,
+                            "CONSTANT_Methodref or CONSTANT_InterfaceMethodref",
+                            |info| matches!(info, MethodRef(_) | InterfaceMethodRef(_)),
+                        )?,
+                        9 => self.check(index, "CONSTANT_InterfaceMethodref", |info| {
+                            matches!(info, InterfaceMethodRef(_))
+                        })?,
+                        kind => return Err(ClassFormatError::BadReferenceKind(kind)),
+                    }
+                }
+                MethodType(method_type) => {
+                    self.check(method_type.descriptor_index, "CONSTANT_Utf8", is_utf8)?
+                }
+                InvokeDynamic(invoke) => self.check(
+                    invoke.name_and_type_index,
+                    "CONSTANT_NameAndType",
+                    is_name_and_type,
+                )?,
+                _ => {}
+            }
+        }
+        return Ok(());
+    }
+
+    fn check(
+        &self,
+        index: u16,
+        expected: &'static str,
+        is_expected: fn(&ConstantInfoEnum) -> bool,
+    ) -> Result<(), ClassFormatError> {
+        return match self.constant(index) {
+            Ok(info) if is_expected(info) => Ok(()),
+            _ => Err(ClassFormatError::BadIndex { index, expected }),
+        };
+    }
+
+    /// the constant at `index`, unlike get_constant_info it doesn't trust
+    /// the index
+    pub fn constant(&self, index: u16) -> Result<&ConstantInfoEnum, ClassFormatError> {
+        return match self.vec.get((index as usize).wrapping_sub(1)) {
+            Some(None) | Option::None => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "a constant",
+            }),
+            Some(info) => Ok(info),
+        };
+    }
+
+    /// the index of the first CONSTANT_Utf8 holding `string`
+    pub fn utf8_index(&self, string: &str) -> Option<u16> {
+        return self
+            .vec
+            .iter()
+            .position(|info| match info {
+                Utf8(utf8) => utf8.val == string,
+                _ => false,
+            })
+            .map(|index| index as u16 + 1);
+    }
+
+    pub fn check_class(&self, index: u16) -> Result<(), ClassFormatError> {
+        return self.check(index, "CONSTANT_Class", is_class);
+    }
+
+    pub fn check_name_and_type(&self, index: u16) -> Result<(), ClassFormatError> {
+        return self.check(index, "CONSTANT_NameAndType", is_name_and_type);
+    }
+
+    pub fn utf8(&self, index: u16) -> Result<&str, ClassFormatError> {
+        return match self.constant(index) {
+            Ok(Utf8(utf8)) => Ok(utf8.val.as_str()),
+            _ => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "CONSTANT_Utf8",
+            }),
+        };
+    }
+
+    pub fn integer(&self, index: u16) -> Result<i32, ClassFormatError> {
+        return match self.constant(index) {
+            Ok(Integer(integer)) => Ok(integer.val),
+            _ => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "CONSTANT_Integer",
+            }),
+        };
+    }
+
+    pub fn long(&self, index: u16) -> Result<i64, ClassFormatError> {
+        return match self.constant(index) {
+            Ok(Long(long)) => Ok(long.val),
+            _ => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "CONSTANT_Long",
+            }),
+        };
+    }
+
+    pub fn float(&self, index: u16) -> Result<f32, ClassFormatError> {
+        return match self.constant(index) {
+            Ok(Float(float)) => Ok(float.val),
+            _ => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "CONSTANT_Float",
+            }),
+        };
+    }
+
+    pub fn double(&self, index: u16) -> Result<f64, ClassFormatError> {
+        return match self.constant(index) {
+            Ok(Double(double)) => Ok(double.val),
+            _ => Err(ClassFormatError::BadIndex {
+                index,
+                expected: "CONSTANT_Double",
+            }),
+        };
     }
 
     fn post_constant_pool(rc_pool: Rc<RefCell<ConstantPool>>) {
@@ -56,6 +212,8 @@ impl ConstantPool {
                 MethodRef(info) => info.0.cp = rc_pool.clone(),
                 MethodRef(info) => info.0.cp = rc_pool.clone(),
                 InterfaceMethodRef(info) => info.0.cp = rc_pool.clone(),
+                MethodType(info) => info.cp = rc_pool.clone(),
+                InvokeDynamic(info) => info.cp = rc_pool.clone(),
                 _ => {}
             }
         }
@@ -128,7 +286,7 @@ enum ConstantInfoTag {
 }
 
 impl ConstantInfoTag {
-    pub fn from(v: u8) -> Self {
+    pub fn from(v: u8) -> Option<Self> {
         let tag = match v {
             1 => ConstantInfoTag::ConstantUtf8,
             3 => ConstantInfoTag::ConstantInteger,
@@ -144,12 +302,9 @@ impl ConstantInfoTag {
             15 => ConstantInfoTag::ConstantMethodHandle,
             16 => ConstantInfoTag::ConstantMethodType,
             18 => ConstantInfoTag::ConstantInvokeDynamic,
-            _ => {
-                println!("tag:{}", v);
-                panic!("java.lang.ClassFormatError: constant pool tag!")
-            }
+            _ => return Option::None,
         };
-        return tag;
+        return Some(tag);
     }
 }
 
@@ -172,8 +327,8 @@ pub enum ConstantInfoEnum {
 }
 
 impl ConstantInfoEnum {
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        match self {
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return match self {
             Utf8(utf8) => utf8.read_info(reader),
             Integer(integer) => integer.read_info(reader),
             Float(float) => float.read_info(reader),
@@ -188,7 +343,47 @@ impl ConstantInfoEnum {
             MethodHandle(method_handle) => method_handle.read_info(reader),
             MethodType(method_type) => method_type.read_info(reader),
             InvokeDynamic(invoke) => invoke.read_info(reader),
-            _ => {}
+            _ => Ok(()),
+        };
+    }
+
+    /// the tag and the info, the second slot of a long or double is
+    /// written by nothing
+    pub fn write_info(&self, writer: &mut ClassWriter) {
+        let tag = match self {
+            Utf8(_) => ConstantInfoTag::ConstantUtf8,
+            Integer(_) => ConstantInfoTag::ConstantInteger,
+            Float(_) => ConstantInfoTag::ConstantFloat,
+            Long(_) => ConstantInfoTag::ConstantLong,
+            Double(_) => ConstantInfoTag::ConstantDouble,
+            Class(_) => ConstantInfoTag::ConstantClass,
+            Str(_) => ConstantInfoTag::ConstantString,
+            FieldRef(_) => ConstantInfoTag::ConstantFieldref,
+            MethodRef(_) => ConstantInfoTag::ConstantMethodref,
+            InterfaceMethodRef(_) => ConstantInfoTag::ConstantInterfaceMethodref,
+            NameAndType(_) => ConstantInfoTag::ConstantNameAndType,
+            MethodHandle(_) => ConstantInfoTag::ConstantMethodHandle,
+            MethodType(_) => ConstantInfoTag::ConstantMethodType,
+            InvokeDynamic(_) => ConstantInfoTag::ConstantInvokeDynamic,
+            None => return,
+        };
+        writer.write_u8(tag as u8);
+        match self {
+            Utf8(utf8) => ConstantInfo::write_info(utf8, writer),
+            Integer(integer) => ConstantInfo::write_info(integer, writer),
+            Float(float) => ConstantInfo::write_info(float, writer),
+            Long(long) => ConstantInfo::write_info(long, writer),
+            Double(double) => ConstantInfo::write_info(double, writer),
+            Class(class) => ConstantInfo::write_info(class, writer),
+            Str(string) => ConstantInfo::write_info(string, writer),
+            FieldRef(field) => field.write_info(writer),
+            MethodRef(method_ref) => method_ref.write_info(writer),
+            InterfaceMethodRef(interface) => interface.write_info(writer),
+            NameAndType(name_and_type) => ConstantInfo::write_info(name_and_type, writer),
+            MethodHandle(method_handle) => method_handle.write_info(writer),
+            MethodType(method_type) => method_type.write_info(writer),
+            InvokeDynamic(invoke) => invoke.write_info(writer),
+            None => {}
         }
     }
 
@@ -230,21 +425,37 @@ impl ConstantInfoEnum {
 }
 
 pub trait ConstantInfo {
-    fn read_info(&mut self, reader: &mut ClassReader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError>;
+
+    /// writes what `read_info` reads, the tag is written by
+    /// `ConstantInfoEnum::write_info`
+    fn write_info(&self, writer: &mut ClassWriter);
 }
 
+/// reads the constant at `index` of the constant pool
 pub fn read_constant_info(
     reader: &mut ClassReader,
     cp: Rc<RefCell<ConstantPool>>,
-) -> ConstantInfoEnum {
-    let tag = reader.read_u8();
-    let mut constant_info = new(tag, cp);
-    constant_info.read_info(reader);
-    return constant_info;
+    index: u16,
+) -> Result<ConstantInfoEnum, ClassFormatError> {
+    let tag = reader.read_u8()?;
+    let mut constant_info = match new(tag, cp) {
+        Some(constant_info) => constant_info,
+        Option::None => return Err(ClassFormatError::BadConstantPoolTag { index, tag }),
+    };
+    // only the constant pool knows where a string is
+    constant_info
+        .read_info(reader)
+        .map_err(|error| match error {
+            ClassFormatError::InvalidUtf8 { .. } => ClassFormatError::InvalidUtf8 { index },
+            error => error,
+        })?;
+    return Ok(constant_info);
 }
 
-pub fn new(tag: u8, cp: Rc<RefCell<ConstantPool>>) -> ConstantInfoEnum {
-    let constant_info: ConstantInfoEnum = match ConstantInfoTag::from(tag) {
+/// None if `tag` is not one of the constant kinds
+pub fn new(tag: u8, cp: Rc<RefCell<ConstantPool>>) -> Option<ConstantInfoEnum> {
+    let constant_info: ConstantInfoEnum = match ConstantInfoTag::from(tag)? {
         ConstantInfoTag::ConstantUtf8 => Utf8(ConstantUtf8Info { val: String::new() }),
         ConstantInfoTag::ConstantInteger => Integer(ConstantIntegerInfo { val: 0 }),
         ConstantInfoTag::ConstantFloat => Float(ConstantFloatInfo { val: 0.0 }),
@@ -289,14 +500,28 @@ pub fn new(tag: u8, cp: Rc<RefCell<ConstantPool>>) -> ConstantInfoEnum {
             reference_index: 0,
         }),
         ConstantInfoTag::ConstantMethodType => MethodType(ConstantMethodTypeInfo {
+            cp: cp.clone(),
             descriptor_index: 0,
         }),
         ConstantInfoTag::ConstantInvokeDynamic => InvokeDynamic(ConstantInvokeDynamicInfo {
+            cp: cp.clone(),
             bootstrap_method_attr_index: 0,
             name_and_type_index: 0,
         }),
     };
-    return constant_info;
+    return Some(constant_info);
+}
+
+fn is_utf8(info: &ConstantInfoEnum) -> bool {
+    return matches!(info, Utf8(_));
+}
+
+fn is_class(info: &ConstantInfoEnum) -> bool {
+    return matches!(info, Class(_));
+}
+
+fn is_name_and_type(info: &ConstantInfoEnum) -> bool {
+    return matches!(info, NameAndType(_));
 }
 
 pub struct ConstantIntegerInfo {
@@ -309,14 +534,19 @@ impl ConstantIntegerInfo {
         return self.val;
     }
 
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        self.val = reader.read_u32() as i32;
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.val = reader.read_u32()? as i32;
+        return Ok(());
     }
 }
 
 impl ConstantInfo for ConstantIntegerInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u32(self.val as u32);
     }
 }
 
@@ -330,15 +560,20 @@ impl ConstantFloatInfo {
         return self.val;
     }
 
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        let byte = reader.read_u32();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let byte = reader.read_u32()?;
         self.val = f32::from_bits(byte);
+        return Ok(());
     }
 }
 
 impl ConstantInfo for ConstantFloatInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u32(self.val.to_bits());
     }
 }
 
@@ -352,15 +587,20 @@ impl ConstantLongInfo {
         return self.val;
     }
 
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        let byte = reader.read_u64();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let byte = reader.read_u64()?;
         self.val = byte as i64;
+        return Ok(());
     }
 }
 
 impl ConstantInfo for ConstantLongInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u64(self.val as u64);
     }
 }
 
@@ -374,16 +614,21 @@ impl ConstantDoubleInfo {
         return self.val;
     }
 
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        let byte = reader.read_u64();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let byte = reader.read_u64()?;
 
         self.val = f64::from_bits(byte);
+        return Ok(());
     }
 }
 
 impl ConstantInfo for ConstantDoubleInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u64(self.val.to_bits());
     }
 }
 
@@ -394,18 +639,53 @@ pub struct ConstantUtf8Info {
 impl ConstantUtf8Info {
     /// here must be using from_utf8_unchecked function,
     /// because java/lang/CharacterData0E.class has unicode char
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        let len = reader.read_u16() as usize;
-        let bytes = reader.read_bytes(len);
+    /// encoded as surrogates; the bytes are checked to be modified UTF-8 first
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let len = reader.read_u16()? as usize;
+        let bytes = reader.read_bytes(len)?;
+        if !is_modified_utf8(&bytes) {
+            // the index is filled in by read_constant_info
+            return Err(ClassFormatError::InvalidUtf8 { index: 0 });
+        }
         unsafe {
             self.val = String::from_utf8_unchecked(bytes);
         }
+        return Ok(());
+    }
+}
+
+/// modified UTF-8 has no zero bytes and no four byte forms, a character
+/// outside the BMP is written as two three byte surrogates
+fn is_modified_utf8(bytes: &[u8]) -> bool {
+    let mut i = 0;
+    while i < bytes.len() {
+        let continuation = match bytes[i] {
+            0x01..=0x7F => 0,
+            0xC0..=0xDF => 1,
+            0xE0..=0xEF => 2,
+            _ => return false,
+        };
+        if i + continuation >= bytes.len() {
+            return false;
+        }
+        for byte in &bytes[i + 1..i + 1 + continuation] {
+            if byte & 0xC0 != 0x80 {
+                return false;
+            }
+        }
+        i += 1 + continuation;
     }
+    return true;
 }
 
 impl ConstantInfo for ConstantUtf8Info {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.val.len() as u16);
+        writer.write_bytes(self.val.as_bytes());
     }
 }
 
@@ -415,8 +695,9 @@ pub struct ConstantStringInfo {
 }
 
 impl ConstantStringInfo {
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        self.string_index = reader.read_u16();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.string_index = reader.read_u16()?;
+        return Ok(());
     }
 
     pub fn string(&self) -> String {
@@ -426,8 +707,12 @@ impl ConstantStringInfo {
 }
 
 impl ConstantInfo for ConstantStringInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.string_index);
     }
 }
 
@@ -437,8 +722,9 @@ pub struct ConstantClassInfo {
 }
 
 impl ConstantClassInfo {
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        self.name_index = reader.read_u16();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.name_index = reader.read_u16()?;
+        return Ok(());
     }
 
     pub fn name(&self) -> String {
@@ -448,8 +734,12 @@ impl ConstantClassInfo {
 }
 
 impl ConstantInfo for ConstantClassInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.name_index);
     }
 }
 
@@ -459,15 +749,31 @@ pub struct ConstantNameAndTypeInfo {
 }
 
 impl ConstantNameAndTypeInfo {
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        self.name_index = reader.read_u16();
-        self.desc_index = reader.read_u16();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.name_index = reader.read_u16()?;
+        self.desc_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    #[inline]
+    pub fn name_index(&self) -> u16 {
+        return self.name_index;
+    }
+
+    #[inline]
+    pub fn desc_index(&self) -> u16 {
+        return self.desc_index;
     }
 }
 
 impl ConstantInfo for ConstantNameAndTypeInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.name_index);
+        writer.write_u16(self.desc_index);
     }
 }
 
@@ -478,9 +784,10 @@ pub struct ConstantMemberRefInfo {
 }
 
 impl ConstantMemberRefInfo {
-    pub fn read_info(&mut self, reader: &mut ClassReader) {
-        self.class_index = reader.read_u16();
-        self.name_and_type_index = reader.read_u16();
+    pub fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.class_index = reader.read_u16()?;
+        self.name_and_type_index = reader.read_u16()?;
+        return Ok(());
     }
 
     pub fn class_name(&self) -> String {
@@ -496,8 +803,13 @@ impl ConstantMemberRefInfo {
 }
 
 impl ConstantInfo for ConstantMemberRefInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.class_index);
+        writer.write_u16(self.name_and_type_index);
     }
 }
 
@@ -511,8 +823,12 @@ impl ConstantFieldRefInfo {
 }
 
 impl ConstantInfo for ConstantFieldRefInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.0.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.0.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        self.0.write_info(writer);
     }
 }
 
@@ -526,8 +842,12 @@ impl ConstantMethodRefInfo {
 }
 
 impl ConstantInfo for ConstantMethodRefInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.0.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.0.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        self.0.write_info(writer);
     }
 }
 
@@ -541,20 +861,45 @@ impl ConstantInterfaceMethodRefInfo {
 }
 
 impl ConstantInfo for ConstantInterfaceMethodRefInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.0.read_info(reader);
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return self.0.read_info(reader);
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        self.0.write_info(writer);
     }
 }
 
 pub struct ConstantInvokeDynamicInfo {
+    cp: Rc<RefCell<ConstantPool>>,
     bootstrap_method_attr_index: u16,
     name_and_type_index: u16,
 }
 
+impl ConstantInvokeDynamicInfo {
+    /// index into the bootstrap_methods array of the BootstrapMethods attribute
+    #[inline]
+    pub fn bootstrap_method_attr_index(&self) -> u16 {
+        return self.bootstrap_method_attr_index;
+    }
+
+    pub fn name_and_descriptor(&self) -> (String, String) {
+        let borrow = (*self.cp).borrow();
+        let (f, s) = borrow.get_name_and_type(self.name_and_type_index as usize);
+        return (f.to_owned(), s.to_owned());
+    }
+}
+
 impl ConstantInfo for ConstantInvokeDynamicInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.bootstrap_method_attr_index = reader.read_u16();
-        self.name_and_type_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.bootstrap_method_attr_index = reader.read_u16()?;
+        self.name_and_type_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.bootstrap_method_attr_index);
+        writer.write_u16(self.name_and_type_index);
     }
 }
 
@@ -563,20 +908,52 @@ pub struct ConstantMethodHandleInfo {
     reference_index: u16,
 }
 
+impl ConstantMethodHandleInfo {
+    /// REF_getField(1) .. REF_invokeInterface(9)
+    #[inline]
+    pub fn reference_kind(&self) -> u8 {
+        return self.reference_kind;
+    }
+
+    #[inline]
+    pub fn reference_index(&self) -> u16 {
+        return self.reference_index;
+    }
+}
+
 impl ConstantInfo for ConstantMethodHandleInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.reference_kind = reader.read_u8();
-        self.reference_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.reference_kind = reader.read_u8()?;
+        self.reference_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u8(self.reference_kind);
+        writer.write_u16(self.reference_index);
     }
 }
 
 pub struct ConstantMethodTypeInfo {
+    cp: Rc<RefCell<ConstantPool>>,
     descriptor_index: u16,
 }
 
+impl ConstantMethodTypeInfo {
+    pub fn descriptor(&self) -> String {
+        let borrow = (*self.cp).borrow();
+        return borrow.get_utf8(self.descriptor_index as usize).to_owned();
+    }
+}
+
 impl ConstantInfo for ConstantMethodTypeInfo {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.descriptor_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.descriptor_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.descriptor_index);
     }
 }
 
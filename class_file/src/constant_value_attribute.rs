@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct ConstantValueAttribute {
     value_index: u16,
@@ -16,7 +18,12 @@ impl ConstantValueAttribute {
 }
 
 impl AttributeInfo for ConstantValueAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.value_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.value_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.value_index);
     }
 }
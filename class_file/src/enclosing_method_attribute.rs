@@ -1,30 +1,67 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
+use std::cell::RefCell;
 use std::rc::Rc;
 
+/// the class and method a local or anonymous class is declared in
 pub struct EnclosingMethodAttribute {
-    cp: Rc<ConstantPool>,
+    cp: Rc<RefCell<ConstantPool>>,
     class_index: u16,
+    /// 0 if the class isn't declared in a method or constructor
     method_index: u16,
 }
 
 impl EnclosingMethodAttribute {
-    pub fn class_name(&self) -> &str {
-        return self.cp.get_utf8(self.class_index as usize);
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> EnclosingMethodAttribute {
+        return EnclosingMethodAttribute {
+            cp,
+            class_index: 0,
+            method_index: 0,
+        };
     }
 
-    pub fn method_name_and_descriptor(&self) -> (&str, &str) {
-        if self.class_index > 0 {
-            return self.cp.get_name_and_type(self.method_index as usize);
+    #[inline]
+    pub fn class_index(&self) -> u16 {
+        return self.class_index;
+    }
+
+    #[inline]
+    pub fn method_index(&self) -> u16 {
+        return self.method_index;
+    }
+
+    pub fn class_name(&self) -> String {
+        let cp = (*self.cp).borrow();
+        return cp.get_class_name(self.class_index as usize).to_owned();
+    }
+
+    pub fn method_name_and_descriptor(&self) -> Option<(String, String)> {
+        if self.method_index == 0 {
+            return None;
         }
-        return ("", "");
+        let cp = (*self.cp).borrow();
+        let (name, descriptor) = cp.get_name_and_type(self.method_index as usize);
+        return Some((name.to_owned(), descriptor.to_owned()));
     }
 }
 
 impl AttributeInfo for EnclosingMethodAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.class_index = reader.read_u16();
-        self.method_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.class_index = reader.read_u16()?;
+        self.method_index = reader.read_u16()?;
+        let cp = (*self.cp).borrow();
+        cp.check_class(self.class_index)?;
+        if self.method_index != 0 {
+            cp.check_name_and_type(self.method_index)?;
+        }
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.class_index);
+        writer.write_u16(self.method_index);
     }
 }
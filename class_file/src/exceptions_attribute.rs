@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use std::ptr;
 
 pub struct ExceptionsAttribute {
@@ -30,7 +32,12 @@ impl ExceptionsAttribute {
 }
 
 impl AttributeInfo for ExceptionsAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.exception_index_table = reader.read_u16_table();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.exception_index_table = reader.read_u16_table()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16_table(&self.exception_index_table);
     }
 }
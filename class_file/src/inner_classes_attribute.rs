@@ -1,29 +1,78 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct InnerClassesAttribute {
     classes: Vec<InnerClassInfo>,
 }
 
-struct InnerClassInfo {
+#[derive(Debug, Clone)]
+pub struct InnerClassInfo {
     inner_class_info_index: u16,
+    /// 0 for local and anonymous classes
     outer_class_info_index: u16,
+    /// 0 for anonymous classes
     inner_name_index: u16,
     inner_class_access_flags: u16,
 }
 
+impl InnerClassesAttribute {
+    pub fn new() -> InnerClassesAttribute {
+        return InnerClassesAttribute { classes: vec![] };
+    }
+
+    #[inline]
+    pub fn classes(&self) -> &Vec<InnerClassInfo> {
+        return &self.classes;
+    }
+}
+
+impl InnerClassInfo {
+    #[inline]
+    pub fn inner_class_info_index(&self) -> u16 {
+        return self.inner_class_info_index;
+    }
+
+    #[inline]
+    pub fn outer_class_info_index(&self) -> u16 {
+        return self.outer_class_info_index;
+    }
+
+    #[inline]
+    pub fn inner_name_index(&self) -> u16 {
+        return self.inner_name_index;
+    }
+
+    #[inline]
+    pub fn inner_class_access_flags(&self) -> u16 {
+        return self.inner_class_access_flags;
+    }
+}
+
 impl AttributeInfo for InnerClassesAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let number_of_classes = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let number_of_classes = reader.read_u16()?;
         let mut classes = Vec::new();
         for _ in 0..number_of_classes {
             classes.push(InnerClassInfo {
-                inner_class_info_index: reader.read_u16(),
-                outer_class_info_index: reader.read_u16(),
-                inner_name_index: reader.read_u16(),
-                inner_class_access_flags: reader.read_u16(),
+                inner_class_info_index: reader.read_u16()?,
+                outer_class_info_index: reader.read_u16()?,
+                inner_name_index: reader.read_u16()?,
+                inner_class_access_flags: reader.read_u16()?,
             })
         }
         self.classes = classes;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.classes.len() as u16);
+        for class in &self.classes {
+            writer.write_u16(class.inner_class_info_index);
+            writer.write_u16(class.outer_class_info_index);
+            writer.write_u16(class.inner_name_index);
+            writer.write_u16(class.inner_class_access_flags);
+        }
     }
 }
@@ -1,20 +1,27 @@
+pub mod annotation_default_attribute;
 pub mod attribute_info;
-mod bootstrap_methods_attribute;
+pub mod bootstrap_methods_attribute;
 pub mod class_file;
+pub mod class_format_error;
 mod class_reader;
+pub mod class_writer;
 pub mod code_attribute;
 pub mod constant_pool;
 mod constant_value_attribute;
-mod enclosing_method_attribute;
+pub mod enclosing_method_attribute;
 pub mod exceptions_attribute;
-mod inner_classes_attribute;
+pub mod inner_classes_attribute;
 pub mod line_number_table_attribute;
 mod local_variable_table_attribute;
-mod local_variable_type_table_attribute;
+pub mod local_variable_type_table_attribute;
 mod makers_attribute;
 pub mod member_info;
+pub mod method_parameters_attribute;
+pub mod parameter_annotations_attribute;
 pub mod runtime_visible_annotations_attribute;
-mod signature_attribute;
+pub mod signature_attribute;
+pub mod source_debug_extension_attribute;
 mod source_file_attribute;
-mod stack_map_table_attribute;
-mod unparsed_attribute;
\ No newline at end of file
+pub mod stack_map_table_attribute;
+pub mod type_annotations_attribute;
+mod unparsed_attribute;
@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use std::ptr;
 
 #[derive(Debug)]
@@ -46,24 +48,31 @@ impl LineNumberTableAttribute {
 }
 
 impl AttributeInfo for LineNumberTableAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let line_number_table_len = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let line_number_table_len = reader.read_u16()?;
         let mut line_number_table = Vec::new();
         for _ in 0..line_number_table_len {
             line_number_table.push(LineNumberTableEntry {
-                start_pc: reader.read_u16(),
-                line_number: reader.read_u16(),
+                start_pc: reader.read_u16()?,
+                line_number: reader.read_u16()?,
             })
         }
         self.line_number_table = line_number_table;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.line_number_table.len() as u16);
+        for entry in &self.line_number_table {
+            writer.write_u16(entry.start_pc);
+            writer.write_u16(entry.line_number);
+        }
     }
 }
 
 #[cfg(test)]
 mod line {
-    use crate::line_number_table_attribute::{
-        LineNumberTableAttribute, LineNumberTableEntry,
-    };
+    use crate::line_number_table_attribute::{LineNumberTableAttribute, LineNumberTableEntry};
 
     #[test]
     fn test_unsafe_copy() {
@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct LocalVariableTableAttribute {
     local_variable_table: Vec<LocalVariableTableEntry>,
@@ -22,18 +24,30 @@ impl LocalVariableTableAttribute {
 }
 
 impl AttributeInfo for LocalVariableTableAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let local_variable_table_len = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let local_variable_table_len = reader.read_u16()?;
         let mut local_variable_table = Vec::new();
         for _ in 0..local_variable_table_len {
             local_variable_table.push(LocalVariableTableEntry {
-                start_pc: reader.read_u16(),
-                length: reader.read_u16(),
-                name_index: reader.read_u16(),
-                descriptor_index: reader.read_u16(),
-                index: reader.read_u16(),
+                start_pc: reader.read_u16()?,
+                length: reader.read_u16()?,
+                name_index: reader.read_u16()?,
+                descriptor_index: reader.read_u16()?,
+                index: reader.read_u16()?,
             })
         }
         self.local_variable_table = local_variable_table;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.local_variable_table.len() as u16);
+        for entry in &self.local_variable_table {
+            writer.write_u16(entry.start_pc);
+            writer.write_u16(entry.length);
+            writer.write_u16(entry.name_index);
+            writer.write_u16(entry.descriptor_index);
+            writer.write_u16(entry.index);
+        }
     }
 }
@@ -1,11 +1,14 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct LocalVariableTypeTableAttribute {
     local_variable_type_table: Vec<LocalVariableTypeTableEntry>,
 }
 
-struct LocalVariableTypeTableEntry {
+#[derive(Debug, Clone)]
+pub struct LocalVariableTypeTableEntry {
     start_pc: u16,
     length: u16,
     name_index: u16,
@@ -13,19 +16,71 @@ struct LocalVariableTypeTableEntry {
     index: u16,
 }
 
+impl LocalVariableTypeTableAttribute {
+    pub fn new() -> LocalVariableTypeTableAttribute {
+        return LocalVariableTypeTableAttribute {
+            local_variable_type_table: vec![],
+        };
+    }
+
+    #[inline]
+    pub fn local_variable_type_table(&self) -> &Vec<LocalVariableTypeTableEntry> {
+        return &self.local_variable_type_table;
+    }
+}
+
+impl LocalVariableTypeTableEntry {
+    #[inline]
+    pub fn start_pc(&self) -> u16 {
+        return self.start_pc;
+    }
+
+    #[inline]
+    pub fn length(&self) -> u16 {
+        return self.length;
+    }
+
+    #[inline]
+    pub fn name_index(&self) -> u16 {
+        return self.name_index;
+    }
+
+    #[inline]
+    pub fn signature_index(&self) -> u16 {
+        return self.signature_index;
+    }
+
+    #[inline]
+    pub fn index(&self) -> u16 {
+        return self.index;
+    }
+}
+
 impl AttributeInfo for LocalVariableTypeTableAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let local_variable_type_table_len = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let local_variable_type_table_len = reader.read_u16()?;
         let mut local_variable_type_table = Vec::new();
         for _ in 0..local_variable_type_table_len {
             local_variable_type_table.push(LocalVariableTypeTableEntry {
-                start_pc: reader.read_u16(),
-                length: reader.read_u16(),
-                name_index: reader.read_u16(),
-                signature_index: reader.read_u16(),
-                index: reader.read_u16(),
+                start_pc: reader.read_u16()?,
+                length: reader.read_u16()?,
+                name_index: reader.read_u16()?,
+                signature_index: reader.read_u16()?,
+                index: reader.read_u16()?,
             })
         }
         self.local_variable_type_table = local_variable_type_table;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.local_variable_type_table.len() as u16);
+        for entry in &self.local_variable_type_table {
+            writer.write_u16(entry.start_pc);
+            writer.write_u16(entry.length);
+            writer.write_u16(entry.name_index);
+            writer.write_u16(entry.signature_index);
+            writer.write_u16(entry.index);
+        }
     }
 }
@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct MakerAttribute {}
 
@@ -12,7 +14,11 @@ impl DeprecatedAttribute {
 }
 
 impl AttributeInfo for DeprecatedAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {}
+    fn read_info(&mut self, _reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return Ok(());
+    }
+
+    fn write_info(&self, _writer: &mut ClassWriter) {}
 }
 
 pub struct SyntheticAttribute(MakerAttribute);
@@ -24,7 +30,9 @@ impl SyntheticAttribute {
 }
 
 impl AttributeInfo for SyntheticAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        unimplemented!()
+    fn read_info(&mut self, _reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        return Ok(());
     }
+
+    fn write_info(&self, _writer: &mut ClassWriter) {}
 }
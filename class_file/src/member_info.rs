@@ -1,8 +1,8 @@
-use crate::attribute_info::Attribute::{
-    Code, ConstantValue, RuntimeVisibleAnnotations,
-};
-use crate::attribute_info::{read_attributes, Attribute, AttributeInfo};
+use crate::attribute_info::Attribute::{Code, ConstantValue};
+use crate::attribute_info::{read_attributes, write_attributes, Attribute};
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::code_attribute::CodeAttribute;
 use crate::constant_pool::ConstantPool;
 use crate::constant_value_attribute::ConstantValueAttribute;
@@ -18,28 +18,50 @@ pub struct MemberInfo {
 }
 
 impl MemberInfo {
-    pub fn read_member(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> MemberInfo {
+    pub fn read_member(
+        reader: &mut ClassReader,
+        cp: Rc<RefCell<ConstantPool>>,
+    ) -> Result<MemberInfo, ClassFormatError> {
         let mut mem = MemberInfo {
             cp: cp.clone(),
-            access_flags: reader.read_u16(),
-            name_index: reader.read_u16(),
-            descriptor_index: reader.read_u16(),
+            access_flags: reader.read_u16()?,
+            name_index: reader.read_u16()?,
+            descriptor_index: reader.read_u16()?,
             attributes: vec![],
         };
-        mem.attributes = read_attributes(reader, cp);
-        return mem;
+        {
+            let pool = (*cp).borrow();
+            pool.utf8(mem.name_index)?;
+            pool.utf8(mem.descriptor_index)?;
+        }
+        mem.attributes = read_attributes(reader, cp)?;
+        return Ok(mem);
     }
 
     pub fn read_members(
         reader: &mut ClassReader,
         cp: Rc<RefCell<ConstantPool>>,
-    ) -> Vec<MemberInfo> {
-        let member_count = reader.read_u16();
+    ) -> Result<Vec<MemberInfo>, ClassFormatError> {
+        let member_count = reader.read_u16()?;
         let mut members: Vec<MemberInfo> = Vec::new();
         for _i in 0..member_count {
-            members.push(MemberInfo::read_member(reader, cp.clone()));
+            members.push(MemberInfo::read_member(reader, cp.clone())?);
+        }
+        return Ok(members);
+    }
+
+    pub fn write_member(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.access_flags);
+        writer.write_u16(self.name_index);
+        writer.write_u16(self.descriptor_index);
+        write_attributes(writer, &self.attributes, &self.cp);
+    }
+
+    pub fn write_members(writer: &mut ClassWriter, members: &[MemberInfo]) {
+        writer.write_u16(members.len() as u16);
+        for member in members {
+            member.write_member(writer);
         }
-        return members;
     }
 
     #[inline]
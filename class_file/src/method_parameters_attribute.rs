@@ -0,0 +1,87 @@
+use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
+use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
+use crate::constant_pool::ConstantPool;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// the names and flags of the formal parameters, javac writes it
+/// with -parameters
+pub struct MethodParametersAttribute {
+    cp: Rc<RefCell<ConstantPool>>,
+    parameters: Vec<MethodParameter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodParameter {
+    /// 0 for a parameter without a name
+    name_index: u16,
+    access_flags: u16,
+}
+
+impl MethodParametersAttribute {
+    #[inline]
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> Self {
+        return MethodParametersAttribute {
+            cp,
+            parameters: vec![],
+        };
+    }
+
+    #[inline]
+    pub fn parameters(&self) -> &Vec<MethodParameter> {
+        return &self.parameters;
+    }
+
+    /// the name of the parameter at `index`, None if it has none
+    pub fn parameter_name(&self, index: usize) -> Option<String> {
+        let parameter = &self.parameters[index];
+        if parameter.name_index == 0 {
+            return None;
+        }
+        let cp = (*self.cp).borrow();
+        return Some(cp.get_utf8(parameter.name_index as usize).to_owned());
+    }
+}
+
+impl MethodParameter {
+    #[inline]
+    pub fn name_index(&self) -> u16 {
+        return self.name_index;
+    }
+
+    #[inline]
+    pub fn access_flags(&self) -> u16 {
+        return self.access_flags;
+    }
+}
+
+impl AttributeInfo for MethodParametersAttribute {
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let parameters_count = reader.read_u8()?;
+        let mut parameters = Vec::with_capacity(parameters_count as usize);
+        let pool = (*self.cp).borrow();
+        for _ in 0..parameters_count {
+            let name_index = reader.read_u16()?;
+            if name_index != 0 {
+                pool.utf8(name_index)?;
+            }
+            parameters.push(MethodParameter {
+                name_index,
+                access_flags: reader.read_u16()?,
+            });
+        }
+        drop(pool);
+        self.parameters = parameters;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u8(self.parameters.len() as u8);
+        for parameter in &self.parameters {
+            writer.write_u16(parameter.name_index);
+            writer.write_u16(parameter.access_flags);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
+use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
+use crate::constant_pool::ConstantPool;
+use crate::runtime_visible_annotations_attribute::AnnotationAttribute;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// RuntimeVisibleParameterAnnotations and RuntimeInvisibleParameterAnnotations,
+/// the annotations of each formal parameter of a method
+pub struct ParameterAnnotationsAttribute {
+    cp: Rc<RefCell<ConstantPool>>,
+    parameter_annotations: Vec<Vec<AnnotationAttribute>>,
+}
+
+impl ParameterAnnotationsAttribute {
+    #[inline]
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> Self {
+        return ParameterAnnotationsAttribute {
+            cp,
+            parameter_annotations: vec![],
+        };
+    }
+
+    /// indexed by parameter, javac may leave out synthetic parameters
+    #[inline]
+    pub fn parameter_annotations(&self) -> &Vec<Vec<AnnotationAttribute>> {
+        return &self.parameter_annotations;
+    }
+}
+
+impl AttributeInfo for ParameterAnnotationsAttribute {
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let num_parameters = reader.read_u8()? as usize;
+        let mut parameter_annotations = Vec::with_capacity(num_parameters);
+        let pool = (*self.cp).borrow();
+        for _ in 0..num_parameters {
+            parameter_annotations.push(AnnotationAttribute::read_table(reader, &pool)?);
+        }
+        drop(pool);
+        self.parameter_annotations = parameter_annotations;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u8(self.parameter_annotations.len() as u8);
+        for annotations in &self.parameter_annotations {
+            AnnotationAttribute::write_table(writer, annotations);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
-use crate::runtime_visible_annotations_attribute::ElementValue::*;
-use std::cell::{Ref, RefCell};
+use std::cell::RefCell;
 use std::rc::Rc;
 
+/// RuntimeVisibleAnnotations and RuntimeInvisibleAnnotations
 pub struct AnnotationsAttribute {
     cp: Rc<RefCell<ConstantPool>>,
     annotations: Vec<AnnotationAttribute>,
@@ -12,31 +14,34 @@ pub struct AnnotationsAttribute {
 
 #[derive(Debug, Clone)]
 pub struct AnnotationAttribute {
+    type_index: u16,
     type_name: String,
-    num_element_value_pairs: u16,
     element_value_pairs: Vec<ElementValuePair>,
 }
 
 #[derive(Debug, Clone)]
-struct ElementValuePair {
-    element_name: String,
+pub struct ElementValuePair {
+    element_name_index: u16,
     element_value: ElementValue,
 }
 
+/// the element_value union, constants are kept as constant pool indices
 #[derive(Debug, Clone)]
-enum ElementValue {
-    ArrayValue(Vec<ElementValue>),
-    EnumConstValue(String, String),
-    IntConstValue(i32),
-    StringConstValue(String),
-    BoolConstValue(bool),
-    ByteConstValue(i8),
-    ShortConstValue(i16),
-    LongConstValue(i64),
-    CharConstValue(char),
-    FloatConstValue(f32),
-    DoubleConstValue(f64),
-    ClassConstValue(String),
+pub enum ElementValue {
+    /// tag is one of B C D F I J S Z s
+    Const {
+        tag: u8,
+        const_value_index: u16,
+    },
+    Enum {
+        type_name_index: u16,
+        const_name_index: u16,
+    },
+    Class {
+        class_info_index: u16,
+    },
+    Annotation(Box<AnnotationAttribute>),
+    Array(Vec<ElementValue>),
 }
 
 impl AnnotationsAttribute {
@@ -55,148 +60,210 @@ impl AnnotationsAttribute {
 }
 
 impl AttributeInfo for AnnotationsAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let num_annotations = reader.read_u16() as usize;
-        let mut annotations = Vec::with_capacity(num_annotations);
-        for i in 0..num_annotations {
-            annotations.push(AnnotationAttribute::new(reader, self.cp.clone()))
-        }
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let pool = (*self.cp).borrow();
+        let annotations = AnnotationAttribute::read_table(reader, &pool)?;
+        drop(pool);
         self.annotations = annotations;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        AnnotationAttribute::write_table(writer, &self.annotations);
     }
 }
 
 impl AnnotationAttribute {
-    fn new(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> Self {
-        let type_index = reader.read_u16();
-        let name = (*cp).borrow().get_utf8(type_index as usize).to_string();
-        let num_element_value_pairs = reader.read_u16();
+    pub(crate) fn read(
+        reader: &mut ClassReader,
+        pool: &ConstantPool,
+    ) -> Result<Self, ClassFormatError> {
+        let type_index = reader.read_u16()?;
+        let type_name = pool.utf8(type_index)?.to_string();
+        let num_element_value_pairs = reader.read_u16()?;
         let mut element_value_pairs = Vec::with_capacity(num_element_value_pairs as usize);
-        for i in 0..num_element_value_pairs {
-            element_value_pairs.push(ElementValuePair::new(reader, cp.clone()));
+        for _ in 0..num_element_value_pairs {
+            let element_name_index = reader.read_u16()?;
+            pool.utf8(element_name_index)?;
+            element_value_pairs.push(ElementValuePair {
+                element_name_index,
+                element_value: ElementValue::read(reader, pool)?,
+            });
         }
-        //        println!("AnnotationAttribute:{},len:{}",name,num_element_value_pairs);
-        return AnnotationAttribute {
-            type_name: name,
-            num_element_value_pairs,
+        return Ok(AnnotationAttribute {
+            type_index,
+            type_name,
             element_value_pairs,
-        };
+        });
     }
 
+    /// a u16 count followed by the annotations
+    pub(crate) fn read_table(
+        reader: &mut ClassReader,
+        pool: &ConstantPool,
+    ) -> Result<Vec<Self>, ClassFormatError> {
+        let num_annotations = reader.read_u16()? as usize;
+        let mut annotations = Vec::with_capacity(num_annotations);
+        for _ in 0..num_annotations {
+            annotations.push(AnnotationAttribute::read(reader, pool)?)
+        }
+        return Ok(annotations);
+    }
+
+    pub(crate) fn write(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.type_index);
+        writer.write_u16(self.element_value_pairs.len() as u16);
+        for pair in &self.element_value_pairs {
+            writer.write_u16(pair.element_name_index);
+            pair.element_value.write(writer);
+        }
+    }
+
+    pub(crate) fn write_table(writer: &mut ClassWriter, annotations: &[AnnotationAttribute]) {
+        writer.write_u16(annotations.len() as u16);
+        for annotation in annotations {
+            annotation.write(writer);
+        }
+    }
+
+    /// the field descriptor of the annotation type, "Ljava/lang/Deprecated;"
     #[inline]
     pub fn name(&self) -> &str {
-        return &self.type_name.as_str();
+        return self.type_name.as_str();
+    }
+
+    #[inline]
+    pub fn type_index(&self) -> u16 {
+        return self.type_index;
+    }
+
+    #[inline]
+    pub fn element_value_pairs(&self) -> &Vec<ElementValuePair> {
+        return &self.element_value_pairs;
     }
 }
 
 impl ElementValuePair {
-    fn new(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> Self {
-        let element_name_index = reader.read_u16() as usize;
-        let tag = reader.read_char();
-        let pool = (*cp).borrow();
-        let element_name = pool.get_constant_info(element_name_index);
+    #[inline]
+    pub fn element_name_index(&self) -> u16 {
+        return self.element_name_index;
+    }
+
+    #[inline]
+    pub fn element_value(&self) -> &ElementValue {
+        return &self.element_value;
+    }
+}
+
+impl ElementValue {
+    pub(crate) fn read(
+        reader: &mut ClassReader,
+        pool: &ConstantPool,
+    ) -> Result<Self, ClassFormatError> {
+        let tag = reader.read_u8()?;
         let value = match tag {
-            '[' => ArrayValue(ElementValue::array_value(reader, cp.clone())),
-            's' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                StringConstValue(constant.string())
-            }
-            'c' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                ClassConstValue(constant.string())
-            }
-            'e' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                let const_name_index = reader.read_u16() as usize;
-                let constant_name = pool.get_constant_info(const_name_index);
-                EnumConstValue(constant.string(), constant_name.string())
-            }
-            'Z' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                BoolConstValue(integer_to_bool(constant.integer()))
+            b'B' | b'C' | b'I' | b'S' | b'Z' => {
+                let const_value_index = reader.read_u16()?;
+                pool.integer(const_value_index)?;
+                ElementValue::Const {
+                    tag,
+                    const_value_index,
+                }
             }
-            'B' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                ByteConstValue(constant.integer() as i8)
+            b'J' => {
+                let const_value_index = reader.read_u16()?;
+                pool.long(const_value_index)?;
+                ElementValue::Const {
+                    tag,
+                    const_value_index,
+                }
             }
-            'S' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                ShortConstValue(constant.integer() as i16)
+            b'F' => {
+                let const_value_index = reader.read_u16()?;
+                pool.float(const_value_index)?;
+                ElementValue::Const {
+                    tag,
+                    const_value_index,
+                }
             }
-            'I' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                IntConstValue(constant.integer())
+            b'D' => {
+                let const_value_index = reader.read_u16()?;
+                pool.double(const_value_index)?;
+                ElementValue::Const {
+                    tag,
+                    const_value_index,
+                }
             }
-            'J' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                LongConstValue(constant.long())
+            b's' => {
+                let const_value_index = reader.read_u16()?;
+                pool.utf8(const_value_index)?;
+                ElementValue::Const {
+                    tag,
+                    const_value_index,
+                }
             }
-            'F' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                FloatConstValue(constant.float())
+            b'e' => {
+                let type_name_index = reader.read_u16()?;
+                let const_name_index = reader.read_u16()?;
+                pool.utf8(type_name_index)?;
+                pool.utf8(const_name_index)?;
+                ElementValue::Enum {
+                    type_name_index,
+                    const_name_index,
+                }
             }
-            'D' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                DoubleConstValue(constant.double())
+            b'c' => {
+                // a return descriptor, "V" for void.class
+                let class_info_index = reader.read_u16()?;
+                pool.utf8(class_info_index)?;
+                ElementValue::Class { class_info_index }
             }
-            'C' => {
-                let const_value_index = reader.read_u16() as usize;
-                let constant = pool.get_constant_info(const_value_index);
-                CharConstValue(constant.integer() as u8 as char)
+            b'@' => ElementValue::Annotation(Box::new(AnnotationAttribute::read(reader, pool)?)),
+            b'[' => {
+                let num_values = reader.read_u16()? as usize;
+                let mut values = Vec::with_capacity(num_values);
+                for _ in 0..num_values {
+                    values.push(ElementValue::read(reader, pool)?);
+                }
+                ElementValue::Array(values)
             }
-            _ => panic!("The invalid annotation value tag!"),
-        };
-        return ElementValuePair {
-            element_name: element_name.string(),
-            element_value: value,
+            _ => return Err(ClassFormatError::BadElementValueTag(tag)),
         };
+        return Ok(value);
     }
-}
 
-impl ElementValue {
-    pub fn array_value(reader: &mut ClassReader, cp: Rc<RefCell<ConstantPool>>) -> Vec<Self> {
-        let num_values = reader.read_u16() as usize;
-        let mut values = Vec::with_capacity(num_values);
-        let pool = (*cp).borrow();
-        for _ in 0..num_values {
-            let tag = reader.read_char();
-            let const_value_index = reader.read_u16() as usize;
-            let constant = pool.get_constant_info(const_value_index);
-            let value = match tag {
-                '[' => panic!("The invlid annotation value tag '[' !"),
-                's' => StringConstValue(constant.string()),
-                'c' => ClassConstValue(constant.string()),
-                'e' => {
-                    let const_name_index = reader.read_u16() as usize;
-                    let constant_name = pool.get_constant_info(const_name_index);
-                    EnumConstValue(constant.string(), constant_name.string())
+    pub(crate) fn write(&self, writer: &mut ClassWriter) {
+        match self {
+            ElementValue::Const {
+                tag,
+                const_value_index,
+            } => {
+                writer.write_u8(*tag);
+                writer.write_u16(*const_value_index);
+            }
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+            } => {
+                writer.write_u8(b'e');
+                writer.write_u16(*type_name_index);
+                writer.write_u16(*const_name_index);
+            }
+            ElementValue::Class { class_info_index } => {
+                writer.write_u8(b'c');
+                writer.write_u16(*class_info_index);
+            }
+            ElementValue::Annotation(annotation) => {
+                writer.write_u8(b'@');
+                annotation.write(writer);
+            }
+            ElementValue::Array(values) => {
+                writer.write_u8(b'[');
+                writer.write_u16(values.len() as u16);
+                for value in values {
+                    value.write(writer);
                 }
-                'Z' => BoolConstValue(integer_to_bool(constant.integer())),
-                'B' => ByteConstValue(constant.integer() as i8),
-                'S' => ShortConstValue(constant.integer() as i16),
-                'I' => IntConstValue(constant.integer()),
-                'J' => LongConstValue(constant.long()),
-                'F' => FloatConstValue(constant.float()),
-                'D' => DoubleConstValue(constant.double()),
-                'C' => CharConstValue(constant.integer() as u8 as char),
-                _ => panic!("The invlid annotation value tag!"),
-            };
-            values.push(value);
+            }
         }
-        return values;
     }
 }
-
-#[inline]
-fn integer_to_bool(int: i32) -> bool {
-    return int == 1;
-}
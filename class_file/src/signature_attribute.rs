@@ -1,21 +1,43 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
+use std::cell::RefCell;
 use std::rc::Rc;
 
 pub struct SignatureAttribute {
-    cp: Rc<ConstantPool>,
+    cp: Rc<RefCell<ConstantPool>>,
     signature_index: u16,
 }
 
 impl SignatureAttribute {
-    pub fn signature(&self) -> &str {
-        return self.cp.get_utf8(self.signature_index as usize);
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> SignatureAttribute {
+        return SignatureAttribute {
+            cp,
+            signature_index: 0,
+        };
+    }
+
+    #[inline]
+    pub fn signature_index(&self) -> u16 {
+        return self.signature_index;
+    }
+
+    pub fn signature(&self) -> String {
+        let cp = (*self.cp).borrow();
+        return cp.get_utf8(self.signature_index as usize).to_owned();
     }
 }
 
 impl AttributeInfo for SignatureAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.signature_index = reader.read_u16()
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.signature_index = reader.read_u16()?;
+        (*self.cp).borrow().utf8(self.signature_index)?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.signature_index);
     }
 }
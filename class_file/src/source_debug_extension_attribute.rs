@@ -0,0 +1,36 @@
+use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
+use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
+
+/// extended debugging information in modified UTF-8, like the SMAP of a JSP,
+/// the VM doesn't interpret it
+pub struct SourceDebugExtensionAttribute {
+    len: u32,
+    debug_extension: Vec<u8>,
+}
+
+impl SourceDebugExtensionAttribute {
+    pub fn new(length: u32) -> SourceDebugExtensionAttribute {
+        return SourceDebugExtensionAttribute {
+            len: length,
+            debug_extension: vec![],
+        };
+    }
+
+    #[inline]
+    pub fn debug_extension(&self) -> &Vec<u8> {
+        return &self.debug_extension;
+    }
+}
+
+impl AttributeInfo for SourceDebugExtensionAttribute {
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.debug_extension = reader.read_bytes(self.len as usize)?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_bytes(&self.debug_extension);
+    }
+}
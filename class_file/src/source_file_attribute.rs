@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 use crate::constant_pool::ConstantPool;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -31,7 +33,12 @@ impl SourceFileAttribute {
 }
 
 impl AttributeInfo for SourceFileAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.source_file_index = reader.read_u16();
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.source_file_index = reader.read_u16()?;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.source_file_index);
     }
 }
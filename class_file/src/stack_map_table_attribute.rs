@@ -1,33 +1,267 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
+/*
+StackMapTable_attribute {
+    u2              attribute_name_index;
+    u4              attribute_length;
+    u2              number_of_entries;
+    stack_map_frame entries[number_of_entries];
+}
+*/
+#[derive(Debug, Clone)]
 pub struct StackMapAttribute {
-    len: u32,
-    entries: Vec<FrameType>,
+    entries: Vec<StackMapFrame>,
 }
 
 impl StackMapAttribute {
-    pub fn new(len: u32) -> StackMapAttribute {
+    pub fn new() -> StackMapAttribute {
         return StackMapAttribute {
-            len: len,
             entries: Vec::new(),
         };
     }
+
+    #[inline]
+    pub fn entries(&self) -> &Vec<StackMapFrame> {
+        return &self.entries;
+    }
 }
 
 impl AttributeInfo for StackMapAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        let number_of_entries = reader.read_u16();
-        let mut entries = Vec::new();
-        let unit_count = ((self.len - 2) / (number_of_entries as u32)) as usize;
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let number_of_entries = reader.read_u16()?;
+        let mut entries = Vec::with_capacity(number_of_entries as usize);
         for _i in 0..number_of_entries {
-            entries.push(FrameType {
-                frame_type: reader.read_bytes(unit_count),
-            });
+            entries.push(StackMapFrame::read(reader)?);
+        }
+        self.entries = entries;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.entries.len() as u16);
+        for frame in &self.entries {
+            frame.write(writer);
         }
     }
 }
 
-struct FrameType {
-    frame_type: Vec<u8>,
+/// one entry of the table, the locals of an `Append` frame and the
+/// items of `locals`/`stack` count long and double once, as in the class file.
+/// `extended` tells the two forms of a frame apart, a compiler may use the
+/// extended one for a small offset_delta as well
+#[derive(Debug, Clone)]
+pub enum StackMapFrame {
+    /// same_frame (0-63) and same_frame_extended (251)
+    Same { offset_delta: u16, extended: bool },
+    /// same_locals_1_stack_item_frame (64-127) and its extended form (247)
+    SameLocals1StackItem {
+        offset_delta: u16,
+        stack: VerificationTypeInfo,
+        extended: bool,
+    },
+    /// chop_frame (248-250), removes the last `k` locals
+    Chop { offset_delta: u16, k: u8 },
+    /// append_frame (252-254)
+    Append {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+    },
+    /// full_frame (255)
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+impl StackMapFrame {
+    fn read(reader: &mut ClassReader) -> Result<StackMapFrame, ClassFormatError> {
+        let frame_type = reader.read_u8()?;
+        let frame = match frame_type {
+            0..=63 => StackMapFrame::Same {
+                offset_delta: frame_type as u16,
+                extended: false,
+            },
+            64..=127 => StackMapFrame::SameLocals1StackItem {
+                offset_delta: (frame_type - 64) as u16,
+                stack: VerificationTypeInfo::read(reader)?,
+                extended: false,
+            },
+            247 => StackMapFrame::SameLocals1StackItem {
+                offset_delta: reader.read_u16()?,
+                stack: VerificationTypeInfo::read(reader)?,
+                extended: true,
+            },
+            248..=250 => StackMapFrame::Chop {
+                offset_delta: reader.read_u16()?,
+                k: 251 - frame_type,
+            },
+            251 => StackMapFrame::Same {
+                offset_delta: reader.read_u16()?,
+                extended: true,
+            },
+            252..=254 => {
+                let offset_delta = reader.read_u16()?;
+                let count = frame_type - 251;
+                let mut locals = Vec::with_capacity(count as usize);
+                for _i in 0..count {
+                    locals.push(VerificationTypeInfo::read(reader)?);
+                }
+                StackMapFrame::Append {
+                    offset_delta,
+                    locals,
+                }
+            }
+            255 => {
+                let offset_delta = reader.read_u16()?;
+                let locals = VerificationTypeInfo::read_table(reader)?;
+                let stack = VerificationTypeInfo::read_table(reader)?;
+                StackMapFrame::Full {
+                    offset_delta,
+                    locals,
+                    stack,
+                }
+            }
+            // 128-246 are reserved for future use
+            _ => return Err(ClassFormatError::BadStackMapFrameType(frame_type)),
+        };
+        return Ok(frame);
+    }
+
+    fn write(&self, writer: &mut ClassWriter) {
+        match self {
+            StackMapFrame::Same {
+                offset_delta,
+                extended,
+            } => {
+                if *extended || *offset_delta > 63 {
+                    writer.write_u8(251);
+                    writer.write_u16(*offset_delta);
+                } else {
+                    writer.write_u8(*offset_delta as u8);
+                }
+            }
+            StackMapFrame::SameLocals1StackItem {
+                offset_delta,
+                stack,
+                extended,
+            } => {
+                if *extended || *offset_delta > 63 {
+                    writer.write_u8(247);
+                    writer.write_u16(*offset_delta);
+                } else {
+                    writer.write_u8(64 + *offset_delta as u8);
+                }
+                stack.write(writer);
+            }
+            StackMapFrame::Chop { offset_delta, k } => {
+                writer.write_u8(251 - k);
+                writer.write_u16(*offset_delta);
+            }
+            StackMapFrame::Append {
+                offset_delta,
+                locals,
+            } => {
+                writer.write_u8(251 + locals.len() as u8);
+                writer.write_u16(*offset_delta);
+                for local in locals {
+                    local.write(writer);
+                }
+            }
+            StackMapFrame::Full {
+                offset_delta,
+                locals,
+                stack,
+            } => {
+                writer.write_u8(255);
+                writer.write_u16(*offset_delta);
+                VerificationTypeInfo::write_table(writer, locals);
+                VerificationTypeInfo::write_table(writer, stack);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn offset_delta(&self) -> u16 {
+        return match self {
+            StackMapFrame::Same { offset_delta, .. } => *offset_delta,
+            StackMapFrame::SameLocals1StackItem { offset_delta, .. } => *offset_delta,
+            StackMapFrame::Chop { offset_delta, .. } => *offset_delta,
+            StackMapFrame::Append { offset_delta, .. } => *offset_delta,
+            StackMapFrame::Full { offset_delta, .. } => *offset_delta,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    /// the constant pool index of a CONSTANT_Class_info
+    Object(u16),
+    /// the offset of the `new` instruction that created the object
+    Uninitialized(u16),
+}
+
+impl VerificationTypeInfo {
+    fn read(reader: &mut ClassReader) -> Result<VerificationTypeInfo, ClassFormatError> {
+        let tag = reader.read_u8()?;
+        let info = match tag {
+            0 => VerificationTypeInfo::Top,
+            1 => VerificationTypeInfo::Integer,
+            2 => VerificationTypeInfo::Float,
+            3 => VerificationTypeInfo::Double,
+            4 => VerificationTypeInfo::Long,
+            5 => VerificationTypeInfo::Null,
+            6 => VerificationTypeInfo::UninitializedThis,
+            7 => VerificationTypeInfo::Object(reader.read_u16()?),
+            8 => VerificationTypeInfo::Uninitialized(reader.read_u16()?),
+            _ => return Err(ClassFormatError::BadVerificationType(tag)),
+        };
+        return Ok(info);
+    }
+
+    fn read_table(reader: &mut ClassReader) -> Result<Vec<VerificationTypeInfo>, ClassFormatError> {
+        let count = reader.read_u16()?;
+        let mut infos = Vec::with_capacity(count as usize);
+        for _i in 0..count {
+            infos.push(VerificationTypeInfo::read(reader)?);
+        }
+        return Ok(infos);
+    }
+
+    fn write(&self, writer: &mut ClassWriter) {
+        match self {
+            VerificationTypeInfo::Top => writer.write_u8(0),
+            VerificationTypeInfo::Integer => writer.write_u8(1),
+            VerificationTypeInfo::Float => writer.write_u8(2),
+            VerificationTypeInfo::Double => writer.write_u8(3),
+            VerificationTypeInfo::Long => writer.write_u8(4),
+            VerificationTypeInfo::Null => writer.write_u8(5),
+            VerificationTypeInfo::UninitializedThis => writer.write_u8(6),
+            VerificationTypeInfo::Object(index) => {
+                writer.write_u8(7);
+                writer.write_u16(*index);
+            }
+            VerificationTypeInfo::Uninitialized(offset) => {
+                writer.write_u8(8);
+                writer.write_u16(*offset);
+            }
+        }
+    }
+
+    fn write_table(writer: &mut ClassWriter, infos: &[VerificationTypeInfo]) {
+        writer.write_u16(infos.len() as u16);
+        for info in infos {
+            info.write(writer);
+        }
+    }
 }
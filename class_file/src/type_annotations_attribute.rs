@@ -0,0 +1,245 @@
+use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
+use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
+use crate::constant_pool::ConstantPool;
+use crate::runtime_visible_annotations_attribute::AnnotationAttribute;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// RuntimeVisibleTypeAnnotations and RuntimeInvisibleTypeAnnotations, found
+/// on classes, members and Code attributes
+pub struct TypeAnnotationsAttribute {
+    cp: Rc<RefCell<ConstantPool>>,
+    annotations: Vec<TypeAnnotation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeAnnotation {
+    target_type: u8,
+    target_info: TargetInfo,
+    target_path: Vec<TypePathEntry>,
+    annotation: AnnotationAttribute,
+}
+
+/// the target_info union, which one follows from the target_type
+#[derive(Debug, Clone)]
+pub enum TargetInfo {
+    TypeParameter {
+        type_parameter_index: u8,
+    },
+    SuperType {
+        supertype_index: u16,
+    },
+    TypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    Empty,
+    FormalParameter {
+        formal_parameter_index: u8,
+    },
+    Throws {
+        throws_type_index: u16,
+    },
+    LocalVar(Vec<LocalVarTarget>),
+    Catch {
+        exception_table_index: u16,
+    },
+    Offset {
+        offset: u16,
+    },
+    TypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalVarTarget {
+    pub start_pc: u16,
+    pub length: u16,
+    pub index: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypePathEntry {
+    pub type_path_kind: u8,
+    pub type_argument_index: u8,
+}
+
+impl TypeAnnotationsAttribute {
+    #[inline]
+    pub fn with_cp(cp: Rc<RefCell<ConstantPool>>) -> Self {
+        return TypeAnnotationsAttribute {
+            cp,
+            annotations: vec![],
+        };
+    }
+
+    #[inline]
+    pub fn annotations(&self) -> &Vec<TypeAnnotation> {
+        return &self.annotations;
+    }
+}
+
+impl AttributeInfo for TypeAnnotationsAttribute {
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        let num_annotations = reader.read_u16()? as usize;
+        let mut annotations = Vec::with_capacity(num_annotations);
+        let pool = (*self.cp).borrow();
+        for _ in 0..num_annotations {
+            annotations.push(TypeAnnotation::read(reader, &pool)?);
+        }
+        drop(pool);
+        self.annotations = annotations;
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_u16(self.annotations.len() as u16);
+        for annotation in &self.annotations {
+            annotation.write(writer);
+        }
+    }
+}
+
+impl TypeAnnotation {
+    fn read(reader: &mut ClassReader, pool: &ConstantPool) -> Result<Self, ClassFormatError> {
+        let target_type = reader.read_u8()?;
+        let target_info = TargetInfo::read(target_type, reader)?;
+        let path_length = reader.read_u8()?;
+        let mut target_path = Vec::with_capacity(path_length as usize);
+        for _ in 0..path_length {
+            target_path.push(TypePathEntry {
+                type_path_kind: reader.read_u8()?,
+                type_argument_index: reader.read_u8()?,
+            });
+        }
+        return Ok(TypeAnnotation {
+            target_type,
+            target_info,
+            target_path,
+            annotation: AnnotationAttribute::read(reader, pool)?,
+        });
+    }
+
+    fn write(&self, writer: &mut ClassWriter) {
+        writer.write_u8(self.target_type);
+        self.target_info.write(writer);
+        writer.write_u8(self.target_path.len() as u8);
+        for entry in &self.target_path {
+            writer.write_u8(entry.type_path_kind);
+            writer.write_u8(entry.type_argument_index);
+        }
+        self.annotation.write(writer);
+    }
+
+    #[inline]
+    pub fn target_type(&self) -> u8 {
+        return self.target_type;
+    }
+
+    #[inline]
+    pub fn target_info(&self) -> &TargetInfo {
+        return &self.target_info;
+    }
+
+    #[inline]
+    pub fn target_path(&self) -> &Vec<TypePathEntry> {
+        return &self.target_path;
+    }
+
+    #[inline]
+    pub fn annotation(&self) -> &AnnotationAttribute {
+        return &self.annotation;
+    }
+}
+
+impl TargetInfo {
+    fn read(target_type: u8, reader: &mut ClassReader) -> Result<Self, ClassFormatError> {
+        let info = match target_type {
+            0x00 | 0x01 => TargetInfo::TypeParameter {
+                type_parameter_index: reader.read_u8()?,
+            },
+            0x10 => TargetInfo::SuperType {
+                supertype_index: reader.read_u16()?,
+            },
+            0x11 | 0x12 => TargetInfo::TypeParameterBound {
+                type_parameter_index: reader.read_u8()?,
+                bound_index: reader.read_u8()?,
+            },
+            0x13..=0x15 => TargetInfo::Empty,
+            0x16 => TargetInfo::FormalParameter {
+                formal_parameter_index: reader.read_u8()?,
+            },
+            0x17 => TargetInfo::Throws {
+                throws_type_index: reader.read_u16()?,
+            },
+            0x40 | 0x41 => {
+                let table_length = reader.read_u16()?;
+                let mut table = Vec::with_capacity(table_length as usize);
+                for _ in 0..table_length {
+                    table.push(LocalVarTarget {
+                        start_pc: reader.read_u16()?,
+                        length: reader.read_u16()?,
+                        index: reader.read_u16()?,
+                    });
+                }
+                TargetInfo::LocalVar(table)
+            }
+            0x42 => TargetInfo::Catch {
+                exception_table_index: reader.read_u16()?,
+            },
+            0x43..=0x46 => TargetInfo::Offset {
+                offset: reader.read_u16()?,
+            },
+            0x47..=0x4B => TargetInfo::TypeArgument {
+                offset: reader.read_u16()?,
+                type_argument_index: reader.read_u8()?,
+            },
+            _ => return Err(ClassFormatError::BadTargetType(target_type)),
+        };
+        return Ok(info);
+    }
+
+    fn write(&self, writer: &mut ClassWriter) {
+        match self {
+            TargetInfo::TypeParameter {
+                type_parameter_index,
+            } => writer.write_u8(*type_parameter_index),
+            TargetInfo::SuperType { supertype_index } => writer.write_u16(*supertype_index),
+            TargetInfo::TypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => {
+                writer.write_u8(*type_parameter_index);
+                writer.write_u8(*bound_index);
+            }
+            TargetInfo::Empty => {}
+            TargetInfo::FormalParameter {
+                formal_parameter_index,
+            } => writer.write_u8(*formal_parameter_index),
+            TargetInfo::Throws { throws_type_index } => writer.write_u16(*throws_type_index),
+            TargetInfo::LocalVar(table) => {
+                writer.write_u16(table.len() as u16);
+                for entry in table {
+                    writer.write_u16(entry.start_pc);
+                    writer.write_u16(entry.length);
+                    writer.write_u16(entry.index);
+                }
+            }
+            TargetInfo::Catch {
+                exception_table_index,
+            } => writer.write_u16(*exception_table_index),
+            TargetInfo::Offset { offset } => writer.write_u16(*offset),
+            TargetInfo::TypeArgument {
+                offset,
+                type_argument_index,
+            } => {
+                writer.write_u16(*offset);
+                writer.write_u8(*type_argument_index);
+            }
+        }
+    }
+}
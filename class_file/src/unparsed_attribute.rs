@@ -1,5 +1,7 @@
 use crate::attribute_info::AttributeInfo;
+use crate::class_format_error::ClassFormatError;
 use crate::class_reader::ClassReader;
+use crate::class_writer::ClassWriter;
 
 pub struct UnparsedAttribute {
     name: String,
@@ -8,17 +10,32 @@ pub struct UnparsedAttribute {
 }
 
 impl UnparsedAttribute {
-    pub fn new(length: u32) -> UnparsedAttribute {
+    pub fn new(name: &str, length: u32) -> UnparsedAttribute {
         return UnparsedAttribute {
-            name: "".to_string(),
+            name: name.to_string(),
             len: length,
             info: None,
         };
     }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        return self.name.as_str();
+    }
+
+    #[inline]
+    pub fn info(&self) -> &[u8] {
+        return self.info.as_deref().unwrap_or(&[]);
+    }
 }
 
 impl AttributeInfo for UnparsedAttribute {
-    fn read_info(&mut self, reader: &mut ClassReader) {
-        self.info = Some(reader.read_bytes(self.len as usize));
+    fn read_info(&mut self, reader: &mut ClassReader) -> Result<(), ClassFormatError> {
+        self.info = Some(reader.read_bytes(self.len as usize)?);
+        return Ok(());
+    }
+
+    fn write_info(&self, writer: &mut ClassWriter) {
+        writer.write_bytes(self.info());
     }
 }
@@ -1,17 +1,22 @@
 use lark_classfile::class_file::ClassFile;
+use lark_classfile::class_format_error::ClassFormatError;
+use crate::gc::heap::Heap;
 use crate::instrument::java_lang_instrument::JavaLangInstrument;
 use crate::invoke_support::parameter::{Parameter, Parameters};
-use crate::invoke_support::{invoke, ReturnType};
+use crate::invoke_support::{invoke, set_pending_exception, ReturnType};
 use crate::jvm::Jvm;
 use crate::oops::class::Class;
 use crate::oops::object::Object;
 use crate::oops::string_pool::StringPool;
+use crate::runtime::thread::JavaThread;
 use crate::utils::{boxed, java_str_to_rust_str};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::{Debug, Error, Formatter};
 use std::rc::Rc;
 use crate::class_loader::class_init_preparation::ClassPreparation;
+use crate::class_loader::class_linker::ClassLinker;
+use crate::class_loader::class_verifier::ClassVerifier;
 
 pub struct ClassLoader {
     pub(in crate::class_loader) verbose_class: bool,
@@ -49,12 +54,20 @@ impl ClassLoader {
         return &self.class_map;
     }
 
-    fn link(class: &Rc<RefCell<Class>>) {
-        ClassLoader::verify(class);
+    /// returns false with a VerifyError pending
+    fn link(class: &Rc<RefCell<Class>>, java_loader: Option<Rc<RefCell<Object>>>) -> bool {
+        ClassLinker::link_methods(class);
+        if !ClassLoader::verify(class, java_loader) {
+            return false;
+        }
         ClassPreparation::prepare(class);
+        return true;
     }
 
-    fn verify(class: &Rc<RefCell<Class>>) {}
+    #[inline]
+    fn verify(class: &Rc<RefCell<Class>>, java_loader: Option<Rc<RefCell<Object>>>) -> bool {
+        return ClassVerifier::verify(class, java_loader, false);
+    }
 
     pub fn define_class_internal(
         class_name: &str,
@@ -63,7 +76,7 @@ impl ClassLoader {
         length: usize,
         class_loader: Rc<RefCell<Object>>,
         protection_domain: Option<Rc<RefCell<Object>>>,
-    ) -> Rc<RefCell<Class>> {
+    ) -> Option<Rc<RefCell<Class>>> {
         let java_name = StringPool::java_string(class_name.to_string());
         let method = JavaLangInstrument::instance().get_transform_method();
         let instrument = JavaLangInstrument::instance().get_instrument();
@@ -86,27 +99,83 @@ impl ClassLoader {
             byte_array = rs;
         }
         let data = Self::extract_data(byte_array.unwrap(), offset, length);
-        return Self::define_class(class_loader, data);
+        return Self::define_class(class_loader, data, class_name);
     }
 
-    fn define_class(java_loader: Rc<RefCell<Object>>, data: Vec<u8>) -> Rc<RefCell<Class>> {
+    /// returns None with a ClassFormatError pending if the data isn't a class
+    /// file, or a VerifyError if the class doesn't verify
+    fn define_class(
+        java_loader: Rc<RefCell<Object>>,
+        data: Vec<u8>,
+        class_name: &str,
+    ) -> Option<Rc<RefCell<Class>>> {
         let loader = (*java_loader).borrow().get_class_loader();
-        let class = Self::parse_class(data);
+        let class = Self::parse_class(data, class_name)?;
         (*class).borrow_mut().set_class_loader(loader.clone());
-        Self::resolve_super_class(java_loader.clone(), class.clone());
-        Self::resolve_interfaces(java_loader.clone(), class.clone());
-        Self::link(&class);
+        if !Self::resolve_super_class(Some(java_loader.clone()), class.clone())
+            || !Self::resolve_interfaces(Some(java_loader.clone()), class.clone())
+        {
+            return None;
+        }
+        // registered before linking, the verifier may load classes that
+        // refer back to it
+        let name = (*class).borrow().name().to_string();
         (*loader)
             .borrow_mut()
             .class_map
-            .insert((*class).borrow().name().to_string(), class.clone());
+            .insert(name.clone(), class.clone());
+        if !Self::link(&class, Some(java_loader.clone())) {
+            (*loader).borrow_mut().class_map.remove(&name);
+            return None;
+        }
         Self::setting_class_object(Some(java_loader),class.clone());
-        return class;
+        return Some(class);
     }
 
-    pub(in crate::class_loader) fn parse_class(data: Vec<u8>) -> Rc<RefCell<Class>> {
-        let class_file = ClassFile::parse(data);
-        return Class::new(class_file);
+    /// defines a class for Unsafe.defineAnonymousClass: it belongs to the
+    /// loader of the host class and has the access of the host class, but
+    /// isn't registered, so it can't be found by name
+    pub fn define_anonymous_class(
+        host: &Rc<RefCell<Class>>,
+        data: Vec<u8>,
+    ) -> Option<Rc<RefCell<Class>>> {
+        let (loader, java_loader, host_name) = {
+            let host = (**host).borrow();
+            (host.loader(), host.get_class_loader(), host.name().to_string())
+        };
+        let class = Self::parse_class(data, host_name.as_str())?;
+        (*class).borrow_mut().set_class_loader(loader);
+        (*class).borrow_mut().set_host_class(host.clone());
+        if !Self::resolve_super_class(java_loader.clone(), class.clone())
+            || !Self::resolve_interfaces(java_loader.clone(), class.clone())
+            || !Self::link(&class, java_loader.clone())
+        {
+            return None;
+        }
+        Self::setting_class_object(java_loader, class.clone());
+        return Some(class);
+    }
+
+    /// returns None with a java.lang.ClassFormatError pending, or an
+    /// UnsupportedClassVersionError if the class file is too new
+    pub(in crate::class_loader) fn parse_class(
+        data: Vec<u8>,
+        class_name: &str,
+    ) -> Option<Rc<RefCell<Class>>> {
+        return match ClassFile::parse(data) {
+            Ok(class_file) => Some(Class::new(class_file)),
+            Err(error) => {
+                let exception = match error {
+                    ClassFormatError::UnsupportedVersion { .. } => {
+                        "java/lang/UnsupportedClassVersionError"
+                    }
+                    _ => "java/lang/ClassFormatError",
+                };
+                let msg = format!("{} in class file {}", error, class_name.replace('.', "/"));
+                set_pending_exception(exception, Some(msg.as_str()));
+                None
+            }
+        };
     }
 
     fn extract_data(byte_array: Rc<RefCell<Object>>, offset: usize, length: usize) -> Vec<u8> {
@@ -120,58 +189,92 @@ impl ClassLoader {
         return bytes;
     }
 
-    fn resolve_super_class(java_loader: Rc<RefCell<Object>>, class: Rc<RefCell<Class>>) {
+    /// false with a NoClassDefFoundError pending if the super class is missing
+    fn resolve_super_class(
+        java_loader: Option<Rc<RefCell<Object>>>,
+        class: Rc<RefCell<Class>>,
+    ) -> bool {
         let mut class = (*class).borrow_mut();
         let super_class_name = class.super_class_name();
         if class.name() != "java/lang/Object" && super_class_name.is_some() {
-            let super_class = Self::load_class(
-                Some(java_loader.clone()),
-                super_class_name.unwrap().as_str(),
-            );
-            class.set_super_class(super_class);
+            let super_class =
+                Self::load_class(java_loader.clone(), super_class_name.unwrap().as_str());
+            match super_class {
+                Some(super_class) => class.set_super_class(super_class),
+                None => return false,
+            }
         }
+        return true;
     }
-    fn resolve_interfaces(java_loader: Rc<RefCell<Object>>, class: Rc<RefCell<Class>>) {
+
+    /// false with a NoClassDefFoundError pending if an interface is missing
+    fn resolve_interfaces(
+        java_loader: Option<Rc<RefCell<Object>>>,
+        class: Rc<RefCell<Class>>,
+    ) -> bool {
         let mut class = (*class).borrow_mut();
         let interfaces_name = class.interfaces_name();
         let len = interfaces_name.len();
         if len > 0 {
             let mut interfaces = Vec::with_capacity(len);
             for name in interfaces_name {
-                let interface = Self::load_class(Some(java_loader.clone()), name);
-                interfaces.push(interface);
+                match Self::load_class(java_loader.clone(), name) {
+                    Some(interface) => interfaces.push(interface),
+                    None => return false,
+                }
             }
             class.set_interfaces(interfaces);
         }
+        return true;
     }
 
+    /// like find_or_load_class, but returns None with a NoClassDefFoundError
+    /// pending if the class cannot be found. An error thrown while defining
+    /// the class stays pending as it is
     pub fn load_class(
         loader_object: Option<Rc<RefCell<Object>>>,
         class_name: &str,
-    ) -> Rc<RefCell<Class>> {
+    ) -> Option<Rc<RefCell<Class>>> {
+        let class = Self::find_or_load_class(loader_object, class_name);
+        if class.is_none() {
+            match JavaThread::take_pending_exception() {
+                Some(exception) if !Self::is_class_not_found(&exception) => {
+                    JavaThread::set_pending_exception(exception)
+                }
+                _ => set_pending_exception("java/lang/NoClassDefFoundError", Some(class_name)),
+            }
+        }
+        return class;
+    }
+
+    /// loadClass throws ClassNotFoundException, resolving a class name
+    /// reports it as NoClassDefFoundError
+    fn is_class_not_found(exception: &Rc<RefCell<Object>>) -> bool {
+        let class = (**exception).borrow().class();
+        let name = (*class).borrow().name().to_string();
+        return name == "java/lang/ClassNotFoundException";
+    }
+
+    /// like load_class, but returns None if the class cannot be found
+    pub fn find_or_load_class(
+        loader_object: Option<Rc<RefCell<Object>>>,
+        class_name: &str,
+    ) -> Option<Rc<RefCell<Class>>> {
         if loader_object.is_none() {
             let bootstrap_loader = Jvm::boot_class_loader();
-            let class =  bootstrap_loader.find_or_create(class_name);
-            if class.is_none() {
-                println!("class not found {}",class_name);
-            }
-            return class.unwrap();
+            return bootstrap_loader.find_or_create(class_name);
         }
         let loader = loader_object.unwrap();
         let class_loader = (*loader).borrow().get_class_loader();
         let class_op: Option<Rc<RefCell<Class>>> = (*class_loader).borrow().find_class(class_name);
         if class_op.is_some() {
-            return class_op.unwrap().clone();
+            return class_op;
         }
-        let mut class: Option<Rc<RefCell<Class>>> = None;
         if class_name.starts_with('[') {
-            class = Some(Self::load_array_class(class_loader.clone(), class_name));
-        } else {
-            class = Self::invoke_load_class(loader.clone(), class_name.replace('/', ".").as_str());
+            return Some(Self::load_array_class(class_loader.clone(), class_name));
         }
-        let value = class.unwrap();
         //Self::setting_class_object(Some(loader),value.clone());
-        return value;
+        return Self::invoke_load_class(loader.clone(), class_name.replace('/', ".").as_str());
     }
 
     pub(in crate::class_loader) fn setting_class_object(
@@ -185,7 +288,7 @@ impl ClassLoader {
             class_object.set_meta(value.clone());
             let constructor_desc = "(Ljava/lang/ClassLoader;)V";
             let constructor = Class::get_constructor(class_of_class.clone(), constructor_desc);
-            let object = Some(boxed(class_object));
+            let object = Some(Heap::allocate(class_object));
             let parameters = vec![
                 Parameter::Object(object.clone()),
                 Parameter::Object(loader_object)
@@ -214,8 +317,9 @@ impl ClassLoader {
             Parameter::Object(Some(loader)),
             Parameter::Object(Some(java_name)),
         ]);
+        // None if loadClass threw, the exception is pending then
         let return_value = invoke(method.unwrap(), Some(params), ReturnType::Object).object();
-        return (*return_value.unwrap()).borrow().meta();
+        return return_value.and_then(|class_object| (*class_object).borrow().meta());
     }
 
     ///load array's class
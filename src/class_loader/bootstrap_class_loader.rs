@@ -1,6 +1,7 @@
 use crate::class_loader::app_class_loader::ClassLoader;
 use crate::class_loader::class_linker::ClassLinker;
 use crate::class_path::class_path::{ClassPath, Entry, FindClassError, new_entry};
+use crate::gc::heap::Heap;
 use crate::oops::class::Class;
 use crate::oops::class_name_helper::PrimitiveTypes;
 use crate::utils::boxed;
@@ -26,6 +27,12 @@ impl BootstrapClassLoader {
         return boot;
     }
 
+    /// the boot and user class paths the VM was started with
+    #[inline]
+    pub fn class_path(&self) -> Rc<ClassPath> {
+        return self.lib_path.clone();
+    }
+
     #[inline]
     pub fn post_constructor(&self) {
         self.load_basic_classes();
@@ -42,7 +49,7 @@ impl BootstrapClassLoader {
             if j_l_class.is_none() {
                 let mut class_object = Class::new_object(&java_lang_class);
                 class_object.set_meta(v.clone());
-                let boxed = boxed(class_object);
+                let boxed = Heap::allocate(class_object);
                 borrow_class.set_java_class(Some(boxed));
             }
         }
@@ -64,7 +71,7 @@ impl BootstrapClassLoader {
         class_object.set_meta(boxed_class.clone());
         (*boxed_class)
             .borrow_mut()
-            .set_java_class(Some(boxed(class_object)));
+            .set_java_class(Some(Heap::allocate(class_object)));
         (*self.class_loader)
             .borrow_mut()
             .class_map
@@ -99,8 +106,14 @@ impl BootstrapClassLoader {
             return None;
         }
         let (bytes, entry) = result.unwrap();
-        let class = self.define_class(bytes);
-        ClassLinker::link(&class);
+        let class = self.define_class(bytes, class_name)?;
+        if !ClassLinker::link(&class) {
+            (*self.class_loader)
+                .borrow_mut()
+                .class_map
+                .remove(class_name);
+            return None;
+        }
         if (*self.class_loader).borrow().verbose_class {
             println!("Loaded {}.class from {}", class_name, entry.to_string());
         }
@@ -126,8 +139,9 @@ impl BootstrapClassLoader {
         return result;
     }
 
-    fn define_class(&self, data: Vec<u8>) -> Rc<RefCell<Class>> {
-        let class = ClassLoader::parse_class(data);
+    /// returns None with a ClassFormatError pending
+    fn define_class(&self, data: Vec<u8>, class_name: &str) -> Option<Rc<RefCell<Class>>> {
+        let class = ClassLoader::parse_class(data, class_name)?;
         (*class)
             .borrow_mut()
             .set_class_loader(self.class_loader.clone());
@@ -137,7 +151,7 @@ impl BootstrapClassLoader {
             .borrow_mut()
             .class_map
             .insert((*class).borrow().name().to_string(), class.clone());
-        return class;
+        return Some(class);
     }
 
     fn resolve_super_class(&self, class: Rc<RefCell<Class>>) {
@@ -1,14 +1,358 @@
 use crate::class_loader::class_init_preparation::ClassPreparation;
 use crate::class_loader::class_verifier::ClassVerifier;
-use crate::oops::class::Class;
+use crate::oops::class::{Class, Itable};
+use crate::oops::method::Method;
+use crate::oops::method_ref::MethodRef;
 use std::cell::RefCell;
+use std::ops::Deref;
 use std::rc::Rc;
 
 pub struct ClassLinker();
 
 impl ClassLinker {
-    pub fn link(class: &Rc<RefCell<Class>>) {
-        ClassVerifier::verify(class);
+    /// links a class of the bootstrap class loader, returns false with a VerifyError pending
+    pub fn link(class: &Rc<RefCell<Class>>) -> bool {
+        Self::link_methods(class);
+        if !ClassVerifier::verify(class, None, true) {
+            return false;
+        }
         ClassPreparation::prepare(class);
+        return true;
+    }
+
+    /// builds the vtable and itable of a class, or numbers the methods of an
+    /// interface, its superclass and interfaces have to be linked before.
+    /// it runs first so classes loaded while verifying or preparing see the tables
+    pub fn link_methods(class: &Rc<RefCell<Class>>) {
+        if (*class).borrow().is_interface() {
+            let borrow = (*class).borrow();
+            let methods = borrow
+                .methods()
+                .iter()
+                .filter(|method| Self::is_virtual(method));
+            for (index, method) in methods.enumerate() {
+                method.set_vtable_index(index);
+            }
+            return;
+        }
+        let vtable = Self::build_vtable((*class).borrow().deref());
+        (*class).borrow_mut().set_vtable(vtable);
+        let itable = Self::build_itable((*class).borrow().deref());
+        (*class).borrow_mut().set_itable(itable);
+    }
+
+    /// methods dispatched through the tables
+    fn is_virtual(method: &Method) -> bool {
+        return !method.is_static() && !method.is_private() && !method.name().starts_with('<');
+    }
+
+    /// the vtable of the superclass with the overridden methods replaced and
+    /// the new methods appended, jvms 5.4.5
+    fn build_vtable(class: &Class) -> Vec<Rc<Method>> {
+        let mut vtable = match class.super_class() {
+            Some(super_class) => (*super_class).borrow().vtable().clone(),
+            None => vec![],
+        };
+        for method in class.methods() {
+            if !Self::is_virtual(method) {
+                continue;
+            }
+            let mut overrides = false;
+            for (index, entry) in vtable.iter_mut().enumerate() {
+                if entry.name() == method.name()
+                    && entry.descriptor() == method.descriptor()
+                    && Self::can_override(entry, class)
+                {
+                    *entry = method.clone();
+                    if !overrides {
+                        method.set_vtable_index(index);
+                        overrides = true;
+                    }
+                }
+            }
+            if !overrides {
+                method.set_vtable_index(vtable.len());
+                vtable.push(method.clone());
+            }
+        }
+        return vtable;
+    }
+
+    /// a package private method is only overridden in its package
+    fn can_override(method: &Method, class: &Class) -> bool {
+        if method.is_public() || method.is_protected() {
+            return true;
+        }
+        return (*method.class()).borrow().package_name() == class.package_name();
+    }
+
+    fn build_itable(class: &Class) -> Itable {
+        let mut itable = vec![];
+        for interface in class.all_interfaces() {
+            let methods = (*interface)
+                .borrow()
+                .methods()
+                .iter()
+                .filter(|method| Self::is_virtual(method))
+                .map(|method| Self::select_interface_method(class, method))
+                .collect();
+            itable.push((interface, methods));
+        }
+        return itable;
+    }
+
+    /// jvms 5.4.6, an instance method of the class or a superclass, else the
+    /// only non-abstract maximally-specific superinterface method. an
+    /// abstract method is selected if there is none, None if there are several
+    fn select_interface_method(class: &Class, interface_method: &Method) -> Option<Rc<Method>> {
+        let name = interface_method.name();
+        let descriptor = interface_method.descriptor();
+        if let Some(method) = Self::look_up_instance_method(class, name, descriptor) {
+            return Some(method);
+        }
+        let methods = MethodRef::maximally_specific_methods(class, name, descriptor);
+        let non_abstract: Vec<&Rc<Method>> = methods
+            .iter()
+            .filter(|method| !method.is_abstract())
+            .collect();
+        match non_abstract.len() {
+            0 => return methods.first().cloned(),
+            1 => return Some(non_abstract[0].clone()),
+            _ => return None,
+        }
+    }
+
+    fn look_up_instance_method(class: &Class, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+        for method in class.methods() {
+            if !method.is_static() && method.name() == name && method.descriptor() == descriptor {
+                return Some(method.clone());
+            }
+        }
+        let super_class = class.super_class()?;
+        let borrow = (*super_class).borrow();
+        return Self::look_up_instance_method(borrow.deref(), name, descriptor);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::class_linker::ClassLinker;
+    use crate::oops::access_flags::{ABSTRACT, INTERFACE, PRIVATE, PUBLIC, STATIC};
+    use crate::oops::class::Class;
+    use crate::oops::method::Method;
+    use lark_classfile::class_file::ClassFile;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// a linked class with ()V methods without code
+    fn class(
+        name: &str,
+        access_flags: u16,
+        super_class: Option<&Rc<RefCell<Class>>>,
+        interfaces: &[&Rc<RefCell<Class>>],
+        methods: &[(&str, u16)],
+    ) -> Rc<RefCell<Class>> {
+        let mut pool: Vec<Vec<u8>> = vec![];
+        let utf8 = |pool: &mut Vec<Vec<u8>>, string: &str| {
+            let mut entry = vec![1u8];
+            entry.extend_from_slice(&(string.len() as u16).to_be_bytes());
+            entry.extend_from_slice(string.as_bytes());
+            pool.push(entry);
+            return pool.len() as u16;
+        };
+        let class_entry = |pool: &mut Vec<Vec<u8>>, name_index: u16| {
+            let mut entry = vec![7u8];
+            entry.extend_from_slice(&name_index.to_be_bytes());
+            pool.push(entry);
+            return pool.len() as u16;
+        };
+        let index = utf8(&mut pool, name);
+        let this_class = class_entry(&mut pool, index);
+        let super_index = match super_class {
+            Some(super_class) => {
+                let index = utf8(&mut pool, (**super_class).borrow().name());
+                class_entry(&mut pool, index)
+            }
+            None => 0,
+        };
+        let mut interface_indices = vec![];
+        for interface in interfaces {
+            let index = utf8(&mut pool, (***interface).borrow().name());
+            interface_indices.push(class_entry(&mut pool, index));
+        }
+        let descriptor = utf8(&mut pool, "()V");
+        let mut method_infos = vec![];
+        for (method_name, flags) in methods {
+            let index = utf8(&mut pool, method_name);
+            method_infos.extend_from_slice(&flags.to_be_bytes());
+            method_infos.extend_from_slice(&index.to_be_bytes());
+            method_infos.extend_from_slice(&descriptor.to_be_bytes());
+            method_infos.extend_from_slice(&[0, 0]);
+        }
+
+        let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52];
+        bytes.extend_from_slice(&(pool.len() as u16 + 1).to_be_bytes());
+        for entry in &pool {
+            bytes.extend_from_slice(entry);
+        }
+        bytes.extend_from_slice(&access_flags.to_be_bytes());
+        bytes.extend_from_slice(&this_class.to_be_bytes());
+        bytes.extend_from_slice(&super_index.to_be_bytes());
+        bytes.extend_from_slice(&(interfaces.len() as u16).to_be_bytes());
+        for index in interface_indices {
+            bytes.extend_from_slice(&index.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(&(methods.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&method_infos);
+        bytes.extend_from_slice(&[0, 0]);
+
+        let class = Class::new(ClassFile::parse(bytes).unwrap());
+        if let Some(super_class) = super_class {
+            (*class).borrow_mut().set_super_class(super_class.clone());
+        }
+        if !interfaces.is_empty() {
+            let interfaces = interfaces
+                .iter()
+                .map(|interface| (*interface).clone())
+                .collect();
+            (*class).borrow_mut().set_interfaces(interfaces);
+        }
+        ClassLinker::link_methods(&class);
+        return class;
+    }
+
+    fn object() -> Rc<RefCell<Class>> {
+        return class(
+            "java/lang/Object",
+            PUBLIC,
+            None,
+            &[],
+            &[("hashCode", PUBLIC)],
+        );
+    }
+
+    /// "class.method" of each entry
+    fn names(methods: &[Rc<Method>]) -> Vec<String> {
+        return methods
+            .iter()
+            .map(|method| format!("{}.{}", (*method.class()).borrow().name(), method.name()))
+            .collect();
+    }
+
+    /// the method the class selected for a method of the interface
+    fn selected(
+        class: &Rc<RefCell<Class>>,
+        interface: &Rc<RefCell<Class>>,
+        name: &str,
+    ) -> Option<Option<String>> {
+        let interface = (**interface).borrow();
+        let method = interface
+            .methods()
+            .iter()
+            .find(|method| method.name() == name)?;
+        let class = (**class).borrow();
+        let methods = class.itable_methods(&interface)?;
+        return Some(
+            methods[method.vtable_index()]
+                .as_ref()
+                .map(|method| format!("{}.{}", (*method.class()).borrow().name(), method.name())),
+        );
+    }
+
+    #[test]
+    fn vtable() {
+        let object = object();
+        let a = class(
+            "a/A",
+            PUBLIC,
+            Some(&object),
+            &[],
+            &[
+                ("<init>", PUBLIC),
+                ("m", PUBLIC),
+                ("p", 0),
+                ("q", PRIVATE),
+                ("s", STATIC),
+            ],
+        );
+        assert_eq!(
+            names((*a).borrow().vtable()),
+            vec!["java/lang/Object.hashCode", "a/A.m", "a/A.p"]
+        );
+        // A.p is package private in another package, B.p doesn't override it
+        let b = class(
+            "b/B",
+            PUBLIC,
+            Some(&a),
+            &[],
+            &[("p", PUBLIC), ("m", PUBLIC), ("hashCode", PUBLIC)],
+        );
+        assert_eq!(
+            names((*b).borrow().vtable()),
+            vec!["b/B.hashCode", "b/B.m", "a/A.p", "b/B.p"]
+        );
+        // C.p overrides both A.p and B.p
+        let c = class("a/C", PUBLIC, Some(&b), &[], &[("p", PUBLIC)]);
+        assert_eq!(
+            names((*c).borrow().vtable()),
+            vec!["b/B.hashCode", "b/B.m", "a/C.p", "a/C.p"]
+        );
+        let borrow = (*b).borrow();
+        let indices: Vec<usize> = borrow.methods().iter().map(|m| m.vtable_index()).collect();
+        assert_eq!(indices, vec![3, 1, 0]);
+    }
+
+    #[test]
+    fn itable() {
+        let object = object();
+        let flags = INTERFACE | ABSTRACT | PUBLIC;
+        let i = class(
+            "I",
+            flags,
+            Some(&object),
+            &[],
+            &[
+                ("m", PUBLIC),
+                ("n", PUBLIC | ABSTRACT),
+                ("s", PUBLIC | STATIC),
+            ],
+        );
+        let j = class("J", flags, Some(&object), &[&i], &[("m", PUBLIC)]);
+        let k = class("K", flags, Some(&object), &[], &[("m", PUBLIC)]);
+        let l = class(
+            "L",
+            flags,
+            Some(&object),
+            &[&i],
+            &[("m", PUBLIC | ABSTRACT)],
+        );
+
+        // J.m is more specific than I.m
+        let c = class("C", PUBLIC | ABSTRACT, Some(&object), &[&i, &j], &[]);
+        assert_eq!(selected(&c, &i, "m"), Some(Some("J.m".to_string())));
+        assert_eq!(selected(&c, &j, "m"), Some(Some("J.m".to_string())));
+        assert_eq!(selected(&c, &i, "n"), Some(Some("I.n".to_string())));
+        assert_eq!(selected(&c, &k, "m"), None);
+
+        // a method of the class or a superclass comes first
+        let d = class("D", PUBLIC, Some(&c), &[], &[("m", PUBLIC), ("n", PUBLIC)]);
+        assert_eq!(selected(&d, &i, "m"), Some(Some("D.m".to_string())));
+        assert_eq!(selected(&d, &i, "n"), Some(Some("D.n".to_string())));
+        let e = class("E", PUBLIC, Some(&d), &[&k], &[]);
+        assert_eq!(selected(&e, &k, "m"), Some(Some("D.m".to_string())));
+
+        // J.m and K.m conflict
+        let f = class("F", PUBLIC, Some(&object), &[&j, &k], &[]);
+        assert_eq!(selected(&f, &k, "m"), Some(None));
+        assert_eq!(selected(&f, &i, "m"), Some(None));
+
+        // L re-abstracts I.m
+        let g = class("G", PUBLIC, Some(&object), &[&l], &[]);
+        assert_eq!(selected(&g, &i, "m"), Some(Some("L.m".to_string())));
+        assert!((*g).borrow().itable_methods(&(*i).borrow()).unwrap()[0]
+            .as_ref()
+            .unwrap()
+            .is_abstract());
     }
 }
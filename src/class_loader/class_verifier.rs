@@ -1,10 +1,1912 @@
+use crate::class_loader::app_class_loader::ClassLoader;
+use crate::invoke_support::set_pending_exception;
+use crate::jvm::Jvm;
 use crate::oops::class::Class;
+use crate::oops::constant_pool::Constant;
+use crate::oops::method::Method;
+use crate::oops::object::Object;
+use crate::runtime::thread::JavaThread;
+use lark_classfile::stack_map_table_attribute::{StackMapFrame, VerificationTypeInfo};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+/// which classes are verified, set by -Xverify
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerifyMode {
+    None,
+    /// only the classes not loaded by the bootstrap class loader, the default
+    Remote,
+    All,
+}
+
+impl VerifyMode {
+    pub fn parse(mode: &str) -> Option<VerifyMode> {
+        return match mode {
+            "none" => Some(VerifyMode::None),
+            "remote" => Some(VerifyMode::Remote),
+            "all" => Some(VerifyMode::All),
+            _ => None,
+        };
+    }
+}
+
+static mut VERIFY_MODE: VerifyMode = VerifyMode::Remote;
+
+/// the first class file version that must be verified by type checking
+const TYPE_CHECKING_VERSION: u16 = 50;
+
+const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+const JAVA_LANG_THROWABLE: &str = "java/lang/Throwable";
+
 pub struct ClassVerifier();
 
 impl ClassVerifier {
-    /// waiting for implementation
-    pub fn verify(class: &Rc<RefCell<Class>>) {}
+    pub fn set_mode(mode: VerifyMode) {
+        unsafe {
+            VERIFY_MODE = mode;
+        }
+    }
+
+    #[inline]
+    pub fn mode() -> VerifyMode {
+        unsafe {
+            return VERIFY_MODE;
+        }
+    }
+
+    /// verifies the class if -Xverify asks for it, `trusted` is true for the
+    /// classes of the bootstrap class loader. The classes the checks need are
+    /// loaded through `java_loader`, the defining loader of the class.
+    /// Returns false with a VerifyError pending
+    pub fn verify(
+        class: &Rc<RefCell<Class>>,
+        java_loader: Option<Rc<RefCell<Object>>>,
+        trusted: bool,
+    ) -> bool {
+        match Self::mode() {
+            VerifyMode::None => return true,
+            VerifyMode::Remote if trusted => return true,
+            _ => {}
+        }
+        let result = Self::verify_class(&(*class).borrow(), java_loader);
+        if let Err(msg) = result {
+            set_pending_exception("java/lang/VerifyError", Some(msg.as_str()));
+            return false;
+        }
+        return true;
+    }
+
+    /// type checks every method with code as JVMS §4.10.1 describes. Class
+    /// files older than version 50 have no StackMapTable, their code only gets
+    /// the checks that need no type inference
+    pub fn verify_class(
+        class: &Class,
+        java_loader: Option<Rc<RefCell<Object>>>,
+    ) -> Result<(), String> {
+        for method in class.methods() {
+            if method.is_abstract() || method.is_native() {
+                continue;
+            }
+            let result = MethodVerifier::new(class, method, java_loader.clone()).verify();
+            if let Err(msg) = result {
+                return Err(format!(
+                    "(class: {}, method: {} signature: {}) {}",
+                    class.name(),
+                    method.name(),
+                    method.descriptor(),
+                    msg
+                ));
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// the types of JVMS §4.10.1.2, a long or double takes two entries
+/// in the locals and on the operand stack, the second one is Top
+#[derive(Debug, Clone, PartialEq)]
+enum VerificationType {
+    Top,
+    Integer,
+    Float,
+    Long,
+    Double,
+    Null,
+    UninitializedThis,
+    /// the offset of the `new` instruction that created the object
+    Uninitialized(usize),
+    /// a class name like java/lang/String or an array descriptor like [I
+    Reference(String),
+}
+
+impl VerificationType {
+    #[inline]
+    fn is_category2(&self) -> bool {
+        return *self == VerificationType::Long || *self == VerificationType::Double;
+    }
+
+    #[inline]
+    fn is_reference(&self) -> bool {
+        return matches!(
+            self,
+            VerificationType::Null
+                | VerificationType::UninitializedThis
+                | VerificationType::Uninitialized(_)
+                | VerificationType::Reference(_)
+        );
+    }
+
+    #[inline]
+    fn is_initialized_reference(&self) -> bool {
+        return matches!(
+            self,
+            VerificationType::Null | VerificationType::Reference(_)
+        );
+    }
+
+    /// the type of a field descriptor, boolean, byte, char and short are int
+    fn from_descriptor(descriptor: &str) -> Result<VerificationType, String> {
+        let first = descriptor.chars().next();
+        let verification_type = match first {
+            Some('B') | Some('C') | Some('I') | Some('S') | Some('Z') => VerificationType::Integer,
+            Some('F') => VerificationType::Float,
+            Some('J') => VerificationType::Long,
+            Some('D') => VerificationType::Double,
+            Some('L') if descriptor.ends_with(';') && descriptor.len() > 2 => {
+                VerificationType::Reference(descriptor[1..descriptor.len() - 1].to_string())
+            }
+            Some('[') => VerificationType::Reference(descriptor.to_string()),
+            _ => return Err(format!("Illegal descriptor {}", descriptor)),
+        };
+        if descriptor.len() != 1 && !verification_type.is_reference() {
+            return Err(format!("Illegal descriptor {}", descriptor));
+        }
+        return Ok(verification_type);
+    }
+
+    fn name(&self) -> String {
+        return match self {
+            VerificationType::Top => "top".to_string(),
+            VerificationType::Integer => "integer".to_string(),
+            VerificationType::Float => "float".to_string(),
+            VerificationType::Long => "long".to_string(),
+            VerificationType::Double => "double".to_string(),
+            VerificationType::Null => "null".to_string(),
+            VerificationType::UninitializedThis => "uninitializedThis".to_string(),
+            VerificationType::Uninitialized(offset) => format!("uninitialized({})", offset),
+            VerificationType::Reference(name) => format!("'{}'", name),
+        };
+    }
+}
+
+/// splits a method descriptor into its parameter types and the return type,
+/// None for void
+fn parse_method_descriptor(
+    descriptor: &str,
+) -> Result<(Vec<VerificationType>, Option<VerificationType>), String> {
+    let bad = || format!("Illegal method descriptor {}", descriptor);
+    if !descriptor.starts_with('(') {
+        return Err(bad());
+    }
+    let bytes = descriptor.as_bytes();
+    let mut parameters = Vec::new();
+    let mut index = 1;
+    while index < bytes.len() && bytes[index] != b')' {
+        let end = field_descriptor_end(bytes, index).ok_or_else(bad)?;
+        parameters.push(VerificationType::from_descriptor(&descriptor[index..end])?);
+        index = end;
+    }
+    if index >= bytes.len() {
+        return Err(bad());
+    }
+    let return_descriptor = &descriptor[index + 1..];
+    if return_descriptor == "V" {
+        return Ok((parameters, None));
+    }
+    if field_descriptor_end(bytes, index + 1) != Some(bytes.len()) {
+        return Err(bad());
+    }
+    let return_type = VerificationType::from_descriptor(return_descriptor)?;
+    return Ok((parameters, Some(return_type)));
+}
+
+/// the index after the field descriptor starting at `start`
+fn field_descriptor_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut index = start;
+    while index < bytes.len() && bytes[index] == b'[' {
+        index += 1;
+    }
+    match bytes.get(index)? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => return Some(index + 1),
+        b'L' => {
+            let semicolon = bytes[index..].iter().position(|b| *b == b';')?;
+            return Some(index + semicolon + 1);
+        }
+        _ => return None,
+    }
+}
+
+/// the type of the elements of an array type, None if it isn't an array
+fn component_type(array: &str) -> Option<VerificationType> {
+    if !array.starts_with('[') {
+        return None;
+    }
+    return VerificationType::from_descriptor(&array[1..]).ok();
+}
+
+/// the array type with the given component, a class name or an array descriptor
+fn array_of(component: &str) -> String {
+    if component.starts_with('[') {
+        return format!("[{}", component);
+    }
+    return format!("[L{};", component);
+}
+
+#[derive(Debug, Clone)]
+struct StackFrame {
+    locals: Vec<VerificationType>,
+    stack: Vec<VerificationType>,
+    /// set in a constructor until the super or this constructor is called
+    flag_this_uninit: bool,
+}
+
+/// the byte length of every opcode with a fixed length, 0 for the invalid ones
+/// and the variable length tableswitch, lookupswitch and wide
+pub(crate) const INSTRUCTION_LENGTHS: [u8; 202] = [
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x00
+    2, 3, 2, 3, 3, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, // 0x10
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x20
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, // 0x30
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x40
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x50
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x60
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x70
+    1, 1, 1, 1, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 0x80
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 3, 3, 3, 3, 3, 3, 3, // 0x90
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 2, 0, 0, 1, 1, 1, 1, // 0xa0
+    1, 1, 3, 3, 3, 3, 3, 3, 3, 5, 5, 3, 2, 3, 1, 1, // 0xb0
+    3, 3, 1, 1, 0, 4, 3, 3, 5, 5, // 0xc0
+];
+
+struct MethodVerifier<'a> {
+    class: &'a Class,
+    method: &'a Method,
+    /// the defining loader of the class, None for the bootstrap class loader
+    java_loader: Option<Rc<RefCell<Object>>>,
+    code: Vec<u8>,
+    max_stack: usize,
+    max_locals: usize,
+    return_type: Option<VerificationType>,
+    /// the start of every instruction
+    instructions: Vec<usize>,
+    is_instruction: Vec<bool>,
+    stack_map: HashMap<usize, StackFrame>,
+    /// start_pc, end_pc, handler_pc and the caught class
+    handlers: Vec<(usize, usize, usize, String)>,
+}
+
+impl<'a> MethodVerifier<'a> {
+    fn new(
+        class: &'a Class,
+        method: &'a Method,
+        java_loader: Option<Rc<RefCell<Object>>>,
+    ) -> MethodVerifier<'a> {
+        let code = method.code();
+        let length = code.len();
+        return MethodVerifier {
+            class,
+            method,
+            java_loader,
+            code,
+            max_stack: method.max_stack(),
+            max_locals: method.max_locals(),
+            return_type: None,
+            instructions: Vec::new(),
+            is_instruction: vec![false; length],
+            stack_map: HashMap::new(),
+            handlers: Vec::new(),
+        };
+    }
+
+    fn verify(&mut self) -> Result<(), String> {
+        if self.code.is_empty() {
+            return Err("Code attribute is missing or empty".to_string());
+        }
+        let (parameters, return_type) = parse_method_descriptor(self.method.descriptor())?;
+        self.return_type = return_type;
+        self.decode_instructions()?;
+        let version = self.class.major_version();
+        if version < TYPE_CHECKING_VERSION
+            || (version == TYPE_CHECKING_VERSION && self.has_subroutines())
+        {
+            // a version 50 class file may fail over to verification by type
+            // inference, which jsr and ret need and this verifier doesn't do
+            return self.check_structure();
+        }
+        self.read_handlers()?;
+        let initial = self.initial_frame(parameters)?;
+        self.read_stack_map(&initial)?;
+
+        let mut frame = Some(initial);
+        for index in 0..self.instructions.len() {
+            let pc = self.instructions[index];
+            if let Some(mapped) = self.stack_map.get(&pc) {
+                if let Some(current) = &frame {
+                    self.check_frame_assignable(current, mapped, pc)?;
+                }
+                frame = Some(mapped.clone());
+            }
+            let current = match frame.take() {
+                Some(current) => current,
+                None => {
+                    return Err(format!(
+                        "Expecting a stackmap frame at branch target {}",
+                        pc
+                    ))
+                }
+            };
+            self.check_handlers(&current, pc)?;
+            frame = self
+                .execute(current, pc)
+                .map_err(|msg| format!("{} at bytecode offset {}", msg, pc))?;
+        }
+        if frame.is_some() {
+            return Err("Falling off the end of the code".to_string());
+        }
+        return Ok(());
+    }
+
+    fn decode_instructions(&mut self) -> Result<(), String> {
+        let mut pc = 0;
+        while pc < self.code.len() {
+            let length = self.instruction_length(pc)?;
+            if pc + length > self.code.len() {
+                return Err(format!("Truncated instruction at bytecode offset {}", pc));
+            }
+            self.instructions.push(pc);
+            self.is_instruction[pc] = true;
+            pc += length;
+        }
+        return Ok(());
+    }
+
+    fn instruction_length(&self, pc: usize) -> Result<usize, String> {
+        let opcode = self.code[pc];
+        match opcode {
+            0xaa | 0xab => {
+                let base = (pc + 4) & !3;
+                let header = if opcode == 0xaa { 12 } else { 8 };
+                if base + header > self.code.len() {
+                    return Err(format!("Truncated switch at bytecode offset {}", pc));
+                }
+                if opcode == 0xaa {
+                    let low = self.read_i32(base + 4);
+                    let high = self.read_i32(base + 8);
+                    if low > high {
+                        return Err(format!("Bad tableswitch at bytecode offset {}", pc));
+                    }
+                    return Ok(base + 12 + 4 * ((high as i64 - low as i64 + 1) as usize) - pc);
+                }
+                let pairs = self.read_i32(base + 4);
+                if pairs < 0 {
+                    return Err(format!("Bad lookupswitch at bytecode offset {}", pc));
+                }
+                return Ok(base + 8 + 8 * pairs as usize - pc);
+            }
+            0xc4 => match self.code.get(pc + 1) {
+                Some(0x84) => return Ok(6),
+                Some(0x15..=0x19) | Some(0x36..=0x3a) | Some(0xa9) => return Ok(4),
+                _ => return Err(format!("Bad wide instruction at bytecode offset {}", pc)),
+            },
+            _ => match INSTRUCTION_LENGTHS.get(opcode as usize) {
+                Some(length) if *length > 0 => return Ok(*length as usize),
+                _ => {
+                    return Err(format!(
+                        "Bad instruction {:#04x} at bytecode offset {}",
+                        opcode, pc
+                    ))
+                }
+            },
+        }
+    }
+
+    /// what is checked without type inference: the branch targets, the
+    /// exception handlers, the local variable numbers and that the code
+    /// doesn't fall off its end
+    fn check_structure(&mut self) -> Result<(), String> {
+        self.read_handlers()?;
+        for index in 0..self.instructions.len() {
+            let pc = self.instructions[index];
+            for target in self.branch_targets(pc) {
+                if target < 0
+                    || target as usize >= self.code.len()
+                    || !self.is_instruction[target as usize]
+                {
+                    return Err(format!(
+                        "Illegal branch target {} at bytecode offset {}",
+                        target, pc
+                    ));
+                }
+            }
+            if let Some((local, category2)) = self.local_variable(pc) {
+                if local + category2 as usize >= self.max_locals {
+                    return Err(format!(
+                        "Illegal local variable number {} at bytecode offset {}",
+                        local, pc
+                    ));
+                }
+            }
+        }
+        let last = *self.instructions.last().unwrap();
+        match self.code[last] {
+            0xa7 | 0xa9 | 0xaa | 0xab | 0xac..=0xb1 | 0xbf | 0xc8 => {}
+            0xc4 if self.code[last + 1] == 0xa9 => {}
+            _ => return Err("Falling off the end of the code".to_string()),
+        }
+        return Ok(());
+    }
+
+    /// the targets of a branch, jsr or switch at `pc`
+    fn branch_targets(&self, pc: usize) -> Vec<i64> {
+        let opcode = self.code[pc];
+        match opcode {
+            0x99..=0xa8 | 0xc6 | 0xc7 => return vec![pc as i64 + self.read_i16(pc + 1)],
+            0xc8 | 0xc9 => return vec![pc as i64 + self.read_i32(pc + 1) as i64],
+            0xaa | 0xab => {
+                let base = (pc + 4) & !3;
+                let mut offsets = vec![self.read_i32(base)];
+                if opcode == 0xaa {
+                    let count = self.read_i32(base + 8) as i64 - self.read_i32(base + 4) as i64 + 1;
+                    for i in 0..count as usize {
+                        offsets.push(self.read_i32(base + 12 + 4 * i));
+                    }
+                } else {
+                    for i in 0..self.read_i32(base + 4) as usize {
+                        offsets.push(self.read_i32(base + 12 + 8 * i));
+                    }
+                }
+                return offsets
+                    .iter()
+                    .map(|offset| pc as i64 + *offset as i64)
+                    .collect();
+            }
+            _ => return vec![],
+        }
+    }
+
+    /// the local variable the instruction at `pc` uses and whether it is a
+    /// long or double
+    fn local_variable(&self, pc: usize) -> Option<(usize, bool)> {
+        let opcode = self.code[pc];
+        return match opcode {
+            0x15..=0x19 | 0x36..=0x3a => Some((
+                self.read_u8(pc + 1),
+                matches!(opcode, 0x16 | 0x18 | 0x37 | 0x39),
+            )),
+            0x1a..=0x2d => Some((
+                ((opcode - 0x1a) % 4) as usize,
+                matches!((opcode - 0x1a) / 4, 1 | 3),
+            )),
+            0x3b..=0x4e => Some((
+                ((opcode - 0x3b) % 4) as usize,
+                matches!((opcode - 0x3b) / 4, 1 | 3),
+            )),
+            0x84 | 0xa9 => Some((self.read_u8(pc + 1), false)),
+            0xc4 => {
+                let wide = self.code[pc + 1];
+                Some((
+                    self.read_u16(pc + 2),
+                    matches!(wide, 0x16 | 0x18 | 0x37 | 0x39),
+                ))
+            }
+            _ => None,
+        };
+    }
+
+    fn has_subroutines(&self) -> bool {
+        for pc in &self.instructions {
+            match self.code[*pc] {
+                0xa8 | 0xa9 | 0xc9 => return true,
+                0xc4 if self.code[*pc + 1] == 0xa9 => return true,
+                _ => {}
+            }
+        }
+        return false;
+    }
+
+    fn read_handlers(&mut self) -> Result<(), String> {
+        let length = self.code.len();
+        for handler in self.method.exception_table().handlers() {
+            let start = handler.start_pc() as usize;
+            let end = handler.end_pc() as usize;
+            let handler_pc = handler.handler_pc() as usize;
+            let valid = start < end
+                && end <= length
+                && self.is_instruction[start]
+                && (end == length || self.is_instruction[end])
+                && handler_pc < length
+                && self.is_instruction[handler_pc];
+            if !valid {
+                return Err(format!(
+                    "Illegal exception table range [{}, {}) handler {}",
+                    start, end, handler_pc
+                ));
+            }
+            let catch_type = match handler.catch_type() {
+                Some(class_ref) => class_ref.class_name().to_string(),
+                None => JAVA_LANG_THROWABLE.to_string(),
+            };
+            let catch = VerificationType::Reference(catch_type.clone());
+            let throwable = VerificationType::Reference(JAVA_LANG_THROWABLE.to_string());
+            if !self.is_assignable(&catch, &throwable)? {
+                return Err(format!(
+                    "Catch type {} is not a subclass of Throwable",
+                    catch_type
+                ));
+            }
+            self.handlers.push((start, end, handler_pc, catch_type));
+        }
+        return Ok(());
+    }
+
+    fn initial_frame(&self, parameters: Vec<VerificationType>) -> Result<StackFrame, String> {
+        let mut locals = Vec::with_capacity(self.max_locals);
+        let mut flag_this_uninit = false;
+        if !self.method.is_static() {
+            if self.method.is_constructor() && self.class.name() != JAVA_LANG_OBJECT {
+                locals.push(VerificationType::UninitializedThis);
+                flag_this_uninit = true;
+            } else {
+                locals.push(VerificationType::Reference(self.class.name().to_string()));
+            }
+        }
+        for parameter in parameters {
+            let category2 = parameter.is_category2();
+            locals.push(parameter);
+            if category2 {
+                locals.push(VerificationType::Top);
+            }
+        }
+        if locals.len() > self.max_locals {
+            return Err("Arguments can't fit into locals".to_string());
+        }
+        locals.resize(self.max_locals, VerificationType::Top);
+        return Ok(StackFrame {
+            locals,
+            stack: Vec::new(),
+            flag_this_uninit,
+        });
+    }
+
+    /// expands the StackMapTable into a frame for each offset, the locals of
+    /// `declared` are kept as the class file lists them for chop and append frames
+    fn read_stack_map(&mut self, initial: &StackFrame) -> Result<(), String> {
+        let entries = match self.method.stack_map_table() {
+            Some(attr) => attr.entries().clone(),
+            None => return Ok(()),
+        };
+        let mut declared = Vec::new();
+        let mut index = 0;
+        while index < initial.locals.len() {
+            let local = initial.locals[index].clone();
+            index += if local.is_category2() { 2 } else { 1 };
+            if local != VerificationType::Top {
+                declared.push(local);
+            }
+        }
+        let mut offset: Option<usize> = None;
+        for entry in &entries {
+            let pc = match offset {
+                None => entry.offset_delta() as usize,
+                Some(last) => last + entry.offset_delta() as usize + 1,
+            };
+            let mut stack = Vec::new();
+            match entry {
+                StackMapFrame::Same { .. } => {}
+                StackMapFrame::SameLocals1StackItem { stack: item, .. } => {
+                    stack.push(self.verification_type(item)?);
+                }
+                StackMapFrame::Chop { k, .. } => {
+                    if (*k as usize) > declared.len() {
+                        return Err(format!("Bad chop frame at bytecode offset {}", pc));
+                    }
+                    declared.truncate(declared.len() - *k as usize);
+                }
+                StackMapFrame::Append { locals, .. } => {
+                    for local in locals {
+                        declared.push(self.verification_type(local)?);
+                    }
+                }
+                StackMapFrame::Full {
+                    locals,
+                    stack: items,
+                    ..
+                } => {
+                    declared.clear();
+                    for local in locals {
+                        declared.push(self.verification_type(local)?);
+                    }
+                    for item in items {
+                        stack.push(self.verification_type(item)?);
+                    }
+                }
+            }
+            if pc >= self.code.len() || !self.is_instruction[pc] {
+                return Err(format!("StackMapTable error: bad offset {}", pc));
+            }
+            let frame = self.expand_frame(&declared, &stack, pc)?;
+            self.stack_map.insert(pc, frame);
+            offset = Some(pc);
+        }
+        return Ok(());
+    }
+
+    fn expand_frame(
+        &self,
+        declared: &Vec<VerificationType>,
+        items: &Vec<VerificationType>,
+        pc: usize,
+    ) -> Result<StackFrame, String> {
+        let mut locals = Vec::with_capacity(self.max_locals);
+        for local in declared {
+            locals.push(local.clone());
+            if local.is_category2() {
+                locals.push(VerificationType::Top);
+            }
+        }
+        if locals.len() > self.max_locals {
+            return Err(format!(
+                "StackMapTable error: locals overflow at bytecode offset {}",
+                pc
+            ));
+        }
+        locals.resize(self.max_locals, VerificationType::Top);
+        let mut stack = Vec::new();
+        for item in items {
+            stack.push(item.clone());
+            if item.is_category2() {
+                stack.push(VerificationType::Top);
+            }
+        }
+        if stack.len() > self.max_stack {
+            return Err(format!(
+                "StackMapTable error: stack overflow at bytecode offset {}",
+                pc
+            ));
+        }
+        let flag_this_uninit = locals.contains(&VerificationType::UninitializedThis);
+        return Ok(StackFrame {
+            locals,
+            stack,
+            flag_this_uninit,
+        });
+    }
+
+    fn verification_type(&self, info: &VerificationTypeInfo) -> Result<VerificationType, String> {
+        let verification_type = match info {
+            VerificationTypeInfo::Top => VerificationType::Top,
+            VerificationTypeInfo::Integer => VerificationType::Integer,
+            VerificationTypeInfo::Float => VerificationType::Float,
+            VerificationTypeInfo::Long => VerificationType::Long,
+            VerificationTypeInfo::Double => VerificationType::Double,
+            VerificationTypeInfo::Null => VerificationType::Null,
+            VerificationTypeInfo::UninitializedThis => VerificationType::UninitializedThis,
+            VerificationTypeInfo::Object(index) => {
+                VerificationType::Reference(self.class_name_at(*index as usize)?)
+            }
+            VerificationTypeInfo::Uninitialized(offset) => {
+                let offset = *offset as usize;
+                if offset >= self.code.len()
+                    || !self.is_instruction[offset]
+                    || self.code[offset] != 0xbb
+                {
+                    return Err(format!("StackMapTable error: no new at offset {}", offset));
+                }
+                VerificationType::Uninitialized(offset)
+            }
+        };
+        return Ok(verification_type);
+    }
+
+    fn constant(&self, index: usize) -> Result<&Constant, String> {
+        let pool = self.class.constant_pool();
+        if index == 0 || index > pool.size() {
+            return Err(format!("Illegal constant pool index {}", index));
+        }
+        return Ok(pool.get_constant_immutable(index));
+    }
+
+    fn class_name_at(&self, index: usize) -> Result<String, String> {
+        match self.constant(index)? {
+            Constant::ClassReference(class_ref) => return Ok(class_ref.class_name().to_string()),
+            _ => return Err(format!("Illegal type at constant pool entry {}", index)),
+        }
+    }
+
+    /// loads a class through the defining loader of the class being verified,
+    /// one that can't be loaded fails the verification
+    fn load_class(&self, name: &str) -> Result<Rc<RefCell<Class>>, String> {
+        if Jvm::instance().is_some() {
+            let class = ClassLoader::find_or_load_class(self.java_loader.clone(), name);
+            if let Some(class) = class {
+                return Ok(class);
+            }
+            // the VerifyError is thrown in place of the loading error
+            JavaThread::take_pending_exception();
+        }
+        return Err(format!("Could not load class {}", name));
+    }
+
+    /// the super classes of a class, starting with the class itself
+    fn class_hierarchy(&self, name: &str) -> Result<Vec<String>, String> {
+        let mut names = vec![name.to_string()];
+        let mut super_class = if name == self.class.name() {
+            self.class.super_class()
+        } else {
+            let class = self.load_class(name)?;
+            let super_class = (*class).borrow().super_class();
+            super_class
+        };
+        while let Some(class) = super_class {
+            let class = (*class).borrow();
+            names.push(class.name().to_string());
+            super_class = class.super_class();
+        }
+        return Ok(names);
+    }
+
+    fn is_interface(&self, name: &str) -> Result<bool, String> {
+        if name == self.class.name() {
+            return Ok(self.class.is_interface());
+        }
+        let class = self.load_class(name)?;
+        let is_interface = (*class).borrow().is_interface();
+        return Ok(is_interface);
+    }
+
+    /// isJavaAssignable of JVMS §4.10.1.2, both classes are loaded and an
+    /// interface target is treated like Object
+    fn is_reference_assignable(&self, from: &str, to: &str) -> Result<bool, String> {
+        if from == to || to == JAVA_LANG_OBJECT {
+            return Ok(true);
+        }
+        if to.starts_with('[') {
+            if !from.starts_with('[') {
+                return Ok(false);
+            }
+            return match (component_type(from), component_type(to)) {
+                (
+                    Some(VerificationType::Reference(from)),
+                    Some(VerificationType::Reference(to)),
+                ) => self.is_reference_assignable(&from, &to),
+                _ => Ok(false),
+            };
+        }
+        if from.starts_with('[') {
+            return Ok(to == "java/lang/Cloneable" || to == "java/io/Serializable");
+        }
+        if self.is_interface(to)? {
+            return Ok(true);
+        }
+        let names = self.class_hierarchy(from)?;
+        return Ok(names.iter().any(|name| name == to));
+    }
+
+    fn is_assignable(
+        &self,
+        from: &VerificationType,
+        to: &VerificationType,
+    ) -> Result<bool, String> {
+        if from == to || *to == VerificationType::Top {
+            return Ok(true);
+        }
+        return match (from, to) {
+            (VerificationType::Null, VerificationType::Reference(_)) => Ok(true),
+            (VerificationType::Reference(from), VerificationType::Reference(to)) => {
+                self.is_reference_assignable(from, to)
+            }
+            _ => Ok(false),
+        };
+    }
+
+    fn check_frame_assignable(
+        &self,
+        from: &StackFrame,
+        to: &StackFrame,
+        target: usize,
+    ) -> Result<(), String> {
+        if from.stack.len() != to.stack.len() {
+            return Err(format!(
+                "Inconsistent stack height at bytecode offset {}",
+                target
+            ));
+        }
+        for (from_type, to_type) in from.locals.iter().zip(to.locals.iter()) {
+            if !self.is_assignable(from_type, to_type)? {
+                return Err(format!(
+                    "Bad local variable type {} for {} at bytecode offset {}",
+                    from_type.name(),
+                    to_type.name(),
+                    target
+                ));
+            }
+        }
+        for (from_type, to_type) in from.stack.iter().zip(to.stack.iter()) {
+            if !self.is_assignable(from_type, to_type)? {
+                return Err(format!(
+                    "Bad type {} on operand stack for {} at bytecode offset {}",
+                    from_type.name(),
+                    to_type.name(),
+                    target
+                ));
+            }
+        }
+        if from.flag_this_uninit && !to.flag_this_uninit {
+            return Err(format!("Bad flags at bytecode offset {}", target));
+        }
+        return Ok(());
+    }
+
+    fn check_target(&self, frame: &StackFrame, target: i64) -> Result<(), String> {
+        if target < 0 || target as usize >= self.code.len() || !self.is_instruction[target as usize]
+        {
+            return Err(format!("Illegal branch target {}", target));
+        }
+        return match self.stack_map.get(&(target as usize)) {
+            Some(mapped) => self.check_frame_assignable(frame, mapped, target as usize),
+            None => Err(format!(
+                "Expecting a stackmap frame at branch target {}",
+                target
+            )),
+        };
+    }
+
+    fn check_handlers(&self, frame: &StackFrame, pc: usize) -> Result<(), String> {
+        for (start, end, handler_pc, catch_type) in &self.handlers {
+            if pc < *start || pc >= *end {
+                continue;
+            }
+            let exception_frame = StackFrame {
+                locals: frame.locals.clone(),
+                stack: vec![VerificationType::Reference(catch_type.clone())],
+                flag_this_uninit: frame.flag_this_uninit,
+            };
+            self.check_target(&exception_frame, *handler_pc as i64)?;
+        }
+        return Ok(());
+    }
+
+    #[inline]
+    fn read_u8(&self, pc: usize) -> usize {
+        return self.code[pc] as usize;
+    }
+
+    #[inline]
+    fn read_u16(&self, pc: usize) -> usize {
+        return (self.code[pc] as usize) << 8 | self.code[pc + 1] as usize;
+    }
+
+    #[inline]
+    fn read_i16(&self, pc: usize) -> i64 {
+        return self.read_u16(pc) as i16 as i64;
+    }
+
+    #[inline]
+    fn read_i32(&self, pc: usize) -> i32 {
+        return i32::from_be_bytes([
+            self.code[pc],
+            self.code[pc + 1],
+            self.code[pc + 2],
+            self.code[pc + 3],
+        ]);
+    }
+
+    /// type checks one instruction, returns the frame after it or None if
+    /// the next instruction can't be reached from this one
+    fn execute(&self, frame: StackFrame, pc: usize) -> Result<Option<StackFrame>, String> {
+        let mut frame = FrameState {
+            frame,
+            verifier: self,
+        };
+        let opcode = self.code[pc];
+        match opcode {
+            0x00 => {}
+            0x01 => frame.push(VerificationType::Null)?,
+            0x02..=0x08 | 0x10 | 0x11 => frame.push(VerificationType::Integer)?,
+            0x09 | 0x0a => frame.push(VerificationType::Long)?,
+            0x0b..=0x0d => frame.push(VerificationType::Float)?,
+            0x0e | 0x0f => frame.push(VerificationType::Double)?,
+            0x12 => {
+                let constant_type = self.ldc_type(self.read_u8(pc + 1), false)?;
+                frame.push(constant_type)?;
+            }
+            0x13 | 0x14 => {
+                let constant_type = self.ldc_type(self.read_u16(pc + 1), opcode == 0x14)?;
+                frame.push(constant_type)?;
+            }
+            0x15..=0x18 => frame.load(self.read_u8(pc + 1), &primitive_type(opcode - 0x15))?,
+            0x19 => frame.load_reference(self.read_u8(pc + 1))?,
+            0x1a..=0x2d => {
+                let index = ((opcode - 0x1a) % 4) as usize;
+                match (opcode - 0x1a) / 4 {
+                    4 => frame.load_reference(index)?,
+                    kind => frame.load(index, &primitive_type(kind))?,
+                }
+            }
+            0x2e..=0x35 => {
+                frame.pop_expect(&VerificationType::Integer)?;
+                let array = frame.pop_array()?;
+                let element = array_element(opcode - 0x2e, &array)?;
+                frame.push(element)?;
+            }
+            0x36..=0x39 => frame.store(self.read_u8(pc + 1), primitive_type(opcode - 0x36))?,
+            0x3a => frame.store_reference(self.read_u8(pc + 1))?,
+            0x3b..=0x4e => {
+                let index = ((opcode - 0x3b) % 4) as usize;
+                match (opcode - 0x3b) / 4 {
+                    4 => frame.store_reference(index)?,
+                    kind => frame.store(index, primitive_type(kind))?,
+                }
+            }
+            0x4f..=0x56 => {
+                let kind = opcode - 0x4f;
+                let value = if kind == 4 {
+                    frame.pop_reference()?
+                } else {
+                    let value = array_value_type(kind);
+                    frame.pop_expect(&value)?;
+                    value
+                };
+                frame.pop_expect(&VerificationType::Integer)?;
+                let array = frame.pop_array()?;
+                let element = array_element(kind, &array)?;
+                if kind == 4 && !value.is_initialized_reference() {
+                    return Err(format!("Bad type {} stored into an array", value.name()));
+                }
+                if kind != 4 && element != value {
+                    return Err(format!("Bad type {} stored into an array", value.name()));
+                }
+            }
+            0x57 => {
+                frame.check_boundary(1)?;
+                frame.pop_entries(1)?;
+            }
+            0x58 => {
+                frame.check_boundary(2)?;
+                frame.pop_entries(2)?;
+            }
+            0x59 => frame.duplicate(1, 0)?,
+            0x5a => frame.duplicate(1, 1)?,
+            0x5b => frame.duplicate(1, 2)?,
+            0x5c => frame.duplicate(2, 0)?,
+            0x5d => frame.duplicate(2, 1)?,
+            0x5e => frame.duplicate(2, 2)?,
+            0x5f => {
+                frame.check_boundary(1)?;
+                frame.check_boundary(2)?;
+                let length = frame.frame.stack.len();
+                frame.frame.stack.swap(length - 1, length - 2);
+            }
+            0x60..=0x73 => {
+                let value = primitive_type((opcode - 0x60) % 4);
+                frame.pop_expect(&value)?;
+                frame.pop_expect(&value)?;
+                frame.push(value)?;
+            }
+            0x74..=0x77 => {
+                let value = primitive_type(opcode - 0x74);
+                frame.pop_expect(&value)?;
+                frame.push(value)?;
+            }
+            0x78..=0x7d => {
+                let value = primitive_type((opcode - 0x78) % 2);
+                frame.pop_expect(&VerificationType::Integer)?;
+                frame.pop_expect(&value)?;
+                frame.push(value)?;
+            }
+            0x7e..=0x83 => {
+                let value = primitive_type((opcode - 0x7e) % 2);
+                frame.pop_expect(&value)?;
+                frame.pop_expect(&value)?;
+                frame.push(value)?;
+            }
+            0x84 => frame.check_load(self.read_u8(pc + 1), &VerificationType::Integer)?,
+            0x85..=0x90 => {
+                let (from, to) = CONVERSIONS[(opcode - 0x85) as usize];
+                frame.pop_expect(&primitive_type(from))?;
+                frame.push(primitive_type(to))?;
+            }
+            0x91..=0x93 => {
+                frame.pop_expect(&VerificationType::Integer)?;
+                frame.push(VerificationType::Integer)?;
+            }
+            0x94..=0x98 => {
+                let value = match opcode {
+                    0x94 => VerificationType::Long,
+                    0x95 | 0x96 => VerificationType::Float,
+                    _ => VerificationType::Double,
+                };
+                frame.pop_expect(&value)?;
+                frame.pop_expect(&value)?;
+                frame.push(VerificationType::Integer)?;
+            }
+            0x99..=0xa6 | 0xc6 | 0xc7 => {
+                match opcode {
+                    0x99..=0x9e => frame.pop_expect(&VerificationType::Integer)?,
+                    0x9f..=0xa4 => {
+                        frame.pop_expect(&VerificationType::Integer)?;
+                        frame.pop_expect(&VerificationType::Integer)?;
+                    }
+                    0xa5 | 0xa6 => {
+                        frame.pop_reference()?;
+                        frame.pop_reference()?;
+                    }
+                    _ => {
+                        frame.pop_reference()?;
+                    }
+                }
+                self.check_target(&frame.frame, pc as i64 + self.read_i16(pc + 1))?;
+            }
+            0xa7 => {
+                self.check_target(&frame.frame, pc as i64 + self.read_i16(pc + 1))?;
+                return Ok(None);
+            }
+            0xc8 => {
+                self.check_target(&frame.frame, pc as i64 + self.read_i32(pc + 1) as i64)?;
+                return Ok(None);
+            }
+            0xaa | 0xab => {
+                frame.pop_expect(&VerificationType::Integer)?;
+                self.check_switch(&frame.frame, pc, opcode == 0xaa)?;
+                return Ok(None);
+            }
+            0xac..=0xb0 => {
+                let value = match opcode {
+                    0xac => VerificationType::Integer,
+                    0xb0 => frame.pop_reference()?,
+                    _ => primitive_type(opcode - 0xac),
+                };
+                if opcode != 0xb0 {
+                    frame.pop_expect(&value)?;
+                }
+                let valid = match &self.return_type {
+                    Some(return_type) if opcode == 0xb0 => {
+                        return_type.is_reference() && self.is_assignable(&value, return_type)?
+                    }
+                    Some(return_type) => *return_type == value,
+                    None => false,
+                };
+                if !valid {
+                    return Err("Method expects a different return type".to_string());
+                }
+                return Ok(None);
+            }
+            0xb1 => {
+                if self.return_type.is_some() {
+                    return Err("Method expects a return value".to_string());
+                }
+                if frame.frame.flag_this_uninit {
+                    return Err("Constructor must call super() or this() before return".to_string());
+                }
+                return Ok(None);
+            }
+            0xb2..=0xb5 => self.check_field_access(&mut frame, opcode, self.read_u16(pc + 1))?,
+            0xb6..=0xba => self.check_invoke(&mut frame, opcode, pc)?,
+            0xbb => {
+                let name = self.class_name_at(self.read_u16(pc + 1))?;
+                if name.starts_with('[') {
+                    return Err(format!("Illegal use of new on array type {}", name));
+                }
+                let created = VerificationType::Uninitialized(pc);
+                if frame.frame.stack.contains(&created) {
+                    return Err("Uninitialized object exists on backward branch".to_string());
+                }
+                for local in frame.frame.locals.iter_mut() {
+                    if *local == created {
+                        *local = VerificationType::Top;
+                    }
+                }
+                frame.push(created)?;
+            }
+            0xbc => {
+                let array = match self.code[pc + 1] {
+                    4 => "[Z",
+                    5 => "[C",
+                    6 => "[F",
+                    7 => "[D",
+                    8 => "[B",
+                    9 => "[S",
+                    10 => "[I",
+                    11 => "[J",
+                    atype => return Err(format!("Illegal newarray type {}", atype)),
+                };
+                frame.pop_expect(&VerificationType::Integer)?;
+                frame.push(VerificationType::Reference(array.to_string()))?;
+            }
+            0xbd => {
+                let component = self.class_name_at(self.read_u16(pc + 1))?;
+                let array = array_of(&component);
+                if array.chars().take_while(|c| *c == '[').count() > 255 {
+                    return Err("Array with too many dimensions".to_string());
+                }
+                frame.pop_expect(&VerificationType::Integer)?;
+                frame.push(VerificationType::Reference(array))?;
+            }
+            0xbe => {
+                frame.pop_array()?;
+                frame.push(VerificationType::Integer)?;
+            }
+            0xbf => {
+                frame.pop_expect(&VerificationType::Reference(
+                    JAVA_LANG_THROWABLE.to_string(),
+                ))?;
+                return Ok(None);
+            }
+            0xc0 => {
+                let name = self.class_name_at(self.read_u16(pc + 1))?;
+                frame.pop_initialized_reference()?;
+                frame.push(VerificationType::Reference(name))?;
+            }
+            0xc1 => {
+                self.class_name_at(self.read_u16(pc + 1))?;
+                frame.pop_initialized_reference()?;
+                frame.push(VerificationType::Integer)?;
+            }
+            0xc2 | 0xc3 => {
+                frame.pop_initialized_reference()?;
+            }
+            0xc4 => {
+                let index = self.read_u16(pc + 2);
+                match self.code[pc + 1] {
+                    0x84 => frame.check_load(index, &VerificationType::Integer)?,
+                    0xa9 => {
+                        return Err(
+                            "jsr and ret are not allowed in this class file version".to_string()
+                        )
+                    }
+                    0x19 => frame.load_reference(index)?,
+                    0x3a => frame.store_reference(index)?,
+                    wide @ 0x15..=0x18 => frame.load(index, &primitive_type(wide - 0x15))?,
+                    wide => frame.store(index, primitive_type(wide - 0x36))?,
+                }
+            }
+            0xc5 => {
+                let name = self.class_name_at(self.read_u16(pc + 1))?;
+                let dimensions = self.code[pc + 3] as usize;
+                let depth = name.chars().take_while(|c| *c == '[').count();
+                if dimensions == 0 || depth < dimensions {
+                    return Err(format!("Illegal dimensions {} for {}", dimensions, name));
+                }
+                for _ in 0..dimensions {
+                    frame.pop_expect(&VerificationType::Integer)?;
+                }
+                frame.push(VerificationType::Reference(name))?;
+            }
+            0xa8 | 0xa9 | 0xc9 => {
+                return Err("jsr and ret are not allowed in this class file version".to_string());
+            }
+            _ => return Err(format!("Bad instruction {:#04x}", opcode)),
+        }
+        return Ok(Some(frame.frame));
+    }
+
+    fn ldc_type(&self, index: usize, wide: bool) -> Result<VerificationType, String> {
+        let constant_type = match (self.constant(index)?, wide) {
+            (Constant::Integer(_), false) => VerificationType::Integer,
+            (Constant::Float(_), false) => VerificationType::Float,
+            (Constant::Str(_), false) => {
+                VerificationType::Reference("java/lang/String".to_string())
+            }
+            (Constant::ClassReference(_), false) => {
+                VerificationType::Reference("java/lang/Class".to_string())
+            }
+            (Constant::MethodTypeReference(_), false) => {
+                VerificationType::Reference("java/lang/invoke/MethodType".to_string())
+            }
+            (Constant::MethodHandleReference(_), false) => {
+                VerificationType::Reference("java/lang/invoke/MethodHandle".to_string())
+            }
+            (Constant::Long(_), true) => VerificationType::Long,
+            (Constant::Double(_), true) => VerificationType::Double,
+            _ => return Err(format!("Illegal type at constant pool entry {}", index)),
+        };
+        return Ok(constant_type);
+    }
+
+    fn check_switch(&self, frame: &StackFrame, pc: usize, table: bool) -> Result<(), String> {
+        let base = (pc + 4) & !3;
+        self.check_target(frame, pc as i64 + self.read_i32(base) as i64)?;
+        if table {
+            let low = self.read_i32(base + 4) as i64;
+            let high = self.read_i32(base + 8) as i64;
+            for i in 0..(high - low + 1) as usize {
+                let offset = self.read_i32(base + 12 + 4 * i) as i64;
+                self.check_target(frame, pc as i64 + offset)?;
+            }
+            return Ok(());
+        }
+        let pairs = self.read_i32(base + 4) as usize;
+        let mut last_key: Option<i32> = None;
+        for i in 0..pairs {
+            let key = self.read_i32(base + 8 + 8 * i);
+            if last_key.is_some_and(|last| key <= last) {
+                return Err("Bad lookupswitch instruction, keys are not sorted".to_string());
+            }
+            last_key = Some(key);
+            let offset = self.read_i32(base + 12 + 8 * i) as i64;
+            self.check_target(frame, pc as i64 + offset)?;
+        }
+        return Ok(());
+    }
+
+    fn check_field_access(
+        &self,
+        frame: &mut FrameState,
+        opcode: u8,
+        index: usize,
+    ) -> Result<(), String> {
+        let (class_name, descriptor) = match self.constant(index)? {
+            Constant::FieldReference(field_ref) => (
+                field_ref.class_name().to_string(),
+                field_ref.descriptor().to_string(),
+            ),
+            _ => return Err(format!("Illegal type at constant pool entry {}", index)),
+        };
+        let field_type = VerificationType::from_descriptor(&descriptor)?;
+        let holder = VerificationType::Reference(class_name.clone());
+        match opcode {
+            // getstatic
+            0xb2 => frame.push(field_type)?,
+            // putstatic
+            0xb3 => frame.pop_expect(&field_type)?,
+            // getfield
+            0xb4 => {
+                frame.pop_expect(&holder)?;
+                frame.push(field_type)?;
+            }
+            // putfield, a constructor may set the fields of its own class before super()
+            _ => {
+                frame.pop_expect(&field_type)?;
+                let object = frame.pop()?;
+                let own_field = object == VerificationType::UninitializedThis
+                    && class_name == self.class.name();
+                if !own_field && !self.is_assignable(&object, &holder)? {
+                    return Err(format!(
+                        "Bad type {} on operand stack for {}",
+                        object.name(),
+                        holder.name()
+                    ));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    fn check_invoke(&self, frame: &mut FrameState, opcode: u8, pc: usize) -> Result<(), String> {
+        let index = self.read_u16(pc + 1);
+        let (class_name, name, descriptor) = match (self.constant(index)?, opcode) {
+            (Constant::MethodReference(method_ref), 0xb6..=0xb8) => (
+                method_ref.class_name().to_string(),
+                method_ref.name().to_string(),
+                method_ref.descriptor().to_string(),
+            ),
+            (Constant::InterfaceMethodReference(method_ref), 0xb7..=0xb9) => (
+                method_ref.class_name().to_string(),
+                method_ref.name().to_string(),
+                method_ref.descriptor().to_string(),
+            ),
+            (Constant::InvokeDynamic(invoke_dynamic), 0xba) => (
+                JAVA_LANG_OBJECT.to_string(),
+                invoke_dynamic.name().to_string(),
+                invoke_dynamic.descriptor().to_string(),
+            ),
+            _ => return Err(format!("Illegal type at constant pool entry {}", index)),
+        };
+        if name == "<clinit>" || (name.starts_with('<') && (name != "<init>" || opcode != 0xb7)) {
+            return Err(format!("Illegal call to {}", name));
+        }
+        let (parameters, return_type) = parse_method_descriptor(&descriptor)?;
+        if opcode == 0xb9 {
+            let slots: usize = parameters
+                .iter()
+                .map(|p| if p.is_category2() { 2 } else { 1 })
+                .sum();
+            if self.read_u8(pc + 3) != slots + 1 || self.read_u8(pc + 4) != 0 {
+                return Err("Inconsistent args count operand in invokeinterface".to_string());
+            }
+        }
+        if opcode == 0xba && self.read_u16(pc + 3) != 0 {
+            return Err("Third and fourth operand bytes of invokedynamic must be zero".to_string());
+        }
+        for parameter in parameters.iter().rev() {
+            frame.pop_expect(parameter)?;
+        }
+        match opcode {
+            // invokevirtual
+            0xb6 => frame.pop_expect(&VerificationType::Reference(class_name.clone()))?,
+            // invokespecial
+            0xb7 if name == "<init>" => {
+                if return_type.is_some() {
+                    return Err("Constructor must return void".to_string());
+                }
+                let object = frame.pop()?;
+                self.initialize_object(frame, &object, &class_name)?;
+            }
+            0xb7 => {
+                let current = VerificationType::Reference(self.class.name().to_string());
+                frame.pop_expect(&current)?;
+            }
+            // invokeinterface
+            0xb9 => {
+                frame.pop_initialized_reference()?;
+            }
+            _ => {}
+        }
+        if let Some(return_type) = return_type {
+            frame.push(return_type)?;
+        }
+        return Ok(());
+    }
+
+    /// replaces every occurrence of the uninitialized object with the initialized class
+    fn initialize_object(
+        &self,
+        frame: &mut FrameState,
+        object: &VerificationType,
+        class_name: &str,
+    ) -> Result<(), String> {
+        let initialized = match object {
+            VerificationType::UninitializedThis => {
+                let is_super =
+                    self.class.super_class_name().map(|s| s.as_str()) == Some(class_name);
+                if class_name != self.class.name() && !is_super {
+                    return Err(format!("Bad <init> method call to {}", class_name));
+                }
+                frame.frame.flag_this_uninit = false;
+                self.class.name().to_string()
+            }
+            VerificationType::Uninitialized(offset) => {
+                let created = self.class_name_at(self.read_u16(offset + 1))?;
+                if created != class_name {
+                    return Err(format!("Bad <init> method call to {}", class_name));
+                }
+                created
+            }
+            _ => return Err(format!("Bad type {} for <init> method call", object.name())),
+        };
+        let initialized = VerificationType::Reference(initialized);
+        for local in frame.frame.locals.iter_mut() {
+            if local == object {
+                *local = initialized.clone();
+            }
+        }
+        for item in frame.frame.stack.iter_mut() {
+            if item == object {
+                *item = initialized.clone();
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// the conversions from i2l to d2f, as the kinds of `primitive_type`
+const CONVERSIONS: [(u8, u8); 12] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (1, 0),
+    (1, 2),
+    (1, 3),
+    (2, 0),
+    (2, 1),
+    (2, 3),
+    (3, 0),
+    (3, 1),
+    (3, 2),
+];
+
+/// int, long, float and double, in the order the opcodes use
+fn primitive_type(kind: u8) -> VerificationType {
+    return match kind {
+        0 => VerificationType::Integer,
+        1 => VerificationType::Long,
+        2 => VerificationType::Float,
+        _ => VerificationType::Double,
+    };
+}
+
+/// the value type of the primitive array loads and stores, the kinds are
+/// int, long, float, double, reference, byte, char and short as the opcodes order them
+fn array_value_type(kind: u8) -> VerificationType {
+    return match kind {
+        0..=3 => primitive_type(kind),
+        _ => VerificationType::Integer,
+    };
+}
+
+/// checks the array of a load or store from iaload to saload, and returns
+/// the type of its elements on the operand stack
+fn array_element(kind: u8, array: &VerificationType) -> Result<VerificationType, String> {
+    let name = match array {
+        VerificationType::Null if kind == 4 => return Ok(VerificationType::Null),
+        VerificationType::Null => return Ok(array_value_type(kind)),
+        VerificationType::Reference(name) => name.as_str(),
+        _ => return Err(format!("Bad type {} for an array", array.name())),
+    };
+    let valid = match kind {
+        0 => name == "[I",
+        1 => name == "[J",
+        2 => name == "[F",
+        3 => name == "[D",
+        4 => name.starts_with("[L") || name.starts_with("[["),
+        5 => name == "[B" || name == "[Z",
+        6 => name == "[C",
+        _ => name == "[S",
+    };
+    if !valid {
+        return Err(format!(
+            "Bad type {} for this array instruction",
+            array.name()
+        ));
+    }
+    if kind == 4 {
+        return component_type(name).ok_or_else(|| format!("Bad array type {}", name));
+    }
+    return Ok(array_value_type(kind));
+}
+
+/// the frame an instruction is changing
+struct FrameState<'a, 'b> {
+    frame: StackFrame,
+    verifier: &'b MethodVerifier<'a>,
+}
+
+impl<'a, 'b> FrameState<'a, 'b> {
+    fn push(&mut self, value: VerificationType) -> Result<(), String> {
+        let category2 = value.is_category2();
+        self.frame.stack.push(value);
+        if category2 {
+            self.frame.stack.push(VerificationType::Top);
+        }
+        if self.frame.stack.len() > self.verifier.max_stack {
+            return Err("Exceeded max stack size".to_string());
+        }
+        return Ok(());
+    }
+
+    /// pops a category 1 value
+    fn pop(&mut self) -> Result<VerificationType, String> {
+        return match self.frame.stack.pop() {
+            Some(VerificationType::Top) => Err("Bad type top on operand stack".to_string()),
+            Some(value) => Ok(value),
+            None => Err("Unable to pop operand off an empty stack".to_string()),
+        };
+    }
+
+    fn pop_expect(&mut self, expected: &VerificationType) -> Result<(), String> {
+        if expected.is_category2() {
+            let length = self.frame.stack.len();
+            if length == 0 {
+                return Err("Unable to pop operand off an empty stack".to_string());
+            }
+            if length < 2
+                || self.frame.stack[length - 1] != VerificationType::Top
+                || self.frame.stack[length - 2] != *expected
+            {
+                return Err(format!("Expecting to find {} on stack", expected.name()));
+            }
+            self.frame.stack.truncate(length - 2);
+            return Ok(());
+        }
+        let value = self.pop()?;
+        if !self.verifier.is_assignable(&value, expected)? {
+            return Err(format!(
+                "Bad type {} on operand stack, expecting {}",
+                value.name(),
+                expected.name()
+            ));
+        }
+        return Ok(());
+    }
+
+    fn pop_reference(&mut self) -> Result<VerificationType, String> {
+        let value = self.pop()?;
+        if !value.is_reference() {
+            return Err(format!(
+                "Expecting to find object/array on stack, found {}",
+                value.name()
+            ));
+        }
+        return Ok(value);
+    }
+
+    fn pop_initialized_reference(&mut self) -> Result<VerificationType, String> {
+        let value = self.pop()?;
+        if !value.is_initialized_reference() {
+            return Err(format!(
+                "Expecting to find object/array on stack, found {}",
+                value.name()
+            ));
+        }
+        return Ok(value);
+    }
+
+    fn pop_array(&mut self) -> Result<VerificationType, String> {
+        let value = self.pop_initialized_reference()?;
+        if let VerificationType::Reference(name) = &value {
+            if !name.starts_with('[') {
+                return Err(format!(
+                    "Expecting to find array on stack, found {}",
+                    value.name()
+                ));
+            }
+        }
+        return Ok(value);
+    }
+
+    /// checks that the `depth` values on top of the stack don't split a long or double
+    fn check_boundary(&self, depth: usize) -> Result<(), String> {
+        let length = self.frame.stack.len();
+        if length < depth {
+            return Err("Unable to pop operand off an empty stack".to_string());
+        }
+        if self.frame.stack[length - depth] == VerificationType::Top {
+            return Err("Bad type top on operand stack".to_string());
+        }
+        return Ok(());
+    }
+
+    fn pop_entries(&mut self, count: usize) -> Result<Vec<VerificationType>, String> {
+        let length = self.frame.stack.len();
+        return Ok(self.frame.stack.split_off(length - count));
+    }
+
+    /// the dup instructions, `count` entries are copied below `skip` more entries
+    fn duplicate(&mut self, count: usize, skip: usize) -> Result<(), String> {
+        self.check_boundary(count)?;
+        if skip > 0 {
+            self.check_boundary(count + skip)?;
+        }
+        let values = self.pop_entries(count)?;
+        let skipped = self.pop_entries(skip)?;
+        self.frame.stack.extend(values.iter().cloned());
+        self.frame.stack.extend(skipped);
+        self.frame.stack.extend(values);
+        if self.frame.stack.len() > self.verifier.max_stack {
+            return Err("Exceeded max stack size".to_string());
+        }
+        return Ok(());
+    }
+
+    fn check_local(&self, index: usize, category2: bool) -> Result<(), String> {
+        let last = if category2 { index + 1 } else { index };
+        if last >= self.verifier.max_locals {
+            return Err(format!("Illegal local variable number {}", index));
+        }
+        return Ok(());
+    }
+
+    /// checks the local of a load or iinc
+    fn check_load(&self, index: usize, expected: &VerificationType) -> Result<(), String> {
+        self.check_local(index, expected.is_category2())?;
+        if self.frame.locals[index] != *expected {
+            return Err(format!(
+                "Bad local variable type {} for {}",
+                self.frame.locals[index].name(),
+                expected.name()
+            ));
+        }
+        return Ok(());
+    }
+
+    fn load(&mut self, index: usize, expected: &VerificationType) -> Result<(), String> {
+        self.check_load(index, expected)?;
+        return self.push(expected.clone());
+    }
+
+    fn load_reference(&mut self, index: usize) -> Result<(), String> {
+        self.check_local(index, false)?;
+        let value = self.frame.locals[index].clone();
+        if !value.is_reference() {
+            return Err(format!(
+                "Bad local variable type {} for a reference",
+                value.name()
+            ));
+        }
+        return self.push(value);
+    }
+
+    fn store(&mut self, index: usize, value: VerificationType) -> Result<(), String> {
+        self.pop_expect(&value)?;
+        return self.set_local(index, value);
+    }
+
+    fn store_reference(&mut self, index: usize) -> Result<(), String> {
+        let value = self.pop_reference()?;
+        return self.set_local(index, value);
+    }
+
+    fn set_local(&mut self, index: usize, value: VerificationType) -> Result<(), String> {
+        let category2 = value.is_category2();
+        self.check_local(index, category2)?;
+        // writing over the second half of a long or double makes its first half unusable
+        if index > 0 && self.frame.locals[index - 1].is_category2() {
+            self.frame.locals[index - 1] = VerificationType::Top;
+        }
+        self.frame.locals[index] = value;
+        if category2 {
+            self.frame.locals[index + 1] = VerificationType::Top;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::class_verifier::ClassVerifier;
+    use crate::oops::class::Class;
+    use lark_classfile::class_file::ClassFile;
+
+    /// assembles a class file with static methods, each one is
+    /// (name, descriptor, max_stack, max_locals, code, raw StackMapTable)
+    struct TestClass {
+        major_version: u16,
+        pool: Vec<Vec<u8>>,
+        methods: Vec<Vec<u8>>,
+    }
+
+    impl TestClass {
+        fn new(major_version: u16) -> TestClass {
+            let mut class = TestClass {
+                major_version,
+                pool: vec![],
+                methods: vec![],
+            };
+            class.class("Test");
+            class.class("java/lang/Object");
+            return class;
+        }
+
+        fn utf8(&mut self, string: &str) -> u16 {
+            let mut entry = vec![1u8];
+            entry.extend_from_slice(&(string.len() as u16).to_be_bytes());
+            entry.extend_from_slice(string.as_bytes());
+            self.pool.push(entry);
+            return self.pool.len() as u16;
+        }
+
+        fn class(&mut self, name: &str) -> u16 {
+            let name_index = self.utf8(name);
+            let mut entry = vec![7u8];
+            entry.extend_from_slice(&name_index.to_be_bytes());
+            self.pool.push(entry);
+            return self.pool.len() as u16;
+        }
+
+        fn method(
+            &mut self,
+            descriptor: &str,
+            max_stack: u16,
+            max_locals: u16,
+            code: &[u8],
+            stack_map: Option<&[u8]>,
+        ) {
+            let name_index = self.utf8("test");
+            let descriptor_index = self.utf8(descriptor);
+            let code_index = self.utf8("Code");
+            let mut attributes = vec![];
+            if let Some(stack_map) = stack_map {
+                let stack_map_index = self.utf8("StackMapTable");
+                attributes.extend_from_slice(&stack_map_index.to_be_bytes());
+                attributes.extend_from_slice(&(stack_map.len() as u32).to_be_bytes());
+                attributes.extend_from_slice(stack_map);
+            }
+            let mut info = vec![];
+            info.extend_from_slice(&max_stack.to_be_bytes());
+            info.extend_from_slice(&max_locals.to_be_bytes());
+            info.extend_from_slice(&(code.len() as u32).to_be_bytes());
+            info.extend_from_slice(code);
+            info.extend_from_slice(&[0, 0]);
+            info.extend_from_slice(&(stack_map.is_some() as u16).to_be_bytes());
+            info.extend_from_slice(&attributes);
+
+            let mut method = vec![0x00, 0x09];
+            method.extend_from_slice(&name_index.to_be_bytes());
+            method.extend_from_slice(&descriptor_index.to_be_bytes());
+            method.extend_from_slice(&[0, 1]);
+            method.extend_from_slice(&code_index.to_be_bytes());
+            method.extend_from_slice(&(info.len() as u32).to_be_bytes());
+            method.extend_from_slice(&info);
+            self.methods.push(method);
+        }
+
+        fn verify(&self) -> Result<(), String> {
+            let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0];
+            bytes.extend_from_slice(&self.major_version.to_be_bytes());
+            bytes.extend_from_slice(&(self.pool.len() as u16 + 1).to_be_bytes());
+            for entry in &self.pool {
+                bytes.extend_from_slice(entry);
+            }
+            // public super, this_class #2, super_class #4, no interfaces and fields
+            bytes.extend_from_slice(&[0x00, 0x21, 0, 2, 0, 4, 0, 0, 0, 0]);
+            bytes.extend_from_slice(&(self.methods.len() as u16).to_be_bytes());
+            for method in &self.methods {
+                bytes.extend_from_slice(method);
+            }
+            bytes.extend_from_slice(&[0, 0]);
+            let class = Class::new(ClassFile::parse(bytes).unwrap());
+            let result = ClassVerifier::verify_class(&(*class).borrow(), None);
+            return result;
+        }
+    }
+
+    fn verify_method(
+        descriptor: &str,
+        max_stack: u16,
+        max_locals: u16,
+        code: &[u8],
+    ) -> Result<(), String> {
+        let mut class = TestClass::new(52);
+        class.method(descriptor, max_stack, max_locals, code, None);
+        return class.verify();
+    }
+
+    #[test]
+    fn test_valid_method() {
+        // iload_0, iload_1, iadd, ireturn
+        let result = verify_method("(II)I", 2, 2, &[0x1a, 0x1b, 0x60, 0xac]);
+        assert_eq!(result, Ok(()));
+        // lload_0, l2i, ireturn
+        let result = verify_method("(J)I", 2, 2, &[0x1e, 0x88, 0xac]);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_bad_operand_type() {
+        // fconst_0, iload_0, iadd, ireturn
+        let result = verify_method("(I)I", 2, 1, &[0x0b, 0x1a, 0x60, 0xac]);
+        assert!(result
+            .unwrap_err()
+            .contains("Bad type float on operand stack"));
+        // iload_0, lreturn
+        let result = verify_method("(I)J", 2, 1, &[0x1a, 0xad]);
+        assert!(result
+            .unwrap_err()
+            .contains("Expecting to find long on stack"));
+        // the first half of a long can't be loaded as an int
+        let result = verify_method("(J)I", 1, 2, &[0x1a, 0xac]);
+        assert!(result.unwrap_err().contains("Bad local variable type long"));
+    }
+
+    #[test]
+    fn test_stack_and_locals_limits() {
+        // iconst_0, iconst_1, iadd, ireturn
+        let result = verify_method("()I", 1, 0, &[0x03, 0x04, 0x60, 0xac]);
+        assert!(result.unwrap_err().contains("Exceeded max stack size"));
+        // iload_1, ireturn
+        let result = verify_method("(I)I", 1, 1, &[0x1b, 0xac]);
+        assert!(result
+            .unwrap_err()
+            .contains("Illegal local variable number 1"));
+        // iadd, ireturn
+        let result = verify_method("()I", 2, 0, &[0x60, 0xac]);
+        assert!(result.unwrap_err().contains("empty stack"));
+    }
+
+    #[test]
+    fn test_stack_map_frames() {
+        // iload_0, ifeq 6, iconst_1, ireturn, iconst_0, ireturn
+        let code = [0x1a, 0x99, 0x00, 0x05, 0x04, 0xac, 0x03, 0xac];
+        let result = verify_method("(I)I", 1, 1, &code);
+        assert!(result
+            .unwrap_err()
+            .contains("Expecting a stackmap frame at branch target 6"));
+
+        // one same_frame at offset 6
+        let mut class = TestClass::new(52);
+        class.method("(I)I", 1, 1, &code, Some(&[0x00, 0x01, 0x06]));
+        assert_eq!(class.verify(), Ok(()));
+
+        // a same_locals_1_stack_item_frame with an int that isn't on the stack
+        let mut class = TestClass::new(52);
+        class.method("(I)I", 1, 1, &code, Some(&[0x00, 0x01, 0x46, 0x01]));
+        assert!(class
+            .verify()
+            .unwrap_err()
+            .contains("Inconsistent stack height"));
+    }
+
+    #[test]
+    fn test_bad_code() {
+        // nop
+        let result = verify_method("()V", 0, 0, &[0x00]);
+        assert!(result
+            .unwrap_err()
+            .contains("Falling off the end of the code"));
+        // goto 1, into the middle of itself
+        let result = verify_method("()V", 0, 0, &[0xa7, 0x00, 0x01]);
+        assert!(result.unwrap_err().contains("Illegal branch target 1"));
+        // sipush with one byte
+        let result = verify_method("()V", 1, 0, &[0x11, 0x00]);
+        assert!(result.unwrap_err().contains("Truncated instruction"));
+        // the reserved impdep1
+        let result = verify_method("()V", 0, 0, &[0xfe, 0xb1]);
+        assert!(result.unwrap_err().contains("Bad instruction"));
+    }
+
+    #[test]
+    fn test_uninitialized_object() {
+        // new java/lang/Object, areturn
+        let result = verify_method("()Ljava/lang/Object;", 1, 0, &[0xbb, 0x00, 0x04, 0xb0]);
+        assert!(result
+            .unwrap_err()
+            .contains("Method expects a different return type"));
+    }
+
+    #[test]
+    fn test_unloaded_class() {
+        // aload_0, areturn, a String is only a Number if the classes say so
+        let result = verify_method(
+            "(Ljava/lang/String;)Ljava/lang/Number;",
+            1,
+            1,
+            &[0x2a, 0xb0],
+        );
+        assert!(result
+            .unwrap_err()
+            .contains("Could not load class java/lang/"));
+    }
+
+    #[test]
+    fn test_old_class_file() {
+        let mut class = TestClass::new(49);
+        class.method("()V", 0, 0, &[0x00, 0xb1], None);
+        assert_eq!(class.verify(), Ok(()));
+
+        // jsr 4, return, astore_0, ret 0
+        let subroutine = [0xa8, 0x00, 0x04, 0xb1, 0x4b, 0xa9, 0x00];
+        let mut class = TestClass::new(49);
+        class.method("()V", 1, 1, &subroutine, None);
+        assert_eq!(class.verify(), Ok(()));
+        // the same in a version 50 class file, without a stack map
+        let mut class = TestClass::new(50);
+        class.method("()V", 1, 1, &subroutine, None);
+        assert_eq!(class.verify(), Ok(()));
+        // ret 1 with one local
+        let mut class = TestClass::new(50);
+        class.method(
+            "()V",
+            1,
+            1,
+            &[0xa8, 0x00, 0x04, 0xb1, 0x4b, 0xa9, 0x01],
+            None,
+        );
+        assert!(class
+            .verify()
+            .unwrap_err()
+            .contains("Illegal local variable number 1"));
+
+        // goto 1, into the middle of itself
+        let mut class = TestClass::new(49);
+        class.method("()V", 0, 0, &[0xa7, 0x00, 0x01], None);
+        assert!(class
+            .verify()
+            .unwrap_err()
+            .contains("Illegal branch target 1"));
+        // nop
+        let mut class = TestClass::new(49);
+        class.method("()V", 0, 0, &[0x00], None);
+        assert!(class
+            .verify()
+            .unwrap_err()
+            .contains("Falling off the end of the code"));
+        // lload_0 with one local
+        let mut class = TestClass::new(49);
+        class.method("()V", 2, 1, &[0x1e, 0x58, 0xb1], None);
+        assert!(class
+            .verify()
+            .unwrap_err()
+            .contains("Illegal local variable number 0"));
+    }
 }
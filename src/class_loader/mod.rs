@@ -3,4 +3,6 @@ pub mod app_class_loader;
 pub mod bootstrap_class_loader;
 mod class_init_preparation;
 mod class_linker;
-mod class_verifier;
+pub mod class_verifier;
+#[cfg(test)]
+pub mod test_classes;
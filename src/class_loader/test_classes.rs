@@ -0,0 +1,249 @@
+use crate::class_loader::app_class_loader::ClassLoader;
+use crate::class_loader::class_init_preparation::ClassPreparation;
+use crate::class_loader::class_linker::ClassLinker;
+use crate::oops::access_flags::PUBLIC;
+use crate::oops::class::Class;
+use crate::oops::object::{MetaData, Object};
+use crate::utils::boxed;
+use lark_classfile::class_file::ClassFile;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// assembles a class file for unit tests, the constant pool entries are
+/// added first so that their indices can go into the code of the methods
+pub struct ClassBuilder {
+    pool: Vec<Vec<u8>>,
+    access_flags: u16,
+    this_class: u16,
+    super_class: u16,
+    interfaces: Vec<u16>,
+    fields: Vec<Vec<u8>>,
+    methods: Vec<Vec<u8>>,
+}
+
+impl ClassBuilder {
+    /// a public class extending java/lang/Object
+    pub fn new(name: &str) -> ClassBuilder {
+        let mut builder = ClassBuilder {
+            pool: vec![],
+            access_flags: PUBLIC,
+            this_class: 0,
+            super_class: 0,
+            interfaces: vec![],
+            fields: vec![],
+            methods: vec![],
+        };
+        builder.this_class = builder.class_ref(name);
+        builder.super_class = builder.class_ref("java/lang/Object");
+        return builder;
+    }
+
+    pub fn access_flags(&mut self, access_flags: u16) -> &mut Self {
+        self.access_flags = access_flags;
+        return self;
+    }
+
+    pub fn super_class(&mut self, name: &str) -> &mut Self {
+        self.super_class = self.class_ref(name);
+        return self;
+    }
+
+    pub fn interface(&mut self, name: &str) -> &mut Self {
+        let index = self.class_ref(name);
+        self.interfaces.push(index);
+        return self;
+    }
+
+    fn entry(&mut self, tag: u8, data: &[u8]) -> u16 {
+        let mut entry = vec![tag];
+        entry.extend_from_slice(data);
+        self.pool.push(entry);
+        return self.pool.len() as u16;
+    }
+
+    pub fn utf8(&mut self, string: &str) -> u16 {
+        let mut data = (string.len() as u16).to_be_bytes().to_vec();
+        data.extend_from_slice(string.as_bytes());
+        return self.entry(1, &data);
+    }
+
+    pub fn int(&mut self, value: i32) -> u16 {
+        return self.entry(3, &value.to_be_bytes());
+    }
+
+    pub fn class_ref(&mut self, name: &str) -> u16 {
+        let name_index = self.utf8(name);
+        return self.entry(7, &name_index.to_be_bytes());
+    }
+
+    pub fn field_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        return self.member_ref(9, class, name, descriptor);
+    }
+
+    pub fn method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        return self.member_ref(10, class, name, descriptor);
+    }
+
+    pub fn interface_method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        return self.member_ref(11, class, name, descriptor);
+    }
+
+    fn member_ref(&mut self, tag: u8, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class_index = self.class_ref(class);
+        let name_index = self.utf8(name);
+        let descriptor_index = self.utf8(descriptor);
+        let mut name_and_type = name_index.to_be_bytes().to_vec();
+        name_and_type.extend_from_slice(&descriptor_index.to_be_bytes());
+        let name_and_type_index = self.entry(12, &name_and_type);
+        let mut data = class_index.to_be_bytes().to_vec();
+        data.extend_from_slice(&name_and_type_index.to_be_bytes());
+        return self.entry(tag, &data);
+    }
+
+    pub fn field(&mut self, access_flags: u16, name: &str, descriptor: &str) -> &mut Self {
+        let info = self.member_info(access_flags, name, descriptor, 0);
+        self.fields.push(info);
+        return self;
+    }
+
+    /// a method without code, for native and abstract methods
+    pub fn method(&mut self, access_flags: u16, name: &str, descriptor: &str) -> &mut Self {
+        let info = self.member_info(access_flags, name, descriptor, 0);
+        self.methods.push(info);
+        return self;
+    }
+
+    /// a method running `code` with room for 8 operands and 8 locals
+    pub fn method_with_code(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        code: &[u8],
+    ) -> &mut Self {
+        let mut info = self.member_info(access_flags, name, descriptor, 1);
+        let attribute_name = self.utf8("Code");
+        info.extend_from_slice(&attribute_name.to_be_bytes());
+        info.extend_from_slice(&(code.len() as u32 + 12).to_be_bytes());
+        info.extend_from_slice(&[0, 8, 0, 8]);
+        info.extend_from_slice(&(code.len() as u32).to_be_bytes());
+        info.extend_from_slice(code);
+        info.extend_from_slice(&[0, 0, 0, 0]);
+        self.methods.push(info);
+        return self;
+    }
+
+    fn member_info(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        attributes_count: u16,
+    ) -> Vec<u8> {
+        let mut info = access_flags.to_be_bytes().to_vec();
+        info.extend_from_slice(&self.utf8(name).to_be_bytes());
+        info.extend_from_slice(&self.utf8(descriptor).to_be_bytes());
+        info.extend_from_slice(&attributes_count.to_be_bytes());
+        return info;
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52];
+        bytes.extend_from_slice(&(self.pool.len() as u16 + 1).to_be_bytes());
+        for entry in &self.pool {
+            bytes.extend_from_slice(entry);
+        }
+        bytes.extend_from_slice(&self.access_flags.to_be_bytes());
+        bytes.extend_from_slice(&self.this_class.to_be_bytes());
+        bytes.extend_from_slice(&self.super_class.to_be_bytes());
+        bytes.extend_from_slice(&(self.interfaces.len() as u16).to_be_bytes());
+        for index in &self.interfaces {
+            bytes.extend_from_slice(&index.to_be_bytes());
+        }
+        for members in &[&self.fields, &self.methods] {
+            bytes.extend_from_slice(&(members.len() as u16).to_be_bytes());
+            for member in members.iter() {
+                bytes.extend_from_slice(member);
+            }
+        }
+        bytes.extend_from_slice(&[0, 0]);
+        return bytes;
+    }
+}
+
+/// a class loader object defining classes without a VM, java/lang/Object
+/// and the java/lang/Class holding the loader are defined by it as well
+pub struct TestLoader {
+    loader: Rc<RefCell<Object>>,
+}
+
+impl TestLoader {
+    pub fn new() -> TestLoader {
+        let mut loader = Object::new(boxed(Class::default()));
+        loader.set_meta_data(MetaData::ClassLoader(boxed(ClassLoader::new())));
+        let test_loader = TestLoader {
+            loader: boxed(loader),
+        };
+        let mut object = ClassBuilder::new("java/lang/Object");
+        object.super_class = 0;
+        test_loader.define(&object);
+        let mut class = ClassBuilder::new("java/lang/Class");
+        class.field(0, "classLoader", "Ljava/lang/ClassLoader;");
+        test_loader.define(&class);
+        test_loader.set_java_class(&test_loader.find("java/lang/Object"));
+        return test_loader;
+    }
+
+    /// links and prepares the class, its superclass and interfaces have to
+    /// be defined before
+    pub fn define(&self, builder: &ClassBuilder) -> Rc<RefCell<Class>> {
+        let class = Class::new(ClassFile::parse(builder.bytes()).unwrap());
+        let super_name = (*class).borrow().super_class_name().cloned();
+        if let Some(super_name) = super_name {
+            let super_class = self.find(super_name.as_str());
+            (*class).borrow_mut().set_super_class(super_class);
+        }
+        let interface_names = (*class).borrow().interfaces_name().clone();
+        if !interface_names.is_empty() {
+            let interfaces = interface_names
+                .iter()
+                .map(|name| self.find(name.as_str()))
+                .collect();
+            (*class).borrow_mut().set_interfaces(interfaces);
+        }
+        ClassLinker::link_methods(&class);
+        ClassPreparation::prepare(&class);
+
+        let class_loader = (*self.loader).borrow().get_class_loader();
+        (*class).borrow_mut().set_class_loader(class_loader.clone());
+        let name = (*class).borrow().name().to_string();
+        (*class_loader)
+            .borrow_mut()
+            .class_map
+            .insert(name.clone(), class.clone());
+        if name != "java/lang/Object" {
+            self.set_java_class(&class);
+        }
+        return class;
+    }
+
+    /// the java/lang/Class object of the class, it tells the loader
+    fn set_java_class(&self, class: &Rc<RefCell<Class>>) {
+        let mut java_class = Object::new(self.find("java/lang/Class"));
+        java_class.set_meta(class.clone());
+        java_class.set_ref_var(
+            "classLoader",
+            "Ljava/lang/ClassLoader;",
+            self.loader.clone(),
+        );
+        (*class)
+            .borrow_mut()
+            .set_java_class(Some(boxed(java_class)));
+    }
+
+    pub fn find(&self, name: &str) -> Rc<RefCell<Class>> {
+        let class_loader = (*self.loader).borrow().get_class_loader();
+        let class = (*class_loader).borrow().find_class(name);
+        return class.unwrap_or_else(|| panic!("{} is not defined", name));
+    }
+}
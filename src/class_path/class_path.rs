@@ -6,12 +6,17 @@ use crate::cmd::Cmd;
 use std::error::Error;
 use std::ffi::OsString;
 use std::fmt::Display;
+use std::fs;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, MAIN_SEPARATOR};
 use std::{env, fmt};
 
+/// the separator of the entries of -cp, CLASSPATH and java.class.path
+#[cfg(windows)]
 pub static PATH_LIST_SEPARATOR: char = ';';
+#[cfg(not(windows))]
+pub static PATH_LIST_SEPARATOR: char = ':';
 
 pub trait Entry {
     fn read_class(&self, class_name: &str) -> Result<(Vec<u8>, Box<dyn Entry>), FindClassError>;
@@ -22,8 +27,7 @@ pub trait Entry {
 pub fn new_entry(path: &String) -> Box<dyn Entry> {
     if path.contains(PATH_LIST_SEPARATOR) {
         return Box::new(composite_entry::new(path));
-    } else if path.ends_with('*') {
-        println!("wildcard");
+    } else if is_wildcard(path) {
         return Box::new(composite_entry::new_wildcard_entry(path));
     } else if path.ends_with(".zip")
         || path.ends_with(".ZIP")
@@ -57,22 +61,27 @@ impl Display for FindClassError {
 }
 
 pub struct ClassPath {
+    /// the JRE the boot classes come from, java.home
+    jre_dir: String,
     boot_class_path: Option<Box<dyn Entry>>,
+    /// the entries of java.class.path in search order, the application
+    /// class loader reads the classes from them
+    user_class_path: Vec<String>,
 }
 
 impl ClassPath {
     pub fn new() -> ClassPath {
         return ClassPath {
+            jre_dir: String::new(),
             boot_class_path: None,
+            user_class_path: Vec::new(),
         };
     }
 
     pub fn parse(jre_option: &String, cp_option: &Vec<String>) -> ClassPath {
-        let mut class_path = ClassPath {
-            boot_class_path: None,
-        };
+        let mut class_path = ClassPath::new();
         class_path.boot_and_ext_class_path(jre_option);
-        //        class_path.user_class_path(cp_option);
+        class_path.parse_user_class_path(cp_option);
         return class_path;
     }
 
@@ -80,50 +89,146 @@ impl ClassPath {
         let jre_dir = ClassPath::get_jre_dir(jre_option);
         let jre_lib_path = jre_dir.clone() + "/lib" + "/*";
         self.boot_class_path = Some(Box::new(new_wildcard_entry(&jre_lib_path)));
-        println!(
-            "boot:{}\n",
-            self.boot_class_path.as_ref().unwrap().to_string()
-        );
+        self.jre_dir = jre_dir;
     }
 
+    /// -Xjre, ./jre or the JRE of JAVA_HOME, which may be a JRE itself
     fn get_jre_dir(jre_option: &String) -> String {
         let path = Path::new(jre_option);
-        if jre_option.is_empty() && path.exists() {
-            return String::from(jre_option);
+        if !jre_option.is_empty() && path.exists() {
+            return absolute_path(jre_option);
         }
         if Path::new("./jre").exists() {
-            return "./jre".to_string();
+            return absolute_path("./jre");
         }
         let java_home =
             env::vars_os().find(|(key, _value)| return key == &OsString::from("JAVA_HOME"));
         if java_home.is_some() {
             let (_key, value) = java_home.unwrap();
-            return value.to_str().unwrap().to_string() + "/jre";
+            let java_home = value.to_str().unwrap().to_string();
+            let jre = java_home.clone() + "/jre";
+            if Path::new(&jre).exists() {
+                return absolute_path(&jre);
+            }
+            return absolute_path(&java_home);
         }
         panic!("Can not find jre folder!")
     }
 
-    fn user_class_path(&mut self, cp_option: &Vec<String>) {
-        let mut class_paths = Vec::with_capacity(cp_option.len());
-        for cp in cp_option {
-            if cp == "" {
-                let entry = new_entry(&".".to_string());
-                class_paths.push(entry);
+    #[inline]
+    pub fn jre_dir(&self) -> &String {
+        return &self.jre_dir;
+    }
+
+    /// the value of the sun.boot.class.path property, the jars of the lib
+    /// directory of the JRE
+    pub fn boot_class_path_property(&self) -> String {
+        let jars = expand_wildcard(&(self.jre_dir.clone() + "/lib/*"));
+        return jars.join(&PATH_LIST_SEPARATOR.to_string());
+    }
+
+    /// -cp wins over the CLASSPATH variable, the current directory is used
+    /// when neither is given
+    fn parse_user_class_path(&mut self, cp_option: &Vec<String>) {
+        let env_class_path;
+        let paths = if !cp_option.is_empty() {
+            cp_option
+        } else {
+            env_class_path = match env::var("CLASSPATH") {
+                Ok(value) => split_path_list(&value),
+                Err(_) => vec![".".to_string()],
+            };
+            &env_class_path
+        };
+        let mut class_paths = Vec::with_capacity(paths.len());
+        for cp in paths {
+            if cp.is_empty() {
+                class_paths.push(".".to_string());
+            } else if is_wildcard(cp) {
+                class_paths.append(&mut expand_wildcard(cp));
             } else {
-                let entry = new_entry(cp);
-                class_paths.push(entry);
+                class_paths.push(cp.clone());
             }
         }
-        //        self.user_class_path = Some(class_paths);
+        self.user_class_path = class_paths;
     }
 
+    /// -jar replaces the user class path with the jar and the entries of the
+    /// Class-Path attribute of its manifest, and names the main class
     pub fn handle_jar(&mut self, cmd: &mut Cmd) {
         if let Some(jar) = cmd.exec_jar_path() {
-            let entry = ZipEntry::new(jar);
-            cmd.set_class(entry.get_main_class().expect("jar中没有主清单属性"));
-            //            self.user_class_path.as_mut().unwrap().push(Box::new(entry));
+            let jar = jar.clone();
+            if !Path::new(&jar).is_file() {
+                eprintln!("Error: Unable to access jarfile {}", jar);
+                std::process::exit(1);
+            }
+            let entry = ZipEntry::new(&jar);
+            match entry.get_main_class() {
+                Some(main_class) => cmd.set_class(main_class),
+                None => {
+                    eprintln!("no main manifest attribute, in {}", jar);
+                    std::process::exit(1);
+                }
+            }
+            // the entries are URLs relative to the directory of the jar
+            let jar_dir = Path::new(&jar).parent().unwrap_or_else(|| Path::new(""));
+            let mut class_paths = vec![jar.clone()];
+            for path in entry.get_class_path() {
+                let path = jar_dir.join(path.trim_start_matches("file:"));
+                class_paths.push(path.to_str().unwrap().to_string());
+            }
+            self.user_class_path = class_paths;
         }
     }
+
+    /// the value of the java.class.path property
+    pub fn class_path_property(&self) -> String {
+        return self.user_class_path.join(&PATH_LIST_SEPARATOR.to_string());
+    }
+}
+
+/// the entries of a -cp or CLASSPATH value, an empty entry is the current directory
+pub fn split_path_list(paths: &str) -> Vec<String> {
+    return paths
+        .split(PATH_LIST_SEPARATOR)
+        .map(|path| if path.is_empty() { "." } else { path })
+        .map(|path| path.to_string())
+        .collect();
+}
+
+fn absolute_path(path: &str) -> String {
+    // windows canonical paths carry the verbatim prefix
+    return match fs::canonicalize(path) {
+        Ok(path) => path.to_str().unwrap().trim_start_matches(r"\\?\").to_string(),
+        Err(_) => path.to_string(),
+    };
+}
+
+/// only "*" and "dir/*" are wildcards, like in the java launcher, an entry
+/// such as "lib/foo*" is a path
+fn is_wildcard(path: &str) -> bool {
+    return match path.strip_suffix('*') {
+        Some(dir) => dir.is_empty() || dir.ends_with('/') || dir.ends_with(MAIN_SEPARATOR),
+        None => false,
+    };
+}
+
+/// "dir/*" stands for the jars in dir, sorted to keep the order stable
+fn expand_wildcard(path: &str) -> Vec<String> {
+    let dir = &path[..path.len() - 1];
+    let dir = if dir.is_empty() { "." } else { dir };
+    let mut jars = Vec::new();
+    if let Ok(read_dir) = fs::read_dir(dir) {
+        for dir_entry in read_dir.flatten() {
+            let file = dir_entry.path();
+            let name = file.to_str().unwrap_or("");
+            if file.is_file() && (name.ends_with(".jar") || name.ends_with(".JAR")) {
+                jars.push(name.to_string());
+            }
+        }
+    }
+    jars.sort();
+    return jars;
 }
 
 impl Entry for ClassPath {
@@ -155,6 +260,9 @@ impl Entry for ClassPath {
 
 #[cfg(test)]
 mod tests {
+    use crate::class_path::class_path::{
+        expand_wildcard, is_wildcard, split_path_list, ClassPath, PATH_LIST_SEPARATOR,
+    };
     use std::ffi::OsString;
     use std::fs::File;
     use std::io::{Error, Read};
@@ -198,4 +306,44 @@ mod tests {
         let bytes: Result<Vec<u8>, Error> = file.bytes().collect();
         return bytes.unwrap();
     }
+
+    #[test]
+    fn user_class_path() {
+        let value = format!("a.jar{0}{0}classes", PATH_LIST_SEPARATOR);
+        assert_eq!(split_path_list(&value), vec!["a.jar", ".", "classes"]);
+
+        let mut class_path = ClassPath::new();
+        class_path.parse_user_class_path(&vec!["".to_string(), "classes".to_string()]);
+        let expected = format!(".{}classes", PATH_LIST_SEPARATOR);
+        assert_eq!(class_path.class_path_property(), expected);
+    }
+
+    #[test]
+    fn wildcard() {
+        let dir = env::temp_dir().join(format!("lark-cp-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested.jar")).unwrap();
+        File::create(dir.join("b.jar")).unwrap();
+        File::create(dir.join("a.JAR")).unwrap();
+        File::create(dir.join("c.txt")).unwrap();
+        let wildcard = dir.join("*");
+        let jars = expand_wildcard(wildcard.to_str().unwrap());
+        let expected: Vec<String> = vec!["a.JAR", "b.jar"]
+            .iter()
+            .map(|name| dir.join(name).to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(jars, expected);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn wildcard_entries() {
+        assert!(is_wildcard("*"));
+        assert!(is_wildcard("lib/*"));
+        assert!(!is_wildcard("lib/foo*"));
+        assert!(!is_wildcard("lib"));
+
+        let mut class_path = ClassPath::new();
+        class_path.parse_user_class_path(&vec!["lib/foo*".to_string()]);
+        assert_eq!(class_path.class_path_property(), "lib/foo*");
+    }
 }
@@ -36,7 +36,7 @@ impl ZipEntry {
     }
 
     pub fn find_manifest(&self) -> Option<Vec<u8>> {
-        let index = self.file_cache.get("/META-INF/MANIFEST.MF");
+        let index = self.file_cache.get("META-INF/MANIFEST.MF");
         if index.is_some() {
             let size = *index.unwrap();
             let zip_file = self.file_cache.file.clone();
@@ -48,17 +48,51 @@ impl ZipEntry {
     }
 
     pub fn get_main_class(&self) -> Option<String> {
-        let jar_file_data = self.find_manifest().expect("This Jar Not Find MANIFEST.MF");
-        let data = String::from_utf8(jar_file_data).expect("get_main_class FromUtf8Error");
-        let lines = data.lines();
-        for line in lines {
-            let (key, value) = line.split_at(line.find(':').unwrap_or(0));
-            if key == "Main-Class" {
-                return Some(value.to_string());
+        return self.manifest_attribute("Main-Class");
+    }
+
+    /// the space separated entries of the Class-Path attribute
+    pub fn get_class_path(&self) -> Vec<String> {
+        return match self.manifest_attribute("Class-Path") {
+            Some(class_path) => class_path
+                .split_whitespace()
+                .map(|path| path.to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    fn manifest_attribute(&self, name: &str) -> Option<String> {
+        let jar_file_data = self.find_manifest()?;
+        let data = String::from_utf8(jar_file_data).expect("manifest FromUtf8Error");
+        return find_attribute(&data, name);
+    }
+}
+
+/// looks `name` up in the main section of a manifest, a line starting with
+/// a space continues the value of the line before it
+fn find_attribute(manifest: &str, name: &str) -> Option<String> {
+    let mut value: Option<String> = None;
+    for line in manifest.lines() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some(continuation) = line.strip_prefix(' ') {
+            if let Some(value) = value.as_mut() {
+                value.push_str(continuation);
+            }
+            continue;
+        }
+        if value.is_some() {
+            break;
+        }
+        if let Some((key, rest)) = line.split_once(':') {
+            if key.eq_ignore_ascii_case(name) {
+                value = Some(rest.trim_start().to_string());
             }
         }
-        return None;
     }
+    return value;
 }
 
 impl Entry for ZipEntry {
@@ -101,7 +135,7 @@ impl FileCache {
 
 #[cfg(test)]
 mod test {
-    use crate::class_path::zip_entry::ZipEntry;
+    use crate::class_path::zip_entry::{find_attribute, ZipEntry};
     use podio::ReadPodExt;
     use std::io::Read;
     use std::{fs, io};
@@ -177,4 +211,15 @@ mod test {
         }
         return bytes;
     }
+
+    #[test]
+    fn test_find_attribute() {
+        let manifest = "Manifest-Version: 1.0\r\nMain-Class: app.Main\r\nClass-Path: lib/a.jar\r\n  lib/b.jar\r\n\r\nName: app/\r\nMain-Class: other.Main\r\n";
+        assert_eq!(find_attribute(manifest, "Main-Class"), Some("app.Main".to_string()));
+        assert_eq!(
+            find_attribute(manifest, "class-path"),
+            Some("lib/a.jar lib/b.jar".to_string())
+        );
+        assert_eq!(find_attribute(manifest, "Launcher-Agent-Class"), None);
+    }
 }
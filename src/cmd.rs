@@ -1,78 +1,257 @@
-use crate::utils::vecs::flat_map;
+use crate::class_loader::class_verifier::VerifyMode;
+use crate::class_path::class_path::{split_path_list, PATH_LIST_SEPARATOR};
+use crate::runtime::stack::MIN_STACK_SIZE;
+use std::collections::VecDeque;
+use std::fs;
 
 pub struct Cmd {
     pub help_flag: bool,
+    /// -X, the help on the non-standard options
+    pub x_help_flag: bool,
     pub version_flag: bool,
+    pub show_version_flag: bool,
     pub verbose_class: bool,
     pub cp_option: Vec<String>,
     pub x_jre_option: String,
     pub class: String,
+    /// the arguments after the main class, passed to main
     pub args: Vec<String>,
     pub exec_jar_path: Option<String>,
+    /// -Xmx, in bytes
+    pub xmx_option: Option<usize>,
+    /// -Xms, in bytes
+    pub xms_option: Option<usize>,
+    /// -Xss, in bytes
+    pub xss_option: Option<usize>,
+    /// -Xverify, -noverify is -Xverify:none
+    pub verify_mode: VerifyMode,
+    /// the -D options in the order given, a later one wins
+    pub system_properties: Vec<(String, String)>,
+    /// the -ea, -da, -esa and -dsa options in the order given
+    pub assertion_options: Vec<String>,
 }
 
 impl Cmd {
     pub fn new() -> Cmd {
         return Cmd {
             help_flag: false,
+            x_help_flag: false,
             version_flag: false,
+            show_version_flag: false,
             verbose_class: false,
             cp_option: vec![],
             x_jre_option: String::new(),
             class: String::new(),
             args: Vec::new(),
             exec_jar_path: None,
+            xmx_option: None,
+            xms_option: None,
+            xss_option: None,
+            verify_mode: VerifyMode::Remote,
+            system_properties: Vec::new(),
+            assertion_options: Vec::new(),
         };
     }
 
+    /// parses the process arguments after JAVA_TOOL_OPTIONS, a bad command
+    /// line prints the usage and exits with status 1
     pub fn parse_cmd() -> Cmd {
-        let args: Vec<String> = std::env::args().collect();
-        let mut cmd: Cmd = Cmd::new();
-        for mut index in 0..args.len() {
-            let arg = args.get(index).unwrap();
-            if arg.starts_with("-Xjre") {
-                let mut array: Vec<&str> = arg.split(':').collect();
-                cmd.x_jre_option = array.remove(1).to_string();
-            }
-            match arg.as_str() {
-                "help" => cmd.help_flag = true,
-                "?" => cmd.help_flag = true,
-                "-version" => cmd.version_flag = true,
-                "-verbose" => cmd.verbose_class = true,
-                "-verbose:class" => cmd.verbose_class = true,
-                "class" => cmd.class = arg.clone(),
-                "-cp" | "-classpath" => {
-                    let null_ptr = "".to_string();
-                    let mut param = args.get(index + 1).unwrap_or_else(|| &null_ptr);
-                    if param.starts_with("-") {
-                        param = &null_ptr;
-                    } else {
-                        index += 1;
-                    }
-                    let array: Vec<&str> = param.split(';').collect();
-                    let mut cps = flat_map::<String, &str>(array);
-                    cmd.cp_option.append(&mut cps);
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let tool_options = std::env::var("JAVA_TOOL_OPTIONS")
+            .ok()
+            .filter(|options| !options.trim().is_empty());
+        if let Some(options) = &tool_options {
+            eprintln!("Picked up JAVA_TOOL_OPTIONS: {}", options);
+        }
+        return match Cmd::parse(args, tool_options.as_deref()) {
+            Ok(cmd) => cmd,
+            Err(msg) => {
+                if !msg.is_empty() {
+                    eprintln!("{}", msg);
                 }
-                "-jar" => {
-                    let param = args
-                        .get(index + 1)
-                        .expect("-jar requires jar file specification");
-                    cmd.exec_jar_path = Some(param.clone())
+                eprint!("{}", Cmd::usage());
+                std::process::exit(1);
+            }
+        };
+    }
+
+    /// the options end at the main class or at the jar file of -jar, what
+    /// follows is passed to main untouched. `@file` inserts the arguments
+    /// written in file while the options are read.
+    pub fn parse(args: Vec<String>, tool_options: Option<&str>) -> Result<Cmd, String> {
+        let mut cmd = Cmd::new();
+        if let Some(options) = tool_options {
+            let mut options: VecDeque<String> = split_arguments(options).into();
+            while let Some(option) = options.pop_front() {
+                if !cmd.parse_option(&option, &mut options)? {
+                    return Err(format!("Unrecognized option: {}", option));
+                }
+            }
+        }
+
+        let mut args: VecDeque<String> = args.into();
+        while let Some(arg) = args.pop_front() {
+            let arg = if arg.starts_with("@@") {
+                arg[1..].to_string()
+            } else if let Some(file) = arg.strip_prefix('@') {
+                let content = fs::read_to_string(file)
+                    .map_err(|error| format!("Error: could not open `{}': {}", file, error))?;
+                for token in split_arguments(&content).into_iter().rev() {
+                    args.push_front(token);
                 }
-                _ => cmd.args.push(arg.clone()),
+                continue;
+            } else {
+                arg
+            };
+            if !arg.starts_with('-') {
+                cmd.class = arg;
+                break;
+            }
+            if !cmd.parse_option(&arg, &mut args)? {
+                return Err(format!("Unrecognized option: {}", arg));
+            }
+            if cmd.exec_jar_path.is_some() {
+                break;
+            }
+        }
+        cmd.args = args.into_iter().collect();
+
+        if let (Some(xms), Some(xmx)) = (cmd.xms_option, cmd.xmx_option) {
+            if xms > xmx {
+                return Err(
+                    "Initial heap size set to a larger value than the maximum heap size"
+                        .to_string(),
+                );
             }
         }
-        cmd.class = cmd.args.remove(1);
-        return cmd;
+        if let Some(xss) = cmd.xss_option {
+            if xss < MIN_STACK_SIZE {
+                return Err(format!(
+                    "The stack size specified is too small, Specify at least {}k",
+                    MIN_STACK_SIZE / 1024
+                ));
+            }
+        }
+        let informational = cmd.help_flag || cmd.x_help_flag || cmd.version_flag;
+        if !informational && cmd.class.is_empty() && cmd.exec_jar_path.is_none() {
+            return Err(String::new());
+        }
+        return Ok(cmd);
+    }
+
+    /// applies one option, `rest` supplies the value of -cp and -jar.
+    /// Returns false for an option that isn't known.
+    fn parse_option(&mut self, option: &str, rest: &mut VecDeque<String>) -> Result<bool, String> {
+        match option {
+            "-?" | "-h" | "-help" => self.help_flag = true,
+            "-X" => self.x_help_flag = true,
+            "-version" => self.version_flag = true,
+            "-showversion" => self.show_version_flag = true,
+            "-verbose" | "-verbose:class" => self.verbose_class = true,
+            "-verbose:gc" | "-verbose:jni" => {}
+            "-server" | "-client" | "-d64" => {}
+            "-cp" | "-classpath" => {
+                let param = rest.pop_front().ok_or_else(|| {
+                    format!("Error: {} requires class path specification", option)
+                })?;
+                // a later -cp replaces an earlier one
+                self.cp_option = split_path_list(&param);
+            }
+            "-jar" => {
+                let param = rest
+                    .pop_front()
+                    .ok_or_else(|| "Error: -jar requires jar file specification".to_string())?;
+                self.exec_jar_path = Some(param);
+            }
+            "-noverify" => self.verify_mode = VerifyMode::None,
+            _ if option.starts_with("-D") => {
+                let property = &option[2..];
+                let (key, value) = property.split_once('=').unwrap_or((property, ""));
+                if key.is_empty() {
+                    return Ok(false);
+                }
+                if key == "java.class.path" {
+                    self.cp_option = split_path_list(value);
+                }
+                self.system_properties
+                    .push((key.to_string(), value.to_string()));
+            }
+            _ if option.starts_with("-Xjre:") => self.x_jre_option = option[6..].to_string(),
+            _ if option.starts_with("-Xmx") => {
+                self.xmx_option = Some(memory_size(option, "maximum heap size")?)
+            }
+            _ if option.starts_with("-Xms") => {
+                self.xms_option = Some(memory_size(option, "initial heap size")?)
+            }
+            _ if option.starts_with("-Xss") => {
+                self.xss_option = Some(memory_size(option, "thread stack size")?)
+            }
+            _ if option.starts_with("-Xverify:") => match VerifyMode::parse(&option[9..]) {
+                Some(mode) => self.verify_mode = mode,
+                None => return Ok(false),
+            },
+            _ if is_assertion_option(option) => self.assertion_options.push(option.to_string()),
+            _ => return Ok(false),
+        }
+        return Ok(true);
+    }
+
+    pub fn usage() -> String {
+        return format!(
+            "Usage: java [-options] class [args...]
+           (to execute a class)
+   or  java [-options] -jar jarfile [args...]
+           (to execute a jar file)
+where options include:
+    -cp <class search path of directories and zip/jar files>
+    -classpath <class search path of directories and zip/jar files>
+                  A {} separated list of directories, JAR archives,
+                  and ZIP archives to search for class files.
+    -D<name>=<value>
+                  set a system property
+    -verbose:[class|gc|jni]
+                  enable verbose output
+    -version      print product version and exit
+    -showversion  print product version and continue
+    -? -help      print this help message
+    -X            print help on non-standard options
+    -ea[:<packagename>...|:<classname>]
+    -enableassertions[:<packagename>...|:<classname>]
+                  enable assertions with specified granularity
+    -da[:<packagename>...|:<classname>]
+    -disableassertions[:<packagename>...|:<classname>]
+                  disable assertions with specified granularity
+    -esa | -enablesystemassertions
+                  enable system assertions
+    -dsa | -disablesystemassertions
+                  disable system assertions
+    @<filepath>   read options from the specified file
+",
+            PATH_LIST_SEPARATOR
+        );
     }
 
     pub fn print_usage() {
-        println!(
-            "Usage: {} [-options] class [args...]\n",
-            std::env::args().nth(0).unwrap()
+        print!("{}", Cmd::usage());
+    }
+
+    pub fn print_x_usage() {
+        print!(
+            "    -Xmx<size>        set maximum Java heap size
+    -Xms<size>        set initial Java heap size
+    -Xss<size>        set java thread stack size
+    -Xverify:<mode>   none, remote or all, -noverify is -Xverify:none
+    -Xjre:<dir>       the JRE whose lib directory holds the boot classes
+
+The -X options are non-standard and subject to change without notice.
+"
         );
     }
 
+    pub fn print_version() {
+        eprintln!("java version \"1.8.0_152\"");
+    }
+
     #[inline]
     pub fn exec_jar_path(&self) -> Option<&String> {
         return self.exec_jar_path.as_ref();
@@ -97,4 +276,219 @@ impl Cmd {
     pub fn class(&self) -> &String {
         return &self.class;
     }
+
+    #[inline]
+    pub fn system_properties(&self) -> &Vec<(String, String)> {
+        return &self.system_properties;
+    }
+}
+
+fn is_assertion_option(option: &str) -> bool {
+    let name = option.split(':').next().unwrap();
+    return match name {
+        "-ea" | "-enableassertions" | "-da" | "-disableassertions" => true,
+        "-esa" | "-enablesystemassertions" | "-dsa" | "-disablesystemassertions" => name == option,
+        _ => false,
+    };
+}
+
+/// the size of an option like -Xmx64m
+fn memory_size(option: &str, name: &str) -> Result<usize, String> {
+    return match parse_memory_size(&option[4..]) {
+        Some(size) if size > 0 => Ok(size),
+        _ => Err(format!("Invalid {}: {}", name, option)),
+    };
+}
+
+/// a size like "64m", the suffixes k, m and g are binary multiples
+pub fn parse_memory_size(size: &str) -> Option<usize> {
+    let (digits, unit) = match size.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&size[..i], c.to_ascii_lowercase()),
+        _ => (size, 'b'),
+    };
+    let multiple: usize = match unit {
+        'b' => 1,
+        'k' => 1024,
+        'm' => 1024 * 1024,
+        'g' => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    return digits.parse::<usize>().ok()?.checked_mul(multiple);
+}
+
+/// splits the content of an @argfile or of JAVA_TOOL_OPTIONS at white space.
+/// Quotes keep white space in an argument and take backslash escapes,
+/// a `#` starting an argument comments out the rest of the line.
+pub fn split_arguments(content: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut chars = content.chars();
+    let mut current = String::new();
+    let mut in_argument = false;
+    while let Some(c) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            '#' if !in_argument => {
+                for c in chars.by_ref() {
+                    if c == '\n' || c == '\r' {
+                        break;
+                    }
+                }
+            }
+            '"' | '\'' => {
+                in_argument = true;
+                while let Some(quoted) = chars.next() {
+                    if quoted == c {
+                        break;
+                    }
+                    if quoted == '\\' {
+                        match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some('f') => current.push('\u{c}'),
+                            Some(escaped) => current.push(escaped),
+                            None => break,
+                        }
+                    } else {
+                        current.push(quoted);
+                    }
+                }
+            }
+            _ => {
+                in_argument = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    return arguments;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::class_verifier::VerifyMode;
+    use crate::cmd::{parse_memory_size, split_arguments, Cmd};
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        return args.iter().map(|arg| arg.to_string()).collect();
+    }
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!(parse_memory_size("1024"), Some(1024));
+        assert_eq!(parse_memory_size("64k"), Some(64 * 1024));
+        assert_eq!(parse_memory_size("16M"), Some(16 * 1024 * 1024));
+        assert_eq!(parse_memory_size("2g"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory_size("m"), None);
+        assert_eq!(parse_memory_size("12x"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let cmd = Cmd::parse(
+            args(&[
+                "-cp",
+                "classes",
+                "-Dk=v",
+                "-Dflag",
+                "-Xss512k",
+                "-Xmx64m",
+                "-ea:app...",
+                "-esa",
+                "-Xverify:all",
+                "app.Main",
+                "-version",
+                "x",
+            ]),
+            None,
+        )
+        .unwrap();
+        assert_eq!(cmd.cp_option, vec!["classes"]);
+        assert_eq!(
+            cmd.system_properties,
+            vec![
+                ("k".to_string(), "v".to_string()),
+                ("flag".to_string(), String::new())
+            ]
+        );
+        assert_eq!(cmd.xss_option, Some(512 * 1024));
+        assert_eq!(cmd.xmx_option, Some(64 * 1024 * 1024));
+        assert_eq!(cmd.assertion_options, vec!["-ea:app...", "-esa"]);
+        assert!(matches!(cmd.verify_mode, VerifyMode::All));
+        assert_eq!(cmd.class, "app.Main");
+        // options after the main class belong to main
+        assert!(!cmd.version_flag);
+        assert_eq!(cmd.args, vec!["-version", "x"]);
+
+        let cmd = Cmd::parse(args(&["-jar", "app.jar", "a"]), None).unwrap();
+        assert_eq!(cmd.exec_jar_path, Some("app.jar".to_string()));
+        assert_eq!(cmd.args, vec!["a"]);
+
+        assert!(Cmd::parse(args(&["-version"]), None).unwrap().version_flag);
+        assert!(Cmd::parse(args(&["-X"]), None).unwrap().x_help_flag);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Cmd::parse(args(&[]), None).err(), Some(String::new()));
+        assert!(Cmd::parse(args(&["help"]), None).is_ok());
+        assert!(Cmd::parse(args(&["-cp"]), None).is_err());
+        assert!(Cmd::parse(args(&["-jar"]), None).is_err());
+        assert!(Cmd::parse(args(&["-Xmx0", "Main"]), None).is_err());
+        assert!(Cmd::parse(args(&["-Xms2m", "-Xmx1m", "Main"]), None).is_err());
+        assert!(Cmd::parse(args(&["-Xss64k", "Main"]), None).is_err());
+        assert!(Cmd::parse(args(&["-esa:x", "Main"]), None).is_err());
+        assert_eq!(
+            Cmd::parse(args(&["-unknown", "Main"]), None).err(),
+            Some("Unrecognized option: -unknown".to_string())
+        );
+        assert!(Cmd::parse(args(&["Main"]), Some("Main")).is_err());
+    }
+
+    #[test]
+    fn test_tool_options() {
+        let cmd = Cmd::parse(args(&["-Dk=cmd", "Main"]), Some("-Dk=tool -Xss1m")).unwrap();
+        assert_eq!(cmd.xss_option, Some(1024 * 1024));
+        // the command line comes later and wins
+        assert_eq!(cmd.system_properties.last().unwrap().1, "cmd");
+    }
+
+    #[test]
+    fn test_arg_file() {
+        let path = std::env::temp_dir().join(format!("lark-args-{}", std::process::id()));
+        fs::write(
+            &path,
+            "# options\n-Dk=\"a b\" -verbose\napp.Main @not-a-file\n",
+        )
+        .unwrap();
+        let arg_file = format!("@{}", path.to_str().unwrap());
+        let cmd = Cmd::parse(args(&[arg_file.as_str(), "x"]), None).unwrap();
+        assert!(cmd.verbose_class);
+        assert_eq!(cmd.system_properties[0].1, "a b");
+        assert_eq!(cmd.class, "app.Main");
+        // arguments of main are never expanded
+        assert_eq!(cmd.args, vec!["@not-a-file", "x"]);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(Cmd::parse(args(&["@@Main"]), None).unwrap().class, "@Main");
+        assert!(Cmd::parse(args(&["@lark-missing-file"]), None).is_err());
+    }
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(
+            split_arguments("  -a\t'b c' \"d\\te\"f # g h\n i"),
+            vec!["-a", "b c", "d\tef", "i"]
+        );
+        assert_eq!(split_arguments("\"\""), vec![""]);
+        assert!(split_arguments(" \n ").is_empty());
+    }
 }
@@ -0,0 +1,153 @@
+use crate::gc::mark_sweep;
+use crate::oops::class::Class;
+use crate::oops::object::{DataType, Object};
+use crate::runtime::slot::Slot;
+use crate::runtime::thread::JavaThread;
+use std::cell::RefCell;
+use std::mem::size_of;
+use std::rc::{Rc, Weak};
+
+/// the -Xmx used when the option is not given
+pub const DEFAULT_MAX_HEAP_SIZE: usize = 256 * 1024 * 1024;
+
+/// Every Java object is allocated here. An object is still an `Rc<RefCell<Object>>`,
+/// so garbage without cycles is freed as soon as the last reference goes away;
+/// the heap keeps a weak reference to each object to account its size against
+/// -Xmx and to let the mark-sweep collector reclaim unreachable cycles.
+pub struct Heap {
+    max_size: usize,
+    /// the survivors of the last collection plus everything allocated since, in bytes
+    used: usize,
+    objects: Vec<Weak<RefCell<Object>>>,
+    threads: Vec<Weak<RefCell<JavaThread>>>,
+}
+
+static mut HEAP: Option<Heap> = None;
+
+impl Heap {
+    pub fn init(max_size: Option<usize>) {
+        unsafe {
+            HEAP = Some(Heap::new(max_size.unwrap_or(DEFAULT_MAX_HEAP_SIZE)));
+        }
+    }
+
+    fn new(max_size: usize) -> Heap {
+        return Heap {
+            max_size,
+            used: 0,
+            objects: Vec::new(),
+            threads: Vec::new(),
+        };
+    }
+
+    fn instance() -> &'static mut Heap {
+        unsafe {
+            if HEAP.is_none() {
+                HEAP = Some(Heap::new(DEFAULT_MAX_HEAP_SIZE));
+            }
+            return HEAP.as_mut().unwrap();
+        }
+    }
+
+    /// puts the object on the heap, it never fails: the instructions
+    /// allocating for Java code call `reserve` first
+    pub fn allocate(object: Object) -> Rc<RefCell<Object>> {
+        return Heap::instance().register(object);
+    }
+
+    /// makes room for `size` bytes, collecting garbage if the heap is full,
+    /// returns false when the object would exceed -Xmx anyway
+    pub fn reserve(size: usize) -> bool {
+        let heap = Heap::instance();
+        if heap.fits(size) {
+            return true;
+        }
+        heap.collect();
+        return heap.fits(size);
+    }
+
+    /// the stacks of every registered thread are roots of the collector
+    pub fn register_thread(thread: &Rc<RefCell<JavaThread>>) {
+        let heap = Heap::instance();
+        heap.threads.retain(|thread| thread.strong_count() > 0);
+        heap.threads.push(Rc::downgrade(thread));
+    }
+
+    /// Runtime.gc
+    pub fn gc() {
+        Heap::instance().collect();
+    }
+
+    #[inline]
+    pub fn used() -> usize {
+        return Heap::instance().used;
+    }
+
+    #[inline]
+    pub fn max_size() -> usize {
+        return Heap::instance().max_size;
+    }
+
+    fn fits(&self, size: usize) -> bool {
+        return self.used.saturating_add(size) <= self.max_size;
+    }
+
+    fn register(&mut self, object: Object) -> Rc<RefCell<Object>> {
+        self.used += object_size(&object);
+        let object = Rc::new(RefCell::new(object));
+        self.objects.push(Rc::downgrade(&object));
+        return object;
+    }
+
+    fn collect(&mut self) {
+        let threads: Vec<Rc<RefCell<JavaThread>>> =
+            self.threads.iter().filter_map(|thread| thread.upgrade()).collect();
+        self.threads = threads.iter().map(Rc::downgrade).collect();
+        let objects: Vec<Rc<RefCell<Object>>> =
+            self.objects.iter().filter_map(|object| object.upgrade()).collect();
+        let survivors = mark_sweep::collect(objects, &threads);
+        self.used = survivors
+            .iter()
+            .map(|object| object_size(&(**object).borrow()))
+            .sum();
+        self.objects = survivors.iter().map(Rc::downgrade).collect();
+    }
+}
+
+/// the memory an object takes, including its header
+pub fn object_size(object: &Object) -> usize {
+    let header = size_of::<RefCell<Object>>() + 2 * size_of::<usize>();
+    let data = match object.data() {
+        DataType::StandardObject(slots) => {
+            slots.as_ref().map_or(0, |slots| slots.len()) * size_of::<Slot>()
+        }
+        DataType::Bytes(array) => array.len(),
+        DataType::Shorts(array) => array.len() * 2,
+        DataType::Chars(array) => array.len() * 2,
+        DataType::Ints(array) => array.len() * 4,
+        DataType::Floats(array) => array.len() * 4,
+        DataType::Longs(array) => array.len() * 8,
+        DataType::Doubles(array) => array.len() * 8,
+        DataType::References(array) => array.len() * size_of::<Option<Rc<RefCell<Object>>>>(),
+    };
+    return header + data;
+}
+
+/// the size of an instance of the class, known before it is created
+pub fn instance_size(class: &Class) -> usize {
+    let header = size_of::<RefCell<Object>>() + 2 * size_of::<usize>();
+    return header + class.instance_slot_count() as usize * size_of::<Slot>();
+}
+
+/// the size of an array of the array class, known before it is created
+pub fn array_size(array_class: &Class, count: usize) -> usize {
+    let header = size_of::<RefCell<Object>>() + 2 * size_of::<usize>();
+    let element = match array_class.name() {
+        "[Z" | "[B" => 1,
+        "[C" | "[S" => 2,
+        "[I" | "[F" => 4,
+        "[J" | "[D" => 8,
+        _ => size_of::<Option<Rc<RefCell<Object>>>>(),
+    };
+    return header.saturating_add(count.saturating_mul(element));
+}
@@ -0,0 +1,196 @@
+use crate::jvm::Jvm;
+use crate::oops::class::Class;
+use crate::oops::object::{DataType, MetaData, Object};
+use crate::oops::string_pool::StringPool;
+use crate::runtime::thread::JavaThread;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Marks every object reachable from the roots and breaks up the rest.
+///
+/// The roots are the frames of every thread, the static variables and
+/// Class objects of the loaded classes, the interned strings and any object
+/// referenced from outside the heap: a frame in the middle of an instruction,
+/// the thread object of an OS thread or a handle held by VM code. The latter
+/// are found by comparing an object's reference count with the references
+/// the heap itself holds to it.
+///
+/// An unmarked object can only be kept alive by a cycle, so its references
+/// are cleared and the cycle is freed once `objects` is dropped.
+/// Returns the marked objects.
+pub fn collect(
+    objects: Vec<Rc<RefCell<Object>>>,
+    threads: &[Rc<RefCell<JavaThread>>],
+) -> Vec<Rc<RefCell<Object>>> {
+    let mut marker = Marker::new(&objects);
+
+    for thread in threads {
+        if let Ok(thread) = thread.try_borrow() {
+            for frame in thread.get_frames() {
+                if let Ok(frame) = frame.try_borrow() {
+                    frame.references().for_each(|object| marker.mark(object));
+                }
+            }
+        }
+    }
+    if Jvm::instance().is_some() {
+        let loader = Jvm::boot_class_loader().basic_loader();
+        if let Ok(loader) = loader.try_borrow() {
+            for class in loader.class_map_immutable().values() {
+                marker.mark_class(class);
+            }
+        };
+    }
+    StringPool::strings().for_each(|object| marker.mark(object));
+    marker.mark_externally_referenced(&objects);
+
+    marker.trace(&objects);
+    return marker.sweep(objects);
+}
+
+struct Marker {
+    index: HashMap<*const RefCell<Object>, usize>,
+    marked: Vec<bool>,
+    gray: Vec<usize>,
+}
+
+impl Marker {
+    fn new(objects: &Vec<Rc<RefCell<Object>>>) -> Marker {
+        let index = objects
+            .iter()
+            .enumerate()
+            .map(|(i, object)| (Rc::as_ptr(object), i))
+            .collect();
+        return Marker {
+            index,
+            marked: vec![false; objects.len()],
+            gray: Vec::new(),
+        };
+    }
+
+    fn mark(&mut self, object: &Rc<RefCell<Object>>) {
+        if let Some(&i) = self.index.get(&Rc::as_ptr(object)) {
+            if !self.marked[i] {
+                self.marked[i] = true;
+                self.gray.push(i);
+            }
+        }
+    }
+
+    fn mark_class(&mut self, class: &Rc<RefCell<Class>>) {
+        if let Ok(class) = class.try_borrow() {
+            if let Some(statics) = class.static_vars() {
+                statics.references().for_each(|object| self.mark(object));
+            }
+            if let Some(java_class) = class.java_class() {
+                self.mark(java_class);
+            }
+        }
+    }
+
+    /// an object referenced more often than the heap refers to it is also
+    /// held by something else, as is an object borrowed right now
+    fn mark_externally_referenced(&mut self, objects: &Vec<Rc<RefCell<Object>>>) {
+        let mut internal = vec![0usize; objects.len()];
+        for object in objects {
+            if let Ok(object) = object.try_borrow() {
+                for_each_reference(&object, |child| {
+                    if let Some(&i) = self.index.get(&Rc::as_ptr(child)) {
+                        internal[i] += 1;
+                    }
+                });
+            }
+        }
+        for (i, object) in objects.iter().enumerate() {
+            // one reference is the `objects` vector itself
+            let external = Rc::strong_count(object) - 1 > internal[i];
+            if external || object.try_borrow_mut().is_err() {
+                self.mark(object);
+            }
+        }
+    }
+
+    fn trace(&mut self, objects: &Vec<Rc<RefCell<Object>>>) {
+        while let Some(i) = self.gray.pop() {
+            let object = match objects[i].try_borrow() {
+                Ok(object) => object,
+                Err(_) => continue,
+            };
+            for_each_reference(&object, |child| self.mark(child));
+            // a class loader keeps the classes it defined alive
+            if let MetaData::ClassLoader(loader) = &object.meta_data {
+                if let Ok(loader) = loader.try_borrow() {
+                    for class in loader.class_map_immutable().values() {
+                        self.mark_class(class);
+                    }
+                }
+            }
+        }
+    }
+
+    fn sweep(&self, objects: Vec<Rc<RefCell<Object>>>) -> Vec<Rc<RefCell<Object>>> {
+        let mut survivors = Vec::with_capacity(objects.len());
+        for (i, object) in objects.into_iter().enumerate() {
+            if self.marked[i] {
+                survivors.push(object);
+            } else if let Ok(mut garbage) = object.try_borrow_mut() {
+                clear_references(&mut garbage);
+            }
+        }
+        return survivors;
+    }
+}
+
+fn for_each_reference<F: FnMut(&Rc<RefCell<Object>>)>(object: &Object, mut f: F) {
+    match object.data() {
+        DataType::StandardObject(Some(slots)) => slots.references().for_each(f),
+        DataType::References(array) => array.iter().flatten().for_each(|object| f(object)),
+        _ => {}
+    }
+}
+
+fn clear_references(object: &mut Object) {
+    match object.mut_data() {
+        DataType::StandardObject(Some(slots)) => slots.clear_references(),
+        DataType::References(array) => array.iter_mut().for_each(|object| *object = None),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gc::mark_sweep::collect;
+    use crate::oops::class::Class;
+    use crate::oops::object::DataType::References;
+    use crate::oops::object::Object;
+    use crate::utils::boxed;
+    use std::rc::Rc;
+
+    fn array(class: &Rc<std::cell::RefCell<Class>>) -> Rc<std::cell::RefCell<Object>> {
+        return boxed(Object::from_data(class.clone(), References(vec![None])));
+    }
+
+    #[test]
+    fn test_collect_cycle() {
+        let class = boxed(Class::default());
+        let (a, b) = (array(&class), array(&class));
+        (*a).borrow_mut().mut_references()[0] = Some(b.clone());
+        (*b).borrow_mut().mut_references()[0] = Some(a.clone());
+        let rooted = array(&class);
+        let child = array(&class);
+        (*rooted).borrow_mut().mut_references()[0] = Some(child.clone());
+
+        let weak_a = Rc::downgrade(&a);
+        let weak_child = Rc::downgrade(&child);
+        let objects = vec![a, b, rooted.clone(), child];
+        let survivors = collect(objects, &[]);
+
+        assert_eq!(survivors.len(), 2);
+        assert!(weak_a.upgrade().is_none());
+        assert!(weak_child.upgrade().is_some());
+        drop(survivors);
+        drop(rooted);
+        assert!(weak_child.upgrade().is_none());
+    }
+}
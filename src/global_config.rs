@@ -31,4 +31,10 @@ impl GlobalConfig {
             return GLOBAL_CONFIG.as_ref().unwrap();
         }
     }
+
+    /// the std::env::consts::OS the VM runs on, like "linux" or "windows"
+    #[inline]
+    pub fn os(&self) -> &'static str {
+        return self.os;
+    }
 }
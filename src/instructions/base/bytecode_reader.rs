@@ -60,6 +60,10 @@ impl BytecodeReader {
         return vec;
     }
 
+    pub fn skip(&mut self, count: usize) {
+        self.pc += count as i32;
+    }
+
     pub fn skip_padding(&mut self) {
         while self.pc % 4 != 0 {
             self.read_u8();
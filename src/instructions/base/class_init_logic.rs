@@ -1,31 +1,263 @@
-use crate::oops::class::Class;
+use crate::gc::heap::Heap;
+use crate::instructions::quick::{quicken, QuickInstruction};
+use crate::invoke_support::parameter::{Parameter, Parameters};
+use crate::invoke_support::{invoke, set_pending_exception, ReturnType};
+use crate::jvm::Jvm;
+use crate::oops::class::{Class, InitState};
+use crate::oops::object::Object;
+use crate::runtime::frame::Frame;
 use crate::runtime::thread::JavaThread;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::thread;
 
-pub fn init_class(thread: Rc<RefCell<JavaThread>>, class: Rc<RefCell<Class>>) {
-    (*class).borrow_mut().set_initialized();
-    schedule_clinit(thread.clone(), class.clone());
-    init_super_class(thread, class);
+/// initializes the class the way JVMS §5.5 describes it: the superclass and
+/// the superinterfaces declaring default methods first, then <clinit> runs on
+/// the current thread. A thread asking for a class another thread is
+/// initializing waits for it, a recursive request of the initializing thread
+/// returns right away. Returns false if the class can't be used, the
+/// exception is pending then.
+pub fn init_class(class: Rc<RefCell<Class>>) -> bool {
+    match claim(&class) {
+        Claim::Done => return true,
+        Claim::Erroneous => {
+            let msg = format!(
+                "Could not initialize class {}",
+                (*class).borrow().java_name()
+            );
+            set_pending_exception("java/lang/NoClassDefFoundError", Some(msg.as_str()));
+            return false;
+        }
+        Claim::Initialize => {}
+    }
+    if !init_super_types(&class) {
+        finish(&class, InitState::Erroneous);
+        return false;
+    }
+    if let Some(clinit) = Class::get_clinit_method(class.clone()) {
+        invoke(clinit, None, ReturnType::Void);
+        if let Some(exception) = JavaThread::take_pending_exception() {
+            let error = initializer_error(exception);
+            finish(&class, InitState::Erroneous);
+            JavaThread::set_pending_exception(error);
+            return false;
+        }
+    }
+    finish(&class, InitState::FullyInitialized);
+    return true;
+}
+
+/// what the current thread has to do about a class it wants to use
+#[derive(Debug, PartialEq)]
+enum Claim {
+    /// the class was Linked, the current thread runs the initialization
+    Initialize,
+    /// initialized, or being initialized by the current thread
+    Done,
+    /// an earlier initialization failed
+    Erroneous,
+}
+
+/// takes over the initialization of a Linked class, waits while another
+/// thread initializes it
+fn claim(class: &Rc<RefCell<Class>>) -> Claim {
+    let current = thread::current().id();
+    let lock = (**class).borrow().init_lock();
+    lock.enter();
+    loop {
+        let state = (**class).borrow().init_state();
+        let claim = match state {
+            InitState::BeingInitialized(owner) if owner != current => None,
+            InitState::BeingInitialized(_) | InitState::FullyInitialized => Some(Claim::Done),
+            InitState::Erroneous => Some(Claim::Erroneous),
+            InitState::Linked => {
+                (**class)
+                    .borrow_mut()
+                    .set_init_state(InitState::BeingInitialized(current));
+                Some(Claim::Initialize)
+            }
+        };
+        if let Some(claim) = claim {
+            lock.exit();
+            return claim;
+        }
+        lock.wait(0, None);
+    }
 }
 
-fn schedule_clinit(thread: Rc<RefCell<JavaThread>>, class: Rc<RefCell<Class>>) {
-    let clinit = Class::get_clinit_method(class);
-    if clinit.is_some() {
-        let new_frame = JavaThread::new_frame(thread.clone(), clinit.unwrap());
-        (*thread).borrow_mut().push_frame(new_frame);
+/// the initialization check of new, getstatic, putstatic and invokestatic,
+/// false with the exception pending if the class can't be used
+#[inline]
+pub fn ensure_initialized(class: &Rc<RefCell<Class>>) -> bool {
+    return (*class).borrow().initialized() || init_class(class.clone());
+}
+
+/// rewrites the instruction to its quick form, which skips the
+/// initialization check. A recursive request of the thread running <clinit>
+/// gets past ensure_initialized before the class is initialized, so the
+/// instruction stays as it is until <clinit> has finished
+pub fn quicken_if_initialized(frame: &Frame, class: &Rc<RefCell<Class>>, quick: QuickInstruction) {
+    if (*class).borrow().initialized() {
+        quicken(frame, quick);
     }
 }
 
-fn init_super_class(thread: Rc<RefCell<JavaThread>>, class: Rc<RefCell<Class>>) {
-    if !(*class).borrow().is_interface() {
-        let super_class = (*class).borrow().super_class();
-        if super_class.is_none() {
-            return;
+/// records the outcome and wakes the threads waiting for it
+fn finish(class: &Rc<RefCell<Class>>, state: InitState) {
+    let lock = (*class).borrow().init_lock();
+    lock.enter();
+    (*class).borrow_mut().set_init_state(state);
+    lock.notify_all();
+    lock.exit();
+}
+
+/// an interface doesn't initialize its superinterfaces, a class initializes
+/// its superclass, then the superinterfaces with default methods in the
+/// order of a depth first walk over the interfaces it implements
+fn init_super_types(class: &Rc<RefCell<Class>>) -> bool {
+    if (*class).borrow().is_interface() {
+        return true;
+    }
+    let super_class = (*class).borrow().super_class();
+    if let Some(super_class) = super_class {
+        if !(*super_class).borrow().initialized() && !init_class(super_class) {
+            return false;
+        }
+    }
+    let mut interfaces = Vec::new();
+    default_method_interfaces(class, &mut interfaces);
+    for interface in interfaces {
+        if !(*interface).borrow().initialized() && !init_class(interface) {
+            return false;
         }
-        let super_class = super_class.unwrap();
-        if !(*super_class).borrow().initialized() {
-            init_class(thread, super_class);
+    }
+    return true;
+}
+
+/// collects the superinterfaces of `class` declaring a non-abstract instance
+/// method, the superinterfaces of an interface come before the interface
+fn default_method_interfaces(class: &Rc<RefCell<Class>>, found: &mut Vec<Rc<RefCell<Class>>>) {
+    let interfaces = match (*class).borrow().interfaces() {
+        Some(interfaces) => interfaces.clone(),
+        None => return,
+    };
+    for interface in interfaces {
+        default_method_interfaces(&interface, found);
+        let declares_default = (*interface)
+            .borrow()
+            .methods()
+            .iter()
+            .any(|method| !method.is_abstract() && !method.is_static());
+        if declares_default && !found.iter().any(|known| Rc::ptr_eq(known, &interface)) {
+            found.push(interface);
         }
     }
 }
+
+/// an Error thrown by <clinit> is passed on, anything else is wrapped into
+/// an ExceptionInInitializerError
+fn initializer_error(exception: Rc<RefCell<Object>>) -> Rc<RefCell<Object>> {
+    let boot_loader = Jvm::boot_class_loader();
+    let error_class = boot_loader.find_or_create("java/lang/Error").unwrap();
+    let exception_class = (*exception).borrow().class();
+    if (*exception_class)
+        .borrow()
+        .is_sub_class_of(&(*error_class).borrow())
+    {
+        return exception;
+    }
+    let wrapper_class = boot_loader
+        .find_or_create("java/lang/ExceptionInInitializerError")
+        .unwrap();
+    let wrapper = Heap::allocate(Class::new_object(&wrapper_class));
+    let constructor = Class::get_constructor(wrapper_class, "(Ljava/lang/Throwable;)V").unwrap();
+    let parameters = vec![
+        Parameter::Object(Some(wrapper.clone())),
+        Parameter::Object(Some(exception)),
+    ];
+    invoke(
+        constructor,
+        Some(Parameters::with_parameters(parameters)),
+        ReturnType::Void,
+    );
+    // the error that kept the wrapper from being built takes its place
+    return JavaThread::take_pending_exception().unwrap_or(wrapper);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::instructions::base::class_init_logic::{
+        claim, ensure_initialized, finish, init_class, Claim,
+    };
+    use crate::oops::class::{Class, InitState};
+    use crate::runtime::vm_lock;
+    use crate::runtime::vm_lock::Unshared;
+    use crate::utils::boxed;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_init_class() {
+        let class = boxed(Class::default());
+        assert!(init_class(class.clone()));
+        assert!((*class).borrow().initialized());
+        assert_eq!(claim(&class), Claim::Done);
+        assert!(ensure_initialized(&class));
+    }
+
+    #[test]
+    fn test_recursive_init() {
+        let class = boxed(Class::default());
+        assert_eq!(claim(&class), Claim::Initialize);
+        // <clinit> asking for its own class gets it as it is
+        assert_eq!(claim(&class), Claim::Done);
+        assert!(init_class(class.clone()));
+        assert!(ensure_initialized(&class));
+        assert!(!(*class).borrow().initialized());
+        let current = thread::current().id();
+        assert_eq!(
+            (*class).borrow().init_state(),
+            InitState::BeingInitialized(current)
+        );
+    }
+
+    #[test]
+    fn test_erroneous() {
+        let mut class = Class::default();
+        class.set_init_state(InitState::Erroneous);
+        let class = boxed(class);
+        assert_eq!(claim(&class), Claim::Erroneous);
+        assert_eq!((*class).borrow().init_state(), InitState::Erroneous);
+    }
+
+    #[test]
+    fn test_wait_for_other_thread() {
+        let class = boxed(Class::default());
+        assert_eq!(claim(&class), Claim::Initialize);
+
+        // the class is only touched with the vm lock held until the join,
+        // the waiter gives the lock up while it waits
+        let payload = unsafe { Unshared::new(class.clone()) };
+        let claimed = Arc::new(AtomicBool::new(false));
+        let waiter_claimed = claimed.clone();
+        let waiter = thread::spawn(move || {
+            vm_lock::acquire();
+            let class = payload.into_inner();
+            let result = claim(&class);
+            waiter_claimed.store(true, Ordering::SeqCst);
+            drop(class);
+            vm_lock::release();
+            return result;
+        });
+        thread::sleep(Duration::from_millis(50));
+        assert!(!claimed.load(Ordering::SeqCst));
+
+        // a failed initialization is what the waiting thread gets to see
+        vm_lock::acquire();
+        finish(&class, InitState::Erroneous);
+        vm_lock::release();
+        assert_eq!(waiter.join().unwrap(), Claim::Erroneous);
+    }
+}
@@ -4,7 +4,7 @@ use crate::runtime::frame::Frame;
 pub trait Instruction {
     fn fetch_operands(&mut self, reader: &mut BytecodeReader);
 
-    fn execute(&mut self, frame: &mut Frame);
+    fn execute(&self, frame: &mut Frame);
 }
 
 ///没有操作数的指令
@@ -20,7 +20,7 @@ impl NoOperandsInstruction {
 impl Instruction for NoOperandsInstruction {
     fn fetch_operands(&mut self, reader: &mut BytecodeReader) {}
 
-    fn execute(&mut self, frame: &mut Frame) {}
+    fn execute(&self, frame: &mut Frame) {}
 }
 
 ///跳转指令
@@ -45,7 +45,7 @@ impl Instruction for BranchInstruction {
         self.offset = reader.read_i16() as i32;
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         unimplemented!()
     }
 }
@@ -77,7 +77,7 @@ impl Instruction for LocalVarsInstruction {
         self.index = reader.read_u8() as usize;
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         unimplemented!()
     }
 }
@@ -104,7 +104,7 @@ impl Instruction for ConstantPoolInstruction {
         self.index = reader.read_u16() as usize;
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         unimplemented!()
     }
 }
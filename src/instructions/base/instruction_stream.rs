@@ -0,0 +1,191 @@
+use crate::class_loader::class_verifier::INSTRUCTION_LENGTHS;
+use crate::instructions::base::bytecode_reader::BytecodeReader;
+use crate::instructions::quick::QuickInstruction;
+use crate::instructions::DecodedInstruction;
+use crate::runtime::frame::Frame;
+use std::cell::OnceCell;
+use std::fmt;
+
+const NO_INSTRUCTION: u32 = u32::MAX;
+
+/// the code of a method decoded once, the interpreter fetches instructions
+/// by pc from here instead of reading the bytecode on every step
+pub struct InstructionStream {
+    instructions: Vec<Entry>,
+    /// bytecode offset -> index into instructions, NO_INSTRUCTION for
+    /// offsets inside an instruction
+    indices: Vec<u32>,
+}
+
+impl InstructionStream {
+    pub fn decode(code: &[u8]) -> InstructionStream {
+        let mut instructions = Vec::new();
+        let mut indices = vec![NO_INSTRUCTION; code.len()];
+        let mut reader = BytecodeReader::new();
+        reader.reset(code.to_vec(), 0);
+        while (reader.pc() as usize) < code.len() {
+            let pc = reader.pc();
+            let inst = DecodedInstruction::decode(&mut reader);
+            if let DecodedInstruction::Unknown(opcode) = inst {
+                // skip its operands so the instructions after it still
+                // start where the verifier saw them
+                let length = match INSTRUCTION_LENGTHS.get(opcode as usize) {
+                    Some(length) if *length > 0 => *length as usize,
+                    _ => 1,
+                };
+                let remaining = code.len() - reader.pc() as usize;
+                reader.skip((length - 1).min(remaining));
+            }
+            indices[pc as usize] = instructions.len() as u32;
+            instructions.push(Entry {
+                next_pc: reader.pc(),
+                instruction: inst,
+                quickened: OnceCell::new(),
+            });
+        }
+        return InstructionStream {
+            instructions,
+            indices,
+        };
+    }
+
+    /// the instruction at pc
+    #[inline]
+    pub fn fetch(&self, pc: i32) -> &Entry {
+        let index = self.indices.get(pc as usize).copied();
+        match index {
+            Some(index) if index != NO_INSTRUCTION => return &self.instructions[index as usize],
+            _ => panic!("no instruction at pc {}", pc),
+        }
+    }
+
+    /// replaces the instruction at pc by its fast form, the first
+    /// quickening wins when a nested call got there before
+    pub fn quicken(&self, pc: i32, quick: QuickInstruction) {
+        let _ = self.fetch(pc).quickened.set(quick);
+    }
+}
+
+pub struct Entry {
+    next_pc: i32,
+    instruction: DecodedInstruction,
+    quickened: OnceCell<QuickInstruction>,
+}
+
+impl Entry {
+    /// the pc of the instruction following this one
+    #[inline]
+    pub fn next_pc(&self) -> i32 {
+        return self.next_pc;
+    }
+
+    #[inline]
+    pub fn execute(&self, frame: &mut Frame) {
+        match self.quickened.get() {
+            Some(quick) => quick.execute(frame),
+            None => self.instruction.execute(frame),
+        }
+    }
+}
+
+impl fmt::Debug for InstructionStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "InstructionStream({} instructions)",
+            self.instructions.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::instructions::base::instruction_stream::InstructionStream;
+    use crate::instructions::quick::QuickInstruction;
+    use crate::instructions::DecodedInstruction;
+    use std::mem::size_of;
+
+    #[test]
+    fn decode() {
+        // iconst_0, istore_0, iinc 0 1, iload_0, sipush 1000, if_icmplt -9,
+        // tableswitch padded to 4, return
+        let code = vec![
+            0x03, 0x3b, 0x84, 0x00, 0x01, 0x1a, 0x11, 0x03, 0xe8, 0xa1, 0xff, 0xf7, 0x1a, 0xaa,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x0c, 0xb1,
+        ];
+        let stream = InstructionStream::decode(&code);
+        assert_eq!(stream.instructions.len(), 9);
+        let entry = stream.fetch(2);
+        assert_eq!(entry.next_pc(), 5);
+        assert!(matches!(entry.instruction, DecodedInstruction::IInc(_)));
+        let entry = stream.fetch(9);
+        assert_eq!(entry.next_pc(), 12);
+        assert!(matches!(entry.instruction, DecodedInstruction::IfICmpLt(_)));
+        let entry = stream.fetch(13);
+        assert_eq!(entry.next_pc(), 32);
+        assert!(matches!(
+            entry.instruction,
+            DecodedInstruction::TableSwitch(_)
+        ));
+        assert!(matches!(
+            stream.fetch(32).instruction,
+            DecodedInstruction::Return(_)
+        ));
+        assert!(size_of::<DecodedInstruction>() <= 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "no instruction at pc 1")]
+    fn fetch_inside_instruction() {
+        let stream = InstructionStream::decode(&[0x84, 0x00, 0x01, 0xb1]);
+        assert!(matches!(
+            stream.fetch(3).instruction,
+            DecodedInstruction::Return(_)
+        ));
+        stream.fetch(1);
+    }
+
+    #[test]
+    fn decode_unknown_opcode() {
+        // iconst_0, jsr_w +6, breakpoint, return, an unknown opcode at the end
+        let code = vec![0x03, 0xc9, 0x00, 0x00, 0x00, 0x06, 0xca, 0xb1, 0xe0];
+        let stream = InstructionStream::decode(&code);
+        assert_eq!(stream.instructions.len(), 5);
+        let entry = stream.fetch(1);
+        assert_eq!(entry.next_pc(), 6);
+        assert!(matches!(
+            entry.instruction,
+            DecodedInstruction::Unknown(0xc9)
+        ));
+        assert!(matches!(
+            stream.fetch(6).instruction,
+            DecodedInstruction::Unknown(0xca)
+        ));
+        assert!(matches!(
+            stream.fetch(7).instruction,
+            DecodedInstruction::Return(_)
+        ));
+        assert_eq!(stream.fetch(8).next_pc(), 9);
+    }
+
+    #[test]
+    fn decode_truncated_unknown_opcode() {
+        let stream = InstructionStream::decode(&[0xb1, 0xc9, 0x00]);
+        assert_eq!(stream.fetch(1).next_pc(), 3);
+    }
+
+    #[test]
+    fn quicken() {
+        // getfield #1, return
+        let stream = InstructionStream::decode(&[0xb4, 0x00, 0x01, 0xb1]);
+        assert!(stream.fetch(0).quickened.get().is_none());
+        stream.quicken(0, QuickInstruction::GetField(2, 'I'));
+        stream.quicken(0, QuickInstruction::GetField(3, 'J'));
+        assert!(matches!(
+            stream.fetch(0).quickened.get(),
+            Some(QuickInstruction::GetField(2, 'I'))
+        ));
+        assert!(stream.fetch(3).quickened.get().is_none());
+    }
+}
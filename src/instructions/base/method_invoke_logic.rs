@@ -1,10 +1,17 @@
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
+use crate::runtime::stack::Stack;
+use crate::oops::class::Class;
 use crate::oops::method::Method;
 use crate::runtime::thread::JavaThread;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 pub fn invoke_method(frame: &mut Frame, method: Rc<Method>) {
+    if !Stack::has_room_for(&method) {
+        throw_exception(frame, "java/lang/StackOverflowError", None);
+        return;
+    }
     let thread = frame.thread();
     let mut new_frame = JavaThread::new_frame(thread.clone(), method.clone());
     let arg_slot_count = method.arg_slot_count();
@@ -18,6 +25,7 @@ pub fn invoke_method(frame: &mut Frame, method: Rc<Method>) {
                 .set_slot((arg_slot_count - 1 - size), slot);
         }
     }
+    new_frame.lock_if_synchronized();
     (*thread).borrow_mut().push_frame(new_frame);
     //    // hack!
     //    if method.is_native() {
@@ -45,6 +53,7 @@ pub fn hack_invoke_method(thread_ptr: Rc<RefCell<JavaThread>>, method: Rc<Method
                 .set_slot((arg_slot_count - 1 - size), slot);
         }
     }
+    new_frame.lock_if_synchronized();
     thread.push_frame(new_frame);
     //    // hack!
     //    if method.is_native() {
@@ -55,3 +64,52 @@ pub fn hack_invoke_method(thread_ptr: Rc<RefCell<JavaThread>>, method: Rc<Method
     //        }
     //    }
 }
+
+/// selects the method a receiver of the class runs for the resolved method
+/// through its vtable or itable, returns None with an IncompatibleClassChangeError
+/// or AbstractMethodError thrown
+pub fn select_method(
+    frame: &mut Frame,
+    receiver_class: &Rc<RefCell<Class>>,
+    resolved_method: &Rc<Method>,
+) -> Option<Rc<Method>> {
+    if resolved_method.is_private() {
+        return Some(resolved_method.clone());
+    }
+    let resolved_class = resolved_method.class();
+    let selected = {
+        let receiver = (**receiver_class).borrow();
+        let interface = (*resolved_class).borrow();
+        if interface.is_interface() {
+            match receiver.itable_methods(&interface) {
+                Some(methods) => Ok(methods[resolved_method.vtable_index()].clone()),
+                None => Err(format!(
+                    "Class {} does not implement the requested interface {}",
+                    receiver.java_name(),
+                    interface.java_name()
+                )),
+            }
+        } else {
+            Ok(Some(receiver.vtable()[resolved_method.vtable_index()].clone()))
+        }
+    };
+    match selected {
+        Ok(Some(method)) if !method.is_abstract() => return Some(method),
+        Ok(Some(_)) => {
+            let msg = format!("{}{}", resolved_method.name(), resolved_method.descriptor());
+            throw_exception(frame, "java/lang/AbstractMethodError", Some(msg.as_str()));
+        }
+        Ok(None) => {
+            let msg = format!(
+                "Conflicting default methods: {}{}",
+                resolved_method.name(),
+                resolved_method.descriptor()
+            );
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", Some(msg.as_str()));
+        }
+        Err(msg) => {
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", Some(msg.as_str()));
+        }
+    }
+    return None;
+}
@@ -2,4 +2,5 @@ pub mod branch_logic;
 pub mod bytecode_reader;
 pub mod class_init_logic;
 pub mod instruction;
+pub mod instruction_stream;
 pub mod method_invoke_logic;
@@ -16,7 +16,7 @@ impl Instruction for Dcmpg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         dcmp(frame, true);
     }
 }
@@ -35,7 +35,7 @@ impl Instruction for Dcmpl {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         dcmp(frame, false);
     }
 }
@@ -16,7 +16,7 @@ impl Instruction for Fcmpg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         fcmp(frame, true);
     }
 }
@@ -35,7 +35,7 @@ impl Instruction for Fcmpl {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         fcmp(frame, false);
     }
 }
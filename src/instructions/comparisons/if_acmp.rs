@@ -24,7 +24,7 @@ impl Instruction for IfACmpEq {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         if acmp(frame) {
             branch(frame, self.0.get_offset());
         }
@@ -45,7 +45,7 @@ impl Instruction for IfACmpNe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         if !acmp(frame) {
             branch(frame, self.0.get_offset());
         }
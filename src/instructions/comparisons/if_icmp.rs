@@ -24,7 +24,7 @@ impl Instruction for IfICmpEq {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 == v2 {
             branch(frame, self.0.get_offset());
@@ -46,7 +46,7 @@ impl Instruction for IfICmpNe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 != v2 {
             branch(frame, self.0.get_offset());
@@ -68,7 +68,7 @@ impl Instruction for IfICmpLt {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 < v2 {
             branch(frame, self.0.get_offset());
@@ -90,7 +90,7 @@ impl Instruction for IfICmpLe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 <= v2 {
             branch(frame, self.0.get_offset());
@@ -112,7 +112,7 @@ impl Instruction for IfICmpGt {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 > v2 {
             branch(frame, self.0.get_offset());
@@ -134,7 +134,7 @@ impl Instruction for IfICmpGe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let (v1, v2) = int_pop(frame);
         if v1 >= v2 {
             branch(frame, self.0.get_offset());
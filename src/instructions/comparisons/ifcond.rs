@@ -17,7 +17,7 @@ impl Instruction for IfEq {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -42,7 +42,7 @@ impl Instruction for IfNe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -67,7 +67,7 @@ impl Instruction for IfLt {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -92,7 +92,7 @@ impl Instruction for IfLe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -117,7 +117,7 @@ impl Instruction for IfGt {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -142,7 +142,7 @@ impl Instruction for IfGe {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -17,7 +17,7 @@ impl Instruction for Lcmp {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
@@ -17,7 +17,7 @@ impl Instruction for AconstNull {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -40,7 +40,7 @@ impl Instruction for Dconst0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -63,7 +63,7 @@ impl Instruction for Dconst1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -86,7 +86,7 @@ impl Instruction for Fconst0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -109,7 +109,7 @@ impl Instruction for Fconst1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -132,7 +132,7 @@ impl Instruction for Fconst2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -155,7 +155,7 @@ impl Instruction for IconstM1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -178,7 +178,7 @@ impl Instruction for Iconst0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -201,7 +201,7 @@ impl Instruction for Iconst1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -224,7 +224,7 @@ impl Instruction for Iconst2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -247,7 +247,7 @@ impl Instruction for Iconst3 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -270,7 +270,7 @@ impl Instruction for Iconst4 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -293,7 +293,7 @@ impl Instruction for Iconst5 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -316,7 +316,7 @@ impl Instruction for Lconst0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -339,7 +339,7 @@ impl Instruction for Lconst1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -19,7 +19,7 @@ impl Instruction for BiPush {
         self.val = reader.read_i8();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -44,7 +44,7 @@ impl Instruction for SiPush {
         self.val = reader.read_i16();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("operand_stack is empty")
@@ -3,8 +3,10 @@ use crate::instructions::base::instruction::{
     ConstantPoolInstruction, Instruction, LocalVarsInstruction,
 };
 use crate::runtime::frame::Frame;
+use crate::invoke_support::method_handle::invoke_classes_initialized;
+use crate::invoke_support::throw_exception;
 use crate::oops::constant_pool::Constant::{
-    ClassReference, Double, Float, Integer, Long, Str,
+    ClassReference, Double, Float, Integer, Long, MethodHandleReference, MethodTypeReference, Str,
 };
 use crate::oops::string_pool::StringPool;
 use crate::oops::constant_pool::Constant;
@@ -23,7 +25,7 @@ impl Instruction for LDC {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         ldc(frame, self.0.get_index());
     }
 }
@@ -42,7 +44,7 @@ impl Instruction for LDCw {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         ldc(frame, self.0.index());
     }
 }
@@ -61,7 +63,7 @@ impl Instruction for LDC2w {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         //        let stack = frame.operand_stack().expect("stack is none");
         let class = frame.method().class();
         let borrow_class = (*class).borrow();
@@ -73,7 +75,10 @@ impl Instruction for LDC2w {
                 .operand_stack()
                 .expect("stack is none")
                 .push_double(*v),
-            _ => panic!("java.lang.ClassFormatError"),
+            _ => {
+                drop(borrow_class);
+                throw_exception(frame, "java/lang/ClassFormatError", Some("ldc2_w of a constant that is not long or double"));
+            }
         }
     }
 }
@@ -93,13 +98,33 @@ fn ldc(frame: &mut Frame, index: usize) {
                 .push_ref(Some(string))
         }
         ClassReference(v) => {
-            let class = v.resolved_class(class.clone());
-            let borrow = (*class).borrow();
-            let obj = borrow.java_class();
-            frame
-                .operand_stack()
-                .expect("stack is none")
-                .push_ref(Some(obj.unwrap().clone()));
+            if let Some(class) = v.resolved_class(class.clone()) {
+                let borrow = (*class).borrow();
+                let obj = borrow.java_class();
+                frame
+                    .operand_stack()
+                    .expect("stack is none")
+                    .push_ref(Some(obj.unwrap().clone()));
+            }
+        }
+        MethodTypeReference(v) => {
+            if invoke_classes_initialized(frame) {
+                let method_type = v.resolved_method_type(class.clone());
+                frame
+                    .operand_stack()
+                    .expect("stack is none")
+                    .push_ref(Some(method_type));
+            }
+        }
+        MethodHandleReference(v) => {
+            if invoke_classes_initialized(frame) {
+                if let Some(method_handle) = v.resolved_method_handle(class.clone()) {
+                    frame
+                        .operand_stack()
+                        .expect("stack is none")
+                        .push_ref(Some(method_handle));
+                }
+            }
         }
         _ => panic!("todo: ldc!"),
     }
@@ -6,7 +6,7 @@ use crate::runtime::frame::Frame;
 pub struct LookUpSwitch {
     default_offset: i32,
     npairs: i32,
-    match_offsets: Vec<i32>,
+    match_offsets: Box<[i32]>,
 }
 
 impl LookUpSwitch {
@@ -15,7 +15,7 @@ impl LookUpSwitch {
         return LookUpSwitch {
             default_offset: 0,
             npairs: 0,
-            match_offsets: vec![],
+            match_offsets: Box::new([]),
         };
     }
 }
@@ -25,10 +25,12 @@ impl Instruction for LookUpSwitch {
         reader.skip_padding();
         self.default_offset = reader.read_i32();
         self.npairs = reader.read_i32();
-        self.match_offsets = reader.read_i32_table((self.npairs * 2) as usize);
+        self.match_offsets = reader
+            .read_i32_table((self.npairs * 2) as usize)
+            .into_boxed_slice();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let val = frame
             .operand_stack()
             .expect("operand_stack is none")
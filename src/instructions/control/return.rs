@@ -16,7 +16,8 @@ impl Instruction for Return {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         (*thread).borrow_mut().pop_frame();
     }
@@ -36,7 +37,8 @@ impl Instruction for AReturn {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         let _current_frame = (*thread).borrow_mut().pop_frame();
         let invoke_frame = (*thread).borrow().current_frame();
@@ -65,7 +67,8 @@ impl Instruction for DReturn {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         let _current_frame = (*thread).borrow_mut().pop_frame();
         let invoke_frame = (*thread).borrow().current_frame();
@@ -93,7 +96,8 @@ impl Instruction for FReturn {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         let _current_frame = (*thread).borrow_mut().pop_frame();
         let invoke_frame = (*thread).borrow().current_frame();
@@ -121,7 +125,8 @@ impl Instruction for IReturn {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         let _current_frame = (*thread).borrow_mut().pop_frame();
         let invoke_frame = (*thread).borrow().current_frame();
@@ -149,7 +154,8 @@ impl Instruction for LReturn {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
+        frame.unlock_if_synchronized();
         let thread = frame.thread();
         let _current_frame = (*thread).borrow_mut().pop_frame();
         let invoke_frame = (*thread).borrow().current_frame();
@@ -6,8 +6,8 @@ use crate::runtime::frame::Frame;
 pub struct TableSwitch {
     default_offset: i32,
     low: i32,
-    high: i32,
-    jump_offsets: Vec<i32>,
+    /// high - low + 1 offsets, a boxed slice keeps the decoded instruction small
+    jump_offsets: Box<[i32]>,
 }
 
 impl TableSwitch {
@@ -16,8 +16,7 @@ impl TableSwitch {
         return TableSwitch {
             default_offset: 0,
             low: 0,
-            high: 0,
-            jump_offsets: vec![],
+            jump_offsets: Box::new([]),
         };
     }
 }
@@ -27,24 +26,26 @@ impl Instruction for TableSwitch {
         reader.skip_padding();
         self.default_offset = reader.read_i32();
         self.low = reader.read_i32();
-        self.high = reader.read_i32();
-        let jump_offset_count = self.high - self.low + 1;
-        self.jump_offsets = reader.read_i32_table(jump_offset_count as usize);
+        let high = reader.read_i32();
+        let jump_offset_count = high - self.low + 1;
+        self.jump_offsets = reader
+            .read_i32_table(jump_offset_count as usize)
+            .into_boxed_slice();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let index = frame
             .operand_stack()
             .expect("operand_stack is none")
             .pop_int();
-        let mut offset = 0;
-        if index >= self.low && index <= self.high {
-            offset = *self
+        let mut offset = self.default_offset;
+        if index >= self.low {
+            if let Some(jump_offset) = self
                 .jump_offsets
-                .get((index - self.low) as usize)
-                .expect("jump_offsets' index is small");
-        } else {
-            offset = self.default_offset;
+                .get(index.wrapping_sub(self.low) as u32 as usize)
+            {
+                offset = *jump_offset;
+            }
         }
         branch(frame, offset);
     }
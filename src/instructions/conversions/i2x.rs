@@ -16,7 +16,7 @@ impl Instruction for I2d {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int();
         stack.push_double(v1 as f64);
@@ -37,7 +37,7 @@ impl Instruction for I2f {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int();
         stack.push_float(v1 as f32);
@@ -58,7 +58,7 @@ impl Instruction for I2l {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int();
         stack.push_long(v1 as i64);
@@ -79,7 +79,7 @@ impl Instruction for I2b {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int() as i8;
         stack.push_int(v1 as i32);
@@ -100,7 +100,7 @@ impl Instruction for I2c {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int() as u16;
         stack.push_int(v1 as i32);
@@ -121,7 +121,7 @@ impl Instruction for I2s {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int() as i16;
         stack.push_int(v1 as i32);
@@ -17,7 +17,7 @@ impl Instruction for IfNull {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let reference = frame
             .operand_stack()
             .expect("operand_stack is none")
@@ -42,7 +42,7 @@ impl Instruction for IfNonNull {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let reference = frame
             .operand_stack()
             .expect("operand_stack is none")
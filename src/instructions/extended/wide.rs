@@ -81,7 +81,7 @@ impl Instruction for Wide {
         }
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         self.modified_instruction.execute(frame);
     }
 }
@@ -34,7 +34,7 @@ impl Instruction for ALoad {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_load(frame, self.0.get_index());
     }
 }
@@ -54,7 +54,7 @@ impl Instruction for ALoad0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_load(frame, 0);
     }
 }
@@ -74,7 +74,7 @@ impl Instruction for ALoad1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_load(frame, 1);
     }
 }
@@ -94,7 +94,7 @@ impl Instruction for ALoad2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_load(frame, 2);
     }
 }
@@ -114,7 +114,7 @@ impl Instruction for ALoad3 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_load(frame, 3);
     }
 }
@@ -1,6 +1,7 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
 use crate::instructions::check_index;
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 pub struct AAload(NoOperandsInstruction);
@@ -17,18 +18,24 @@ impl Instruction for AAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let references = borrow.references();
-        check_index(references.len(), index);
-        stack.push_ref(references[index].clone());
+        if !check_index(frame, references.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_ref(references[index as usize].clone());
     }
 }
 
@@ -46,18 +53,24 @@ impl Instruction for BAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let bytes = borrow.bytes();
-        check_index(bytes.len(), index);
-        stack.push_int(bytes[index] as i32);
+        if !check_index(frame, bytes.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(bytes[index as usize] as i32);
     }
 }
 
@@ -75,18 +88,24 @@ impl Instruction for CAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let chars = borrow.chars();
-        check_index(chars.len(), index);
-        stack.push_int(chars[index] as i32);
+        if !check_index(frame, chars.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(chars[index as usize] as i32);
     }
 }
 
@@ -104,18 +123,24 @@ impl Instruction for DAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let doubles = borrow.doubles();
-        check_index(doubles.len(), index);
-        stack.push_double(doubles[index]);
+        if !check_index(frame, doubles.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_double(doubles[index as usize]);
     }
 }
 
@@ -133,18 +158,24 @@ impl Instruction for FAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let floats = borrow.floats();
-        check_index(floats.len(), index);
-        stack.push_float(floats[index]);
+        if !check_index(frame, floats.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_float(floats[index as usize]);
     }
 }
 
@@ -162,18 +193,24 @@ impl Instruction for IAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let ints = borrow.ints();
-        check_index(ints.len(), index);
-        stack.push_int(ints[index]);
+        if !check_index(frame, ints.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(ints[index as usize]);
     }
 }
 
@@ -191,18 +228,24 @@ impl Instruction for LAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let longs = borrow.longs();
-        check_index(longs.len(), index);
-        stack.push_long(longs[index]);
+        if !check_index(frame, longs.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_long(longs[index as usize]);
     }
 }
 
@@ -220,17 +263,23 @@ impl Instruction for SAload {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let borrow = (*object).borrow();
         let shorts = borrow.shorts();
-        check_index(shorts.len(), index);
-        stack.push_int(shorts[index] as i32);
+        if !check_index(frame, shorts.len(), index) {
+            return;
+        }
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(shorts[index as usize] as i32);
     }
 }
@@ -16,7 +16,7 @@ impl Instruction for DAdd {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
@@ -39,7 +39,7 @@ impl Instruction for FAdd {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
@@ -62,7 +62,7 @@ impl Instruction for IAdd {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
@@ -85,7 +85,7 @@ impl Instruction for LAdd {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
@@ -1,5 +1,6 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 ///d_div
@@ -17,7 +18,7 @@ impl Instruction for DDiv {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
@@ -41,7 +42,7 @@ impl Instruction for FDiv {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
@@ -65,11 +66,15 @@ impl Instruction for IDiv {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
-        let rs = v1 / v2;
+        if v2 == 0 {
+            throw_exception(frame, "java/lang/ArithmeticException", Some("/ by zero"));
+            return;
+        }
+        let rs = v1.wrapping_div(v2);
         stack.push_int(rs);
     }
 }
@@ -89,11 +94,15 @@ impl Instruction for LDiv {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
-        let rs = v1 / v2;
+        if v2 == 0 {
+            throw_exception(frame, "java/lang/ArithmeticException", Some("/ by zero"));
+            return;
+        }
+        let rs = v1.wrapping_div(v2);
         stack.push_long(rs);
     }
 }
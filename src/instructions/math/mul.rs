@@ -16,7 +16,7 @@ impl Instruction for DMul {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
@@ -39,7 +39,7 @@ impl Instruction for FMul {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
@@ -62,7 +62,7 @@ impl Instruction for IMul {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_int() as i64;
         let v1 = stack.pop_int() as i64;
@@ -85,7 +85,7 @@ impl Instruction for LMul {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
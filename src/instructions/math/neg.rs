@@ -17,7 +17,7 @@ impl Instruction for DNeg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_double();
         stack.push_double(-v1);
@@ -39,7 +39,7 @@ impl Instruction for FNeg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_float();
         stack.push_float(-v1);
@@ -61,7 +61,7 @@ impl Instruction for INeg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_int();
         stack.push_int(-v1);
@@ -83,7 +83,7 @@ impl Instruction for LNeg {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v1 = stack.pop_long();
         stack.push_long(-v1);
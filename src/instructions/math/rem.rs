@@ -1,5 +1,6 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 ///drem
@@ -17,11 +18,11 @@ impl Instruction for DRem {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
-        let rs = v1.rem_euclid(v2);
+        let rs = v1 % v2;
         stack.push_double(rs);
     }
 }
@@ -41,11 +42,11 @@ impl Instruction for FRem {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
-        let rs = v1.rem_euclid(v2);
+        let rs = v1 % v2;
         stack.push_float(rs);
     }
 }
@@ -65,11 +66,15 @@ impl Instruction for IRem {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
-        let rs = v1.rem_euclid(v2);
+        if v2 == 0 {
+            throw_exception(frame, "java/lang/ArithmeticException", Some("/ by zero"));
+            return;
+        }
+        let rs = v1.wrapping_rem(v2);
         stack.push_int(rs);
     }
 }
@@ -89,11 +94,15 @@ impl Instruction for LRem {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
-        let rs = v1.rem_euclid(v2);
+        if v2 == 0 {
+            throw_exception(frame, "java/lang/ArithmeticException", Some("/ by zero"));
+            return;
+        }
+        let rs = v1.wrapping_rem(v2);
         stack.push_long(rs);
     }
 }
@@ -1,3 +1,4 @@
+use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::Instruction;
 use crate::instructions::comparisons::dcmp::{Dcmpg, Dcmpl};
 use crate::instructions::comparisons::fcmp::{Fcmpg, Fcmpl};
@@ -51,6 +52,7 @@ use crate::instructions::references::check_cast::CheckCast;
 use crate::instructions::references::get_field::GetField;
 use crate::instructions::references::get_static::GetStatic;
 use crate::instructions::references::instance_of::InstanceOf;
+use crate::instructions::references::invoke_dynamic::InvokeDynamic;
 use crate::instructions::references::invoke_interface::InvokeInterface;
 use crate::instructions::references::invoke_special::InvokeSpecial;
 use crate::instructions::references::invoke_static::InvokeStatic;
@@ -73,6 +75,8 @@ use crate::instructions::stores::dstore::{DStore, DStore0, DStore1, DStore2, DSt
 use crate::instructions::stores::fstore::{FStore, FStore0, FStore1, FStore2, FStore3};
 use crate::instructions::stores::istore::{IStore, IStore0, IStore1, IStore2, IStore3};
 use crate::instructions::stores::lstore::{LStore, LStore0, LStore1, LStore2, LStore3};
+use crate::invoke_support::throw_exception;
+use crate::runtime::frame::Frame;
 
 pub mod base;
 mod comparisons;
@@ -82,226 +86,788 @@ mod conversions;
 mod extended;
 mod loads;
 mod math;
+pub mod quick;
 pub mod references;
 mod reserved;
 mod stack;
 mod stores;
 
-pub fn new_instruction(opcode: u8) -> Box<dyn Instruction> {
-    let inst: Box<dyn Instruction> = match opcode {
-        0x00 => Box::new(Nop::new()),
-        0x01 => Box::new(AconstNull::new()),
-        0x02 => Box::new(IconstM1::new()),
-        0x03 => Box::new(Iconst0::new()),
-        0x04 => Box::new(Iconst1::new()),
-        0x05 => Box::new(Iconst2::new()),
-        0x06 => Box::new(Iconst3::new()),
-        0x07 => Box::new(Iconst4::new()),
-        0x08 => Box::new(Iconst5::new()),
-        0x09 => Box::new(Lconst0::new()),
-        0x0a => Box::new(Lconst1::new()),
-        0x0b => Box::new(Fconst0::new()),
-        0x0c => Box::new(Fconst1::new()),
-        0x0d => Box::new(Fconst2::new()),
-        0x0e => Box::new(Dconst0::new()),
-        0x0f => Box::new(Dconst1::new()),
-        0x10 => Box::new(BiPush::new()),
-        0x11 => Box::new(SiPush::new()),
-        0x12 => Box::new(LDC::new()),
-        0x13 => Box::new(LDCw::new()),
-        0x14 => Box::new(LDC2w::new()),
-        0x15 => Box::new(ILoad::new()),
-        0x16 => Box::new(LLoad::new()),
-        0x17 => Box::new(FLoad::new()),
-        0x18 => Box::new(DLoad::new()),
-        0x19 => Box::new(ALoad::new()),
-        0x1a => Box::new(ILoad0::new()),
-        0x1b => Box::new(ILoad1::new()),
-        0x1c => Box::new(ILoad2::new()),
-        0x1d => Box::new(ILoad3::new()),
-        0x1e => Box::new(LLoad0::new()),
-        0x1f => Box::new(LLoad1::new()),
-        0x20 => Box::new(LLoad2::new()),
-        0x21 => Box::new(LLoad3::new()),
-        0x22 => Box::new(FLoad0::new()),
-        0x23 => Box::new(FLoad1::new()),
-        0x24 => Box::new(FLoad2::new()),
-        0x25 => Box::new(FLoad3::new()),
-        0x26 => Box::new(DLoad0::new()),
-        0x27 => Box::new(DLoad1::new()),
-        0x28 => Box::new(DLoad2::new()),
-        0x29 => Box::new(DLoad3::new()),
-        0x2a => Box::new(ALoad0::new()),
-        0x2b => Box::new(ALoad1::new()),
-        0x2c => Box::new(ALoad2::new()),
-        0x2d => Box::new(ALoad3::new()),
-        0x2e => Box::new(IAload::new()),
-        0x2f => Box::new(LAload::new()),
-        0x30 => Box::new(FAload::new()),
-        0x31 => Box::new(DAload::new()),
-        0x32 => Box::new(AAload::new()),
-        0x33 => Box::new(BAload::new()),
-        0x34 => Box::new(CAload::new()),
-        0x35 => Box::new(SAload::new()),
-        0x36 => Box::new(IStore::new()),
-        0x37 => Box::new(LStore::new()),
-        0x38 => Box::new(FStore::new()),
-        0x39 => Box::new(DStore::new()),
-        0x3a => Box::new(AStore::new()),
-        0x3b => Box::new(IStore0::new()),
-        0x3c => Box::new(IStore1::new()),
-        0x3d => Box::new(IStore2::new()),
-        0x3e => Box::new(IStore3::new()),
-        0x3f => Box::new(LStore0::new()),
-        0x40 => Box::new(LStore1::new()),
-        0x41 => Box::new(LStore2::new()),
-        0x42 => Box::new(LStore3::new()),
-        0x43 => Box::new(FStore0::new()),
-        0x44 => Box::new(FStore1::new()),
-        0x45 => Box::new(FStore2::new()),
-        0x46 => Box::new(FStore3::new()),
-        0x47 => Box::new(DStore0::new()),
-        0x48 => Box::new(DStore1::new()),
-        0x49 => Box::new(DStore2::new()),
-        0x4a => Box::new(DStore3::new()),
-        0x4b => Box::new(AStore0::new()),
-        0x4c => Box::new(AStore1::new()),
-        0x4d => Box::new(AStore2::new()),
-        0x4e => Box::new(AStore3::new()),
-        0x4f => Box::new(IAStore::new()),
-        0x50 => Box::new(LAStore::new()),
-        0x51 => Box::new(FAStore::new()),
-        0x52 => Box::new(DAStore::new()),
-        0x53 => Box::new(AAStore::new()),
-        0x54 => Box::new(BAStore::new()),
-        0x55 => Box::new(CAStore::new()),
-        0x56 => Box::new(SAStore::new()),
-        0x57 => Box::new(Pop::new()),
-        0x58 => Box::new(Pop2::new()),
-        0x59 => Box::new(Dup::new()),
-        0x5a => Box::new(DupX1::new()),
-        0x5b => Box::new(DupX2::new()),
-        0x5c => Box::new(Dup2::new()),
-        0x5d => Box::new(Dup2X1::new()),
-        0x5e => Box::new(Dup2X2::new()),
-        0x5f => Box::new(Swap::new()),
-        0x60 => Box::new(IAdd::new()),
-        0x61 => Box::new(LAdd::new()),
-        0x62 => Box::new(FAdd::new()),
-        0x63 => Box::new(DAdd::new()),
-        0x64 => Box::new(ISub::new()),
-        0x65 => Box::new(LSub::new()),
-        0x66 => Box::new(FSub::new()),
-        0x67 => Box::new(DSub::new()),
-        0x68 => Box::new(IMul::new()),
-        0x69 => Box::new(LMul::new()),
-        0x6a => Box::new(FMul::new()),
-        0x6b => Box::new(DMul::new()),
-        0x6c => Box::new(IDiv::new()),
-        0x6d => Box::new(LDiv::new()),
-        0x6e => Box::new(FDiv::new()),
-        0x6f => Box::new(DDiv::new()),
-        0x70 => Box::new(IRem::new()),
-        0x71 => Box::new(LRem::new()),
-        0x72 => Box::new(FRem::new()),
-        0x73 => Box::new(DRem::new()),
-        0x74 => Box::new(INeg::new()),
-        0x75 => Box::new(LNeg::new()),
-        0x76 => Box::new(FNeg::new()),
-        0x77 => Box::new(DNeg::new()),
-        0x78 => Box::new(IShl::new()),
-        0x79 => Box::new(LShl::new()),
-        0x7a => Box::new(IShr::new()),
-        0x7b => Box::new(LShr::new()),
-        0x7c => Box::new(IuShr::new()),
-        0x7d => Box::new(LuShr::new()),
-        0x7e => Box::new(IAnd::new()),
-        0x7f => Box::new(LAnd::new()),
-        0x80 => Box::new(IOr::new()),
-        0x81 => Box::new(LOr::new()),
-        0x82 => Box::new(IXor::new()),
-        0x83 => Box::new(LXor::new()),
-        0x84 => Box::new(IInc::new()),
-        0x85 => Box::new(I2l::new()),
-        0x86 => Box::new(I2f::new()),
-        0x87 => Box::new(I2d::new()),
-        0x88 => Box::new(L2i::new()),
-        0x89 => Box::new(L2f::new()),
-        0x8a => Box::new(L2d::new()),
-        0x8b => Box::new(F2i::new()),
-        0x8c => Box::new(F2l::new()),
-        0x8d => Box::new(F2d::new()),
-        0x8e => Box::new(D2i::new()),
-        0x8f => Box::new(D2l::new()),
-        0x90 => Box::new(D2f::new()),
-        0x91 => Box::new(I2b::new()),
-        0x92 => Box::new(I2c::new()),
-        0x93 => Box::new(I2s::new()),
-        0x94 => Box::new(Lcmp::new()),
-        0x95 => Box::new(Fcmpl::new()),
-        0x96 => Box::new(Fcmpg::new()),
-        0x97 => Box::new(Dcmpl::new()),
-        0x98 => Box::new(Dcmpg::new()),
-        0x99 => Box::new(IfEq::new()),
-        0x9a => Box::new(IfNe::new()),
-        0x9b => Box::new(IfLt::new()),
-        0x9c => Box::new(IfGe::new()),
-        0x9d => Box::new(IfGt::new()),
-        0x9e => Box::new(IfLe::new()),
-        0x9f => Box::new(IfICmpEq::new()),
-        0xa0 => Box::new(IfICmpNe::new()),
-        0xa1 => Box::new(IfICmpLt::new()),
-        0xa2 => Box::new(IfICmpGe::new()),
-        0xa3 => Box::new(IfICmpGt::new()),
-        0xa4 => Box::new(IfICmpLe::new()),
-        0xa5 => Box::new(IfACmpEq::new()),
-        0xa6 => Box::new(IfACmpNe::new()),
-        0xa7 => Box::new(Goto::new()),
-        //        0xa8 => {},
-        //        0xa9 => {},
-        0xaa => Box::new(TableSwitch::new()),
-        0xab => Box::new(LookUpSwitch::new()),
-        0xac => Box::new(IReturn::new()),
-        0xad => Box::new(LReturn::new()),
-        0xae => Box::new(FReturn::new()),
-        0xaf => Box::new(DReturn::new()),
-        0xb0 => Box::new(AReturn::new()),
-        0xb1 => Box::new(Return::new()),
-        0xb2 => Box::new(GetStatic::new()),
-        0xb3 => Box::new(PutStatic::new()),
-        0xb4 => Box::new(GetField::new()),
-        0xb5 => Box::new(PutField::new()),
-        0xb6 => Box::new(InvokeVirtual::new()),
-        0xb7 => Box::new(InvokeSpecial::new()),
-        0xb8 => Box::new(InvokeStatic::new()),
-        0xb9 => Box::new(InvokeInterface::new()),
-        //        0xba => {},
-        0xbb => Box::new(New::new()),
-        0xbc => Box::new(NewArray::new()),
-        0xbd => Box::new(ANewArray::new()),
-        0xbe => Box::new(ArrayLength::new()),
-        0xbf => Box::new(AThrow::new()),
-        0xc0 => Box::new(CheckCast::new()),
-        0xc1 => Box::new(InstanceOf::new()),
-        0xc2 => Box::new(MonitorEnter::new()),
-        0xc3 => Box::new(MonitorExit::new()),
-        0xc4 => Box::new(Wide::new()),
-        0xc5 => Box::new(MultiANewArray::new()),
-        0xc6 => Box::new(IfNull::new()),
-        0xc7 => Box::new(IfNonNull::new()),
-        0xc8 => Box::new(GotoW::new()),
-        0xfe => Box::new(InvokeNative::new()),
-        _c => {
-            println!("opcode:{}", _c);
-            panic!("instruction error")
+/// maps each opcode to the instruction that implements it, generating
+/// the `DecodedInstruction` enum and its decode and execute dispatch
+macro_rules! instruction_set {
+    ($($opcode:literal => $name:ident,)*) => {
+        /// an instruction with its operands inline, decoded once per method
+        pub enum DecodedInstruction {
+            $($name($name),)*
+            /// an opcode the interpreter doesn't implement, executing it
+            /// throws InternalError
+            Unknown(u8),
+        }
+
+        impl DecodedInstruction {
+            /// reads the instruction at the reader's pc, the operands of
+            /// an unknown opcode are left to the caller
+            pub fn decode(reader: &mut BytecodeReader) -> DecodedInstruction {
+                let opcode = reader.read_u8();
+                let inst = match opcode {
+                    $($opcode => {
+                        let mut inst = $name::new();
+                        inst.fetch_operands(reader);
+                        DecodedInstruction::$name(inst)
+                    })*
+                    _ => DecodedInstruction::Unknown(opcode),
+                };
+                return inst;
+            }
+
+            #[inline]
+            pub fn execute(&self, frame: &mut Frame) {
+                match self {
+                    $(DecodedInstruction::$name(inst) => inst.execute(frame),)*
+                    DecodedInstruction::Unknown(opcode) => unknown_opcode(frame, *opcode),
+                }
+            }
         }
     };
-    //    println!("instruction:{:?}",opcode);
-    return inst;
 }
 
-fn check_index(arr_len: usize, index: usize) {
-    if index >= arr_len {
-        panic!("ArrayIndexOutOfBoundsException")
+instruction_set! {
+    0x00 => Nop,
+    0x01 => AconstNull,
+    0x02 => IconstM1,
+    0x03 => Iconst0,
+    0x04 => Iconst1,
+    0x05 => Iconst2,
+    0x06 => Iconst3,
+    0x07 => Iconst4,
+    0x08 => Iconst5,
+    0x09 => Lconst0,
+    0x0a => Lconst1,
+    0x0b => Fconst0,
+    0x0c => Fconst1,
+    0x0d => Fconst2,
+    0x0e => Dconst0,
+    0x0f => Dconst1,
+    0x10 => BiPush,
+    0x11 => SiPush,
+    0x12 => LDC,
+    0x13 => LDCw,
+    0x14 => LDC2w,
+    0x15 => ILoad,
+    0x16 => LLoad,
+    0x17 => FLoad,
+    0x18 => DLoad,
+    0x19 => ALoad,
+    0x1a => ILoad0,
+    0x1b => ILoad1,
+    0x1c => ILoad2,
+    0x1d => ILoad3,
+    0x1e => LLoad0,
+    0x1f => LLoad1,
+    0x20 => LLoad2,
+    0x21 => LLoad3,
+    0x22 => FLoad0,
+    0x23 => FLoad1,
+    0x24 => FLoad2,
+    0x25 => FLoad3,
+    0x26 => DLoad0,
+    0x27 => DLoad1,
+    0x28 => DLoad2,
+    0x29 => DLoad3,
+    0x2a => ALoad0,
+    0x2b => ALoad1,
+    0x2c => ALoad2,
+    0x2d => ALoad3,
+    0x2e => IAload,
+    0x2f => LAload,
+    0x30 => FAload,
+    0x31 => DAload,
+    0x32 => AAload,
+    0x33 => BAload,
+    0x34 => CAload,
+    0x35 => SAload,
+    0x36 => IStore,
+    0x37 => LStore,
+    0x38 => FStore,
+    0x39 => DStore,
+    0x3a => AStore,
+    0x3b => IStore0,
+    0x3c => IStore1,
+    0x3d => IStore2,
+    0x3e => IStore3,
+    0x3f => LStore0,
+    0x40 => LStore1,
+    0x41 => LStore2,
+    0x42 => LStore3,
+    0x43 => FStore0,
+    0x44 => FStore1,
+    0x45 => FStore2,
+    0x46 => FStore3,
+    0x47 => DStore0,
+    0x48 => DStore1,
+    0x49 => DStore2,
+    0x4a => DStore3,
+    0x4b => AStore0,
+    0x4c => AStore1,
+    0x4d => AStore2,
+    0x4e => AStore3,
+    0x4f => IAStore,
+    0x50 => LAStore,
+    0x51 => FAStore,
+    0x52 => DAStore,
+    0x53 => AAStore,
+    0x54 => BAStore,
+    0x55 => CAStore,
+    0x56 => SAStore,
+    0x57 => Pop,
+    0x58 => Pop2,
+    0x59 => Dup,
+    0x5a => DupX1,
+    0x5b => DupX2,
+    0x5c => Dup2,
+    0x5d => Dup2X1,
+    0x5e => Dup2X2,
+    0x5f => Swap,
+    0x60 => IAdd,
+    0x61 => LAdd,
+    0x62 => FAdd,
+    0x63 => DAdd,
+    0x64 => ISub,
+    0x65 => LSub,
+    0x66 => FSub,
+    0x67 => DSub,
+    0x68 => IMul,
+    0x69 => LMul,
+    0x6a => FMul,
+    0x6b => DMul,
+    0x6c => IDiv,
+    0x6d => LDiv,
+    0x6e => FDiv,
+    0x6f => DDiv,
+    0x70 => IRem,
+    0x71 => LRem,
+    0x72 => FRem,
+    0x73 => DRem,
+    0x74 => INeg,
+    0x75 => LNeg,
+    0x76 => FNeg,
+    0x77 => DNeg,
+    0x78 => IShl,
+    0x79 => LShl,
+    0x7a => IShr,
+    0x7b => LShr,
+    0x7c => IuShr,
+    0x7d => LuShr,
+    0x7e => IAnd,
+    0x7f => LAnd,
+    0x80 => IOr,
+    0x81 => LOr,
+    0x82 => IXor,
+    0x83 => LXor,
+    0x84 => IInc,
+    0x85 => I2l,
+    0x86 => I2f,
+    0x87 => I2d,
+    0x88 => L2i,
+    0x89 => L2f,
+    0x8a => L2d,
+    0x8b => F2i,
+    0x8c => F2l,
+    0x8d => F2d,
+    0x8e => D2i,
+    0x8f => D2l,
+    0x90 => D2f,
+    0x91 => I2b,
+    0x92 => I2c,
+    0x93 => I2s,
+    0x94 => Lcmp,
+    0x95 => Fcmpl,
+    0x96 => Fcmpg,
+    0x97 => Dcmpl,
+    0x98 => Dcmpg,
+    0x99 => IfEq,
+    0x9a => IfNe,
+    0x9b => IfLt,
+    0x9c => IfGe,
+    0x9d => IfGt,
+    0x9e => IfLe,
+    0x9f => IfICmpEq,
+    0xa0 => IfICmpNe,
+    0xa1 => IfICmpLt,
+    0xa2 => IfICmpGe,
+    0xa3 => IfICmpGt,
+    0xa4 => IfICmpLe,
+    0xa5 => IfACmpEq,
+    0xa6 => IfACmpNe,
+    0xa7 => Goto,
+    //    0xa8 => Jsr,
+    //    0xa9 => Ret,
+    0xaa => TableSwitch,
+    0xab => LookUpSwitch,
+    0xac => IReturn,
+    0xad => LReturn,
+    0xae => FReturn,
+    0xaf => DReturn,
+    0xb0 => AReturn,
+    0xb1 => Return,
+    0xb2 => GetStatic,
+    0xb3 => PutStatic,
+    0xb4 => GetField,
+    0xb5 => PutField,
+    0xb6 => InvokeVirtual,
+    0xb7 => InvokeSpecial,
+    0xb8 => InvokeStatic,
+    0xb9 => InvokeInterface,
+    0xba => InvokeDynamic,
+    0xbb => New,
+    0xbc => NewArray,
+    0xbd => ANewArray,
+    0xbe => ArrayLength,
+    0xbf => AThrow,
+    0xc0 => CheckCast,
+    0xc1 => InstanceOf,
+    0xc2 => MonitorEnter,
+    0xc3 => MonitorExit,
+    0xc4 => Wide,
+    0xc5 => MultiANewArray,
+    0xc6 => IfNull,
+    0xc7 => IfNonNull,
+    0xc8 => GotoW,
+    0xfe => InvokeNative,
+}
+
+fn unknown_opcode(frame: &mut Frame, opcode: u8) {
+    let msg = format!("unimplemented opcode {:#04x}", opcode);
+    throw_exception(frame, "java/lang/InternalError", Some(msg.as_str()));
+}
+
+/// throws ArrayIndexOutOfBoundsException unless the index is inside the array
+fn check_index(frame: &mut Frame, arr_len: usize, index: i32) -> bool {
+    if index < 0 || index as usize >= arr_len {
+        throw_exception(
+            frame,
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(index.to_string().as_str()),
+        );
+        return false;
+    }
+    return true;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::test_classes::{ClassBuilder, TestLoader};
+    use crate::instructions::quick::QuickInstruction;
+    use crate::interpreter::step;
+    use crate::invoke_support::thrown;
+    use crate::oops::access_flags::{
+        ABSTRACT, FINAL, INTERFACE, NATIVE, PRIVATE, PROTECTED, PUBLIC, STATIC,
+    };
+    use crate::oops::class::Class;
+    use crate::oops::method::Method;
+    use crate::oops::object::DataType::{
+        Bytes, Chars, Doubles, Floats, Ints, Longs, References, Shorts,
+    };
+    use crate::oops::object::{DataType, Object};
+    use crate::runtime::operand_stack::OperandStack;
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Reference = Option<Rc<RefCell<Object>>>;
+    type PushValue = fn(&mut OperandStack);
+
+    /// executes the first instruction of the method on the operands and
+    /// returns the class name of the exception it threw
+    fn thrown_by(method: Rc<Method>, operands: impl FnOnce(&mut OperandStack)) -> String {
+        let thread = boxed(JavaThread::new_thread());
+        let mut frame = JavaThread::new_frame(thread.clone(), method);
+        operands(frame.operand_stack().unwrap());
+        (*thread).borrow_mut().push_frame(frame);
+        step(&thread);
+        return thrown::take_class_name();
+    }
+
+    fn code(code: &[u8]) -> Rc<Method> {
+        return Rc::new(Method::with_code(8, 8, code.to_vec()));
+    }
+
+    /// the static method a/Caller.run, `code` adds the constants it refers to
+    fn caller(loader: &TestLoader, code: impl FnOnce(&mut ClassBuilder) -> Vec<u8>) -> Rc<Method> {
+        let mut builder = ClassBuilder::new("a/Caller");
+        let code = code(&mut builder);
+        builder.method_with_code(PUBLIC | STATIC, "run", "()V", &code);
+        let class = loader.define(&builder);
+        let method = (*class).borrow().methods()[0].clone();
+        return method;
+    }
+
+    /// an instruction taking a constant pool index
+    fn with_index(opcode: u8, index: u16) -> Vec<u8> {
+        let [high, low] = index.to_be_bytes();
+        return vec![opcode, high, low];
+    }
+
+    fn array(data: DataType) -> Reference {
+        let mut array = Object::new(boxed(Class::default()));
+        array.data = data;
+        return Some(boxed(array));
+    }
+
+    fn object(class: &Rc<RefCell<Class>>) -> Reference {
+        return Some(boxed(Object::new(class.clone())));
+    }
+
+    fn class(loader: &TestLoader, name: &str, access_flags: u16) -> Rc<RefCell<Class>> {
+        let mut builder = ClassBuilder::new(name);
+        builder.access_flags(access_flags);
+        return loader.define(&builder);
+    }
+
+    #[test]
+    fn test_array_load() {
+        // iaload, laload, faload, daload, aaload, baload, caload, saload
+        let arrays = vec![
+            (0x2e, Ints(vec![0])),
+            (0x2f, Longs(vec![0])),
+            (0x30, Floats(vec![0.0])),
+            (0x31, Doubles(vec![0.0])),
+            (0x32, References(vec![None])),
+            (0x33, Bytes(vec![0])),
+            (0x34, Chars(vec![0])),
+            (0x35, Shorts(vec![0])),
+        ];
+        for (opcode, data) in arrays {
+            let method = code(&[opcode]);
+            let thrown = thrown_by(method.clone(), |stack| {
+                stack.push_ref(None);
+                stack.push_int(0);
+            });
+            assert_eq!(thrown, "java/lang/NullPointerException");
+            let array = array(data);
+            for index in &[-1, 1] {
+                let thrown = thrown_by(method.clone(), |stack| {
+                    stack.push_ref(array.clone());
+                    stack.push_int(*index);
+                });
+                assert_eq!(thrown, "java/lang/ArrayIndexOutOfBoundsException");
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_store() {
+        // iastore, lastore, fastore, dastore, aastore, bastore, castore, sastore
+        let arrays: Vec<(u8, DataType, PushValue)> = vec![
+            (0x4f, Ints(vec![0]), |stack| stack.push_int(0)),
+            (0x50, Longs(vec![0]), |stack| stack.push_long(0)),
+            (0x51, Floats(vec![0.0]), |stack| stack.push_float(0.0)),
+            (0x52, Doubles(vec![0.0]), |stack| stack.push_double(0.0)),
+            (0x53, References(vec![None]), |stack| stack.push_ref(None)),
+            (0x54, Bytes(vec![0]), |stack| stack.push_int(0)),
+            (0x55, Chars(vec![0]), |stack| stack.push_int(0)),
+            (0x56, Shorts(vec![0]), |stack| stack.push_int(0)),
+        ];
+        for (opcode, data, push_value) in arrays {
+            let method = code(&[opcode]);
+            let thrown = thrown_by(method.clone(), |stack| {
+                stack.push_ref(None);
+                stack.push_int(0);
+                push_value(stack);
+            });
+            assert_eq!(thrown, "java/lang/NullPointerException");
+            let array = array(data);
+            for index in &[-1, 1] {
+                let thrown = thrown_by(method.clone(), |stack| {
+                    stack.push_ref(array.clone());
+                    stack.push_int(*index);
+                    push_value(stack);
+                });
+                assert_eq!(thrown, "java/lang/ArrayIndexOutOfBoundsException");
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_store_type() {
+        let loader = TestLoader::new();
+        class(&loader, "a/A", PUBLIC);
+        let b = class(&loader, "a/B", PUBLIC);
+        let mut array = Object::new(class(&loader, "[La/A;", PUBLIC));
+        array.data = References(vec![None]);
+        let array = Some(boxed(array));
+        // aastore
+        let thrown = thrown_by(code(&[0x53]), |stack| {
+            stack.push_ref(array);
+            stack.push_int(0);
+            stack.push_ref(object(&b));
+        });
+        assert_eq!(thrown, "java/lang/ArrayStoreException");
+    }
+
+    #[test]
+    fn test_divide_by_zero() {
+        // idiv, irem
+        for opcode in &[0x6c, 0x70] {
+            let thrown = thrown_by(code(&[*opcode]), |stack| {
+                stack.push_int(1);
+                stack.push_int(0);
+            });
+            assert_eq!(thrown, "java/lang/ArithmeticException");
+        }
+        // ldiv, lrem
+        for opcode in &[0x6d, 0x71] {
+            let thrown = thrown_by(code(&[*opcode]), |stack| {
+                stack.push_long(1);
+                stack.push_long(0);
+            });
+            assert_eq!(thrown, "java/lang/ArithmeticException");
+        }
+    }
+
+    #[test]
+    fn test_null_reference() {
+        // arraylength, athrow
+        for opcode in &[0xbe, 0xbf] {
+            let thrown = thrown_by(code(&[*opcode]), |stack| stack.push_ref(None));
+            assert_eq!(thrown, "java/lang/NullPointerException");
+        }
+    }
+
+    #[test]
+    fn test_negative_array_size() {
+        let loader = TestLoader::new();
+        class(&loader, "a/A", PUBLIC);
+        class(&loader, "[[La/A;", PUBLIC);
+        // newarray int
+        let thrown = thrown_by(code(&[0xbc, 10]), |stack| stack.push_int(-1));
+        assert_eq!(thrown, "java/lang/NegativeArraySizeException");
+        // anewarray a/A
+        let method = caller(&loader, |caller| with_index(0xbd, caller.class_ref("a/A")));
+        let thrown = thrown_by(method, |stack| stack.push_int(-1));
+        assert_eq!(thrown, "java/lang/NegativeArraySizeException");
+        // multianewarray [[La/A; 2
+        let method = caller(&loader, |caller| {
+            let mut code = with_index(0xc5, caller.class_ref("[[La/A;"));
+            code.push(2);
+            code
+        });
+        let thrown = thrown_by(method, |stack| {
+            stack.push_int(1);
+            stack.push_int(-1);
+        });
+        assert_eq!(thrown, "java/lang/NegativeArraySizeException");
+    }
+
+    #[test]
+    fn test_ldc2_w() {
+        let loader = TestLoader::new();
+        let method = caller(&loader, |caller| with_index(0x14, caller.int(1)));
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/ClassFormatError");
+    }
+
+    #[test]
+    fn test_class_resolution() {
+        let loader = TestLoader::new();
+        class(&loader, "a/A", PUBLIC);
+        class(&loader, "a/B", PUBLIC);
+        class(&loader, "a/Abstract", PUBLIC | ABSTRACT);
+        class(&loader, "b/Hidden", 0);
+        let new = |name: &str| caller(&loader, |caller| with_index(0xbb, caller.class_ref(name)));
+        assert_eq!(
+            thrown_by(new("b/Hidden"), |_| {}),
+            "java/lang/IllegalAccessError"
+        );
+        assert_eq!(
+            thrown_by(new("a/Abstract"), |_| {}),
+            "java/lang/InstantiationError"
+        );
+        // checkcast a/A
+        let method = caller(&loader, |caller| with_index(0xc0, caller.class_ref("a/A")));
+        let b = object(&loader.find("a/B"));
+        let thrown = thrown_by(method, |stack| stack.push_ref(b));
+        assert_eq!(thrown, "java/lang/ClassCastException");
+    }
+
+    /// a/A with fields of every kind
+    fn fields(loader: &TestLoader) {
+        let mut builder = ClassBuilder::new("a/A");
+        builder
+            .field(PUBLIC, "i", "I")
+            .field(PUBLIC, "f", "F")
+            .field(PUBLIC, "j", "J")
+            .field(PUBLIC, "d", "D")
+            .field(PUBLIC, "l", "Ljava/lang/Object;")
+            .field(PUBLIC | STATIC, "s", "I")
+            .field(PUBLIC | FINAL, "final", "I")
+            .field(PUBLIC | STATIC | FINAL, "staticFinal", "I")
+            .field(PRIVATE, "private", "I");
+        loader.define(&builder);
+    }
+
+    /// a/Caller.run executing the instruction on the field a/A.name
+    fn field_access(loader: &TestLoader, opcode: u8, name: &str, descriptor: &str) -> Rc<Method> {
+        return caller(loader, |caller| {
+            with_index(opcode, caller.field_ref("a/A", name, descriptor))
+        });
+    }
+
+    #[test]
+    fn test_field_resolution() {
+        let loader = TestLoader::new();
+        fields(&loader);
+        // getfield
+        let method = field_access(&loader, 0xb4, "missing", "I");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/NoSuchFieldError");
+        let method = field_access(&loader, 0xb4, "private", "I");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/IllegalAccessError");
+    }
+
+    #[test]
+    fn test_get_field() {
+        let loader = TestLoader::new();
+        fields(&loader);
+        // getfield
+        let method = field_access(&loader, 0xb4, "s", "I");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        let method = field_access(&loader, 0xb4, "i", "I");
+        let thrown = thrown_by(method, |stack| stack.push_ref(None));
+        assert_eq!(thrown, "java/lang/NullPointerException");
+        // getstatic
+        let method = field_access(&loader, 0xb2, "i", "I");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+    }
+
+    #[test]
+    fn test_put_field() {
+        let loader = TestLoader::new();
+        fields(&loader);
+        // putfield
+        let method = field_access(&loader, 0xb5, "s", "I");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        let method = field_access(&loader, 0xb5, "final", "I");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/IllegalAccessError");
+        let kinds: Vec<(&str, &str, PushValue)> = vec![
+            ("i", "I", |stack| stack.push_int(0)),
+            ("f", "F", |stack| stack.push_float(0.0)),
+            ("j", "J", |stack| stack.push_long(0)),
+            ("d", "D", |stack| stack.push_double(0.0)),
+            ("l", "Ljava/lang/Object;", |stack| stack.push_ref(None)),
+        ];
+        for (name, descriptor, push_value) in kinds {
+            let method = field_access(&loader, 0xb5, name, descriptor);
+            let thrown = thrown_by(method, |stack| {
+                stack.push_ref(None);
+                push_value(stack);
+            });
+            assert_eq!(thrown, "java/lang/NullPointerException");
+        }
+        // putstatic
+        let method = field_access(&loader, 0xb3, "i", "I");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        let method = field_access(&loader, 0xb3, "staticFinal", "I");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/IllegalAccessError");
+    }
+
+    /// a/A with an instance and a static method, a/B extending it, b/Base
+    /// with a protected method and the abstract a/Abstract
+    fn methods(loader: &TestLoader) {
+        let mut a = ClassBuilder::new("a/A");
+        a.method(PUBLIC, "<init>", "()V")
+            .method(PUBLIC, "m", "()V")
+            .method(PUBLIC | STATIC, "s", "()V")
+            .method(PRIVATE, "private", "()V");
+        loader.define(&a);
+        let mut b = ClassBuilder::new("a/B");
+        b.super_class("a/A");
+        loader.define(&b);
+        let mut base = ClassBuilder::new("b/Base");
+        base.method(PROTECTED, "protected", "()V");
+        loader.define(&base);
+        let mut abstract_class = ClassBuilder::new("a/Abstract");
+        abstract_class
+            .access_flags(PUBLIC | ABSTRACT)
+            .method(PUBLIC | ABSTRACT, "m", "()V");
+        loader.define(&abstract_class);
+        let mut interface = ClassBuilder::new("a/I");
+        interface
+            .access_flags(PUBLIC | INTERFACE | ABSTRACT)
+            .method(PUBLIC | ABSTRACT, "m", "()V")
+            .method(PUBLIC | STATIC, "s", "()V");
+        loader.define(&interface);
+    }
+
+    /// a/Caller.run executing the instruction on the method class.name()V,
+    /// a/Caller extends b/Base
+    fn call(loader: &TestLoader, opcode: u8, class: &str, name: &str) -> Rc<Method> {
+        return caller(loader, |caller| {
+            caller.super_class("b/Base");
+            let index = match opcode {
+                0xb9 => caller.interface_method_ref(class, name, "()V"),
+                _ => caller.method_ref(class, name, "()V"),
+            };
+            let mut code = with_index(opcode, index);
+            if opcode == 0xb9 {
+                code.extend_from_slice(&[1, 0]);
+            }
+            code
+        });
+    }
+
+    #[test]
+    fn test_method_resolution() {
+        let loader = TestLoader::new();
+        methods(&loader);
+        let icce = "java/lang/IncompatibleClassChangeError";
+        // invokestatic of an interface method through a Methodref
+        assert_eq!(thrown_by(call(&loader, 0xb8, "a/I", "s"), |_| {}), icce);
+        let method = call(&loader, 0xb8, "a/A", "missing");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/NoSuchMethodError");
+        let method = call(&loader, 0xb6, "a/B", "private");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/IllegalAccessError");
+        // invokeinterface of a class method
+        assert_eq!(thrown_by(call(&loader, 0xb9, "a/A", "m"), |_| {}), icce);
+        let method = call(&loader, 0xb9, "a/I", "missing");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/NoSuchMethodError");
+    }
+
+    #[test]
+    fn test_interface_method_access() {
+        let loader = TestLoader::new();
+        // a package private method of an interface in another package
+        let mut j = ClassBuilder::new("b/J");
+        j.access_flags(PUBLIC | INTERFACE | ABSTRACT)
+            .method(ABSTRACT, "package", "()V");
+        loader.define(&j);
+        let mut i = ClassBuilder::new("a/I");
+        i.access_flags(PUBLIC | INTERFACE | ABSTRACT)
+            .interface("b/J");
+        loader.define(&i);
+        let method = caller(&loader, |caller| {
+            let mut code = with_index(0xb9, caller.interface_method_ref("a/I", "package", "()V"));
+            code.extend_from_slice(&[1, 0]);
+            code
+        });
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/IllegalAccessError");
+    }
+
+    #[test]
+    fn test_invoke_static() {
+        let loader = TestLoader::new();
+        methods(&loader);
+        let method = call(&loader, 0xb8, "a/A", "m");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+    }
+
+    #[test]
+    fn test_invoke_virtual() {
+        let loader = TestLoader::new();
+        methods(&loader);
+        let method = call(&loader, 0xb6, "a/A", "s");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        let method = call(&loader, 0xb6, "a/A", "m");
+        let thrown = thrown_by(method, |stack| stack.push_ref(None));
+        assert_eq!(thrown, "java/lang/NullPointerException");
+        // a protected method of b/Base on a receiver that isn't an a/Caller
+        let base = object(&loader.find("b/Base"));
+        let method = call(&loader, 0xb6, "b/Base", "protected");
+        let thrown = thrown_by(method, |stack| stack.push_ref(base));
+        assert_eq!(thrown, "java/lang/IllegalAccessError");
+        let receiver = object(&loader.find("a/Abstract"));
+        let method = call(&loader, 0xb6, "a/Abstract", "m");
+        let thrown = thrown_by(method, |stack| stack.push_ref(receiver));
+        assert_eq!(thrown, "java/lang/AbstractMethodError");
+    }
+
+    #[test]
+    fn test_invoke_special() {
+        let loader = TestLoader::new();
+        methods(&loader);
+        // the constructor of a/A through a/B
+        let method = call(&loader, 0xb7, "a/B", "<init>");
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/NoSuchMethodError");
+        let method = call(&loader, 0xb7, "a/A", "s");
+        assert_eq!(
+            thrown_by(method, |_| {}),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        let method = call(&loader, 0xb7, "a/A", "m");
+        let thrown = thrown_by(method, |stack| stack.push_ref(None));
+        assert_eq!(thrown, "java/lang/NullPointerException");
+        let base = object(&loader.find("b/Base"));
+        let method = call(&loader, 0xb7, "b/Base", "protected");
+        let thrown = thrown_by(method, |stack| stack.push_ref(base));
+        assert_eq!(thrown, "java/lang/IllegalAccessError");
+        let receiver = object(&loader.find("a/Abstract"));
+        let method = call(&loader, 0xb7, "a/Abstract", "m");
+        let thrown = thrown_by(method, |stack| stack.push_ref(receiver));
+        assert_eq!(thrown, "java/lang/AbstractMethodError");
+
+        // the quickened form checks the receiver only
+        let resolved = (*loader.find("a/A")).borrow().methods()[1].clone();
+        let thread = boxed(JavaThread::new_thread());
+        let mut frame = JavaThread::new_frame(thread, code(&[]));
+        frame.operand_stack().unwrap().push_ref(None);
+        QuickInstruction::InvokeSpecial(resolved).execute(&mut frame);
+        assert_eq!(thrown::take_class_name(), "java/lang/NullPointerException");
+    }
+
+    #[test]
+    fn test_invoke_interface() {
+        let loader = TestLoader::new();
+        methods(&loader);
+        let mut abstract_class = ClassBuilder::new("a/AbstractI");
+        abstract_class
+            .access_flags(PUBLIC | ABSTRACT)
+            .interface("a/I");
+        loader.define(&abstract_class);
+        let mut package_private = ClassBuilder::new("a/PackagePrivate");
+        package_private.interface("a/I").method(0, "m", "()V");
+        loader.define(&package_private);
+
+        let icce = "java/lang/IncompatibleClassChangeError";
+        assert_eq!(thrown_by(call(&loader, 0xb9, "a/I", "s"), |_| {}), icce);
+        let method = call(&loader, 0xb9, "a/I", "m");
+        let thrown = thrown_by(method.clone(), |stack| stack.push_ref(None));
+        assert_eq!(thrown, "java/lang/NullPointerException");
+        let receivers = vec![
+            ("a/A", icce),
+            ("a/AbstractI", "java/lang/AbstractMethodError"),
+            ("a/PackagePrivate", "java/lang/IllegalAccessError"),
+        ];
+        for (class, exception) in receivers {
+            let receiver = object(&loader.find(class));
+            let thrown = thrown_by(method.clone(), |stack| stack.push_ref(receiver));
+            assert_eq!(thrown, exception);
+        }
+    }
+
+    #[test]
+    fn test_invoke_native() {
+        let loader = TestLoader::new();
+        let mut builder = ClassBuilder::new("a/A");
+        builder.method(PUBLIC | STATIC | NATIVE, "missing", "()V");
+        let class = loader.define(&builder);
+        let method = (*class).borrow().methods()[0].clone();
+        assert_eq!(thrown_by(method, |_| {}), "java/lang/UnsatisfiedLinkError");
     }
 }
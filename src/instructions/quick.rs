@@ -0,0 +1,73 @@
+use crate::instructions::base::method_invoke_logic::invoke_method;
+use crate::instructions::references::get_field::GetField;
+use crate::instructions::references::get_static::GetStatic;
+use crate::instructions::references::invoke_interface::InvokeInterface;
+use crate::instructions::references::invoke_virtual::VirtualCall;
+use crate::instructions::references::new::New;
+use crate::instructions::references::put_field::PutField;
+use crate::instructions::references::put_static::PutStatic;
+use crate::invoke_support::throw_exception;
+use crate::oops::class::Class;
+use crate::oops::method::Method;
+use crate::runtime::frame::Frame;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// the resolved fast form an instruction is rewritten to after it executed
+/// once: the symbolic reference, the checks that only depend on it and the
+/// class initialization are done, what's left depends on the operands
+pub enum QuickInstruction {
+    /// the slot of the field and the first char of its descriptor
+    GetField(usize, char),
+    PutField(usize, char),
+    /// the initialized class holding the static field
+    GetStatic(Rc<RefCell<Class>>, usize, char),
+    PutStatic(Rc<RefCell<Class>>, usize, char),
+    /// the method of an initialized class
+    InvokeStatic(Rc<Method>),
+    /// the method selected for the call site, only the receiver is checked
+    InvokeSpecial(Rc<Method>),
+    InvokeVirtual(VirtualCall),
+    /// the resolved interface method, selected from the receiver's itable
+    InvokeInterface(Rc<Method>),
+    /// an initialized class that can be instantiated
+    New(Rc<RefCell<Class>>),
+}
+
+impl QuickInstruction {
+    #[inline]
+    pub fn execute(&self, frame: &mut Frame) {
+        match self {
+            QuickInstruction::GetField(slot_id, kind) => GetField::get(frame, *slot_id, *kind),
+            QuickInstruction::PutField(slot_id, kind) => PutField::put(frame, *slot_id, *kind),
+            QuickInstruction::GetStatic(class, slot_id, kind) => {
+                GetStatic::get(frame, class, *slot_id, *kind)
+            }
+            QuickInstruction::PutStatic(class, slot_id, kind) => {
+                PutStatic::put(frame, class, *slot_id, *kind)
+            }
+            QuickInstruction::InvokeStatic(method) => invoke_method(frame, method.clone()),
+            QuickInstruction::InvokeSpecial(method) => {
+                let receiver = frame
+                    .operand_stack()
+                    .expect("stack is none")
+                    .get_ref_from_top(method.arg_slot_count() - 1);
+                if receiver.is_none() {
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
+                }
+                invoke_method(frame, method.clone());
+            }
+            QuickInstruction::InvokeVirtual(call) => call.execute(frame),
+            QuickInstruction::InvokeInterface(method) => InvokeInterface::invoke(frame, method),
+            QuickInstruction::New(class) => New::allocate(frame, class),
+        }
+    }
+}
+
+/// rewrites the instruction the frame executes to its fast form, the
+/// thread's pc is the one of that instruction
+pub fn quicken(frame: &Frame, quick: QuickInstruction) {
+    let pc = (*frame.thread()).borrow().get_pc();
+    frame.method().instructions().quicken(pc, quick);
+}
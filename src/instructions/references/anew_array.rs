@@ -1,10 +1,11 @@
+use crate::gc::heap::{array_size, Heap};
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
 use crate::oops::constant_pool::Constant::ClassReference;
-use crate::utils::boxed;
-use crate::instructions::references::ResolveClassRef;
+use crate::instructions::references::{throw_out_of_memory, ResolveClassRef};
+use crate::invoke_support::throw_exception;
 
 pub struct ANewArray(ConstantPoolInstruction);
 
@@ -20,17 +21,27 @@ impl Instruction for ANewArray {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
-        let component_class = self.resolve_class_ref(class);
+        let component_class = match self.resolve_class_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         let stack = frame.operand_stack().expect("stack is none");
         let count = stack.pop_int();
         if count < 0 {
-            panic!("java.lang.NegativeArraySizeException")
+            let msg = count.to_string();
+            throw_exception(frame, "java/lang/NegativeArraySizeException", Some(msg.as_str()));
+            return;
         }
         let array_class = Class::create_array_class(component_class);
+        if !Heap::reserve(array_size(&(*array_class).borrow(), count as usize)) {
+            throw_out_of_memory(frame);
+            return;
+        }
+        let stack = frame.operand_stack().expect("stack is none");
         let array = Class::new_array(&array_class, count as usize);
-        stack.push_ref(Some(boxed(array)));
+        stack.push_ref(Some(Heap::allocate(array)));
     }
 }
 
@@ -1,5 +1,6 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 pub struct ArrayLength(NoOperandsInstruction);
@@ -16,11 +17,12 @@ impl Instruction for ArrayLength {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let object = stack.pop_ref();
         if object.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let array_len = (*object.unwrap()).borrow().array_length();
         stack.push_int(array_len as i32);
@@ -1,9 +1,9 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 use crate::oops::object::Object;
 use crate::runtime::thread::JavaThread;
-use crate::utils::java_str_to_rust_str;
 use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
@@ -40,12 +40,18 @@ impl AThrow {
             frame.set_next_pc(pc);
             return true;
         }
+        frame.unlock_if_synchronized();
         (*thread).borrow_mut().pop_frame();
         loop {
             if (*thread).borrow().is_stack_empty() {
                 break;
             }
             let frame = (*thread).borrow().current_frame();
+            if (*frame).borrow().is_entry_frame() {
+                // the VM called into Java here, the caller rethrows it
+                JavaThread::set_pending_exception(object);
+                return true;
+            }
             /**
              **
             {
@@ -62,29 +68,25 @@ impl AThrow {
                 mut_borrow.set_next_pc(handler_pc);
                 return true;
             }
+            (*frame).borrow_mut().unlock_if_synchronized();
             (*thread).borrow_mut().pop_frame();
         }
         return false;
     }
 
+    /// throws `exception` at the current instruction of the frame
+    pub fn throw(frame: &mut Frame, exception: Rc<RefCell<Object>>) {
+        let thread = frame.thread();
+        if !Self::find_and_goto_exception_handler(frame, exception.clone()) {
+            Self::handle_uncaught_exception(thread, exception);
+        }
+    }
+
+    /// the exception unwound the whole stack, it stays pending for the code
+    /// that started the thread, which dispatches it to the uncaught exception handler
     fn handle_uncaught_exception(thread: Rc<RefCell<JavaThread>>, object: Rc<RefCell<Object>>) {
         (*thread).borrow_mut().clear_stack();
-        let _java_msg = (*object)
-            .borrow()
-            .get_ref_var("detailMessage", "Ljava/lang/String;");
-        //        let rust_msg = java_str_to_rust_str(java_msg.unwrap());
-        let bor_obj = (*object).borrow();
-        let stes = bor_obj.trace().expect("The exception object hasn't trace");
-        let ex_class = bor_obj.class();
-
-        let detail_message = bor_obj
-            .get_ref_var("detailMessage", "Ljava/lang/String;")
-            .map_or("".to_string(), |v| java_str_to_rust_str(v));
-
-        println!("\t{},{}", (*ex_class).borrow().java_name(), detail_message);
-        for ste in stes {
-            println!("\tat {}", ste.to_string());
-        }
+        JavaThread::set_pending_exception(object);
     }
 }
 
@@ -93,23 +95,13 @@ impl Instruction for AThrow {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let ex = frame.operand_stack().expect("stack is none").pop_ref();
         if ex.is_none() {
-            panic!("java.lang.NullPointerException");
-        }
-        let thread = frame.thread();
-        let object = ex.unwrap();
-
-        //        let meta = (*object).borrow().meta();
-        //        println!("ex class : {}",(*meta.unwrap()).borrow().java_name());
-        {
-            let method = frame.method_ptr();
-            let class = (*object).borrow().class();
-        }
-        if !Self::find_and_goto_exception_handler(frame, object.clone()) {
-            Self::handle_uncaught_exception(thread.clone(), object);
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
+        Self::throw(frame, ex.unwrap());
     }
 }
 
@@ -3,6 +3,7 @@ use crate::instructions::base::instruction::{ConstantPoolInstruction, Instructio
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::ClassReference;
 use crate::instructions::references::ResolveClassRef;
+use crate::invoke_support::throw_exception;
 
 pub struct CheckCast(ConstantPoolInstruction);
 
@@ -18,7 +19,7 @@ impl Instruction for CheckCast {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let reference = stack.pop_ref();
         stack.push_ref(reference.clone());
@@ -27,9 +28,19 @@ impl Instruction for CheckCast {
         }
         let c = frame.method().class();
 
-        let class = self.resolve_class_ref(c);
-        if !(*reference.unwrap()).borrow().is_instance_of(class) {
-            panic!("java.lang.ClassCastException");
+        let class = match self.resolve_class_ref(c) {
+            Some(resolved) => resolved,
+            None => return,
+        };
+        let object = reference.unwrap();
+        if !(*object).borrow().is_instance_of(class.clone()) {
+            let object_class = (*object).borrow().class();
+            let msg = format!(
+                "{} cannot be cast to {}",
+                (*object_class).borrow().java_name(),
+                (*class).borrow().java_name()
+            );
+            throw_exception(frame, "java/lang/ClassCastException", Some(msg.as_str()));
         }
     }
 }
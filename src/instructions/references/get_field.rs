@@ -3,6 +3,8 @@ use crate::instructions::base::instruction::{ConstantPoolInstruction, Instructio
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::FieldReference;
 use crate::instructions::references::ResolveFieldRef;
+use crate::instructions::quick::{quicken, QuickInstruction};
+use crate::invoke_support::throw_exception;
 
 pub struct GetField(ConstantPoolInstruction);
 
@@ -18,26 +20,38 @@ impl Instruction for GetField {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
 
-        let field_option = self.resolve_field_ref(class);
+        let field_option = match self.resolve_field_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         let field = (*field_option).borrow();
         if field.parent().is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
+        let slot_id = field.slot_id();
+        let first_char = field.parent().descriptor().chars().next().unwrap();
+        quicken(frame, QuickInstruction::GetField(slot_id, first_char));
+        GetField::get(frame, slot_id, first_char);
+    }
+}
+
+impl GetField {
+    /// pushes the field at slot_id of the object on top of the stack,
+    /// first_char is the first char of the field's descriptor
+    pub fn get(frame: &mut Frame, slot_id: usize, first_char: char) {
         let stack = frame.operand_stack().expect("stack is none");
         let reference = stack.pop_ref();
         if reference.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
-        let desc = field.parent().descriptor();
-        let slot_id = field.slot_id();
-
         let object = reference.unwrap();
         let borrow_object = (*object).borrow();
         let slots = borrow_object.fields_immutable();
-        let first_char = desc.chars().next().unwrap();
 
         match first_char {
             'Z' | 'B' | 'C' | 'S' | 'I' => stack.push_int(slots.get_int(slot_id)),
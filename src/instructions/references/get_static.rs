@@ -1,9 +1,14 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::class_init_logic::{ensure_initialized, quicken_if_initialized};
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
+use crate::oops::class::Class;
 use crate::runtime::frame::Frame;
+use std::cell::RefCell;
+use std::rc::Rc;
 use crate::oops::constant_pool::Constant::FieldReference;
 use crate::instructions::references::ResolveFieldRef;
+use crate::instructions::quick::QuickInstruction;
+use crate::invoke_support::throw_exception;
 
 pub struct GetStatic(ConstantPoolInstruction);
 
@@ -19,26 +24,38 @@ impl Instruction for GetStatic {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
 
-        let field_option = self.resolve_field_ref(class);
-        let field = (*field_option).borrow();
-        let class = field.parent().class();
-        if !(*class).borrow().initialized() {
-            frame.revert_next_pc();
-            init_class(frame.thread(), class.clone());
+        let field_option = match self.resolve_field_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
+        // <clinit> may resolve the same field, nothing of it is borrowed meanwhile
+        let class = (*field_option).borrow().parent().class();
+        if !ensure_initialized(&class) {
             return;
         }
+        let field = (*field_option).borrow();
         if !field.parent().is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
-        let desc = field.parent().descriptor();
         let slot_id = field.slot_id();
-        let mut borrow_class = (*class).borrow_mut();
+        let first_char = field.parent().descriptor().chars().next().unwrap();
+        let quick = QuickInstruction::GetStatic(class.clone(), slot_id, first_char);
+        quicken_if_initialized(frame, &class, quick);
+        GetStatic::get(frame, &class, slot_id, first_char);
+    }
+}
+
+impl GetStatic {
+    /// pushes the static field at slot_id of the class,
+    /// first_char is the first char of the field's descriptor
+    pub fn get(frame: &mut Frame, class: &Rc<RefCell<Class>>, slot_id: usize, first_char: char) {
+        let mut borrow_class = (**class).borrow_mut();
         let slots = borrow_class.mut_static_vars().expect("slots is none");
         let stack = frame.operand_stack().expect("stack is none");
-        let first_char = desc.chars().next().unwrap();
         match first_char {
             'Z' | 'B' | 'C' | 'S' | 'I' => stack.push_int(slots.get_int(slot_id)),
             'F' => stack.push_float(slots.get_float(slot_id)),
@@ -19,7 +19,7 @@ impl Instruction for InstanceOf {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         //        let stack = frame.operand_stack().expect("stack is none");
         let reference = frame.operand_stack().expect("stack is none").pop_ref();
         if reference.is_none() {
@@ -28,7 +28,10 @@ impl Instruction for InstanceOf {
         }
         let class = frame.method().class();
 
-        let class = self.resolve_class_ref(class);
+        let class = match self.resolve_class_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         if (*reference.unwrap()).borrow().is_instance_of(class) {
             frame.operand_stack().expect("stack is none").push_int(1);
         } else {
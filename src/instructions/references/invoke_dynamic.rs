@@ -0,0 +1,291 @@
+use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::Heap;
+use crate::instructions::base::bytecode_reader::BytecodeReader;
+use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
+use crate::invoke_support::method_handle::{
+    box_parameter, invoke_basic, invoke_classes_initialized, method_type,
+};
+use crate::invoke_support::parameter::{Parameter, Parameters};
+use crate::invoke_support::{invoke, set_pending_exception, throw_exception, ReturnType};
+use crate::jvm::Jvm;
+use crate::oops::class::Class;
+use crate::oops::constant_pool::Constant::{
+    ClassReference, Double, Float, Integer, InterfaceMethodReference,
+    InvokeDynamic as InvokeDynamicReference, Long, MethodHandleReference, MethodReference,
+    MethodTypeReference, Str,
+};
+use crate::oops::method::Method;
+use crate::oops::method_handle_ref::REF_INVOKE_STATIC;
+use crate::oops::object::Object;
+use crate::oops::string_pool::StringPool;
+use crate::runtime::frame::Frame;
+use crate::runtime::thread::JavaThread;
+use lark_classfile::bootstrap_methods_attribute::BootstrapMethod;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// MethodHandles.Lookup.PUBLIC | PRIVATE | PROTECTED | PACKAGE
+const LOOKUP_ALL_MODES: i32 = 0x0f;
+
+/// invokedynamic indexbyte1 indexbyte2 0 0
+pub struct InvokeDynamic(ConstantPoolInstruction);
+
+impl InvokeDynamic {
+    #[inline]
+    pub fn new() -> InvokeDynamic {
+        return InvokeDynamic(ConstantPoolInstruction::new());
+    }
+
+    fn invoke_dynamic_info(&self, class: &Rc<RefCell<Class>>) -> (usize, String, String, usize) {
+        let borrow = (**class).borrow();
+        let constant = borrow.constant_pool().get_constant_immutable(self.0.index());
+        return match constant {
+            InvokeDynamicReference(info) => (
+                info.bootstrap_method_attr_index(),
+                info.name().to_string(),
+                info.descriptor().to_string(),
+                info.arg_slot_count(),
+            ),
+            _ => panic!("Unknown constant type"),
+        };
+    }
+}
+
+impl Instruction for InvokeDynamic {
+    fn fetch_operands(&mut self, reader: &mut BytecodeReader) {
+        self.0.fetch_operands(reader);
+        reader.read_u8();
+        reader.read_u8();
+    }
+
+    fn execute(&self, frame: &mut Frame) {
+        let pc = (*frame.thread()).borrow().get_pc();
+        let method = frame.method_ptr();
+        let class = method.class();
+        let (bootstrap_index, name, descriptor, arg_slot_count) = self.invoke_dynamic_info(&class);
+
+        let mut call_site = method.call_site(pc);
+        if call_site.is_none() {
+            call_site = link_call_site(frame, class, bootstrap_index, name.as_str(), descriptor.as_str());
+            if call_site.is_none() {
+                return;
+            }
+            method.set_call_site(pc, call_site.clone().unwrap());
+        }
+        let target = (*call_site.unwrap())
+            .borrow()
+            .get_ref_var("target", "Ljava/lang/invoke/MethodHandle;");
+        if target.is_none() {
+            throw_exception(frame, "java/lang/NullPointerException", Some("CallSite target is null"));
+            return;
+        }
+        invoke_basic(frame, target.unwrap(), arg_slot_count);
+    }
+}
+
+/// jvms 5.4.3.6, returns None if the instruction has to be executed again
+/// (a class was scheduled for initialization) or an error was thrown
+fn link_call_site(
+    frame: &mut Frame,
+    class: Rc<RefCell<Class>>,
+    bootstrap_index: usize,
+    name: &str,
+    descriptor: &str,
+) -> Option<Rc<RefCell<Object>>> {
+    let bootstrap = (*class).borrow().bootstrap_method(bootstrap_index).cloned();
+    if bootstrap.is_none() {
+        let msg = format!("no BootstrapMethods entry {} in {}", bootstrap_index, (*class).borrow().name());
+        throw_exception(frame, "java/lang/BootstrapMethodError", Some(msg.as_str()));
+        return None;
+    }
+    let bootstrap = bootstrap.unwrap();
+    let bootstrap_method = resolve_bootstrap_method(class.clone(), &bootstrap);
+    if JavaThread::has_pending_exception() {
+        return None;
+    }
+    if bootstrap_method.is_none() {
+        throw_exception(frame, "java/lang/BootstrapMethodError", Some("bootstrap method is not REF_invokeStatic"));
+        return None;
+    }
+    let bootstrap_method = bootstrap_method.unwrap();
+
+    let bootstrap_class = bootstrap_method.class();
+    if !(*bootstrap_class).borrow().initialized() && !init_class(bootstrap_class) {
+        return None;
+    }
+    if !invoke_classes_initialized(frame) {
+        return None;
+    }
+
+    let loader = (*class).borrow().get_class_loader();
+    let mut parameters = vec![
+        Parameter::Object(Some(lookup(class.clone()))),
+        Parameter::Object(Some(StringPool::java_string(name.to_string()))),
+        Parameter::Object(Some(method_type(descriptor, loader))),
+    ];
+    let mut static_arguments = Vec::with_capacity(bootstrap.bootstrap_arguments().len());
+    for index in bootstrap.bootstrap_arguments() {
+        match static_argument(class.clone(), *index as usize) {
+            Some(argument) => static_arguments.push(argument),
+            None => return None,
+        }
+    }
+    let parameter_types = bootstrap_method.method_descriptor().parameter_types().clone();
+    adapt_static_arguments(
+        &mut parameters,
+        static_arguments,
+        &parameter_types[3..],
+        bootstrap_method.is_varargs(),
+    );
+
+    let call_site = invoke(
+        bootstrap_method,
+        Some(Parameters::with_parameters(parameters)),
+        ReturnType::Object,
+    )
+    .object();
+    if JavaThread::has_pending_exception() {
+        return None;
+    }
+    let call_site_class = Jvm::boot_class_loader().find_or_create("java/lang/invoke/CallSite").unwrap();
+    let is_call_site = match &call_site {
+        Some(object) => (*object).borrow().is_instance_of(call_site_class),
+        None => false,
+    };
+    if !is_call_site {
+        throw_exception(frame, "java/lang/BootstrapMethodError", Some("bootstrap method did not return a CallSite"));
+        return None;
+    }
+    return call_site;
+}
+
+fn resolve_bootstrap_method(class: Rc<RefCell<Class>>, bootstrap: &BootstrapMethod) -> Option<Rc<Method>> {
+    let (kind, reference_index) = match (*class)
+        .borrow()
+        .constant_pool()
+        .get_constant_immutable(bootstrap.bootstrap_method_ref() as usize)
+    {
+        MethodHandleReference(handle) => (handle.reference_kind(), handle.reference_index()),
+        _ => panic!("Unknown constant type"),
+    };
+    if kind != REF_INVOKE_STATIC {
+        return None;
+    }
+    let constant = (*class)
+        .borrow_mut()
+        .mut_constant_pool()
+        .take_constant(reference_index);
+    let (method, constant) = match constant {
+        MethodReference(mut method_ref) => (
+            method_ref.resolved_method(class.clone()),
+            MethodReference(method_ref),
+        ),
+        InterfaceMethodReference(mut method_ref) => (
+            method_ref.resolved_interface_method(class.clone()),
+            InterfaceMethodReference(method_ref),
+        ),
+        _ => panic!("Unknown constant type"),
+    };
+    (*class)
+        .borrow_mut()
+        .mut_constant_pool()
+        .restoration_constant(reference_index, constant);
+    return method;
+}
+
+/// the caller's full-privilege lookup, IMPL_LOOKUP.in(caller)
+fn lookup(caller: Rc<RefCell<Class>>) -> Rc<RefCell<Object>> {
+    let lookup_class =
+        ClassLoader::load_class(None, "java/lang/invoke/MethodHandles$Lookup").unwrap();
+    let object = Heap::allocate(Class::new_object(&lookup_class));
+    let constructor = Class::get_constructor(lookup_class, "(Ljava/lang/Class;I)V")
+        .expect("MethodHandles.Lookup.<init> not found");
+    let parameters = vec![
+        Parameter::Object(Some(object.clone())),
+        Parameter::Object((*caller).borrow().get_java_class()),
+        Parameter::Int(LOOKUP_ALL_MODES),
+    ];
+    invoke(constructor, Some(Parameters::with_parameters(parameters)), ReturnType::Void);
+    return object;
+}
+
+/// a loadable constant used as static argument of the bootstrap method
+/// returns None with the resolution error pending
+fn static_argument(class: Rc<RefCell<Class>>, index: usize) -> Option<Parameter> {
+    let mut constant = (*class).borrow_mut().mut_constant_pool().take_constant(index);
+    let argument = match &mut constant {
+        Integer(v) => Some(Parameter::Int(*v)),
+        Float(v) => Some(Parameter::Float(*v)),
+        Long(v) => Some(Parameter::Long(*v)),
+        Double(v) => Some(Parameter::Double(*v)),
+        Str(v) => Some(Parameter::Object(Some(StringPool::java_string(v.clone())))),
+        ClassReference(v) => v
+            .resolved_class(class.clone())
+            .map(|resolved_class| Parameter::Object((*resolved_class).borrow().get_java_class())),
+        MethodTypeReference(v) => Some(Parameter::Object(Some(v.resolved_method_type(class.clone())))),
+        MethodHandleReference(v) => v
+            .resolved_method_handle(class.clone())
+            .map(|method_handle| Parameter::Object(Some(method_handle))),
+        _ => {
+            set_pending_exception("java/lang/ClassFormatError", Some("not a loadable constant"));
+            None
+        }
+    };
+    (*class)
+        .borrow_mut()
+        .mut_constant_pool()
+        .restoration_constant(index, constant);
+    return argument;
+}
+
+/// match the static arguments to the declared parameter types of the bootstrap method,
+/// boxing primitives for reference parameters and collecting a trailing varargs array
+fn adapt_static_arguments(
+    parameters: &mut Vec<Parameter>,
+    mut static_arguments: Vec<Parameter>,
+    parameter_types: &[String],
+    is_varargs: bool,
+) {
+    let mut positional = parameter_types.len();
+    if is_varargs && positional > 0 {
+        positional -= 1;
+    }
+    let rest = match static_arguments.len() > positional {
+        true => static_arguments.split_off(positional),
+        false => vec![],
+    };
+    for (argument, parameter_type) in static_arguments.into_iter().zip(parameter_types) {
+        let first = parameter_type.chars().next().unwrap();
+        match first {
+            'L' | '[' => parameters.push(Parameter::Object(box_parameter(argument))),
+            _ => parameters.push(argument),
+        }
+    }
+    if is_varargs && !parameter_types.is_empty() {
+        let array_type = parameter_types.last().unwrap();
+        let array_class = ClassLoader::load_class(None, array_type.as_str()).unwrap();
+        let mut array = Class::new_array(&array_class, rest.len());
+        let references = array.mut_references();
+        for (i, argument) in rest.into_iter().enumerate() {
+            references[i] = box_parameter(argument);
+        }
+        parameters.push(Parameter::Object(Some(Heap::allocate(array))));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::test_classes::{ClassBuilder, TestLoader};
+    use crate::instructions::references::invoke_dynamic::static_argument;
+    use crate::invoke_support::thrown;
+
+    #[test]
+    fn test_static_argument() {
+        let mut builder = ClassBuilder::new("a/A");
+        let index = builder.field_ref("a/A", "f", "I");
+        let class = TestLoader::new().define(&builder);
+        assert!(static_argument(class, index as usize).is_none());
+        assert_eq!(thrown::take_class_name(), "java/lang/ClassFormatError");
+    }
+}
@@ -1,11 +1,13 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::Instruction;
-use crate::instructions::base::method_invoke_logic::invoke_method;
+use crate::instructions::base::method_invoke_logic::{invoke_method, select_method};
+use crate::instructions::quick::{quicken, QuickInstruction};
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::InterfaceMethodReference;
-use crate::oops::method_ref::MethodRef;
-use std::ops::Deref;
+use crate::oops::method::Method;
 use crate::instructions::references::{ResolveMethodRef, ResolveInterfaceMethodRef};
+use crate::invoke_support::throw_exception;
+use std::rc::Rc;
 
 pub struct InvokeInterface {
     index: usize,
@@ -25,43 +27,45 @@ impl Instruction for InvokeInterface {
         reader.read_u8();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let current_class = frame.method().class();
-        let (interface,resolved_method) = self.
-            resolved_interface_method_ref_tuple(current_class);
+        let resolved_method = match self.resolved_interface_method_ref(current_class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         if resolved_method.is_static() || resolved_method.is_private() {
-            panic!("java.lang.IncompatibleClassChangeError")
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
+        Self::invoke(frame, &resolved_method);
+        quicken(frame, QuickInstruction::InvokeInterface(resolved_method));
+    }
+}
 
+impl InvokeInterface {
+    /// selects the method for the receiver from its itable, a resolved
+    /// method of Object from its vtable
+    pub fn invoke(frame: &mut Frame, resolved_method: &Rc<Method>) {
         let object = frame
             .operand_stack()
             .expect("stack is none")
             .get_ref_from_top(resolved_method.arg_slot_count() - 1);
 
         if object.is_none() {
-            panic!("java.lang.NullPointerException") // todo
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object_class = (*object.unwrap()).borrow().class();
-
-        if !(*object_class)
-            .borrow()
-            .is_implements((*interface).borrow().deref())
-        {
-            panic!("java.lang.IncompatibleClassChangeError")
-        }
-        let method_to_be_invoked = MethodRef::look_up_method_in_class(
-            object_class,
-            resolved_method.name(),
-            resolved_method.descriptor(),
-        );
-        if method_to_be_invoked.is_none() || method_to_be_invoked.as_ref().unwrap().is_abstract() {
-            panic!("java.lang.AbstractMethodError")
-        }
-        if !method_to_be_invoked.as_ref().unwrap().is_public() {
-            panic!("java.lang.IllegalAccessError")
+        let method_to_be_invoked = match select_method(frame, &object_class, resolved_method) {
+            Some(method) => method,
+            None => return,
+        };
+        if !method_to_be_invoked.is_public() {
+            throw_exception(frame, "java/lang/IllegalAccessError", None);
+            return;
         }
 
-        invoke_method(frame, method_to_be_invoked.unwrap());
+        invoke_method(frame, method_to_be_invoked);
     }
 }
 
@@ -7,6 +7,8 @@ use crate::oops::method_ref::MethodRef;
 use std::borrow::Borrow;
 use std::ops::Deref;
 use crate::instructions::references::ResolveMethodRef;
+use crate::instructions::quick::{quicken, QuickInstruction};
+use crate::invoke_support::throw_exception;
 
 pub struct InvokeSpecial(ConstantPoolInstruction);
 
@@ -22,39 +24,51 @@ impl Instruction for InvokeSpecial {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
 
-        let (resolved_class,resolved_method) = self.
-            resolved_method_ref_tuple(class.clone());
+        let (resolved_class, resolved_method) = match self.resolved_method_ref_tuple(class.clone()) {
+            Some(resolved) => resolved,
+            None => return,
+        };
 
         if resolved_method.name() == "<init>" && resolved_method.class() != resolved_class {
-            panic!("java.lang.NoSuchMethodError")
+            let msg = format!("{}{}", resolved_method.name(), resolved_method.descriptor());
+            throw_exception(frame, "java/lang/NoSuchMethodError", Some(msg.as_str()));
+            return;
         }
         if resolved_method.is_static() {
-            panic!("java.lang.IncompatibleClassChangeError")
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
         let object = frame
             .operand_stack()
             .expect("stack is none")
             .get_ref_from_top(resolved_method.arg_slot_count() - 1);
         if object.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
 
         let method_class = resolved_method.class();
         let borrow_method_class = (*method_class).borrow();
         let object_class = (*object.unwrap()).borrow().class();
         let borrow_class = (*class).borrow();
-        if resolved_method.is_protected()
+        // the access check of a protected method depends on the receiver,
+        // such a call site isn't quickened
+        let checks_receiver = resolved_method.is_protected()
             && borrow_method_class.is_super_class_of((*class).borrow().deref())
-            && borrow_method_class.package_name() != borrow_class.package_name()
+            && borrow_method_class.package_name() != borrow_class.package_name();
+        if checks_receiver
             && object_class != class
             && !(*object_class)
                 .borrow()
                 .is_sub_class_of(borrow_class.deref())
         {
-            panic!("java.lang.IllegalAccessError");
+            drop(borrow_class);
+            drop(borrow_method_class);
+            throw_exception(frame, "java/lang/IllegalAccessError", None);
+            return;
         };
         let mut method_to_be_invoked = Some(resolved_method.clone());
         if borrow_class.is_super()
@@ -71,10 +85,18 @@ impl Instruction for InvokeSpecial {
         }
 
         if method_to_be_invoked.is_none() || method_to_be_invoked.as_ref().unwrap().is_abstract() {
-            panic!("java.lang.AbstractMethodError")
+            drop(borrow_class);
+            drop(borrow_method_class);
+            let msg = format!("{}{}", resolved_method.name(), resolved_method.descriptor());
+            throw_exception(frame, "java/lang/AbstractMethodError", Some(msg.as_str()));
+            return;
         }
 
-        invoke_method(frame, method_to_be_invoked.unwrap());
+        let method = method_to_be_invoked.unwrap();
+        if !checks_receiver {
+            quicken(frame, QuickInstruction::InvokeSpecial(method.clone()));
+        }
+        invoke_method(frame, method);
     }
 }
 
@@ -1,10 +1,13 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::class_init_logic::{ensure_initialized, quicken_if_initialized};
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
 use crate::instructions::base::method_invoke_logic::invoke_method;
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::MethodReference;
 use crate::instructions::references::ResolveMethodRef;
+use crate::instructions::quick::QuickInstruction;
+use crate::invoke_support::throw_exception;
+use crate::invoke_support::method_handle::invoke_polymorphic;
 
 pub struct InvokeStatic(ConstantPoolInstruction);
 
@@ -20,19 +23,27 @@ impl Instruction for InvokeStatic {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
+        if let Some((name, descriptor)) = self.signature_polymorphic(class.clone()) {
+            invoke_polymorphic(frame, name.as_str(), descriptor.as_str());
+            return;
+        }
 
-        let resolved_method = self.resolved_method_ref(class);
+        let resolved_method = match self.resolved_method_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         if !resolved_method.is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
         let class = resolved_method.class();
-        if !(*class).borrow().initialized() {
-            frame.revert_next_pc();
-            init_class(frame.thread(), class.clone());
+        if !ensure_initialized(&class) {
             return;
         }
+        let quick = QuickInstruction::InvokeStatic(resolved_method.clone());
+        quicken_if_initialized(frame, &class, quick);
         invoke_method(frame, resolved_method);
     }
 }
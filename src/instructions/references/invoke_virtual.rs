@@ -1,17 +1,16 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
-use crate::instructions::base::method_invoke_logic::invoke_method;
+use crate::instructions::base::method_invoke_logic::{invoke_method, select_method};
 use crate::jvm::Jvm;
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::MethodReference;
-use crate::oops::method_ref::MethodRef;
 use std::ops::Deref;
 use std::rc::Rc;
 use crate::oops::method::Method;
-use std::cell::RefCell;
-use crate::oops::class::Class;
 use crate::instructions::references::ResolveMethodRef;
+use crate::instructions::quick::{quicken, QuickInstruction};
 use crate::invoke_support::throw_exception;
+use crate::invoke_support::method_handle::invoke_polymorphic;
 
 pub struct InvokeVirtual(ConstantPoolInstruction);
 
@@ -27,14 +26,55 @@ impl Instruction for InvokeVirtual {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let current_class = frame.method().class();
+        if let Some((name, descriptor)) = self.signature_polymorphic(current_class.clone()) {
+            invoke_polymorphic(frame, name.as_str(), descriptor.as_str());
+            return;
+        }
 
-        let resolved_method = self.resolved_method_ref(current_class.clone());
+        let resolved_method = match self.resolved_method_ref(current_class.clone()) {
+            Some(resolved) => resolved,
+            None => return,
+        };
         if resolved_method.is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
 
+        let resolved_method_class = resolved_method.class();
+        // a protected method of a superclass in another package can only be
+        // called on objects of the current class and its subclasses
+        let checks_receiver = resolved_method.is_protected()
+            && (*resolved_method_class)
+                .borrow()
+                .is_super_class_of((*current_class).borrow().deref())
+            && (*resolved_method_class).borrow().package_name()
+                != (*current_class).borrow().package_name();
+        let call = VirtualCall::new(resolved_method, checks_receiver);
+        call.execute(frame);
+        quicken(frame, QuickInstruction::InvokeVirtual(call));
+    }
+}
+
+/// an invokevirtual with its method resolved, the method is selected from
+/// the receiver's vtable
+pub struct VirtualCall {
+    resolved_method: Rc<Method>,
+    checks_receiver: bool,
+}
+
+impl VirtualCall {
+    #[inline]
+    pub fn new(resolved_method: Rc<Method>, checks_receiver: bool) -> VirtualCall {
+        return VirtualCall {
+            resolved_method,
+            checks_receiver,
+        };
+    }
+
+    pub fn execute(&self, frame: &mut Frame) {
+        let resolved_method = &self.resolved_method;
         let object = frame
             .operand_stack()
             .expect("stack is none")
@@ -46,37 +86,24 @@ impl Instruction for InvokeVirtual {
             //            }
             throw_exception(frame, "java/lang/NullPointerException", None);
             return;
-            //panic!("java.lang.NullPointerException");
         }
         let obj_class = (*object.unwrap()).borrow().class();
-        let resolved_method_class = resolved_method.class();
-        if resolved_method.is_protected()
-            && (*resolved_method_class)
-                .borrow()
-                .is_super_class_of((*current_class).borrow().deref())
-            && (*resolved_method_class).borrow().package_name()
-                != (*current_class).borrow().package_name()
-            && obj_class != current_class
-            && !(*obj_class)
-                .borrow()
-                .is_sub_class_of((*current_class).borrow().deref())
-        {
-            if !((*obj_class).borrow().is_array() && resolved_method.name() == "clone") {
-                panic!("java.lang.IllegalAccessError")
+        if self.checks_receiver {
+            let current_class = frame.method().class();
+            if obj_class != current_class
+                && !(*obj_class)
+                    .borrow()
+                    .is_sub_class_of((*current_class).borrow().deref())
+                && !((*obj_class).borrow().is_array() && resolved_method.name() == "clone")
+            {
+                throw_exception(frame, "java/lang/IllegalAccessError", None);
+                return;
             }
-            //            panic!("java.lang.IllegalAccessError")
         }
 
-        let method_to_be_invoked = MethodRef::look_up_method_in_class(
-            obj_class,
-            resolved_method.name(),
-            resolved_method.descriptor(),
-        );
-        if method_to_be_invoked.is_none() || method_to_be_invoked.as_ref().unwrap().is_abstract() {
-            panic!("java.lang.AbstractMethodError")
+        if let Some(method) = select_method(frame, &obj_class, resolved_method) {
+            invoke_method(frame, method);
         }
-
-        invoke_method(frame, method_to_be_invoked.unwrap());
     }
 }
 
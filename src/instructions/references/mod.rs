@@ -6,6 +6,9 @@ use crate::oops::method::Method;
 use crate::oops::constant_pool::Constant::{MethodReference, FieldReference, ClassReference, InterfaceMethodReference};
 use std::ops::Deref;
 use crate::oops::constant_pool::Constant;
+use crate::invoke_support::method_handle::is_signature_polymorphic;
+use crate::invoke_support::throw_exception;
+use crate::runtime::frame::Frame;
 
 pub mod anew_array;
 pub mod array_length;
@@ -14,6 +17,7 @@ pub mod check_cast;
 pub mod get_field;
 pub mod get_static;
 pub mod instance_of;
+pub mod invoke_dynamic;
 pub mod invoke_interface;
 pub mod invoke_special;
 pub mod invoke_static;
@@ -25,8 +29,15 @@ pub mod new_array;
 pub mod put_field;
 pub mod put_static;
 
+/// The resolve methods return None when resolution failed, the error is
+/// pending then and the instruction has to return right away. A reference
+/// resolved before is read from the constant pool without taking it out.
 trait ResolveFieldRef {
-    fn resolve_field_ref(&self,class:Rc<RefCell<Class>>) -> Rc<RefCell<Field>> {
+    fn resolve_field_ref(&self,class:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Field>>> {
+        let resolved = (*class).borrow().constant_pool().resolved_field(self.get_index());
+        if resolved.is_some() {
+            return resolved;
+        }
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -35,7 +46,7 @@ trait ResolveFieldRef {
             FieldReference(refe) => refe,
             _ => panic!("Unknown constant type"),
         };
-        let field = field_ref.resolved_field(class.clone()).unwrap();
+        let field = field_ref.resolved_field(class.clone());
         (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -47,7 +58,11 @@ trait ResolveFieldRef {
 }
 
 trait ResolveClassRef {
-    fn resolve_class_ref(&self,class:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    fn resolve_class_ref(&self,class:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
+        let resolved = (*class).borrow().constant_pool().resolved_class(self.get_index());
+        if resolved.is_some() {
+            return resolved;
+        }
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -68,7 +83,11 @@ trait ResolveClassRef {
 }
 
 trait ResolveMethodRef {
-    fn resolved_method_ref(&self,class:Rc<RefCell<Class>>) -> Rc<Method> {
+    fn resolved_method_ref(&self,class:Rc<RefCell<Class>>) -> Option<Rc<Method>> {
+        let resolved = (*class).borrow().constant_pool().resolved_method(self.get_index());
+        if resolved.is_some() {
+            return resolved;
+        }
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -82,10 +101,25 @@ trait ResolveMethodRef {
             .borrow_mut()
             .mut_constant_pool()
             .restoration_constant(self.get_index(),Constant::MethodReference(method_ref));
-        return method.unwrap();
+        return method;
     }
 
-    fn resolved_method_ref_tuple(&self,class:Rc<RefCell<Class>>) -> (Rc<RefCell<Class>>,Rc<Method>) {
+    /// the name and descriptor of the call if it targets a signature polymorphic
+    /// method of MethodHandle, which is never resolved against the descriptor
+    fn signature_polymorphic(&self,class:Rc<RefCell<Class>>) -> Option<(String,String)> {
+        let borrow = (*class).borrow();
+        match borrow.constant_pool().get_constant_immutable(self.get_index()) {
+            MethodReference(refe) if is_signature_polymorphic(refe.class_name(), refe.name()) => {
+                Some((refe.name().to_string(), refe.descriptor().to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn resolved_method_ref_tuple(&self,class:Rc<RefCell<Class>>) -> Option<(Rc<RefCell<Class>>,Rc<Method>)> {
+        if let Some(resolved) = resolved_tuple(&class, self.get_index()) {
+            return Some(resolved);
+        }
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -100,14 +134,18 @@ trait ResolveMethodRef {
             .borrow_mut()
             .mut_constant_pool()
             .restoration_constant(self.get_index(),Constant::MethodReference(method_ref));
-        return (resolved_class,method.unwrap());
+        return Some((resolved_class?,method?));
     }
 
     fn get_index(&self) -> usize;
 }
 
 trait ResolveInterfaceMethodRef {
-    fn resolved_interface_method_ref(&self,class:Rc<RefCell<Class>>) -> Rc<Method> {
+    fn resolved_interface_method_ref(&self,class:Rc<RefCell<Class>>) -> Option<Rc<Method>> {
+        let resolved = (*class).borrow().constant_pool().resolved_method(self.get_index());
+        if resolved.is_some() {
+            return resolved;
+        }
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -121,26 +159,20 @@ trait ResolveInterfaceMethodRef {
             .borrow_mut()
             .mut_constant_pool()
             .restoration_constant(self.get_index(),Constant::InterfaceMethodReference(method_ref));
-        return method.unwrap();
-    }
-
-    fn resolved_interface_method_ref_tuple(&self,class:Rc<RefCell<Class>>) -> (Rc<RefCell<Class>>,Rc<Method>) {
-        let constant = (*class)
-            .borrow_mut()
-            .mut_constant_pool()
-            .take_constant(self.get_index());
-        let mut method_ref = match constant {
-            InterfaceMethodReference(refe) => refe,
-            _ => panic!("Unknown constant type")
-        };
-        let method = method_ref.resolved_interface_method(class.clone());
-        let resolved_class = method_ref.resolved_class(class.clone());
-        (*class)
-            .borrow_mut()
-            .mut_constant_pool()
-            .restoration_constant(self.get_index(),Constant::InterfaceMethodReference(method_ref));
-        return (resolved_class,method.unwrap());
+        return method;
     }
 
     fn get_index(&self) -> usize;
 }
+
+/// the class and method of a method reference resolved before
+fn resolved_tuple(class: &Rc<RefCell<Class>>, index: usize) -> Option<(Rc<RefCell<Class>>, Rc<Method>)> {
+    let borrow = (**class).borrow();
+    let pool = borrow.constant_pool();
+    return Some((pool.resolved_class(index)?, pool.resolved_method(index)?));
+}
+
+/// the object does not fit into the heap even after a collection
+fn throw_out_of_memory(frame: &mut Frame) {
+    throw_exception(frame, "java/lang/OutOfMemoryError", Some("Java heap space"));
+}
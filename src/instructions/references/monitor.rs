@@ -1,5 +1,6 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 pub struct MonitorEnter(NoOperandsInstruction);
@@ -16,11 +17,14 @@ impl Instruction for MonitorEnter {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let object = frame.operand_stack().expect("stack is none").pop_ref();
         if object.is_none() {
-            panic!("java.lang.NullPointerException")
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
+        let monitor = (*object.unwrap()).borrow().monitor();
+        monitor.enter();
     }
 }
 
@@ -38,10 +42,14 @@ impl Instruction for MonitorExit {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let object = frame.operand_stack().expect("stack is none").pop_ref();
         if object.is_none() {
-            panic!("java.lang.NullPointerException")
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
+        }
+        if !(*object.unwrap()).borrow().monitor().exit() {
+            throw_exception(frame, "java/lang/IllegalMonitorStateException", None);
         }
     }
 }
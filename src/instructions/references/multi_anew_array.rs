@@ -1,13 +1,14 @@
+use crate::gc::heap::{array_size, Heap};
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::Instruction;
 use crate::runtime::frame::Frame;
 use crate::oops::array_object::ArrayObject;
 use crate::oops::class::Class;
 use crate::oops::constant_pool::Constant::ClassReference;
-use crate::utils::boxed;
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::instructions::references::ResolveClassRef;
+use crate::instructions::references::{throw_out_of_memory, ResolveClassRef};
+use crate::invoke_support::throw_exception;
 
 pub struct MultiANewArray {
     index: u16,
@@ -23,21 +24,45 @@ impl MultiANewArray {
         };
     }
 
-    fn pop_and_check_counts(frame: &mut Frame, dimensions: usize) -> Vec<i32> {
+    /// returns None after throwing NegativeArraySizeException
+    fn pop_and_check_counts(frame: &mut Frame, dimensions: usize) -> Option<Vec<i32>> {
         let stack = frame.operand_stack().expect("stack is none");
-        let mut counts = Vec::with_capacity(dimensions);
-        for dimension in 1..dimensions {
-            let index = dimensions - dimension;
+        let mut counts = vec![0; dimensions];
+        for index in (0..dimensions).rev() {
             counts[index] = stack.pop_int();
-            if counts[index] < 0 {
-                panic!("java.lang.NegativeArraySizeException")
+        }
+        if let Some(count) = counts.iter().find(|count| **count < 0) {
+            let msg = count.to_string();
+            throw_exception(frame, "java/lang/NegativeArraySizeException", Some(msg.as_str()));
+            return None;
+        }
+        return Some(counts);
+    }
+
+    /// the size of all the arrays the counts create together, an empty
+    /// dimension leaves the ones below it out
+    fn total_size(counts: &[i32], arr_class: Rc<RefCell<Class>>) -> usize {
+        let mut size = 0usize;
+        let mut arrays = 1usize;
+        let mut class = arr_class;
+        for (index, count) in counts.iter().enumerate() {
+            let count = *count as usize;
+            let array = array_size(&(*class).borrow(), count);
+            size = size.saturating_add(arrays.saturating_mul(array));
+            arrays = arrays.saturating_mul(count);
+            if arrays == 0 {
+                break;
+            }
+            if index + 1 < counts.len() {
+                let component_class = (*class).borrow().component_class();
+                class = component_class;
             }
         }
-        return counts;
+        return size;
     }
 
     fn new_multi_dimensional_array(
-        mut counts: Vec<i32>,
+        counts: &[i32],
         arr_class: Rc<RefCell<Class>>,
     ) -> ArrayObject {
         let count = counts[0] as usize;
@@ -45,8 +70,8 @@ impl MultiANewArray {
         if counts.len() > 1 {
             let refs = arr.mut_references();
             for i in 0..refs.len() {
-                refs[i] = Some(boxed(MultiANewArray::new_multi_dimensional_array(
-                    counts.split_off(1),
+                refs[i] = Some(Heap::allocate(MultiANewArray::new_multi_dimensional_array(
+                    &counts[1..],
                     (*arr_class).borrow().component_class(),
                 )));
             }
@@ -61,16 +86,27 @@ impl Instruction for MultiANewArray {
         self.dimensions = reader.read_u8();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let current_class = frame.method().class();
 
-        let array_class = self.resolve_class_ref(current_class);
-        let counts = MultiANewArray::pop_and_check_counts(frame, self.dimensions as usize);
-        let arr = MultiANewArray::new_multi_dimensional_array(counts, array_class);
+        let array_class = match self.resolve_class_ref(current_class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
+        let counts = match MultiANewArray::pop_and_check_counts(frame, self.dimensions as usize) {
+            Some(counts) => counts,
+            None => return,
+        };
+        // every dimension is reserved before the first array is built
+        if !Heap::reserve(MultiANewArray::total_size(&counts, array_class.clone())) {
+            throw_out_of_memory(frame);
+            return;
+        }
+        let arr = MultiANewArray::new_multi_dimensional_array(&counts, array_class);
         frame
             .operand_stack()
             .expect("stack is none")
-            .push_ref(Some(boxed(arr)));
+            .push_ref(Some(Heap::allocate(arr)));
     }
 }
 
@@ -79,3 +115,25 @@ impl ResolveClassRef for MultiANewArray {
         return self.index as usize;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::test_classes::{ClassBuilder, TestLoader};
+    use crate::gc::heap::{array_size, DEFAULT_MAX_HEAP_SIZE};
+    use crate::instructions::references::multi_anew_array::MultiANewArray;
+
+    #[test]
+    fn test_total_size() {
+        let loader = TestLoader::new();
+        let longs = loader.define(&ClassBuilder::new("[J"));
+        let arrays = loader.define(&ClassBuilder::new("[[J"));
+        let size = MultiANewArray::total_size(&[2, 3], arrays.clone());
+        let expected = array_size(&(*arrays).borrow(), 2) + 2 * array_size(&(*longs).borrow(), 3);
+        assert_eq!(size, expected);
+        // the inner dimension is left out when there are no outer arrays
+        let size = MultiANewArray::total_size(&[0, 50000], arrays.clone());
+        assert_eq!(size, array_size(&(*arrays).borrow(), 0));
+        let size = MultiANewArray::total_size(&[50000, 50000], arrays);
+        assert!(size > DEFAULT_MAX_HEAP_SIZE);
+    }
+}
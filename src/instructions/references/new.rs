@@ -1,13 +1,15 @@
+use crate::gc::heap::{instance_size, Heap};
 use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::class_init_logic::{ensure_initialized, quicken_if_initialized};
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
 use crate::oops::constant_pool::Constant::ClassReference;
-use crate::utils::boxed;
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::instructions::references::ResolveClassRef;
+use crate::instructions::references::{throw_out_of_memory, ResolveClassRef};
+use crate::instructions::quick::QuickInstruction;
+use crate::invoke_support::throw_exception;
 
 pub struct New(ConstantPoolInstruction);
 
@@ -23,27 +25,46 @@ impl Instruction for New {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let class = frame.method().class();
 
-        let class = self.resolve_class_ref(class);
-        if !(*class).borrow().initialized() {
-            frame.revert_next_pc();
-            init_class(frame.thread(), class.clone());
+        let class = match self.resolve_class_ref(class) {
+            Some(resolved) => resolved,
+            None => return,
+        };
+        if !ensure_initialized(&class) {
             return;
         }
         let ref_class = (*class).borrow();
         if ref_class.is_interface() || ref_class.is_abstract() {
-            panic!("java.lang.InstantiationError")
+            let name = ref_class.java_name();
+            drop(ref_class);
+            throw_exception(frame, "java/lang/InstantiationError", Some(name.as_str()));
+            return;
+        }
+        drop(ref_class);
+        quicken_if_initialized(frame, &class, QuickInstruction::New(class.clone()));
+        New::allocate(frame, &class);
+    }
+}
+
+impl New {
+    /// pushes a new instance of the class, which can be instantiated
+    pub fn allocate(frame: &mut Frame, class: &Rc<RefCell<Class>>) {
+        let ref_class = (**class).borrow();
+        if !Heap::reserve(instance_size(&ref_class)) {
+            drop(ref_class);
+            throw_out_of_memory(frame);
+            return;
         }
         let object = match ref_class.is_class_loader() {
-            true => Class::new_class_loader_object(&class),
-            false => Class::new_object(&class),
+            true => Class::new_class_loader_object(class),
+            false => Class::new_object(class),
         };
         frame
             .operand_stack()
             .expect("")
-            .push_ref(Some(boxed(object)));
+            .push_ref(Some(Heap::allocate(object)));
     }
 }
 
@@ -1,10 +1,12 @@
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::{array_size, Heap};
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::Instruction;
+use crate::instructions::references::throw_out_of_memory;
+use crate::invoke_support::throw_exception;
 use crate::jvm::Jvm;
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
-use crate::utils::boxed;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -33,15 +35,22 @@ impl Instruction for NewArray {
         self.atype = reader.read_u8();
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let count = stack.pop_int();
         if count < 0 {
-            panic!("java.lang.NegativeArraySizeException")
+            let msg = count.to_string();
+            throw_exception(frame, "java/lang/NegativeArraySizeException", Some(msg.as_str()));
+            return;
         }
         let array_class = get_primitive_array_class(self.atype);
+        if !Heap::reserve(array_size(&(*array_class).borrow(), count as usize)) {
+            throw_out_of_memory(frame);
+            return;
+        }
+        let stack = frame.operand_stack().expect("stack is none");
         let array_object = Class::new_array(&array_class, count as usize);
-        stack.push_ref(Some(boxed(array_object)));
+        stack.push_ref(Some(Heap::allocate(array_object)));
     }
 }
 
@@ -7,6 +7,8 @@ use std::rc::Rc;
 use crate::oops::class::Class;
 use crate::oops::field::Field;
 use crate::instructions::references::ResolveFieldRef;
+use crate::instructions::quick::{quicken, QuickInstruction};
+use crate::invoke_support::throw_exception;
 
 pub struct PutField(ConstantPoolInstruction);
 
@@ -22,34 +24,46 @@ impl Instruction for PutField {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let current_method = frame.method();
         let current_class = current_method.class();
 
-        let field_option = self.resolve_field_ref(current_class.clone());
+        let field_option = match self.resolve_field_ref(current_class.clone()) {
+            Some(resolved) => resolved,
+            None => return,
+        };
 
         let field = (*field_option).borrow();
         let class = field.parent().class();
         if field.parent().is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
         if field.parent().is_final() {
             if current_class != class || current_method.name() != "<init>" {
-                panic!("java.lang.IllegalAccessError");
+                throw_exception(frame, "java/lang/IllegalAccessError", None);
+                return;
             }
         }
-        let desc = field.parent().descriptor();
         let slot_id = field.slot_id();
+        let first_char = field.parent().descriptor().chars().next().unwrap();
+        quicken(frame, QuickInstruction::PutField(slot_id, first_char));
+        PutField::put(frame, slot_id, first_char);
+    }
+}
 
+impl PutField {
+    /// pops a value and an object and sets the field at slot_id,
+    /// first_char is the first char of the field's descriptor
+    pub fn put(frame: &mut Frame, slot_id: usize, first_char: char) {
         let stack = frame.operand_stack().expect("stack is none");
-
-        let first_char = desc.chars().next().unwrap();
         match first_char {
             'Z' | 'B' | 'C' | 'S' | 'I' => {
                 let val = stack.pop_int();
                 let reference = stack.pop_ref();
                 if reference.is_none() {
-                    panic!("java.lang.NullPointerException");
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
                 }
                 (*reference.unwrap())
                     .borrow_mut()
@@ -60,7 +74,8 @@ impl Instruction for PutField {
                 let val = stack.pop_float();
                 let reference = stack.pop_ref();
                 if reference.is_none() {
-                    panic!("java.lang.NullPointerException");
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
                 }
                 (*reference.unwrap())
                     .borrow_mut()
@@ -71,7 +86,8 @@ impl Instruction for PutField {
                 let val = stack.pop_long();
                 let reference = stack.pop_ref();
                 if reference.is_none() {
-                    panic!("java.lang.NullPointerException");
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
                 }
                 (*reference.unwrap())
                     .borrow_mut()
@@ -82,7 +98,8 @@ impl Instruction for PutField {
                 let val = stack.pop_double();
                 let reference = stack.pop_ref();
                 if reference.is_none() {
-                    panic!("java.lang.NullPointerException");
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
                 }
                 (*reference.unwrap())
                     .borrow_mut()
@@ -93,7 +110,8 @@ impl Instruction for PutField {
                 let val = stack.pop_ref();
                 let reference = stack.pop_ref();
                 if reference.is_none() {
-                    panic!("java.lang.NullPointerException");
+                    throw_exception(frame, "java/lang/NullPointerException", None);
+                    return;
                 }
                 (*reference.unwrap())
                     .borrow_mut()
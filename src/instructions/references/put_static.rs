@@ -1,5 +1,5 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::class_init_logic::{ensure_initialized, quicken_if_initialized};
 use crate::instructions::base::instruction::{ConstantPoolInstruction, Instruction};
 use crate::runtime::frame::Frame;
 use crate::oops::constant_pool::Constant::FieldReference;
@@ -8,6 +8,8 @@ use std::cell::RefCell;
 use crate::oops::field::Field;
 use crate::oops::class::Class;
 use crate::instructions::references::ResolveFieldRef;
+use crate::instructions::quick::QuickInstruction;
+use crate::invoke_support::throw_exception;
 
 pub struct PutStatic(ConstantPoolInstruction);
 
@@ -23,33 +25,46 @@ impl Instruction for PutStatic {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let current_method = frame.method();
         let current_class = current_method.class();
 
-        let field_option = self.resolve_field_ref(current_class.clone());
+        let field_option = match self.resolve_field_ref(current_class.clone()) {
+            Some(resolved) => resolved,
+            None => return,
+        };
 
-        let field = (*field_option).borrow();
-        let class = field.parent().class();
-        if !(*class).borrow().initialized() {
-            frame.revert_next_pc();
-            init_class(frame.thread(), class.clone());
+        // <clinit> may resolve the same field, nothing of it is borrowed meanwhile
+        let class = (*field_option).borrow().parent().class();
+        if !ensure_initialized(&class) {
             return;
         }
+        let field = (*field_option).borrow();
         if !field.parent().is_static() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            throw_exception(frame, "java/lang/IncompatibleClassChangeError", None);
+            return;
         }
         if field.parent().is_final() {
             if current_class != class || current_method.name() != "<clinit>" {
-                panic!("java.lang.IllegalAccessError");
+                throw_exception(frame, "java/lang/IllegalAccessError", None);
+                return;
             }
         }
-        let desc = field.parent().descriptor();
         let slot_id = field.slot_id();
-        let mut borrow_class = (*class).borrow_mut();
+        let first_char = field.parent().descriptor().chars().next().unwrap();
+        let quick = QuickInstruction::PutStatic(class.clone(), slot_id, first_char);
+        quicken_if_initialized(frame, &class, quick);
+        PutStatic::put(frame, &class, slot_id, first_char);
+    }
+}
+
+impl PutStatic {
+    /// pops a value into the static field at slot_id of the class,
+    /// first_char is the first char of the field's descriptor
+    pub fn put(frame: &mut Frame, class: &Rc<RefCell<Class>>, slot_id: usize, first_char: char) {
+        let mut borrow_class = (**class).borrow_mut();
         let slots = borrow_class.mut_static_vars().expect("slots is none");
         let stack = frame.operand_stack().expect("stack is none");
-        let first_char = desc.chars().next().unwrap();
         match first_char {
             'Z' | 'B' | 'C' | 'S' | 'I' => slots.set_int(slot_id, stack.pop_int()),
             'F' => slots.set_float(slot_id, stack.pop_float()),
@@ -1,5 +1,6 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
+use crate::invoke_support::throw_exception;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 
@@ -17,7 +18,7 @@ impl Instruction for InvokeNative {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let method = frame.method();
         let class = method.class();
         let class_name = (*class).borrow().name().to_string();
@@ -27,7 +28,8 @@ impl Instruction for InvokeNative {
             Registry::find_native_method(class_name.as_str(), method_name, method_desc);
         if native_method.is_none() {
             let method_info = class_name.to_string() + "." + method_name + method_desc;
-            panic!("java.lang.UnsatisfiedLinkError: {}", method_info);
+            throw_exception(frame, "java/lang/UnsatisfiedLinkError", Some(method_info.as_str()));
+            return;
         }
         //        println!("native method:{}",method_name);
         native_method.unwrap()(frame);
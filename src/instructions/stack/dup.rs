@@ -17,7 +17,7 @@ impl Instruction for Dup {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot = stack.pop_slot();
         stack.push_slot(slot.clone());
@@ -40,7 +40,7 @@ impl Instruction for DupX1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -65,7 +65,7 @@ impl Instruction for DupX2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot_1 = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -92,7 +92,7 @@ impl Instruction for Dup2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot_1 = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -118,7 +118,7 @@ impl Instruction for Dup2X1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot_1 = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -146,7 +146,7 @@ impl Instruction for Dup2X2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot_1 = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -17,7 +17,7 @@ impl Instruction for Pop {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         stack.pop_slot();
     }
@@ -38,7 +38,7 @@ impl Instruction for Pop2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         stack.pop_slot();
         stack.pop_slot();
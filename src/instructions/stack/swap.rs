@@ -16,7 +16,7 @@ impl Instruction for Swap {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("operand_stack is none");
         let slot = stack.pop_slot();
         let slot_2 = stack.pop_slot();
@@ -1,6 +1,7 @@
 use crate::instructions::base::bytecode_reader::BytecodeReader;
 use crate::instructions::base::instruction::{Instruction, NoOperandsInstruction};
 use crate::instructions::check_index;
+use crate::invoke_support::throw_exception;
 use crate::runtime::frame::Frame;
 
 pub struct AAStore(NoOperandsInstruction);
@@ -17,19 +18,33 @@ impl Instruction for AAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_ref();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
-        let mut borrow = (*object).borrow_mut();
-        let references = borrow.mut_references();
-        check_index(references.len(), index);
-        references[index] = val;
+        let (length, array_class) = {
+            let borrow = (*object).borrow();
+            (borrow.references().len(), borrow.class())
+        };
+        if !check_index(frame, length, index) {
+            return;
+        }
+        if let Some(value) = &val {
+            let component_class = (*array_class).borrow().component_class();
+            if !(**value).borrow().is_instance_of(component_class) {
+                let value_class = (**value).borrow().class();
+                let name = (*value_class).borrow().java_name();
+                throw_exception(frame, "java/lang/ArrayStoreException", Some(name.as_str()));
+                return;
+            }
+        }
+        (*object).borrow_mut().mut_references()[index as usize] = val;
     }
 }
 
@@ -47,19 +62,22 @@ impl Instruction for BAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_int();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let bytes = borrow.mut_bytes();
-        check_index(bytes.len(), index);
-        bytes[index] = val as i8;
+        if !check_index(frame, bytes.len(), index) {
+            return;
+        }
+        bytes[index as usize] = val as i8;
     }
 }
 
@@ -77,19 +95,22 @@ impl Instruction for CAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_int();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let chars = borrow.mut_chars();
-        check_index(chars.len(), index);
-        chars[index] = val as u16;
+        if !check_index(frame, chars.len(), index) {
+            return;
+        }
+        chars[index as usize] = val as u16;
     }
 }
 
@@ -107,19 +128,22 @@ impl Instruction for DAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_double();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let doubles = borrow.mut_doubles();
-        check_index(doubles.len(), index);
-        doubles[index] = val;
+        if !check_index(frame, doubles.len(), index) {
+            return;
+        }
+        doubles[index as usize] = val;
     }
 }
 
@@ -137,19 +161,22 @@ impl Instruction for FAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_float();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let floats = borrow.mut_floats();
-        check_index(floats.len(), index);
-        floats[index] = val;
+        if !check_index(frame, floats.len(), index) {
+            return;
+        }
+        floats[index as usize] = val;
     }
 }
 
@@ -167,19 +194,22 @@ impl Instruction for IAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_int();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let ints = borrow.mut_ints();
-        check_index(ints.len(), index);
-        ints[index] = val;
+        if !check_index(frame, ints.len(), index) {
+            return;
+        }
+        ints[index as usize] = val;
     }
 }
 
@@ -197,19 +227,22 @@ impl Instruction for LAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_long();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let longs = borrow.mut_longs();
-        check_index(longs.len(), index);
-        longs[index] = val;
+        if !check_index(frame, longs.len(), index) {
+            return;
+        }
+        longs[index as usize] = val;
     }
 }
 
@@ -227,18 +260,21 @@ impl Instruction for SAStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         let stack = frame.operand_stack().expect("stack is none");
         let val = stack.pop_int();
-        let index = stack.pop_int() as usize;
+        let index = stack.pop_int();
         let arr_ref = stack.pop_ref();
         if arr_ref.is_none() {
-            panic!("java.lang.NullPointerException");
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
         }
         let object = arr_ref.unwrap();
         let mut borrow = (*object).borrow_mut();
         let shorts = borrow.mut_shorts();
-        check_index(shorts.len(), index);
-        shorts[index] = val as i16;
+        if !check_index(frame, shorts.len(), index) {
+            return;
+        }
+        shorts[index as usize] = val as i16;
     }
 }
@@ -35,7 +35,7 @@ impl Instruction for AStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_store(frame, self.0.get_index());
     }
 }
@@ -55,7 +55,7 @@ impl Instruction for AStore0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_store(frame, 0);
     }
 }
@@ -75,7 +75,7 @@ impl Instruction for AStore1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_store(frame, 1);
     }
 }
@@ -95,7 +95,7 @@ impl Instruction for AStore2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_store(frame, 2);
     }
 }
@@ -115,7 +115,7 @@ impl Instruction for AStore3 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         a_store(frame, 3);
     }
 }
@@ -35,7 +35,7 @@ impl Instruction for IStore {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         i_store(frame, self.0.get_index());
     }
 }
@@ -55,7 +55,7 @@ impl Instruction for IStore0 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         i_store(frame, 0);
     }
 }
@@ -75,7 +75,7 @@ impl Instruction for IStore1 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         i_store(frame, 1);
     }
 }
@@ -95,7 +95,7 @@ impl Instruction for IStore2 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         i_store(frame, 2);
     }
 }
@@ -115,7 +115,7 @@ impl Instruction for IStore3 {
         self.0.fetch_operands(reader);
     }
 
-    fn execute(&mut self, frame: &mut Frame) {
+    fn execute(&self, frame: &mut Frame) {
         i_store(frame, 3);
     }
 }
@@ -1,5 +1,6 @@
 pub mod java_lang_instrument;
 
+use crate::gc::heap::Heap;
 use crate::invoke_support::parameter::{Parameter, Parameters};
 use crate::invoke_support::{invoke, ReturnType};
 use crate::jvm::Jvm;
@@ -7,7 +8,6 @@ use crate::oops::class::Class;
 use crate::oops::object::Object;
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::utils::boxed;
 
 const CONSTRUCTOR_DESC: &str = "(JZZ)V";
 
@@ -17,7 +17,7 @@ pub fn create_instrumentation() -> Rc<RefCell<Object>> {
         .find_or_create("sun/instrument/InstrumentationImpl")
         .expect("can't find sun.instrument.InstrumentationImpl");
     let constructor = Class::get_constructor(class.clone(), CONSTRUCTOR_DESC);
-    let object = Some(boxed(Class::new_object(&class)));
+    let object = Some(Heap::allocate(Class::new_object(&class)));
     let parameters = vec![
         Parameter::Object(object.clone()),
         Parameter::Long(0),
@@ -1,11 +1,9 @@
-use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::new_instruction;
-use crate::native::init;
+use crate::instructions::references::athrow::AThrow;
 use crate::oops::class::Class;
 use crate::oops::object::Object;
 use crate::runtime::thread::JavaThread;
+use crate::runtime::vm_lock;
 use crate::utils::boxed;
-use chrono::Local;
 use std::cell::RefCell;
 use std::ops::DerefMut;
 use std::rc::Rc;
@@ -16,49 +14,61 @@ pub fn interpret(thread: Rc<RefCell<JavaThread>>) {
 }
 
 #[inline]
-pub fn circulate(mut thread: Rc<RefCell<JavaThread>>) {
-    let mut reader = BytecodeReader::new();
-    init();
-    println!("start {:?}", Local::now());
+pub fn circulate(thread: Rc<RefCell<JavaThread>>) {
+    run(thread);
+}
+
+/// runs the thread until its stack is empty, other threads
+/// get the vm lock between two instructions
+pub fn run(thread: Rc<RefCell<JavaThread>>) {
+    JavaThread::enter(thread.clone());
     loop {
-        //        let mut borrow_thread = (*thread).borrow_mut();
-        let current_frame = (*thread).borrow().current_frame();
-        let pc = (*current_frame).borrow().next_pc();
-        (*thread).borrow_mut().set_pc(pc);
-        let method = (*current_frame).borrow().method_ptr();
-        let bytecode = method.code();
-        //       println!("method:{}, {}, {}",method.name(),method.descriptor(),(*method.class()).borrow().name());
-        reader.reset(bytecode, pc);
-        let opcode = reader.read_u8();
-        let mut inst = new_instruction(opcode);
-        inst.fetch_operands(&mut reader);
-        (*current_frame).borrow_mut().set_next_pc(reader.pc());
-        inst.execute((*current_frame).borrow_mut().deref_mut());
+        step(&thread);
         if (*thread).borrow().is_stack_empty() {
             break;
         }
+        throw_pending_exception(&thread);
+        vm_lock::safepoint();
         //        let ten_millis = time::Duration::from_millis(50);
         //        std::thread::sleep(ten_millis);
     }
-    println!("end {:?}", Local::now());
+    JavaThread::leave();
+}
+
+/// executes the instruction at the next pc of the current frame
+#[inline]
+pub fn step(thread: &Rc<RefCell<JavaThread>>) {
+    let current_frame = (*thread).borrow().current_frame();
+    let pc = (*current_frame).borrow().next_pc();
+    (*thread).borrow_mut().set_pc(pc);
+    let method = (*current_frame).borrow().method_ptr();
+    //       println!("method:{}, {}, {}",method.name(),method.descriptor(),(*method.class()).borrow().name());
+    let entry = method.instructions().fetch(pc);
+    (*current_frame).borrow_mut().set_next_pc(entry.next_pc());
+    entry.execute((*current_frame).borrow_mut().deref_mut());
+}
+
+/// throws the exception an instruction left pending, from the frame it ran in;
+/// once it unwound to an entry frame it stays pending for the VM code that
+/// called into Java
+pub fn throw_pending_exception(thread: &Rc<RefCell<JavaThread>>) {
+    if !JavaThread::has_pending_exception() || (*thread).borrow().is_stack_empty() {
+        return;
+    }
+    let frame = (*thread).borrow().current_frame();
+    if (*frame).borrow().is_entry_frame() {
+        return;
+    }
+    let exception = JavaThread::take_pending_exception().unwrap();
+    AThrow::throw((*frame).borrow_mut().deref_mut(), exception);
 }
 
 /// java method, return ref
 #[inline]
 pub fn invoke_java_method(mut thread: Rc<RefCell<JavaThread>>) -> Option<Rc<RefCell<Object>>> {
-    let mut reader = BytecodeReader::new();
     loop {
-        let current_frame = (*thread).borrow().current_frame();
-        let pc = (*current_frame).borrow().next_pc();
-        (*thread).borrow_mut().set_pc(pc);
-        let method = (*current_frame).borrow().method_ptr();
-        let bytecode = method.code();
-        reader.reset(bytecode, pc);
-        let opcode = reader.read_u8();
-        let mut inst = new_instruction(opcode);
-        inst.fetch_operands(&mut reader);
-        (*current_frame).borrow_mut().set_next_pc(reader.pc());
-        inst.execute((*current_frame).borrow_mut().deref_mut());
+        step(&thread);
+        throw_pending_exception(&thread);
         if (*thread).borrow().stack_size() == 1 {
             break;
         }
@@ -76,7 +86,50 @@ pub fn invoke_java_method(mut thread: Rc<RefCell<JavaThread>>) -> Option<Rc<RefC
 
 #[cfg(test)]
 mod test {
+    use crate::interpreter::run;
+    use crate::oops::method::Method;
+    use crate::runtime::thread::JavaThread;
+    use std::cell::RefCell;
     use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    /// counts local 0 down to zero: iinc 0 -1, iload_0, ifgt -4, return
+    const COUNT_DOWN: [u8; 8] = [0x84, 0x00, 0xff, 0x1a, 0x9d, 0xff, 0xfc, 0xb1];
+
+    fn count_down(count: i32) -> Duration {
+        let thread = Rc::new(RefCell::new(JavaThread::new_thread()));
+        let method = Rc::new(Method::with_code(1, 1, COUNT_DOWN.to_vec()));
+        let mut frame = JavaThread::new_frame(thread.clone(), method);
+        frame.local_vars().unwrap().set_int(0, count);
+        (*thread).borrow_mut().push_frame(frame);
+        let frame = (*thread).borrow().current_frame();
+        let start = Instant::now();
+        run(thread);
+        let elapsed = start.elapsed();
+        assert_eq!((*frame).borrow_mut().local_vars().unwrap().get_int(0), 0);
+        return elapsed;
+    }
+
+    #[test]
+    fn test_count_down() {
+        count_down(1000);
+    }
+
+    /// the interpreter micro-benchmark, run it with
+    /// cargo test --release bench_count_down -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_count_down() {
+        let count = 10_000_000;
+        let elapsed = count_down(count);
+        let instructions = count as f64 * 3.0;
+        println!(
+            "{} instructions in {:?}, {:.1} ns per instruction",
+            instructions,
+            elapsed,
+            elapsed.as_nanos() as f64 / instructions
+        );
+    }
 
     #[test]
     fn test_rc() {
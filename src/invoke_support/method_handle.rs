@@ -0,0 +1,540 @@
+use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::Heap;
+use crate::instructions::base::class_init_logic::init_class;
+use crate::instructions::base::method_invoke_logic::{invoke_method, select_method};
+use crate::invoke_support::parameter::{Parameter, Parameters};
+use crate::invoke_support::{invoke, set_pending_exception, throw_exception, ReturnType};
+use crate::jvm::Jvm;
+use crate::oops::class::Class;
+use crate::oops::class_name_helper::PrimitiveTypes;
+use crate::oops::method::Method;
+use crate::oops::method_descriptor::MethodDescriptorParser;
+use crate::oops::method_handle_ref::REF_INVOKE_VIRTUAL;
+use crate::oops::method_ref::MethodRef;
+use crate::oops::object::{MetaData, Object};
+use crate::oops::string_pool::StringPool;
+use crate::runtime::frame::Frame;
+use crate::runtime::thread::JavaThread;
+use crate::utils::java_str_to_rust_str;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const METHOD_HANDLE: &str = "java/lang/invoke/MethodHandle";
+
+/// jvms 2.9, the methods of java.lang.invoke.MethodHandle
+/// that accept any call site descriptor
+const SIGNATURE_POLYMORPHIC: [&str; 7] = [
+    "invokeExact",
+    "invoke",
+    "invokeBasic",
+    "linkToVirtual",
+    "linkToStatic",
+    "linkToSpecial",
+    "linkToInterface",
+];
+
+#[inline]
+pub fn is_signature_polymorphic(class_name: &str, name: &str) -> bool {
+    return class_name == METHOD_HANDLE && SIGNATURE_POLYMORPHIC.contains(&name);
+}
+
+/// the VM calls into MethodType and MethodHandleNatives while linking,
+/// returns false if one of them failed to initialize
+pub fn invoke_classes_initialized(frame: &mut Frame) -> bool {
+    let boot_loader = Jvm::boot_class_loader();
+    let classes = vec![
+        boot_loader.find_or_create("java/lang/invoke/MethodType").unwrap(),
+        boot_loader.find_or_create("java/lang/invoke/MethodHandleNatives").unwrap(),
+    ];
+    for class in classes {
+        if !(*class).borrow().initialized() && !init_class(class) {
+            return false;
+        }
+    }
+    return true;
+}
+
+/// invokes a signature polymorphic method of MethodHandle with the call site descriptor,
+/// the receiver and arguments are already on the operand stack
+pub fn invoke_polymorphic(frame: &mut Frame, name: &str, descriptor: &str) {
+    let arg_slot_count = arg_slot_count(descriptor);
+    match name {
+        "invokeBasic" => {
+            let method_handle = frame
+                .operand_stack()
+                .expect("stack is none")
+                .get_ref_from_top(arg_slot_count);
+            if method_handle.is_none() {
+                throw_exception(frame, "java/lang/NullPointerException", None);
+                return;
+            }
+            if let Some(method) = vmentry(method_handle.unwrap()) {
+                invoke_method(frame, method);
+            }
+        }
+        "invokeExact" | "invoke" => invoke_handle(frame, name, descriptor, arg_slot_count),
+        "linkToStatic" | "linkToSpecial" => {
+            let member_name = frame.operand_stack().expect("stack is none").pop_ref();
+            if member_name.is_none() {
+                throw_exception(frame, "java/lang/NullPointerException", None);
+                return;
+            }
+            if let Some(method) = member_name_method(member_name.unwrap()) {
+                invoke_method(frame, method);
+            }
+        }
+        "linkToVirtual" | "linkToInterface" => {
+            let member_name = frame.operand_stack().expect("stack is none").pop_ref();
+            if member_name.is_none() {
+                throw_exception(frame, "java/lang/NullPointerException", None);
+                return;
+            }
+            let method = match member_name_method(member_name.unwrap()) {
+                Some(method) => method,
+                None => return,
+            };
+            let receiver = frame
+                .operand_stack()
+                .expect("stack is none")
+                .get_ref_from_top(method.arg_slot_count() - 1);
+            if receiver.is_none() {
+                throw_exception(frame, "java/lang/NullPointerException", None);
+                return;
+            }
+            let class = (*receiver.unwrap()).borrow().class();
+            if let Some(method_to_be_invoked) = select_method(frame, &class, &method) {
+                invoke_method(frame, method_to_be_invoked);
+            }
+        }
+        _ => {
+            let msg = format!("MethodHandle.{}", name);
+            throw_exception(frame, "java/lang/UnsatisfiedLinkError", Some(msg.as_str()));
+        }
+    }
+}
+
+/// MethodHandle.invoke and invokeExact are linked once per call site by
+/// MethodHandleNatives.linkMethod, the invoker takes the receiver handle,
+/// the arguments and the appendix
+fn invoke_handle(frame: &mut Frame, name: &str, descriptor: &str, arg_slot_count: usize) {
+    let receiver = frame
+        .operand_stack()
+        .expect("stack is none")
+        .get_ref_from_top(arg_slot_count);
+    if receiver.is_none() {
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
+    }
+    let pc = (*frame.thread()).borrow().get_pc();
+    let caller = frame.method_ptr();
+    let mut invoker = caller.invoker(pc);
+    if invoker.is_none() {
+        if !invoke_classes_initialized(frame) {
+            return;
+        }
+        invoker = link_method(caller.class(), name, descriptor);
+        if invoker.is_none() {
+            return;
+        }
+        caller.set_invoker(pc, invoker.clone().unwrap());
+    }
+    let (member_name, appendix) = invoker.unwrap();
+    let method = match member_name_method(member_name) {
+        Some(method) => method,
+        None => return,
+    };
+    if appendix.is_some() {
+        frame.operand_stack().expect("stack is none").push_ref(appendix);
+    }
+    invoke_method(frame, method);
+}
+
+/// the linked MemberName and its appendix
+type LinkedMethod = (Rc<RefCell<Object>>, Option<Rc<RefCell<Object>>>);
+
+/// MethodHandleNatives.linkMethod(callerClass, refKind, defc, name, type, appendixResult),
+/// None with the exception linkMethod threw pending
+fn link_method(caller: Rc<RefCell<Class>>, name: &str, descriptor: &str) -> Option<LinkedMethod> {
+    let natives = ClassLoader::load_class(None, "java/lang/invoke/MethodHandleNatives").unwrap();
+    let link = Class::get_static_method(
+        natives,
+        "linkMethod",
+        "(Ljava/lang/Class;ILjava/lang/Class;Ljava/lang/String;Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/invoke/MemberName;",
+    )
+    .expect("MethodHandleNatives.linkMethod not found");
+    let object_array_class = ClassLoader::load_class(None, "[Ljava/lang/Object;").unwrap();
+    let appendix_result = Heap::allocate(Class::new_array(&object_array_class, 1));
+    let method_handle = ClassLoader::load_class(None, METHOD_HANDLE).unwrap();
+    let loader = (*caller).borrow().get_class_loader();
+    let parameters = vec![
+        Parameter::Object((*caller).borrow().get_java_class()),
+        Parameter::Int(REF_INVOKE_VIRTUAL as i32),
+        Parameter::Object((*method_handle).borrow().get_java_class()),
+        Parameter::Object(Some(StringPool::java_string(name.to_string()))),
+        Parameter::Object(Some(method_type(descriptor, loader))),
+        Parameter::Object(Some(appendix_result.clone())),
+    ];
+    let value = invoke(link, Some(Parameters::with_parameters(parameters)), ReturnType::Object);
+    let member_name = value.object();
+    if member_name.is_none() {
+        if !JavaThread::has_pending_exception() {
+            let msg = "MethodHandleNatives.linkMethod returned null";
+            set_pending_exception("java/lang/InternalError", Some(msg));
+        }
+        return None;
+    }
+    let appendix = (*appendix_result).borrow_mut().mut_references()[0].clone();
+    return Some((member_name.unwrap(), appendix));
+}
+
+/// MethodHandle.invokeBasic semantics for a call whose arguments are already on the stack:
+/// the receiver handle is slid under the arg slots and `form.vmentry` is invoked
+pub fn invoke_basic(frame: &mut Frame, method_handle: Rc<RefCell<Object>>, arg_slot_count: usize) {
+    let method = match vmentry(method_handle.clone()) {
+        Some(method) => method,
+        None => return,
+    };
+
+    let stack = frame.operand_stack().expect("stack is none");
+    let mut args = Vec::with_capacity(arg_slot_count);
+    for _ in 0..arg_slot_count {
+        args.push(stack.pop_slot());
+    }
+    stack.push_ref(Some(method_handle));
+    while let Some(slot) = args.pop() {
+        stack.push_slot(slot);
+    }
+    invoke_method(frame, method);
+}
+
+/// the method behind `mh.form.vmentry`, None with an exception pending
+fn vmentry(method_handle: Rc<RefCell<Object>>) -> Option<Rc<Method>> {
+    return pending_on_error(resolve_vmentry(&method_handle));
+}
+
+/// the method a java.lang.invoke.MemberName refers to, cached in the
+/// MemberName's meta data once looked up. None with an InternalError
+/// pending if the MemberName is incomplete, or a NoSuchMethodError if
+/// there is no such method
+pub fn member_name_method(member_name: Rc<RefCell<Object>>) -> Option<Rc<Method>> {
+    return pending_on_error(resolve_member_name(&member_name));
+}
+
+/// the exception class and message for a MemberName the VM can't use
+type LinkError = (&'static str, String);
+
+fn pending_on_error(result: Result<Rc<Method>, LinkError>) -> Option<Rc<Method>> {
+    return match result {
+        Ok(method) => Some(method),
+        Err((class_name, msg)) => {
+            set_pending_exception(class_name, Some(msg.as_str()));
+            None
+        }
+    };
+}
+
+fn resolve_vmentry(method_handle: &Rc<RefCell<Object>>) -> Result<Rc<Method>, LinkError> {
+    let form = required_field(
+        method_handle,
+        "MethodHandle",
+        "form",
+        "Ljava/lang/invoke/LambdaForm;",
+    )?;
+    let vmentry = required_field(
+        &form,
+        "LambdaForm",
+        "vmentry",
+        "Ljava/lang/invoke/MemberName;",
+    )?;
+    return resolve_member_name(&vmentry);
+}
+
+fn resolve_member_name(member_name: &Rc<RefCell<Object>>) -> Result<Rc<Method>, LinkError> {
+    if let MetaData::Method(method) = &(**member_name).borrow().meta_data {
+        return Ok(method.clone());
+    }
+    let class_object = required_field(member_name, "MemberName", "clazz", "Ljava/lang/Class;")?;
+    let class = (*class_object).borrow().meta().unwrap();
+    let name = required_field(member_name, "MemberName", "name", "Ljava/lang/String;")?;
+    let name = java_str_to_rust_str(name);
+    let type_object = required_field(member_name, "MemberName", "type", "Ljava/lang/Object;")?;
+    let descriptor = member_type_descriptor(type_object);
+    let method = MethodRef::look_up_method(class.clone(), name.as_str(), descriptor.as_str());
+    if method.is_none() {
+        let msg = format!("{}.{}{}", (*class).borrow().java_name(), name, descriptor);
+        return Err(("java/lang/NoSuchMethodError", msg));
+    }
+    let method = method.unwrap();
+    (**member_name)
+        .borrow_mut()
+        .set_meta_data(MetaData::Method(method.clone()));
+    return Ok(method);
+}
+
+/// a reference field of a java.lang.invoke object the VM relies on,
+/// an InternalError if it is null
+fn required_field(
+    object: &Rc<RefCell<Object>>,
+    owner: &str,
+    name: &str,
+    descriptor: &str,
+) -> Result<Rc<RefCell<Object>>, LinkError> {
+    return match (**object).borrow().get_ref_var(name, descriptor) {
+        Some(value) => Ok(value),
+        None => Err((
+            "java/lang/InternalError",
+            format!("{}.{} is null", owner, name),
+        )),
+    };
+}
+
+/// MemberName.type holds a descriptor String, a MethodType or the Class of a field
+pub fn member_type_descriptor(type_object: Rc<RefCell<Object>>) -> String {
+    let type_class = (*type_object).borrow().class();
+    let type_class_name = (*type_class).borrow().name().to_string();
+    return match type_class_name.as_str() {
+        "java/lang/String" => java_str_to_rust_str(type_object),
+        "java/lang/Class" => {
+            let class = (*type_object).borrow().meta().unwrap();
+            let name = (*class).borrow().name().to_string();
+            PrimitiveTypes::instance()
+                .unwrap()
+                .to_descriptor(name.as_str())
+        }
+        _ => method_type_descriptor(type_object),
+    };
+}
+
+/// MethodType.fromMethodDescriptorString(descriptor, loader)
+pub fn method_type(descriptor: &str, loader: Option<Rc<RefCell<Object>>>) -> Rc<RefCell<Object>> {
+    let class = ClassLoader::load_class(None, "java/lang/invoke/MethodType").unwrap();
+    let method = Class::get_static_method(
+        class,
+        "fromMethodDescriptorString",
+        "(Ljava/lang/String;Ljava/lang/ClassLoader;)Ljava/lang/invoke/MethodType;",
+    )
+    .expect("MethodType.fromMethodDescriptorString not found");
+    let parameters = vec![
+        Parameter::Object(Some(StringPool::java_string(descriptor.to_string()))),
+        Parameter::Object(loader),
+    ];
+    let value = invoke(
+        method,
+        Some(Parameters::with_parameters(parameters)),
+        ReturnType::Object,
+    );
+    return value.object().expect("MethodType is null");
+}
+
+/// MethodType.toMethodDescriptorString()
+pub fn method_type_descriptor(method_type: Rc<RefCell<Object>>) -> String {
+    let class = Jvm::boot_class_loader()
+        .find_or_create("java/lang/invoke/MethodType")
+        .unwrap();
+    let method = Class::get_instance_method(class, "toMethodDescriptorString", "()Ljava/lang/String;")
+        .expect("MethodType.toMethodDescriptorString not found");
+    let value = invoke(
+        method,
+        Some(Parameters::with_parameters(vec![Parameter::Object(Some(method_type))])),
+        ReturnType::Object,
+    );
+    return java_str_to_rust_str(value.object().expect("descriptor is null"));
+}
+
+/// MethodHandleNatives.linkMethodHandleConstant(callerClass, refKind, defc, name, type)
+pub fn link_method_handle_constant(
+    caller: Rc<RefCell<Class>>,
+    reference_kind: u8,
+    class: Rc<RefCell<Class>>,
+    name: &str,
+    type_object: Rc<RefCell<Object>>,
+) -> Option<Rc<RefCell<Object>>> {
+    let natives = ClassLoader::load_class(None, "java/lang/invoke/MethodHandleNatives").unwrap();
+    let link = Class::get_static_method(
+        natives,
+        "linkMethodHandleConstant",
+        "(Ljava/lang/Class;ILjava/lang/Class;Ljava/lang/String;Ljava/lang/Object;)Ljava/lang/invoke/MethodHandle;",
+    )
+    .expect("MethodHandleNatives.linkMethodHandleConstant not found");
+    let parameters = vec![
+        Parameter::Object((*caller).borrow().get_java_class()),
+        Parameter::Int(reference_kind as i32),
+        Parameter::Object((*class).borrow().get_java_class()),
+        Parameter::Object(Some(StringPool::java_string(name.to_string()))),
+        Parameter::Object(Some(type_object)),
+    ];
+    let value = invoke(link, Some(Parameters::with_parameters(parameters)), ReturnType::Object);
+    // None if linking threw, the exception is pending then
+    return value.object();
+}
+
+/// wraps a primitive parameter with the valueOf method of its box class
+pub fn box_parameter(parameter: Parameter) -> Option<Rc<RefCell<Object>>> {
+    let (class_name, descriptor) = match &parameter {
+        Parameter::Object(object) => return object.clone(),
+        Parameter::Int(_) => ("java/lang/Integer", "(I)Ljava/lang/Integer;"),
+        Parameter::Long(_) => ("java/lang/Long", "(J)Ljava/lang/Long;"),
+        Parameter::Float(_) => ("java/lang/Float", "(F)Ljava/lang/Float;"),
+        Parameter::Double(_) => ("java/lang/Double", "(D)Ljava/lang/Double;"),
+        _ => panic!("Unsupported parameter"),
+    };
+    let class = ClassLoader::load_class(None, class_name).unwrap();
+    let value_of = Class::get_static_method(class, "valueOf", descriptor).unwrap();
+    let value = invoke(
+        value_of,
+        Some(Parameters::with_parameters(vec![parameter])),
+        ReturnType::Object,
+    );
+    return value.object();
+}
+
+fn arg_slot_count(descriptor: &str) -> usize {
+    let md = MethodDescriptorParser::parse_method_descriptor(descriptor);
+    let mut count = 0;
+    for parameter_type in md.parameter_types() {
+        count += 1;
+        if parameter_type.as_str() == "J" || parameter_type.as_str() == "D" {
+            count += 1;
+        }
+    }
+    return count;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::invoke_support::method_handle::{
+        arg_slot_count, invoke_polymorphic, is_signature_polymorphic, resolve_member_name,
+        resolve_vmentry,
+    };
+    use crate::invoke_support::thrown;
+    use crate::oops::class::Class;
+    use crate::oops::method::Method;
+    use crate::oops::object::{MetaData, Object};
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use lark_classfile::class_file::ClassFile;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// a class with the given reference fields, their slots laid out
+    fn class_with_fields(name: &str, fields: &[(&str, &str)]) -> Rc<RefCell<Class>> {
+        let mut pool: Vec<Vec<u8>> = vec![];
+        let mut utf8 = |string: &str| {
+            let mut entry = vec![1u8];
+            entry.extend_from_slice(&(string.len() as u16).to_be_bytes());
+            entry.extend_from_slice(string.as_bytes());
+            pool.push(entry);
+            return pool.len() as u16;
+        };
+        let name_index = utf8(name);
+        let mut field_infos = vec![];
+        for (field_name, descriptor) in fields {
+            field_infos.extend_from_slice(&[0, 0]);
+            field_infos.extend_from_slice(&utf8(field_name).to_be_bytes());
+            field_infos.extend_from_slice(&utf8(descriptor).to_be_bytes());
+            field_infos.extend_from_slice(&[0, 0]);
+        }
+        let mut class_entry = vec![7u8];
+        class_entry.extend_from_slice(&name_index.to_be_bytes());
+        pool.push(class_entry);
+        let this_class = pool.len() as u16;
+
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+        bytes.extend_from_slice(&(pool.len() as u16 + 1).to_be_bytes());
+        for entry in &pool {
+            bytes.extend_from_slice(entry);
+        }
+        bytes.extend_from_slice(&[0, 0x21]);
+        bytes.extend_from_slice(&this_class.to_be_bytes());
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        bytes.extend_from_slice(&(fields.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&field_infos);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+
+        let class = Class::new(ClassFile::parse(bytes).unwrap());
+        for (slot_id, field) in (*class).borrow().fields().iter().enumerate() {
+            (**field).borrow_mut().set_slot(slot_id);
+        }
+        let count = fields.len() as u32;
+        (*class).borrow_mut().set_instance_slot_count(count);
+        return class;
+    }
+
+    fn member_name() -> Rc<RefCell<Object>> {
+        let class = class_with_fields(
+            "java/lang/invoke/MemberName",
+            &[
+                ("clazz", "Ljava/lang/Class;"),
+                ("name", "Ljava/lang/String;"),
+                ("type", "Ljava/lang/Object;"),
+            ],
+        );
+        return boxed(Object::new(class));
+    }
+
+    #[test]
+    fn test_signature_polymorphic() {
+        let method_handle = "java/lang/invoke/MethodHandle";
+        assert!(is_signature_polymorphic(method_handle, "invokeExact"));
+        assert!(is_signature_polymorphic(method_handle, "linkToStatic"));
+        assert!(!is_signature_polymorphic(method_handle, "bindTo"));
+        assert!(!is_signature_polymorphic("java/lang/Object", "invoke"));
+    }
+
+    #[test]
+    fn test_arg_slot_count() {
+        assert_eq!(arg_slot_count("()V"), 0);
+        assert_eq!(arg_slot_count("(ILjava/lang/Object;)V"), 2);
+        assert_eq!(arg_slot_count("(JD[J)I"), 5);
+    }
+
+    #[test]
+    fn test_incomplete_member_name() {
+        let member_name = member_name();
+        let (class_name, msg) = resolve_member_name(&member_name).err().unwrap();
+        assert_eq!(class_name, "java/lang/InternalError");
+        assert_eq!(msg, "MemberName.clazz is null");
+    }
+
+    #[test]
+    fn test_cached_member_name() {
+        let member_name = member_name();
+        let method = Rc::new(Method::new());
+        (*member_name)
+            .borrow_mut()
+            .set_meta_data(MetaData::Method(method.clone()));
+        let resolved = resolve_member_name(&member_name).ok().unwrap();
+        assert!(Rc::ptr_eq(&resolved, &method));
+    }
+
+    #[test]
+    fn test_missing_lambda_form() {
+        let handle_class = class_with_fields(
+            "java/lang/invoke/MethodHandle",
+            &[("form", "Ljava/lang/invoke/LambdaForm;")],
+        );
+        let form_class = class_with_fields(
+            "java/lang/invoke/LambdaForm",
+            &[("vmentry", "Ljava/lang/invoke/MemberName;")],
+        );
+        let method_handle = boxed(Object::new(handle_class));
+        let (class_name, msg) = resolve_vmentry(&method_handle).err().unwrap();
+        assert_eq!(class_name, "java/lang/InternalError");
+        assert_eq!(msg, "MethodHandle.form is null");
+
+        let form = boxed(Object::new(form_class));
+        (*method_handle)
+            .borrow_mut()
+            .set_ref_var("form", "Ljava/lang/invoke/LambdaForm;", form);
+        let (_, msg) = resolve_vmentry(&method_handle).err().unwrap();
+        assert_eq!(msg, "LambdaForm.vmentry is null");
+    }
+
+    #[test]
+    fn test_unknown_polymorphic() {
+        let thread = boxed(JavaThread::new_thread());
+        let mut frame = JavaThread::new_frame(thread, Rc::new(Method::with_code(1, 0, vec![])));
+        invoke_polymorphic(&mut frame, "linkToNative", "()V");
+        assert_eq!(thrown::take_class_name(), "java/lang/UnsatisfiedLinkError");
+    }
+}
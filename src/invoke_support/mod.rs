@@ -1,42 +1,59 @@
-use crate::instructions::base::bytecode_reader::BytecodeReader;
-use crate::instructions::new_instruction;
-use crate::interpreter::invoke_java_method;
+use crate::gc::heap::Heap;
+use crate::interpreter::{invoke_java_method, step, throw_pending_exception};
 use crate::invoke_support::parameter::{Parameter, Parameters};
 use crate::invoke_support::return_value::ReturnValue;
-use crate::jvm::JVM;
+use crate::jvm::Jvm;
 use crate::runtime::frame::Frame;
+use crate::runtime::stack::Stack;
 use crate::oops::method::Method;
+use crate::oops::object::Object;
 use crate::oops::string_pool::StringPool;
 use crate::runtime::thread::JavaThread;
 use crate::utils::boxed;
 use std::cell::RefCell;
-use std::ops::DerefMut;
 use std::process::exit;
 use std::rc::Rc;
 use std::thread::sleep_ms;
 use crate::class_loader::app_class_loader::ClassLoader;
 use crate::oops::class::Class;
 use crate::instructions::references::athrow::AThrow;
-use crate::instructions::base::instruction::Instruction;
 
+pub mod method_handle;
 pub mod parameter;
 pub mod return_value;
+/// unit tests run without a VM to create exceptions in, the exceptions the
+/// VM would throw are recorded per thread instead
+#[cfg(test)]
+pub mod thrown;
 
 pub fn invoke(
     method: Rc<Method>,
     params: Option<Parameters>,
     return_type: ReturnType,
 ) -> ReturnValue {
+    if !Stack::has_room_for_call(&method) {
+        set_pending_exception("java/lang/StackOverflowError", None);
+        return zero_value(return_type);
+    }
+    // an exception raised earlier must not be taken for one thrown by this call
+    let raised = JavaThread::take_pending_exception();
     let thread = create_execute_env(method, params);
+    JavaThread::enter(thread.clone());
     let return_value = executable(thread, return_type);
+    JavaThread::leave();
+    if raised.is_some() {
+        JavaThread::set_pending_exception(raised.unwrap());
+    }
     return return_value;
 }
 
 fn create_execute_env(method: Rc<Method>, params: Option<Parameters>) -> Rc<RefCell<JavaThread>> {
     let thread = boxed(JavaThread::new_thread());
-    let mut dummy_frame = JavaThread::new_frame(thread.clone(), method.clone());
+    Heap::register_thread(&thread);
+    let dummy_frame = Frame::new_entry_frame(thread.clone(), method.clone());
     let mut frame = JavaThread::new_frame(thread.clone(), method);
     prepare_parameter(&mut frame, params);
+    frame.lock_if_synchronized();
     (*thread).borrow_mut().push_frame(dummy_frame);
     (*thread).borrow_mut().push_frame(frame);
     return thread;
@@ -72,20 +89,9 @@ fn prepare_parameter(frame: &mut Frame, params: Option<Parameters>) {
 }
 
 fn executable(mut thread: Rc<RefCell<JavaThread>>, return_type: ReturnType) -> ReturnValue {
-    let mut reader = BytecodeReader::new();
     loop {
-        let current_frame = (*thread).borrow().current_frame();
-        let pc = (*current_frame).borrow().next_pc();
-        (*thread).borrow_mut().set_pc(pc);
-        let method = (*current_frame).borrow().method_ptr();
-        let bytecode = method.code();
-        reader.reset(bytecode, pc);
-        let opcode = reader.read_u8();
-        //println!("method:{}, {}, {},inst:{}",method.name(),method.descriptor(),(*method.class()).borrow().name(),opcode);
-        let mut inst = new_instruction(opcode);
-        inst.fetch_operands(&mut reader);
-        (*current_frame).borrow_mut().set_next_pc(reader.pc());
-        inst.execute((*current_frame).borrow_mut().deref_mut());
+        step(&thread);
+        throw_pending_exception(&thread);
         if (*thread).borrow().stack_size() == 1 {
             break;
         }
@@ -95,6 +101,10 @@ fn executable(mut thread: Rc<RefCell<JavaThread>>, return_type: ReturnType) -> R
         //sleep_ms(500);
     }
     let value_frame = (*thread).borrow_mut().pop_frame();
+    if JavaThread::has_pending_exception() {
+        // the invoked method threw, the caller sees the pending exception
+        return zero_value(return_type);
+    }
     let mut frame_borrow = (*value_frame).borrow_mut();
     let stack = frame_borrow.operand_stack().expect("stack is none");
     let value = match return_type {
@@ -112,6 +122,21 @@ fn executable(mut thread: Rc<RefCell<JavaThread>>, return_type: ReturnType) -> R
     return value;
 }
 
+fn zero_value(return_type: ReturnType) -> ReturnValue {
+    return match return_type {
+        ReturnType::Void => ReturnValue::Void,
+        ReturnType::Boolean => ReturnValue::Boolean(false),
+        ReturnType::Byte => ReturnValue::Byte(0),
+        ReturnType::Short => ReturnValue::Short(0),
+        ReturnType::Int => ReturnValue::Int(0),
+        ReturnType::Long => ReturnValue::Long(0),
+        ReturnType::Float => ReturnValue::Float(0.0),
+        ReturnType::Double => ReturnValue::Double(0.0),
+        ReturnType::Char => ReturnValue::Char('\0'),
+        ReturnType::Object => ReturnValue::Object(None),
+    };
+}
+
 pub enum ReturnType {
     Void,
     Boolean,
@@ -125,27 +150,50 @@ pub enum ReturnType {
     Object,
 }
 
-pub fn throw_exception(frame: &mut Frame, class_name: &str, msg: Option<&str>) {
-    let class = frame.method().class();
-    let class_loader = (*class).borrow().get_class_loader();
-    let exception_class = ClassLoader::load_class(class_loader,class_name);
-    let mut object = Class::new_object(&exception_class);
+/// creates a Throwable of the boot class `class_name` with a detail message
+pub fn new_exception(class_name: &str, msg: Option<&str>) -> Rc<RefCell<Object>> {
+    let exception_class = Jvm::boot_class_loader()
+        .find_or_create(class_name)
+        .expect("exception class not found");
+    let object = Class::new_object(&exception_class);
     let constructor_desc = "(Ljava/lang/String;)V";
     let detail_message = match msg.is_some() {
         true => Some(StringPool::java_string(msg.unwrap().to_string())),
         false => None
     };
     let constructor = Class::get_constructor(exception_class.clone(), constructor_desc);
-    let object_ptr = Some(boxed(object));
+    let object_ptr = Heap::allocate(object);
     let parameters = vec![
-        Parameter::Object(object_ptr.clone()),
+        Parameter::Object(Some(object_ptr.clone())),
         Parameter::Object(detail_message)
     ];
     invoke(constructor.unwrap(),Some(Parameters::with_parameters(parameters)),ReturnType::Void);
-    frame
-        .operand_stack()
-        .expect("stack is none")
-        .push_ref(object_ptr);
-    let mut athrow = AThrow::new();
-    athrow.execute(frame);
+    return object_ptr;
+}
+
+/// throws a new exception at the current instruction of the frame
+pub fn throw_exception(frame: &mut Frame, class_name: &str, msg: Option<&str>) {
+    #[cfg(test)]
+    {
+        if Jvm::instance().is_none() {
+            thrown::record(class_name, msg);
+            return;
+        }
+    }
+    let exception = new_exception(class_name, msg);
+    AThrow::throw(frame, exception);
+}
+
+/// raises an exception where no frame is at hand, like during constant pool
+/// resolution; the interpreter throws it once the current instruction returns
+pub fn set_pending_exception(class_name: &str, msg: Option<&str>) {
+    #[cfg(test)]
+    {
+        if Jvm::instance().is_none() {
+            thrown::record(class_name, msg);
+            return;
+        }
+    }
+    let exception = new_exception(class_name, msg);
+    JavaThread::set_pending_exception(exception);
 }
@@ -0,0 +1,22 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static THROWN: RefCell<Vec<(String, Option<String>)>> = const { RefCell::new(vec![]) };
+}
+
+pub fn record(class_name: &str, msg: Option<&str>) {
+    let exception = (class_name.to_string(), msg.map(str::to_string));
+    THROWN.with(|thrown| thrown.borrow_mut().push(exception));
+}
+
+/// the class names and messages thrown since the last call, oldest first
+pub fn take() -> Vec<(String, Option<String>)> {
+    return THROWN.with(|thrown| thrown.borrow_mut().split_off(0));
+}
+
+/// the class name of the only exception thrown since the last call
+pub fn take_class_name() -> String {
+    let mut thrown = take();
+    assert_eq!(thrown.len(), 1, "thrown: {:?}", thrown);
+    return thrown.remove(0).0;
+}
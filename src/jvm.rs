@@ -1,7 +1,10 @@
 use crate::class_loader::app_class_loader::ClassLoader;
 use crate::class_loader::bootstrap_class_loader::BootstrapClassLoader;
+use crate::class_loader::class_verifier::ClassVerifier;
 use crate::class_path::class_path::ClassPath;
 use crate::cmd::Cmd;
+use crate::gc::heap::Heap;
+use crate::global_config::GlobalConfig;
 use crate::instructions::base::class_init_logic::init_class;
 use crate::instructions::base::instruction::Instruction;
 use crate::instructions::base::method_invoke_logic::invoke_method;
@@ -9,17 +12,23 @@ use crate::instructions::references::athrow::AThrow;
 use crate::interpreter::{interpret, invoke_java_method};
 use crate::invoke_support::parameter::{Parameter, Parameters};
 use crate::invoke_support::{invoke, ReturnType};
+use crate::native;
+use crate::native::java::lang::throwable::print_uncaught_exception;
 use crate::prims::perf_data::Variability;
 use crate::runtime::frame::Frame;
+use crate::runtime::java_assertions::JavaAssertions;
 use crate::oops::class::Class;
-use crate::oops::object::Object;
+use crate::oops::object::{MetaData, Object};
 use crate::oops::string_pool::StringPool;
-use crate::runtime::thread::JavaThread;
-use crate::utils::{boxed, java_str_to_rust_str};
-use chrono::Local;
+use crate::runtime::os_thread::OsThread;
+use crate::runtime::stack::Stack;
+use crate::runtime::thread::{JavaThread, NORM_PRIORITY, THREAD_STATUS_RUNNABLE};
+use crate::runtime::vm_lock;
+use crate::utils::boxed;
 use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct Jvm {
     cmd: Cmd,
@@ -37,13 +46,23 @@ impl Jvm {
         if cmd.exec_jar_path().is_some() {
             cp.handle_jar(&mut cmd);
         }
+        GlobalConfig::init(&cmd);
+        Heap::init(cmd.xmx_option);
+        if let Some(size) = cmd.xss_option {
+            Stack::set_default_size(size);
+        }
+        Stack::set_thread_stack_size(0);
+        ClassVerifier::set_mode(cmd.verify_mode);
+        JavaAssertions::init(&cmd.assertion_options);
         let class_path = Rc::new(cp);
         let class_loader = BootstrapClassLoader::new(class_path);
+        let main_thread = boxed(JavaThread::new_main_thread());
+        Heap::register_thread(&main_thread);
         let jvm = Jvm {
             cmd,
             boot_class_loader: class_loader,
             ext_class_loader: None,
-            main_thread: boxed(JavaThread::new_main_thread()),
+            main_thread,
             app_class_loader: None,
         };
         unsafe {
@@ -52,6 +71,11 @@ impl Jvm {
         }
     }
 
+    #[inline]
+    pub fn cmd(&self) -> &Cmd {
+        return &self.cmd;
+    }
+
     #[inline]
     pub fn main_thread(&self) -> Rc<RefCell<JavaThread>> {
         return self.main_thread.clone();
@@ -69,53 +93,141 @@ impl Jvm {
         }
     }
 
-    pub fn start(&mut self) {
+    /// runs the main class, returns the exit status of the process
+    pub fn start(&mut self) -> i32 {
         //        let builder = (*self.main_thread).borrow_mut().std_thread();
         //        let join_handler = builder.spawn(move || {
+        vm_lock::acquire();
+        native::init();
         self.boot_class_loader.post_constructor();
+        self.create_main_thread();
         self.init_vm();
-        println!("init vm! {:?}", Local::now());
-        self.exec_main();
+        if let Some(exception) = JavaThread::take_pending_exception() {
+            eprintln!("Error occurred during initialization of VM");
+            print_uncaught_exception("main", exception);
+            return 1;
+        }
+        let status = self.exec_main();
+        self.destroy();
+        return status;
         //        }).unwrap();
         //        join_handler.join().expect_err("thread::spawn failed");
     }
 
+    /// the java.lang.Thread of the main thread and its "system" and "main"
+    /// groups exist before any other Java code asks for the current thread
+    fn create_main_thread(&mut self) {
+        let group_class = self
+            .boot_class_loader
+            .find_or_create("java/lang/ThreadGroup")
+            .unwrap();
+        let thread_class = self
+            .boot_class_loader
+            .find_or_create("java/lang/Thread")
+            .unwrap();
+        init_class(group_class.clone());
+        init_class(thread_class.clone());
+
+        let system_group = Heap::allocate(Class::new_object(&group_class));
+        let constructor = Class::get_instance_method(group_class.clone(), "<init>", "()V");
+        let params = vec![Parameter::Object(Some(system_group.clone()))];
+        invoke(
+            constructor.unwrap(),
+            Some(Parameters::with_parameters(params)),
+            ReturnType::Void,
+        );
+
+        let name = StringPool::java_string("main".to_string());
+        let main_group = Heap::allocate(Class::new_object(&group_class));
+        let constructor = Class::get_instance_method(
+            group_class,
+            "<init>",
+            "(Ljava/lang/ThreadGroup;Ljava/lang/String;)V",
+        );
+        let params = vec![
+            Parameter::Object(Some(main_group.clone())),
+            Parameter::Object(Some(system_group)),
+            Parameter::Object(Some(name.clone())),
+        ];
+        invoke(
+            constructor.unwrap(),
+            Some(Parameters::with_parameters(params)),
+            ReturnType::Void,
+        );
+
+        let os_thread = Arc::new(OsThread::new(NORM_PRIORITY));
+        os_thread.attach();
+        let mut thread_object = Class::new_object(&thread_class);
+        thread_object.set_int_var("priority", "I", NORM_PRIORITY);
+        thread_object.set_int_var("threadStatus", "I", THREAD_STATUS_RUNNABLE);
+        thread_object.set_long_var("eetop", "J", Arc::as_ptr(&os_thread) as i64);
+        thread_object.set_meta_data(MetaData::Thread(os_thread));
+        let thread_object = Heap::allocate(thread_object);
+        // Thread's constructor already asks for its parent, the current thread
+        JavaThread::set_current_thread_object(Some(thread_object.clone()));
+        let constructor = Class::get_instance_method(
+            thread_class,
+            "<init>",
+            "(Ljava/lang/ThreadGroup;Ljava/lang/String;)V",
+        );
+        let params = vec![
+            Parameter::Object(Some(thread_object)),
+            Parameter::Object(Some(main_group)),
+            Parameter::Object(Some(name)),
+        ];
+        invoke(
+            constructor.unwrap(),
+            Some(Parameters::with_parameters(params)),
+            ReturnType::Void,
+        );
+    }
+
     fn init_vm(&mut self) {
         let vm_class = self
             .boot_class_loader
             .find_or_create("sun/misc/VM")
             .unwrap();
-        init_class(self.main_thread.clone(), vm_class);
-        interpret(self.main_thread.clone());
+        init_class(vm_class);
 
         let ext_class = self
             .boot_class_loader
             .find_or_create("sun/misc/Launcher$ExtClassLoader")
             .unwrap();
-        init_class(self.main_thread.clone(), ext_class.clone());
+        init_class(ext_class.clone());
 
         let app_class = self
             .boot_class_loader
             .find_or_create("sun/misc/Launcher$AppClassLoader")
             .unwrap();
-        init_class(self.main_thread.clone(), app_class.clone());
+        init_class(app_class.clone());
 
-        interpret(self.main_thread.clone());
         self.ext_class_loader = self.create_ext_loader(ext_class);
         self.app_class_loader = self.create_app_loader(app_class, self.ext_class_loader.clone());
-        display_loader_url(self.app_class_loader.clone());
     }
 
-    fn exec_main(&self) {
+    fn exec_main(&self) -> i32 {
         let class_name = self.cmd.class.clone().replace('.', "/");
         //let class_name = self.cmd.class.clone();
 
         let main_class =
-            ClassLoader::load_class(self.app_class_loader.clone(), class_name.as_str());
+            match ClassLoader::load_class(self.app_class_loader.clone(), class_name.as_str()) {
+                Some(class) => class,
+                None => {
+                    let exception = JavaThread::take_pending_exception().unwrap();
+                    let exception_class = (*exception).borrow().class();
+                    if (*exception_class).borrow().name() == "java/lang/NoClassDefFoundError" {
+                        let name = self.cmd.class.as_str();
+                        eprintln!("Error: Could not find or load main class {}", name);
+                    } else {
+                        print_uncaught_exception("main", exception);
+                    }
+                    return 1;
+                }
+            };
         let main_method = (*main_class).borrow().get_main_method();
         if main_method.is_none() {
             println!("Main method not found in class {}", self.cmd.class.as_str());
-            return;
+            return 1;
         }
         let args_arr = self.create_args_array();
         let mut frame = JavaThread::new_frame(self.main_thread.clone(), main_method.unwrap());
@@ -125,6 +237,27 @@ impl Jvm {
             .set_ref(0, Some(args_arr));
         (*self.main_thread).borrow_mut().push_frame(frame);
         interpret(self.main_thread.clone());
+        // like the java launcher, main ending with an exception fails the process
+        if let Some(exception) = JavaThread::take_pending_exception() {
+            JavaThread::dispatch_uncaught_exception(JavaThread::current_thread_object(), exception);
+            return 1;
+        }
+        return 0;
+    }
+
+    /// DestroyJavaVM, the VM ends once the last non-daemon thread has
+    /// ended, after the shutdown hooks ran
+    fn destroy(&self) {
+        JavaThread::wait_for_non_daemon_threads();
+        let shutdown_class = self
+            .boot_class_loader
+            .find_or_create("java/lang/Shutdown")
+            .unwrap();
+        if init_class(shutdown_class.clone()) {
+            let shutdown = Class::get_static_method(shutdown_class, "shutdown", "()V").unwrap();
+            invoke(shutdown, None, ReturnType::Void);
+        }
+        JavaThread::take_pending_exception();
     }
 
     fn create_args_array(&self) -> Rc<RefCell<Object>> {
@@ -138,7 +271,7 @@ impl Jvm {
         for i in 0..java_args.len() {
             java_args[i] = Some(StringPool::java_string(self.cmd.args[i].clone()));
         }
-        return boxed(args_arr);
+        return Heap::allocate(args_arr);
     }
 
     fn create_ext_loader(&self, ext_class: Rc<RefCell<Class>>) -> Option<Rc<RefCell<Object>>> {
@@ -166,43 +299,3 @@ impl Jvm {
         return value;
     }
 }
-
-fn display_loader_url(class_loader: Option<Rc<RefCell<Object>>>) {
-    let obj = class_loader.unwrap();
-    let ucp = (*obj)
-        .borrow()
-        .get_ref_var("ucp", "Lsun/misc/URLClassPath;");
-
-    let parent = (*obj)
-        .borrow()
-        .get_ref_var("parent", "Ljava/lang/ClassLoader;");
-    if parent.is_some() {
-        let parent = (*parent.unwrap()).borrow().class();
-        println!("parent:{}", (*parent).borrow().java_name());
-    }
-
-    let boot_loader = Jvm::boot_class_loader();
-    let class = boot_loader.find_or_create("java/net/URL").unwrap();
-    let method = Class::get_instance_method(class, "toString", "()Ljava/lang/String;").unwrap();
-    if ucp.is_some() {
-        let ucp = ucp.unwrap();
-        let path = (*ucp)
-            .borrow()
-            .get_ref_var("path", "Ljava/util/ArrayList;")
-            .unwrap();
-        let data = (*path)
-            .borrow()
-            .get_ref_var("elementData", "[Ljava/lang/Object;")
-            .unwrap();
-        let bor = (*data).borrow();
-        let objs = bor.references();
-        for ob in objs {
-            if ob.is_some() {
-                let param = Parameters::with_parameters(vec![Parameter::Object(ob.clone())]);
-                let string = invoke(method.clone(), Some(param), ReturnType::Object).object();
-                let rust_str = java_str_to_rust_str(string.unwrap());
-                println!("URL:{}", rust_str);
-            }
-        }
-    }
-}
@@ -1,9 +1,11 @@
 use crate::cmd::Cmd;
 use crate::jvm::Jvm;
+use std::process;
 
 mod class_loader;
 mod class_path;
 mod cmd;
+mod gc;
 mod global_config;
 mod instructions;
 mod instrument;
@@ -19,14 +21,22 @@ mod utils;
 
 fn main() {
     let cmd = Cmd::parse_cmd();
-    if cmd.version_flag {
-        println!("java version \"1.8.0_152\"");
-    } else if cmd.help_flag || cmd.class.as_str() == "" {
+    if cmd.help_flag {
         Cmd::print_usage();
-    } else {
-        Jvm::new(cmd).start();
+        return;
+    }
+    if cmd.x_help_flag {
+        Cmd::print_x_usage();
+        return;
+    }
+    if cmd.version_flag || cmd.show_version_flag {
+        Cmd::print_version();
+    }
+    if cmd.version_flag {
+        return;
     }
-    println!("Hello, world!");
+    let status = Jvm::new(cmd).start();
+    process::exit(status);
 }
 
 #[cfg(test)]
@@ -41,14 +51,10 @@ mod tests {
     #[test]
     fn start_jvm() {
         let cmd = Cmd {
-            help_flag: false,
-            version_flag: false,
             verbose_class: true,
             cp_option: vec!["D:/workspace/rust-jvm".to_string()],
-            x_jre_option: "".to_string(),
             class: "testJava.ClassLoaderTest".to_string(),
-            args: vec![],
-            exec_jar_path: None,
+            ..Cmd::new()
         };
         let vec = "ha哈哈";
         let s: Vec<u16> = vec.encode_utf16().collect();
@@ -70,14 +76,10 @@ mod tests {
     #[test]
     fn start_jvm_main() {
         let cmd = Cmd {
-            help_flag: false,
-            version_flag: false,
             verbose_class: false,
             cp_option: vec!["D:/test".to_string()],
-            x_jre_option: "".to_string(),
             class: "com.compile.Main".to_string(),
-            args: vec![],
-            exec_jar_path: None,
+            ..Cmd::new()
         };
         Jvm::new(cmd).start();
     }
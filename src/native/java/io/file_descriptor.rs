@@ -1,9 +1,23 @@
+use crate::invoke_support::throw_exception;
 use crate::native::registry::Registry;
+use crate::oops::class::Class;
+use crate::oops::object::Object;
 use crate::runtime::frame::Frame;
+use crate::runtime::vm_lock;
+use std::cell::RefCell;
+use std::io;
+use std::io::{stderr, stdout, ErrorKind, Write};
+use std::rc::Rc;
+
+/// the descriptors the `FileDescriptor.in/out/err` statics are created with
+pub const STDIN_FD: i32 = 0;
+pub const STDOUT_FD: i32 = 1;
+pub const STDERR_FD: i32 = 2;
 
 pub fn init() {
     Registry::register("java/io/FileDescriptor", "initIDs", "()V", init_ids);
     Registry::register("java/io/FileDescriptor", "set", "(I)J", set);
+    Registry::register("java/io/FileDescriptor", "sync", "()V", sync);
 }
 
 pub fn init_ids(frame: &mut Frame) {}
@@ -16,3 +30,124 @@ pub fn set(frame: &mut Frame) {
         .expect("stack is none")
         .push_long(fd as i64);
 }
+
+/// public native void sync() throws SyncFailedException;
+/// ()V
+pub fn sync(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    let fd = descriptor_value(&this);
+    let rs = vm_lock::blocking(|| match fd_table::with_file(fd, |file| file.sync_all()) {
+        Some(rs) => rs,
+        None if fd == STDOUT_FD => stdout().flush(),
+        None if fd == STDERR_FD => stderr().flush(),
+        None if fd == STDIN_FD => Ok(()),
+        None => Err(io::Error::from(ErrorKind::InvalidInput)),
+    });
+    if rs.is_err() {
+        throw_exception(frame, "java/io/SyncFailedException", Some("sync failed"));
+    }
+}
+
+/// the descriptor behind the `fd` field of a FileInputStream, FileOutputStream
+/// or RandomAccessFile, -1 once it is closed
+pub fn stream_fd(stream: &Rc<RefCell<Object>>) -> i32 {
+    let descriptor = (**stream)
+        .borrow()
+        .get_ref_var("fd", "Ljava/io/FileDescriptor;");
+    return match descriptor {
+        Some(descriptor) => descriptor_value(&descriptor),
+        None => -1,
+    };
+}
+
+/// stores `fd` in the FileDescriptor of `stream`, the windows class library
+/// keeps it in `handle` and leaves `fd` at -1
+pub fn set_stream_fd(stream: &Rc<RefCell<Object>>, fd: i32) {
+    let descriptor = (**stream)
+        .borrow()
+        .get_ref_var("fd", "Ljava/io/FileDescriptor;")
+        .expect("the FileDescriptor is null");
+    let class = (*descriptor).borrow().class.clone();
+    if Class::get_field(Some(class), "handle", "J", false).is_some() {
+        (*descriptor)
+            .borrow_mut()
+            .set_long_var("handle", "J", fd as i64);
+    } else {
+        (*descriptor).borrow_mut().set_int_var("fd", "I", fd);
+    }
+}
+
+fn descriptor_value(descriptor: &Rc<RefCell<Object>>) -> i32 {
+    let borrow = (**descriptor).borrow();
+    let fd = borrow.get_int_var("fd", "I");
+    if fd != -1 {
+        return fd;
+    }
+    if Class::get_field(Some(borrow.class.clone()), "handle", "J", false).is_some() {
+        return borrow.get_long_var("handle", "J") as i32;
+    }
+    return -1;
+}
+
+/// The VM-level table of the files opened by the class library, keyed by the
+/// value kept in `FileDescriptor.fd` (or `handle`). 0, 1 and 2 are never
+/// handed out, they always name the standard streams. All threads share it,
+/// the table is only locked to look a file up, never while it is used, so a
+/// read blocking on one file can't hold up opening or closing another.
+pub mod fd_table {
+    use std::collections::BTreeMap;
+    use std::fs::File;
+    use std::sync::{Arc, Mutex};
+
+    const FIRST_FD: i32 = 3;
+
+    static FD_TABLE: Mutex<BTreeMap<i32, Arc<File>>> = Mutex::new(BTreeMap::new());
+
+    /// stores `file` under the lowest free descriptor and returns it
+    pub fn insert(file: File) -> i32 {
+        let mut table = FD_TABLE.lock().unwrap();
+        let mut fd = FIRST_FD;
+        while table.contains_key(&fd) {
+            fd += 1;
+        }
+        table.insert(fd, Arc::new(file));
+        return fd;
+    }
+
+    /// runs `op` on the file of `fd` after the table is unlocked again, None
+    /// if `fd` isn't open
+    pub fn with_file<T, F: FnOnce(&File) -> T>(fd: i32, op: F) -> Option<T> {
+        let file = FD_TABLE.lock().unwrap().get(&fd).cloned();
+        return file.map(|file| op(&file));
+    }
+
+    /// takes the file out of the table, it is closed once the last
+    /// `with_file` using it has returned
+    pub fn remove(fd: i32) -> Option<Arc<File>> {
+        return FD_TABLE.lock().unwrap().remove(&fd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::java::io::file_descriptor::fd_table;
+    use std::fs::File;
+
+    #[test]
+    fn test_fd_table() {
+        let path = std::env::temp_dir().join(format!("lark-fd-table-{}", std::process::id()));
+        let fd = fd_table::insert(File::create(&path).unwrap());
+        assert!(fd >= 3);
+        let other = fd_table::insert(File::open(&path).unwrap());
+        assert_ne!(fd, other);
+        assert!(fd_table::with_file(other, |_| ()).is_some());
+        // the table is free again while a file is used
+        let nested = fd_table::with_file(fd, |_| fd_table::with_file(other, |_| ()));
+        assert_eq!(nested, Some(Some(())));
+        assert!(fd_table::remove(other).is_some());
+        assert!(fd_table::with_file(other, |_| ()).is_none());
+        assert!(fd_table::remove(other).is_none());
+        fd_table::remove(fd);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
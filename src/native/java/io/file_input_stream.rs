@@ -1,12 +1,13 @@
+use crate::native::java::io::file_descriptor::{fd_table, STDIN_FD};
+use crate::native::java::io::io_util::{
+    file_close, file_open, open_fd, read, read_bytes, read_single, throw_io_exception,
+};
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
-use crate::oops::object::{MetaData, Object};
-use crate::utils::java_str_to_rust_str;
-use std::fs::File;
-use std::io::{stderr, stdin, stdout, Read, Seek, SeekFrom};
-use std::path::Path;
-use crate::invoke_support::throw_exception;
-use std::error::Error;
+use crate::runtime::vm_lock;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Seek, SeekFrom};
 
 pub fn init() {
     Registry::register("java/io/FileInputStream", "initIDs", "()V", init_ids);
@@ -17,12 +18,18 @@ pub fn init() {
         open0,
     );
     Registry::register("java/io/FileInputStream", "close0", "()V", close0);
+    Registry::register("java/io/FileInputStream", "read0", "()I", read0);
     Registry::register(
         "java/io/FileInputStream",
         "readBytes",
         "([BII)I",
         read_bytes,
     );
+    // the natives were renamed to skip0 and available0 in later updates of 8
+    Registry::register("java/io/FileInputStream", "skip", "(J)J", skip);
+    Registry::register("java/io/FileInputStream", "skip0", "(J)J", skip);
+    Registry::register("java/io/FileInputStream", "available", "()I", available);
+    Registry::register("java/io/FileInputStream", "available0", "()I", available);
 }
 
 pub fn init_ids(frame: &mut Frame) {}
@@ -30,78 +37,88 @@ pub fn init_ids(frame: &mut Frame) {}
 /// private native void open0(String name) throws FileNotFoundException;
 /// (Ljava/lang/String;)V
 pub fn open0(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("LocalVars is none");
-    let this = vars.get_ref(0).unwrap();
-    let name = vars.get_ref(1);
-    let rust_str = java_str_to_rust_str(name.unwrap());
-    let path = Path::new(&rust_str);
-    let file = File::open(path);
-    if file.is_err() {
-        let mut msg = String::new();
-        let error = file.err().unwrap();
-        msg.push_str(path.to_str().unwrap());
-        msg.push(' ');
-        msg.push_str(error.to_string().as_str());
-        throw_exception(frame,"java/io/FileNotFoundException",Some(msg.as_str()));
-    } else {
-        (*this).borrow_mut().set_file(file.unwrap());
-    }
+    file_open(frame, OpenOptions::new().read(true));
 }
 
-/// private native int readBytes(byte b[], int off, int len) throws IOException;
-/// ([BII)I
-pub fn read_bytes(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("LocalVars is none");
-    let this = vars.get_ref(0).unwrap();
-    let byte_array = vars.get_ref(1).unwrap();
-    let offset = vars.get_int(2) as usize;
-    let length = vars.get_int(3);
+/// private native int read0() throws IOException;
+/// ()I
+pub fn read0(frame: &mut Frame) {
+    read_single(frame);
+}
 
-    if length <= 0 {
-        frame.operand_stack().expect("stack is none").push_int(0);
-        return;
+/// private native long skip0(long n) throws IOException;
+/// (J)J
+pub fn skip(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_ref(0).unwrap();
+    let n = vars.get_long(1);
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+    let seek = |mut file: &File| {
+        let current = file.stream_position()?;
+        let end = file.seek(SeekFrom::Current(n))?;
+        Ok(end as i64 - current as i64)
+    };
+    let rs = vm_lock::blocking(|| match fd_table::with_file(fd, seek) {
+        Some(rs) => rs,
+        // standard input can't seek, the bytes are read and dropped
+        None if fd == STDIN_FD => skip_by_reading(fd, n),
+        None => Err(io::Error::from(io::ErrorKind::InvalidInput)),
+    });
+    match rs {
+        Ok(skipped) => frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_long(skipped),
+        Err(error) => throw_io_exception(frame, &error),
     }
+}
 
-    let length = length as usize;
-
-    let mut bytes = vec![0u8; length];
-    let file = (*this).borrow().file();
-    let rs = file.borrow_mut().read(bytes.as_mut_slice());
-    let mut size = -1;
-    let read_size = rs.expect("the file seek has error");
-    if read_size != 0 {
-        let mut mut_byte_array = (*byte_array).borrow_mut();
-        let mut_array = mut_byte_array.mut_bytes();
-        for i in 0..read_size {
-            mut_array[offset + i] = bytes[i] as i8;
+fn skip_by_reading(fd: i32, n: i64) -> io::Result<i64> {
+    let mut skipped = 0i64;
+    let mut buf = [0u8; 512];
+    while skipped < n {
+        let size = std::cmp::min(buf.len() as i64, n - skipped) as usize;
+        let read_size = read(fd, &mut buf[..size])?;
+        if read_size == 0 {
+            break;
         }
-        size = read_size as i32;
+        skipped += read_size as i64;
     }
-    frame.operand_stack().expect("stack is none").push_int(size);
+    return Ok(skipped);
 }
 
-fn unique_path(path: String) -> String {
-    let paths: Vec<&str> = path.split('/').collect();
-    let mut path_str = String::new();
-    for p in paths {
-        if !path_str.contains(p) {
-            path_str.push_str(p);
-            path_str.push('/');
-        }
+/// private native int available0() throws IOException;
+/// ()I
+pub fn available(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+    let remaining = |mut file: &File| {
+        let current = file.stream_position()?;
+        let length = file.metadata()?.len();
+        Ok(length.saturating_sub(current))
+    };
+    let rs = vm_lock::blocking(|| {
+        // nothing is known about what is buffered for the standard streams
+        return fd_table::with_file(fd, remaining).unwrap_or(Ok(0));
+    });
+    match rs {
+        Ok(remaining) => frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(std::cmp::min(remaining, i32::MAX as u64) as i32),
+        Err(error) => throw_io_exception(frame, &error),
     }
-    assert_ne!(0, path_str.len());
-    path_str.pop();
-    return path_str;
 }
 
 /// private native void close0() throws IOException;
 /// ()V
 pub fn close0(frame: &mut Frame) {
-    //    let vars = frame.local_vars().expect("LocalVars is none");
-    //    let name = vars.get_ref(1);
-    //    let rust_str = java_str_to_rust_str(name.unwrap());
-    //    let path = Path::new(&rust_str);
-    //    if !path.exists() {
-    //        // throws FileNotFoundException;
-    //    }
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    file_close(&this);
 }
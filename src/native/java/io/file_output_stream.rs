@@ -1,42 +1,51 @@
+use crate::native::java::io::io_util::{file_close, file_open, write_bytes, write_single};
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
-use std::io;
-use std::io::Write;
+use std::fs::OpenOptions;
 
 pub fn init() {
+    Registry::register(
+        "java/io/FileOutputStream",
+        "open0",
+        "(Ljava/lang/String;Z)V",
+        open0,
+    );
+    Registry::register("java/io/FileOutputStream", "write", "(IZ)V", write);
     Registry::register(
         "java/io/FileOutputStream",
         "writeBytes",
         "([BIIZ)V",
         write_bytes,
     );
+    Registry::register("java/io/FileOutputStream", "close0", "()V", close0);
     Registry::register("java/io/FileOutputStream", "initIDs", "()V", init_ids);
 }
 
-// private native void writeBytes(byte b[], int off, int len, boolean append) throws IOException;
-// ([BIIZ)V
-pub fn write_bytes(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("vars is none");
-    let b = vars.get_ref(1).unwrap();
-    let off = vars.get_int(2) as usize;
-    let len = vars.get_int(3) as usize;
-    let borrow = (*b).borrow();
-    let java_bytes = borrow.bytes();
-    let bytes = byte_change(java_bytes);
-    let slice = &bytes[off..(off + len)];
-    let mut out = io::stdout();
-    let rs = out.write(slice);
-    if rs.is_err() {
-        println!("Error:{:?}", rs.err().unwrap())
+/// private native void open0(String name, boolean append) throws FileNotFoundException;
+/// (Ljava/lang/String;Z)V
+pub fn open0(frame: &mut Frame) {
+    let append = frame.local_vars().expect("vars is none").get_boolean(2);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true);
+    if append {
+        options.append(true);
+    } else {
+        options.truncate(true);
     }
+    file_open(frame, &options);
 }
 
-fn byte_change(java_bytes: &Vec<i8>) -> Vec<u8> {
-    let mut vec = Vec::with_capacity(java_bytes.len());
-    for java_byte in java_bytes {
-        vec.push(*java_byte as u8);
-    }
-    return vec;
+/// private native void write(int b, boolean append) throws IOException;
+/// (IZ)V
+pub fn write(frame: &mut Frame) {
+    write_single(frame);
+}
+
+/// private native void close0() throws IOException;
+/// ()V
+pub fn close0(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    file_close(&this);
 }
 
 pub fn init_ids(frame: &mut Frame) {}
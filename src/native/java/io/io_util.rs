@@ -0,0 +1,273 @@
+use crate::invoke_support::throw_exception;
+use crate::native::java::io::file_descriptor::{
+    fd_table, set_stream_fd, stream_fd, STDERR_FD, STDIN_FD, STDOUT_FD,
+};
+use crate::oops::object::Object;
+use crate::runtime::frame::Frame;
+use crate::runtime::vm_lock;
+use crate::utils::java_str_to_rust_str;
+use std::cell::RefCell;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{stderr, stdin, stdout, ErrorKind, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+// the helpers FileInputStream, FileOutputStream and RandomAccessFile share,
+// named after their counterparts in io_util.c
+
+/// opens the file named by the string in local 1 and stores its descriptor
+/// in the FileDescriptor of `this`
+pub fn file_open(frame: &mut Frame, options: &OpenOptions) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_ref(0).unwrap();
+    let name = match vars.get_ref(1) {
+        Some(name) => java_str_to_rust_str(name),
+        None => {
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
+        }
+    };
+    let path = Path::new(&name);
+    // opening a directory for reading succeeds on most platforms
+    if path.is_dir() {
+        let msg = format!("{} (Is a directory)", name);
+        throw_exception(frame, "java/io/FileNotFoundException", Some(msg.as_str()));
+        return;
+    }
+    // opening a fifo blocks until the other end is opened as well
+    let rs = vm_lock::blocking(|| options.open(path).map(fd_table::insert));
+    match rs {
+        Ok(fd) => set_stream_fd(&this, fd),
+        Err(error) => {
+            let msg = format!("{} ({})", name, error_reason(&error));
+            throw_exception(frame, "java/io/FileNotFoundException", Some(msg.as_str()));
+        }
+    }
+}
+
+/// closes the descriptor of `this`, the standard streams stay open
+pub fn file_close(this: &Rc<RefCell<Object>>) {
+    let fd = stream_fd(this);
+    if fd == -1 {
+        return;
+    }
+    set_stream_fd(this, -1);
+    vm_lock::blocking(|| fd_table::remove(fd));
+}
+
+/// the descriptor of `this`, throws "Stream Closed" when it has none
+pub fn open_fd(frame: &mut Frame, this: &Rc<RefCell<Object>>) -> Option<i32> {
+    let fd = stream_fd(this);
+    if fd == -1 {
+        throw_exception(frame, "java/io/IOException", Some("Stream Closed"));
+        return None;
+    }
+    return Some(fd);
+}
+
+/// reads one byte from the descriptor of local 0, -1 at end of file
+pub fn read_single(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+    let mut byte = [0u8; 1];
+    match read(fd, &mut byte) {
+        Ok(0) => frame.operand_stack().expect("stack is none").push_int(-1),
+        Ok(_) => frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_int(byte[0] as i32),
+        Err(error) => throw_io_exception(frame, &error),
+    }
+}
+
+/// reads `len` bytes into `b[off..]` for `readBytes(byte b[], int off, int len)`,
+/// returning -1 at end of file
+pub fn read_bytes(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_ref(0).unwrap();
+    let byte_array = vars.get_ref(1);
+    let offset = vars.get_int(2);
+    let length = vars.get_int(3);
+    let byte_array = match check_bounds(frame, byte_array, offset, length) {
+        Some(byte_array) => byte_array,
+        None => return,
+    };
+    if length == 0 {
+        frame.operand_stack().expect("stack is none").push_int(0);
+        return;
+    }
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+
+    let mut bytes = vec![0u8; length as usize];
+    let read_size = match read(fd, bytes.as_mut_slice()) {
+        Ok(read_size) => read_size,
+        Err(error) => {
+            throw_io_exception(frame, &error);
+            return;
+        }
+    };
+    let mut size = -1;
+    if read_size != 0 {
+        let mut mut_byte_array = (*byte_array).borrow_mut();
+        let mut_array = mut_byte_array.mut_bytes();
+        let offset = offset as usize;
+        for i in 0..read_size {
+            mut_array[offset + i] = bytes[i] as i8;
+        }
+        size = read_size as i32;
+    }
+    frame.operand_stack().expect("stack is none").push_int(size);
+}
+
+/// writes the low byte of local 1 to the descriptor of local 0
+pub fn write_single(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_ref(0).unwrap();
+    let byte = vars.get_int(1) as u8;
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+    if let Err(error) = write(fd, &[byte]) {
+        throw_io_exception(frame, &error);
+    }
+}
+
+/// writes `b[off..off + len]` for `writeBytes(byte b[], int off, int len)`
+pub fn write_bytes(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_ref(0).unwrap();
+    let byte_array = vars.get_ref(1);
+    let offset = vars.get_int(2);
+    let length = vars.get_int(3);
+    let byte_array = match check_bounds(frame, byte_array, offset, length) {
+        Some(byte_array) => byte_array,
+        None => return,
+    };
+    if length == 0 {
+        return;
+    }
+    let fd = match open_fd(frame, &this) {
+        Some(fd) => fd,
+        None => return,
+    };
+
+    let bytes: Vec<u8> = {
+        let borrow = (*byte_array).borrow();
+        let java_bytes = &borrow.bytes()[offset as usize..(offset + length) as usize];
+        java_bytes.iter().map(|byte| *byte as u8).collect()
+    };
+    if let Err(error) = write(fd, bytes.as_slice()) {
+        throw_io_exception(frame, &error);
+    }
+}
+
+/// throws NullPointerException or IndexOutOfBoundsException unless
+/// `off` and `len` select a range of the array
+fn check_bounds(
+    frame: &mut Frame,
+    byte_array: Option<Rc<RefCell<Object>>>,
+    offset: i32,
+    length: i32,
+) -> Option<Rc<RefCell<Object>>> {
+    let byte_array = match byte_array {
+        Some(byte_array) => byte_array,
+        None => {
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return None;
+        }
+    };
+    let array_length = (*byte_array).borrow().bytes().len() as i64;
+    if offset < 0 || length < 0 || array_length - (offset as i64) < length as i64 {
+        throw_exception(frame, "java/lang/IndexOutOfBoundsException", None);
+        return None;
+    }
+    return Some(byte_array);
+}
+
+/// reads from an open descriptor, standard input is read directly. The
+/// vm lock is given up while the read blocks.
+pub fn read(fd: i32, buf: &mut [u8]) -> io::Result<usize> {
+    return vm_lock::blocking(|| match fd_table::with_file(fd, |mut file| file.read(buf)) {
+        Some(rs) => rs,
+        None if fd == STDIN_FD => stdin().read(buf),
+        None => Err(io::Error::from(ErrorKind::InvalidInput)),
+    });
+}
+
+/// writes all of `buf` to an open descriptor, standard output and error are
+/// written directly. The vm lock is given up while the write blocks.
+pub fn write(fd: i32, buf: &[u8]) -> io::Result<()> {
+    return vm_lock::blocking(|| match fd_table::with_file(fd, |mut file| file.write_all(buf)) {
+        Some(rs) => rs,
+        None if fd == STDOUT_FD => stdout().write_all(buf),
+        None if fd == STDERR_FD => stderr().write_all(buf),
+        None => Err(io::Error::from(ErrorKind::InvalidInput)),
+    });
+}
+
+pub fn throw_io_exception(frame: &mut Frame, error: &io::Error) {
+    let msg = error_reason(error);
+    throw_exception(frame, "java/io/IOException", Some(msg.as_str()));
+}
+
+/// the message of `error` without the " (os error N)" suffix, the way
+/// strerror words it
+pub fn error_reason(error: &io::Error) -> String {
+    let mut reason = error.to_string();
+    if let Some(index) = reason.find(" (os error") {
+        reason.truncate(index);
+    }
+    return reason;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::java::io::file_descriptor::fd_table;
+    use crate::native::java::io::io_util::{error_reason, read, write};
+    use std::fs;
+    use std::fs::{File, OpenOptions};
+    use std::io;
+
+    #[test]
+    fn test_read_write() {
+        let path = std::env::temp_dir().join(format!("lark-io-util-{}", std::process::id()));
+        let writer = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        let fd = fd_table::insert(writer);
+        write(fd, b"lark").unwrap();
+        let reader = fd_table::insert(File::open(&path).unwrap());
+        let mut buf = [0u8; 8];
+        assert_eq!(read(reader, &mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], b"lark");
+        assert_eq!(read(reader, &mut buf).unwrap(), 0);
+
+        fd_table::remove(reader);
+        fd_table::remove(fd);
+        assert!(read(reader, &mut buf).is_err());
+        assert!(write(fd, b"lark").is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_error_reason() {
+        let error = io::Error::new(io::ErrorKind::Other, "Stream Closed");
+        assert_eq!(error_reason(&error), "Stream Closed");
+        #[cfg(unix)]
+        assert_eq!(
+            error_reason(&io::Error::from_raw_os_error(2)),
+            "No such file or directory"
+        );
+    }
+}
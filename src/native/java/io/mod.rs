@@ -1,11 +1,24 @@
 mod file_descriptor;
 pub mod file_input_stream;
 pub mod file_output_stream;
+mod io_util;
+mod random_access_file;
+#[cfg(unix)]
+mod unix_file_system;
 mod win_nt_file_system;
 
+use crate::global_config::GlobalConfig;
+
 pub fn init() {
     file_output_stream::init();
     file_input_stream::init();
     file_descriptor::init();
-    win_nt_file_system::init();
+    random_access_file::init();
+    match GlobalConfig::instance().os() {
+        "windows" => win_nt_file_system::init(),
+        #[cfg(unix)]
+        _ => unix_file_system::init(),
+        #[cfg(not(unix))]
+        _ => {}
+    }
 }
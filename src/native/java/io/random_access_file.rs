@@ -0,0 +1,205 @@
+use crate::invoke_support::throw_exception;
+use crate::native::java::io::file_descriptor::fd_table;
+use crate::native::java::io::io_util::{
+    file_close, file_open, open_fd, read_bytes, read_single, throw_io_exception, write_bytes,
+    write_single,
+};
+use crate::native::registry::Registry;
+use crate::runtime::frame::Frame;
+use crate::runtime::vm_lock;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Seek, SeekFrom};
+
+/// @Native private static final int O_RDONLY = 1;
+/// @Native private static final int O_RDWR =   2;
+/// @Native private static final int O_SYNC =   4;
+/// @Native private static final int O_DSYNC =  8;
+const O_RDONLY: i32 = 1;
+const O_RDWR: i32 = 2;
+const O_SYNC: i32 = 4;
+const O_DSYNC: i32 = 8;
+
+pub fn init() {
+    Registry::register("java/io/RandomAccessFile", "initIDs", "()V", init_ids);
+    Registry::register(
+        "java/io/RandomAccessFile",
+        "open0",
+        "(Ljava/lang/String;I)V",
+        open0,
+    );
+    Registry::register("java/io/RandomAccessFile", "read0", "()I", read0);
+    Registry::register(
+        "java/io/RandomAccessFile",
+        "readBytes",
+        "([BII)I",
+        read_bytes,
+    );
+    Registry::register("java/io/RandomAccessFile", "write0", "(I)V", write0);
+    Registry::register(
+        "java/io/RandomAccessFile",
+        "writeBytes",
+        "([BII)V",
+        write_bytes,
+    );
+    Registry::register(
+        "java/io/RandomAccessFile",
+        "getFilePointer",
+        "()J",
+        get_file_pointer,
+    );
+    Registry::register("java/io/RandomAccessFile", "seek0", "(J)V", seek0);
+    Registry::register("java/io/RandomAccessFile", "length", "()J", length);
+    Registry::register("java/io/RandomAccessFile", "setLength", "(J)V", set_length);
+    Registry::register("java/io/RandomAccessFile", "close0", "()V", close0);
+}
+
+pub fn init_ids(_frame: &mut Frame) {}
+
+/// private native void open0(String name, int mode) throws FileNotFoundException;
+/// (Ljava/lang/String;I)V
+pub fn open0(frame: &mut Frame) {
+    let mode = frame.local_vars().expect("vars is none").get_int(2);
+    file_open(frame, &open_options(mode));
+}
+
+/// "r" opens an existing file for reading, the other modes create it
+fn open_options(mode: i32) -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.read(mode & (O_RDONLY | O_RDWR) != 0);
+    if mode & O_RDWR != 0 {
+        options.write(true).create(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        if mode & O_SYNC != 0 {
+            options.custom_flags(libc::O_SYNC);
+        } else if mode & O_DSYNC != 0 {
+            options.custom_flags(libc::O_DSYNC);
+        }
+    }
+    return options;
+}
+
+/// private native int read0() throws IOException;
+/// ()I
+pub fn read0(frame: &mut Frame) {
+    read_single(frame);
+}
+
+/// private native void write0(int b) throws IOException;
+/// (I)V
+pub fn write0(frame: &mut Frame) {
+    write_single(frame);
+}
+
+/// public native long getFilePointer() throws IOException;
+/// ()J
+pub fn get_file_pointer(frame: &mut Frame) {
+    let rs = with_file(frame, |mut file| file.stream_position());
+    if let Some(position) = rs {
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_long(position as i64);
+    }
+}
+
+/// private native void seek0(long pos) throws IOException;
+/// (J)V
+pub fn seek0(frame: &mut Frame) {
+    let pos = frame.local_vars().expect("vars is none").get_long(1);
+    if pos < 0 {
+        throw_exception(frame, "java/io/IOException", Some("Negative seek offset"));
+        return;
+    }
+    with_file(frame, |mut file| file.seek(SeekFrom::Start(pos as u64)));
+}
+
+/// public native long length() throws IOException;
+/// ()J
+pub fn length(frame: &mut Frame) {
+    let rs = with_file(frame, |file| file.metadata());
+    if let Some(metadata) = rs {
+        frame
+            .operand_stack()
+            .expect("stack is none")
+            .push_long(metadata.len() as i64);
+    }
+}
+
+/// public native void setLength(long newLength) throws IOException;
+/// (J)V
+pub fn set_length(frame: &mut Frame) {
+    let new_length = frame.local_vars().expect("vars is none").get_long(1);
+    if new_length < 0 {
+        throw_exception(frame, "java/io/IOException", Some("Negative seek offset"));
+        return;
+    }
+    let new_length = new_length as u64;
+    // the file pointer is kept unless the file got shorter than it
+    with_file(frame, |mut file| {
+        let current = file.stream_position()?;
+        file.set_len(new_length)?;
+        if current > new_length {
+            file.seek(SeekFrom::End(0))?;
+        }
+        Ok(())
+    });
+}
+
+/// private native void close0() throws IOException;
+/// ()V
+pub fn close0(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    file_close(&this);
+}
+
+/// runs `op` on the file of `this` without the vm lock, throwing IOException
+/// when it is closed or `op` fails
+fn with_file<T, F>(frame: &mut Frame, op: F) -> Option<T>
+where
+    F: FnOnce(&File) -> io::Result<T>,
+{
+    let this = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    let fd = open_fd(frame, &this)?;
+    let rs = vm_lock::blocking(|| match fd_table::with_file(fd, op) {
+        Some(rs) => rs,
+        // the standard streams never back a RandomAccessFile
+        None => Err(io::Error::from(io::ErrorKind::InvalidInput)),
+    });
+    return match rs {
+        Ok(value) => Some(value),
+        Err(error) => {
+            throw_io_exception(frame, &error);
+            None
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::java::io::random_access_file::{open_options, O_RDONLY, O_RDWR};
+    use std::fs;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn test_open_options() {
+        let path = std::env::temp_dir().join(format!("lark-raf-{}", std::process::id()));
+        assert!(open_options(O_RDONLY).open(&path).is_err());
+
+        let mut file = open_options(O_RDWR).open(&path).unwrap();
+        file.write_all(b"lark").unwrap();
+        drop(file);
+        // "rw" keeps what is already in the file
+        let mut file = open_options(O_RDWR).open(&path).unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "lark");
+
+        let mut file = open_options(O_RDONLY).open(&path).unwrap();
+        assert!(file.write_all(b"lark").is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,447 @@
+use crate::gc::heap::Heap;
+use crate::invoke_support::throw_exception;
+use crate::jvm::Jvm;
+use crate::native::registry::Registry;
+use crate::oops::class::Class;
+use crate::oops::string_pool::StringPool;
+use crate::runtime::frame::Frame;
+use crate::utils::java_str_to_rust_str;
+use std::ffi::CString;
+use std::fs;
+use std::fs::{DirBuilder, OpenOptions};
+use std::io::ErrorKind;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// @Native public static final int BA_EXISTS    = 0x01;
+/// @Native public static final int BA_REGULAR   = 0x02;
+/// @Native public static final int BA_DIRECTORY = 0x04;
+const BA_EXISTS: i32 = 0x01;
+const BA_REGULAR: i32 = 0x02;
+const BA_DIRECTORY: i32 = 0x04;
+
+/// @Native public static final int ACCESS_READ    = 0x04;
+/// @Native public static final int ACCESS_WRITE   = 0x02;
+/// @Native public static final int ACCESS_EXECUTE = 0x01;
+const ACCESS_READ: i32 = 0x04;
+const ACCESS_WRITE: i32 = 0x02;
+const ACCESS_EXECUTE: i32 = 0x01;
+
+/// @Native public static final int SPACE_TOTAL  = 0;
+/// @Native public static final int SPACE_FREE   = 1;
+/// @Native public static final int SPACE_USABLE = 2;
+const SPACE_TOTAL: i32 = 0;
+const SPACE_FREE: i32 = 1;
+const SPACE_USABLE: i32 = 2;
+
+pub fn init() {
+    Registry::register("java/io/UnixFileSystem", "initIDs", "()V", init_ids);
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "canonicalize0",
+        "(Ljava/lang/String;)Ljava/lang/String;",
+        canonicalize0,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "getBooleanAttributes0",
+        "(Ljava/io/File;)I",
+        get_boolean_attributes0,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "getLastModifiedTime",
+        "(Ljava/io/File;)J",
+        get_last_modified_time,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "getLength",
+        "(Ljava/io/File;)J",
+        get_length,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "setPermission",
+        "(Ljava/io/File;IZZ)Z",
+        set_permission,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "createFileExclusively",
+        "(Ljava/lang/String;)Z",
+        create_file_exclusively,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "delete0",
+        "(Ljava/io/File;)Z",
+        delete0,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "list",
+        "(Ljava/io/File;)[Ljava/lang/String;",
+        list,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "createDirectory",
+        "(Ljava/io/File;)Z",
+        create_directory,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "rename0",
+        "(Ljava/io/File;Ljava/io/File;)Z",
+        rename0,
+    );
+    Registry::register(
+        "java/io/UnixFileSystem",
+        "getSpace",
+        "(Ljava/io/File;I)J",
+        get_space,
+    );
+}
+
+/// java/io/UnixFileSystem.initIDs()V
+pub fn init_ids(_frame: &mut Frame) {}
+
+/// the path field of the java.io.File in the local variable,
+/// None with a NullPointerException thrown if the file is null
+fn file_path(frame: &mut Frame, index: usize) -> Option<String> {
+    let java_file = frame.local_vars().expect("vars is none").get_ref(index);
+    let java_file = match java_file {
+        Some(file) => file,
+        None => {
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return None;
+        }
+    };
+    let java_path = (*java_file)
+        .borrow()
+        .get_ref_var("path", "Ljava/lang/String;");
+    return Some(java_str_to_rust_str(java_path.unwrap()));
+}
+
+/// private native String canonicalize0(String path) throws IOException;
+/// (Ljava/lang/String;)Ljava/lang/String;
+pub fn canonicalize0(frame: &mut Frame) {
+    let java_path = frame.local_vars().expect("vars is none").get_ref(1);
+    let path = match java_path {
+        Some(path) => java_str_to_rust_str(path),
+        None => return throw_exception(frame, "java/lang/NullPointerException", None),
+    };
+    let canonical = canonicalize_path(Path::new(&path));
+    let java_string = StringPool::java_string(canonical.to_string_lossy().to_string());
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(Some(java_string));
+}
+
+/// like realpath, but a file that doesn't exist is fine: the longest prefix
+/// that exists is resolved and the rest is appended with `.` and `..` collapsed
+fn canonicalize_path(path: &Path) -> PathBuf {
+    if let Ok(real) = fs::canonicalize(path) {
+        return real;
+    }
+    let mut collapsed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                collapsed.pop();
+            }
+            other => collapsed.push(other.as_os_str()),
+        }
+    }
+    let mut prefix = collapsed.clone();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(real) = fs::canonicalize(&prefix) {
+            let mut canonical = real;
+            for name in rest.iter().rev() {
+                canonical.push(name);
+            }
+            return canonical;
+        }
+        match (prefix.file_name(), prefix.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                prefix = parent.to_path_buf();
+            }
+            _ => return collapsed,
+        }
+    }
+}
+
+/// public native int getBooleanAttributes0(File f);
+/// (Ljava/io/File;)I
+/// the Java side adds BA_HIDDEN for the names starting with a dot
+pub fn get_boolean_attributes0(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let mut attribute = 0;
+    if let Ok(metadata) = fs::metadata(&path) {
+        attribute |= BA_EXISTS;
+        if metadata.is_file() {
+            attribute |= BA_REGULAR;
+        }
+        if metadata.is_dir() {
+            attribute |= BA_DIRECTORY;
+        }
+    }
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_int(attribute);
+}
+
+/// public native long getLastModifiedTime(File f);
+/// (Ljava/io/File;)J
+pub fn get_last_modified_time(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let time = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_millis() as i64);
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(time);
+}
+
+/// public native long getLength(File f);
+/// (Ljava/io/File;)J
+pub fn get_length(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let len = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(len as i64);
+}
+
+/// public native boolean setPermission(File f, int access, boolean enable, boolean owneronly);
+/// (Ljava/io/File;IZZ)Z
+pub fn set_permission(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let vars = frame.local_vars().expect("vars is none");
+    let access = vars.get_int(2);
+    let enable = vars.get_boolean(3);
+    let owner_only = vars.get_boolean(4);
+    let changed = match permission_mask(access, owner_only) {
+        Some(mask) => fs::metadata(&path)
+            .and_then(|metadata| {
+                let mode = metadata.permissions().mode();
+                let mode = if enable { mode | mask } else { mode & !mask };
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode))
+            })
+            .is_ok(),
+        None => false,
+    };
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(changed);
+}
+
+/// the mode bits of ACCESS_READ, ACCESS_WRITE or ACCESS_EXECUTE
+fn permission_mask(access: i32, owner_only: bool) -> Option<u32> {
+    let (owner, all) = match access {
+        ACCESS_READ => (0o400, 0o444),
+        ACCESS_WRITE => (0o200, 0o222),
+        ACCESS_EXECUTE => (0o100, 0o111),
+        _ => return None,
+    };
+    return Some(if owner_only { owner } else { all });
+}
+
+/// public native boolean createFileExclusively(String path) throws IOException;
+/// (Ljava/lang/String;)Z
+pub fn create_file_exclusively(frame: &mut Frame) {
+    let java_path = frame.local_vars().expect("vars is none").get_ref(1);
+    let path = match java_path {
+        Some(path) => java_str_to_rust_str(path),
+        None => return throw_exception(frame, "java/lang/NullPointerException", None),
+    };
+    let result = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o666)
+        .open(&path);
+    let created = match result {
+        Ok(_) => true,
+        Err(ref e) if e.kind() == ErrorKind::AlreadyExists => false,
+        Err(e) => {
+            let msg = e.to_string();
+            return throw_exception(frame, "java/io/IOException", Some(msg.as_str()));
+        }
+    };
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(created);
+}
+
+/// public native boolean delete0(File f);
+/// (Ljava/io/File;)Z
+/// removes a file or an empty directory, a symbolic link itself is removed
+pub fn delete0(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let deleted = match fs::symlink_metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir(&path).is_ok(),
+        Ok(_) => fs::remove_file(&path).is_ok(),
+        Err(_) => false,
+    };
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(deleted);
+}
+
+/// public native String[] list(File f);
+/// (Ljava/io/File;)[Ljava/lang/String;
+/// null if the directory can't be read
+pub fn list(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let names: Option<Vec<String>> = fs::read_dir(&path).ok().map(|entries| {
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect()
+    });
+    let array = names.map(|names| {
+        let string_class = Jvm::boot_class_loader()
+            .find_or_create("java/lang/String")
+            .unwrap();
+        let array_class = (*string_class).borrow().array_class();
+        let mut array = Class::new_array(&array_class, names.len());
+        let references = array.mut_references();
+        for (i, name) in names.into_iter().enumerate() {
+            references[i] = Some(StringPool::java_string(name));
+        }
+        Heap::allocate(array)
+    });
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(array);
+}
+
+/// public native boolean createDirectory(File f);
+/// (Ljava/io/File;)Z
+pub fn create_directory(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let created = DirBuilder::new().mode(0o777).create(&path).is_ok();
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(created);
+}
+
+/// public native boolean rename0(File f1, File f2);
+/// (Ljava/io/File;Ljava/io/File;)Z
+pub fn rename0(frame: &mut Frame) {
+    let from = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let to = match file_path(frame, 2) {
+        Some(path) => path,
+        None => return,
+    };
+    let renamed = fs::rename(&from, &to).is_ok();
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(renamed);
+}
+
+/// public native long getSpace(File f, int t);
+/// (Ljava/io/File;I)J
+/// 0 if the file system can't be queried
+pub fn get_space(frame: &mut Frame) {
+    let path = match file_path(frame, 1) {
+        Some(path) => path,
+        None => return,
+    };
+    let kind = frame.local_vars().expect("vars is none").get_int(2);
+    let space = file_system_space(&path, kind).unwrap_or(0);
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(space as i64);
+}
+
+fn file_system_space(path: &str, kind: i32) -> Option<u64> {
+    let c_path = CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let blocks = match kind {
+        SPACE_TOTAL => stat.f_blocks,
+        SPACE_FREE => stat.f_bfree,
+        SPACE_USABLE => stat.f_bavail,
+        _ => return None,
+    };
+    return Some(stat.f_frsize as u64 * blocks as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::java::io::unix_file_system::{
+        canonicalize_path, file_system_space, permission_mask, SPACE_TOTAL, SPACE_USABLE,
+    };
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_canonicalize_path() {
+        let dir = fs::canonicalize(std::env::temp_dir()).unwrap();
+        assert_eq!(canonicalize_path(&dir.join(".").join("..")), dir.parent().unwrap());
+        let missing = dir.join("lark-missing").join("..").join("lark-file");
+        assert_eq!(canonicalize_path(&missing), dir.join("lark-file"));
+        assert_eq!(canonicalize_path(Path::new("/")), Path::new("/"));
+    }
+
+    #[test]
+    fn test_permission_mask() {
+        assert_eq!(permission_mask(0x04, true), Some(0o400));
+        assert_eq!(permission_mask(0x02, false), Some(0o222));
+        assert_eq!(permission_mask(0x01, false), Some(0o111));
+        assert_eq!(permission_mask(0x08, false), None);
+    }
+
+    #[test]
+    fn test_file_system_space() {
+        let total = file_system_space("/", SPACE_TOTAL).unwrap();
+        let usable = file_system_space("/", SPACE_USABLE).unwrap();
+        assert!(total >= usable);
+        assert_eq!(file_system_space("/lark-missing", SPACE_TOTAL), None);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::Heap;
 use crate::instructions::base::class_init_logic::init_class;
 use crate::instructions::base::method_invoke_logic::{hack_invoke_method, invoke_method};
+use crate::invoke_support::throw_exception;
 use crate::jvm::Jvm;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
@@ -10,8 +12,10 @@ use crate::oops::object::DataType::Bytes;
 use crate::oops::object::MetaData::{Field, Method};
 use crate::oops::object::Object;
 use crate::oops::string_pool::StringPool;
+use crate::runtime::java_assertions::JavaAssertions;
 use crate::runtime::operand_stack::OperandStack;
-use crate::utils::{boxed, java_str_to_rust_str};
+use crate::runtime::thread::JavaThread;
+use crate::utils::java_str_to_rust_str;
 use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
@@ -122,8 +126,20 @@ pub fn get_name0(frame: &mut Frame) {
         .push_ref(Some(name_obj));
 }
 
+/// private static native boolean desiredAssertionStatus0(Class<?> clazz);
+/// (Ljava/lang/Class;)Z
 pub fn desired_assertion_status0(frame: &mut Frame) {
-    frame.operand_stack().expect("stack null").push_int(0);
+    let java_class = frame.local_vars().expect("vars is none").get_ref(0).unwrap();
+    let class = (*java_class).borrow().meta().unwrap();
+    let enabled = {
+        let borrow = (*class).borrow();
+        let system_class = borrow.get_class_loader().is_none();
+        JavaAssertions::instance().enabled(borrow.name(), system_class)
+    };
+    frame
+        .operand_stack()
+        .expect("stack null")
+        .push_boolean(enabled);
 }
 
 pub fn for_name0(frame: &mut Frame) {
@@ -134,18 +150,26 @@ pub fn for_name0(frame: &mut Frame) {
 
     let rust_name = java_str_to_rust_str(name.unwrap()).replace('.', "/");
 
-    let class = ClassLoader::load_class(java_loader, rust_name.as_str());
+    let class = match ClassLoader::find_or_load_class(java_loader, rust_name.as_str()) {
+        Some(class) => class,
+        None => {
+            // loadClass left its exception pending, the bootstrap loader didn't
+            if !JavaThread::has_pending_exception() {
+                let name = rust_name.replace('/', ".");
+                let class_name = Some(name.as_str());
+                throw_exception(frame, "java/lang/ClassNotFoundException", class_name);
+            }
+            return;
+        }
+    };
     let java_class = (*class).borrow().get_java_class();
-    if initialize && !(*class).borrow().initialized() {
-        let thread = frame.thread();
-        frame.set_next_pc((*thread).borrow().get_pc());
-        init_class(thread, class);
-    } else {
-        frame
-            .operand_stack()
-            .expect("stack null")
-            .push_ref(java_class);
+    if initialize && !(*class).borrow().initialized() && !init_class(class) {
+        return;
     }
+    frame
+        .operand_stack()
+        .expect("stack null")
+        .push_ref(java_class);
 }
 
 pub fn is_interface(frame: &mut Frame) {
@@ -219,7 +243,7 @@ pub fn get_interfaces0(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack null")
-        .push_ref(Some(boxed(class_arr)));
+        .push_ref(Some(Heap::allocate(class_arr)));
 }
 
 // []*Class => Class[]
@@ -320,7 +344,7 @@ pub fn get_declared_constructors0(frame: &mut Frame) {
     let class_arr_class = (*constructor_class).borrow().array_class();
     let constructor_arr = Class::new_array(&class_arr_class, constructor_count);
 
-    let boxed_arr = Some(boxed(constructor_arr));
+    let boxed_arr = Some(Heap::allocate(constructor_arr));
     frame
         .operand_stack()
         .expect("stack null")
@@ -339,23 +363,23 @@ pub fn get_declared_constructors0(frame: &mut Frame) {
             let constructor = constructors[i].clone();
             let mut constructor_obj = Class::new_object(&constructor_class);
             constructor_obj.set_meta_data(Method(constructor.clone()));
-            let object = Some(boxed(constructor_obj));
+            let object = Some(Heap::allocate(constructor_obj));
             constructor_objs[i] = object.clone();
 
             let mut ops = OperandStack::new(9).unwrap();
             ops.push_ref(object); // this
             ops.push_ref(Some(class_obj.clone())); // declaringClass
             let parameter_types = constructor.parameter_types().unwrap();
-            ops.push_ref(Some(boxed(to_class_arr(&parameter_types)))); // parameterTypes
+            ops.push_ref(Some(Heap::allocate(to_class_arr(&parameter_types)))); // parameterTypes
             let exception_types = constructor.exception_types().unwrap_or_else(|| Vec::new());
-            ops.push_ref(Some(boxed(to_class_arr(&exception_types)))); // checkedExceptions
+            ops.push_ref(Some(Heap::allocate(to_class_arr(&exception_types)))); // checkedExceptions
             ops.push_int(constructor.access_flags() as i32); // modifiers
             ops.push_int(0); // todo slot
             ops.push_ref(get_signature_str(constructor.signature())); // signature
             let mut data: Vec<u8> = vec![0, 20];
-            ops.push_ref(Some(boxed(to_byte_arr(Some((data))).unwrap())));
+            ops.push_ref(Some(Heap::allocate(to_byte_arr(Some((data))).unwrap())));
             let mut data: Vec<u8> = vec![0, 20]; // annotations
-            ops.push_ref(Some(boxed(to_byte_arr(Some(data)).unwrap()))); // parameterAnnotations
+            ops.push_ref(Some(Heap::allocate(to_byte_arr(Some(data)).unwrap()))); // parameterAnnotations
 
             let shim_frame = Frame::new_shim_frame(thread.clone(), ops);
             (*thread).borrow_mut().push_frame(shim_frame);
@@ -386,7 +410,7 @@ pub fn get_declared_fields0(frame: &mut Frame) {
     let field_arr_class = (*field_class).borrow().array_class();
     let field_arr = Class::new_array(&field_arr_class, field_count);
 
-    let boxed_arr = Some(boxed(field_arr));
+    let boxed_arr = Some(Heap::allocate(field_arr));
     frame
         .operand_stack()
         .expect("stack null")
@@ -403,7 +427,7 @@ pub fn get_declared_fields0(frame: &mut Frame) {
             let field = fields[i].clone();
             let mut field_obj = Class::new_object(&field_class);
             field_obj.set_meta_data(Field(field.clone()));
-            let object = Some(boxed(field_obj));
+            let object = Some(Heap::allocate(field_obj));
             field_objs[i] = object.clone();
 
             let mut ops = OperandStack::new(8).unwrap();
@@ -418,7 +442,7 @@ pub fn get_declared_fields0(frame: &mut Frame) {
             ops.push_ref(get_signature_str((*field).borrow().signature())); // signature
             let mut data: Vec<u8> = vec![0, 20];
 
-            ops.push_ref(Some(boxed(to_byte_arr(Some(data)).unwrap()))); // annotations
+            ops.push_ref(Some(Heap::allocate(to_byte_arr(Some(data)).unwrap()))); // annotations
 
             let shim_frame = Frame::new_shim_frame(thread.clone(), ops);
             (*thread).borrow_mut().push_frame(shim_frame);
@@ -456,7 +480,7 @@ pub fn get_declared_methods0(frame: &mut Frame) {
     let method_arr_class = (*method_class).borrow().array_class();
     let method_arr = Class::new_array(&method_arr_class, method_count);
 
-    let boxed_arr = Some(boxed(method_arr));
+    let boxed_arr = Some(Heap::allocate(method_arr));
     frame
         .operand_stack()
         .expect("stack null")
@@ -474,7 +498,7 @@ pub fn get_declared_methods0(frame: &mut Frame) {
             let method = methods[i].clone();
             let mut method_obj = Class::new_object(&method_class);
             method_obj.set_meta_data(Method(method.clone()));
-            let object = Some(boxed(method_obj));
+            let object = Some(Heap::allocate(method_obj));
             method_objs[i] = object.clone();
 
             let mut ops = OperandStack::new(8).unwrap();
@@ -482,15 +506,15 @@ pub fn get_declared_methods0(frame: &mut Frame) {
             ops.push_ref(Some(class_obj.clone())); // declaringClass
             ops.push_ref(Some(StringPool::java_string(method.name().to_string()))); // name
             let parameter_types = method.parameter_types().unwrap();
-            ops.push_ref(Some(boxed(to_class_arr(&parameter_types)))); // parameterTypes
+            ops.push_ref(Some(Heap::allocate(to_class_arr(&parameter_types)))); // parameterTypes
             ops.push_ref((*method.return_type()).borrow().get_java_class()); // returnType
             let exception_types = method.exception_types().unwrap_or_else(|| Vec::new());
-            ops.push_ref(Some(boxed(to_class_arr(&exception_types)))); // checkedExceptions
+            ops.push_ref(Some(Heap::allocate(to_class_arr(&exception_types)))); // checkedExceptions
             ops.push_int(method.access_flags() as i32); // modifiers
             ops.push_int(0); // todo: slot
             ops.push_ref(get_signature_str(method.signature())); // signature
             let mut data: Vec<u8> = vec![0, 20];
-            ops.push_ref(Some(boxed(to_byte_arr(Some(data)).unwrap()))); // annotations
+            ops.push_ref(Some(Heap::allocate(to_byte_arr(Some(data)).unwrap()))); // annotations
                                                                          //            ops.push_ref(toByteArr(classLoader, method.ParameterAnnotationData())) // parameterAnnotations
             ops.push_ref(None);
             let mut data: Vec<u8> = vec![0, 20];
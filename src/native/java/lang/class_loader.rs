@@ -1,8 +1,15 @@
 use crate::class_loader::app_class_loader::ClassLoader;
 use crate::jvm::{Jvm, JVM};
 use crate::native::registry::Registry;
+use crate::gc::heap::Heap;
+use crate::oops::class::Class;
+use crate::oops::object::Object;
+use crate::oops::string_pool::StringPool;
 use crate::runtime::frame::Frame;
+use crate::runtime::java_assertions::JavaAssertions;
 use crate::utils::java_str_to_rust_str;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub fn init() {
     Registry::register(
@@ -35,6 +42,12 @@ pub fn init() {
         "(Ljava/lang/String;[BIILjava/security/ProtectionDomain;Ljava/lang/String;)Ljava/lang/Class;",
         define_class1,
     );
+    Registry::register(
+        "java/lang/ClassLoader",
+        "retrieveDirectives",
+        "()Ljava/lang/AssertionStatusDirectives;",
+        retrieve_directives,
+    );
 }
 
 /// public static native String findBuiltinLib(String name);
@@ -114,6 +127,11 @@ pub fn define_class1(frame: &mut Frame) {
         this,
         protection_domain,
     );
+    let class = match class {
+        Some(class) => class,
+        // the VerifyError is pending
+        None => return,
+    };
     let java_class = (*class).borrow().get_java_class();
 
     frame
@@ -121,3 +139,44 @@ pub fn define_class1(frame: &mut Frame) {
         .expect("stack is none")
         .push_ref(java_class);
 }
+
+/// private static native AssertionStatusDirectives retrieveDirectives();
+/// ()Ljava/lang/AssertionStatusDirectives;
+pub fn retrieve_directives(frame: &mut Frame) {
+    let loader = Jvm::boot_class_loader();
+    let directives_class = loader
+        .find_or_create("java/lang/AssertionStatusDirectives")
+        .unwrap();
+    let assertions = JavaAssertions::instance();
+    let (classes, class_enabled) = directive_arrays(assertions.classes());
+    let (packages, package_enabled) = directive_arrays(assertions.packages());
+
+    let mut directives = Class::new_object(&directives_class);
+    directives.set_ref_var("classes", "[Ljava/lang/String;", classes);
+    directives.set_ref_var("classEnabled", "[Z", class_enabled);
+    directives.set_ref_var("packages", "[Ljava/lang/String;", packages);
+    directives.set_ref_var("packageEnabled", "[Z", package_enabled);
+    directives.set_int_var("deflt", "Z", assertions.user_default() as i32);
+    let directives = Heap::allocate(directives);
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(Some(directives));
+}
+
+/// the names, in the binary form the class library compares, and the
+/// switches of the String[] and boolean[] fields of the directives
+fn directive_arrays(switches: &[(String, bool)]) -> (Rc<RefCell<Object>>, Rc<RefCell<Object>>) {
+    let loader = Jvm::boot_class_loader();
+    let string_class = loader.find_or_create("java/lang/String").unwrap();
+    let names_class = (*string_class).borrow().array_class();
+    let mut names = Class::new_array(&names_class, switches.len());
+    let mut enabled = Class::new_array(&loader.find_or_create("[Z").unwrap(), switches.len());
+    let java_names = names.mut_references();
+    let java_enabled = enabled.mut_bytes();
+    for (index, (name, switch)) in switches.iter().enumerate() {
+        java_names[index] = Some(StringPool::java_string(name.replace('/', ".")));
+        java_enabled[index] = *switch as i8;
+    }
+    return (Heap::allocate(names), Heap::allocate(enabled));
+}
@@ -0,0 +1,674 @@
+use crate::gc::heap::Heap;
+use crate::invoke_support::method_handle::{
+    box_parameter, is_signature_polymorphic, member_type_descriptor,
+};
+use crate::invoke_support::parameter::Parameter;
+use crate::invoke_support::throw_exception;
+use crate::jvm::Jvm;
+use crate::native::registry::Registry;
+use crate::oops::class::Class;
+use crate::oops::method::Method;
+use crate::oops::method_handle_ref::{
+    REF_GET_FIELD, REF_GET_STATIC, REF_INVOKE_INTERFACE, REF_INVOKE_SPECIAL, REF_INVOKE_STATIC,
+    REF_INVOKE_VIRTUAL, REF_PUT_STATIC,
+};
+use crate::oops::method_ref::MethodRef;
+use crate::oops::object::{MetaData, Object};
+use crate::oops::string_pool::StringPool;
+use crate::runtime::frame::Frame;
+use crate::utils::java_str_to_rust_str;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// MethodHandleNatives.Constants
+const MN_IS_METHOD: i32 = 0x00010000;
+const MN_IS_CONSTRUCTOR: i32 = 0x00020000;
+const MN_IS_FIELD: i32 = 0x00040000;
+const MN_SEARCH_SUPERCLASSES: i32 = 0x00100000;
+const MN_SEARCH_INTERFACES: i32 = 0x00200000;
+const MN_REFERENCE_KIND_SHIFT: i32 = 24;
+const MN_REFERENCE_KIND_MASK: i32 = 0x0F;
+const ALL_KINDS: i32 = MN_IS_METHOD | MN_IS_CONSTRUCTOR | MN_IS_FIELD;
+
+pub fn init() {
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "init",
+        "(Ljava/lang/invoke/MemberName;Ljava/lang/Object;)V",
+        init_member_name,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "expand",
+        "(Ljava/lang/invoke/MemberName;)V",
+        expand,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "resolve",
+        "(Ljava/lang/invoke/MemberName;Ljava/lang/Class;)Ljava/lang/invoke/MemberName;",
+        resolve,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "getMembers",
+        "(Ljava/lang/Class;Ljava/lang/String;Ljava/lang/String;ILjava/lang/Class;I[Ljava/lang/invoke/MemberName;)I",
+        get_members,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "objectFieldOffset",
+        "(Ljava/lang/invoke/MemberName;)J",
+        field_offset,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "staticFieldOffset",
+        "(Ljava/lang/invoke/MemberName;)J",
+        field_offset,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "staticFieldBase",
+        "(Ljava/lang/invoke/MemberName;)Ljava/lang/Object;",
+        static_field_base,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "getMemberVMInfo",
+        "(Ljava/lang/invoke/MemberName;)Ljava/lang/Object;",
+        get_member_vm_info,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "getConstant",
+        "(I)I",
+        get_constant,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "getNamedCon",
+        "(I[Ljava/lang/Object;)I",
+        get_constant,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "setCallSiteTargetNormal",
+        "(Ljava/lang/invoke/CallSite;Ljava/lang/invoke/MethodHandle;)V",
+        set_call_site_target,
+    );
+    Registry::register(
+        "java/lang/invoke/MethodHandleNatives",
+        "setCallSiteTargetVolatile",
+        "(Ljava/lang/invoke/CallSite;Ljava/lang/invoke/MethodHandle;)V",
+        set_call_site_target,
+    );
+}
+
+/// static native void init(MemberName self, Object ref);
+/// (Ljava/lang/invoke/MemberName;Ljava/lang/Object;)V
+pub fn init_member_name(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+    let reference = vars.get_ref(1).unwrap();
+
+    let meta_data = reflect_meta_data(reference);
+    let (class, flags) = match member_flags(&meta_data) {
+        Some(member) => member,
+        None => {
+            throw_exception(frame, "java/lang/InternalError", Some("unsupported member reference"));
+            return;
+        }
+    };
+    set_member(&member_name, meta_data, class, flags);
+}
+
+/// the class declaring the method or field and its MemberName flags, None
+/// for anything else
+fn member_flags(meta_data: &MetaData) -> Option<(Rc<RefCell<Class>>, i32)> {
+    return match meta_data {
+        MetaData::Method(method) => Some((method.class(), method_flags(method))),
+        MetaData::Field(field) => {
+            let borrow = (**field).borrow();
+            let kind = match borrow.parent().is_static() {
+                true => REF_GET_STATIC,
+                false => REF_GET_FIELD,
+            };
+            let flags = borrow.access_flags() as i32
+                | MN_IS_FIELD
+                | ((kind as i32) << MN_REFERENCE_KIND_SHIFT);
+            Some((borrow.parent().class(), flags))
+        }
+        _ => None,
+    };
+}
+
+fn set_member(
+    member_name: &Rc<RefCell<Object>>,
+    meta_data: MetaData,
+    class: Rc<RefCell<Class>>,
+    flags: i32,
+) {
+    let mut borrow = (**member_name).borrow_mut();
+    borrow.set_ref_var(
+        "clazz",
+        "Ljava/lang/Class;",
+        (*class).borrow().get_java_class().unwrap(),
+    );
+    borrow.set_int_var("flags", "I", flags);
+    borrow.set_meta_data(meta_data);
+}
+
+/// static native void expand(MemberName self);
+/// (Ljava/lang/invoke/MemberName;)V
+pub fn expand(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+
+    let meta_data = (*member_name).borrow().meta_data.clone();
+    let (class, name, descriptor) = match &meta_data {
+        MetaData::Method(method) => (
+            method.class(),
+            method.name().to_string(),
+            method.descriptor().to_string(),
+        ),
+        MetaData::Field(field) => {
+            let borrow = (**field).borrow();
+            (
+                borrow.parent().class(),
+                borrow.name().to_string(),
+                borrow.descriptor().to_string(),
+            )
+        }
+        _ => {
+            throw_exception(frame, "java/lang/IllegalArgumentException", Some("MemberName not resolved"));
+            return;
+        }
+    };
+    let mut borrow = (*member_name).borrow_mut();
+    if borrow.get_ref_var("clazz", "Ljava/lang/Class;").is_none() {
+        let java_class = (*class).borrow().get_java_class().unwrap();
+        borrow.set_ref_var("clazz", "Ljava/lang/Class;", java_class);
+    }
+    if borrow.get_ref_var("name", "Ljava/lang/String;").is_none() {
+        borrow.set_ref_var("name", "Ljava/lang/String;", StringPool::java_string(name));
+    }
+    if borrow.get_ref_var("type", "Ljava/lang/Object;").is_none() {
+        borrow.set_ref_var("type", "Ljava/lang/Object;", StringPool::java_string(descriptor));
+    }
+}
+
+/// static native MemberName resolve(MemberName self, Class<?> caller) throws LinkageError;
+/// (Ljava/lang/invoke/MemberName;Ljava/lang/Class;)Ljava/lang/invoke/MemberName;
+pub fn resolve(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+
+    let (class, name, type_object, flags) = {
+        let borrow = (*member_name).borrow();
+        let class_object = borrow.get_ref_var("clazz", "Ljava/lang/Class;");
+        let name = borrow.get_ref_var("name", "Ljava/lang/String;");
+        let type_object = borrow.get_ref_var("type", "Ljava/lang/Object;");
+        if class_object.is_none() || name.is_none() || type_object.is_none() {
+            drop(borrow);
+            throw_exception(frame, "java/lang/IllegalArgumentException", Some("MemberName not complete"));
+            return;
+        }
+        let class = (*class_object.unwrap()).borrow().meta().unwrap();
+        let name = java_str_to_rust_str(name.unwrap());
+        (class, name, type_object.unwrap(), borrow.get_int_var("flags", "I"))
+    };
+    let descriptor = member_type_descriptor(type_object);
+    let reference_kind = ((flags >> MN_REFERENCE_KIND_SHIFT) & MN_REFERENCE_KIND_MASK) as u8;
+    let class_name = (*class).borrow().name().to_string();
+
+    let (meta_data, access_flags) = match flags & ALL_KINDS {
+        MN_IS_METHOD | MN_IS_CONSTRUCTOR => {
+            let method = match flags & MN_IS_CONSTRUCTOR != 0 {
+                true => Class::get_constructor(class.clone(), descriptor.as_str()),
+                false => look_up_method(class.clone(), name.as_str(), descriptor.as_str()),
+            };
+            if method.is_none() {
+                let msg = format!("{}.{}{}", class_name, name, descriptor);
+                throw_exception(frame, "java/lang/NoSuchMethodError", Some(msg.as_str()));
+                return;
+            }
+            let method = method.unwrap();
+            let access_flags = method.access_flags() as i32;
+            (MetaData::Method(method), access_flags)
+        }
+        MN_IS_FIELD => {
+            let is_static = reference_kind == REF_GET_STATIC || reference_kind == REF_PUT_STATIC;
+            let field = Class::get_field(Some(class.clone()), name.as_str(), descriptor.as_str(), is_static);
+            if field.is_none() {
+                let msg = format!("{}.{}", class_name, name);
+                throw_exception(frame, "java/lang/NoSuchFieldError", Some(msg.as_str()));
+                return;
+            }
+            let field = field.unwrap();
+            let access_flags = (*field).borrow().access_flags() as i32;
+            (MetaData::Field(field), access_flags)
+        }
+        _ => {
+            throw_exception(frame, "java/lang/LinkageError", Some("MemberName is not a member"));
+            return;
+        }
+    };
+    {
+        let mut borrow = (*member_name).borrow_mut();
+        borrow.set_int_var("flags", "I", flags | access_flags);
+        borrow.set_meta_data(meta_data);
+    }
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(Some(member_name));
+}
+
+/// static native int getMembers(Class<?> defc, String matchName, String matchSig,
+///         int matchFlags, Class<?> caller, int skip, MemberName[] results);
+/// (Ljava/lang/Class;Ljava/lang/String;Ljava/lang/String;ILjava/lang/Class;I[Ljava/lang/invoke/MemberName;)I
+/// Fills `results` with the members matching the name, signature and kinds
+/// after skipping `skip` of them, the way init fills a single MemberName.
+/// Returns how many matched past the skipped ones, a count larger than
+/// `results` makes the caller come back with a bigger array.
+pub fn get_members(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let class_object = vars.get_ref(0);
+    let match_name = vars.get_ref(1).map(java_str_to_rust_str);
+    let match_sig = vars.get_ref(2).map(java_str_to_rust_str);
+    let match_flags = vars.get_int(3);
+    let skip = vars.get_int(5).max(0) as usize;
+    let results = vars.get_ref(6);
+    if class_object.is_none() || results.is_none() {
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
+    }
+    let class = (*class_object.unwrap()).borrow().meta().unwrap();
+    let members: Vec<MetaData> = find_members(class, match_flags)
+        .into_iter()
+        .filter(|member| {
+            let (name, descriptor) = member_name_and_type(member);
+            let name_matches = match_name
+                .as_ref()
+                .is_none_or(|match_name| *match_name == name);
+            let sig_matches = match_sig
+                .as_ref()
+                .is_none_or(|match_sig| *match_sig == descriptor);
+            return name_matches && sig_matches;
+        })
+        .skip(skip)
+        .collect();
+    let results = results.unwrap();
+    let slots = (*results).borrow().references().clone();
+    for (member, member_name) in members.iter().zip(slots) {
+        if let (Some(member_name), Some((class, flags))) = (member_name, member_flags(member)) {
+            set_member(&member_name, member.clone(), class, flags);
+        }
+    }
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_int(members.len() as i32);
+}
+
+/// the fields, methods and constructors of `class` the flags ask for,
+/// followed by those of its superclasses and superinterfaces when they are
+/// searched as well. Constructors are never inherited, class initializers
+/// are never members.
+fn find_members(class: Rc<RefCell<Class>>, match_flags: i32) -> Vec<MetaData> {
+    let mut classes = vec![class.clone()];
+    if match_flags & MN_SEARCH_SUPERCLASSES != 0 {
+        let mut current = (*class).borrow().super_class();
+        while let Some(super_class) = current {
+            current = (*super_class).borrow().super_class();
+            classes.push(super_class);
+        }
+    }
+    if match_flags & MN_SEARCH_INTERFACES != 0 {
+        let mut index = 0;
+        while index < classes.len() {
+            let interfaces = (*classes[index]).borrow().interfaces().cloned();
+            for interface in interfaces.unwrap_or_default() {
+                if !classes.iter().any(|class| Rc::ptr_eq(class, &interface)) {
+                    classes.push(interface);
+                }
+            }
+            index += 1;
+        }
+    }
+    let mut members = vec![];
+    for current in &classes {
+        let borrow = (**current).borrow();
+        if match_flags & MN_IS_FIELD != 0 {
+            for field in borrow.fields() {
+                members.push(MetaData::Field(field.clone()));
+            }
+        }
+        for method in borrow.methods() {
+            let kind = match method.is_constructor() {
+                true if Rc::ptr_eq(current, &class) => MN_IS_CONSTRUCTOR,
+                true => continue,
+                false if method.is_clinit() => continue,
+                false => MN_IS_METHOD,
+            };
+            if match_flags & kind != 0 {
+                members.push(MetaData::Method(method.clone()));
+            }
+        }
+    }
+    return members;
+}
+
+fn member_name_and_type(member: &MetaData) -> (String, String) {
+    return match member {
+        MetaData::Method(method) => (method.name().to_string(), method.descriptor().to_string()),
+        MetaData::Field(field) => {
+            let borrow = (**field).borrow();
+            (borrow.name().to_string(), borrow.descriptor().to_string())
+        }
+        _ => (String::new(), String::new()),
+    };
+}
+
+/// static native long objectFieldOffset(MemberName self);
+/// static native long staticFieldOffset(MemberName self);
+/// (Ljava/lang/invoke/MemberName;)J
+pub fn field_offset(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+
+    let slot_id = match &(*member_name).borrow().meta_data {
+        MetaData::Field(field) => Some((**field).borrow().slot_id()),
+        _ => None,
+    };
+    if slot_id.is_none() {
+        throw_exception(frame, "java/lang/InternalError", Some("MemberName is not a field"));
+        return;
+    }
+    let slot_id = slot_id.unwrap();
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(slot_id as i64);
+}
+
+/// static native Object staticFieldBase(MemberName self);
+/// (Ljava/lang/invoke/MemberName;)Ljava/lang/Object;
+pub fn static_field_base(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+
+    let class_object = (*member_name)
+        .borrow()
+        .get_ref_var("clazz", "Ljava/lang/Class;");
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(class_object);
+}
+
+/// static native Object getMemberVMInfo(MemberName self);
+/// (Ljava/lang/invoke/MemberName;)Ljava/lang/Object;
+pub fn get_member_vm_info(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let member_name = vars.get_ref(0).unwrap();
+
+    // {vmindex, vmtarget}: the slot and holder of a field, -1 and the MemberName for a method
+    let (vm_index, vm_target) = match &(*member_name).borrow().meta_data {
+        MetaData::Field(field) => (
+            (**field).borrow().slot_id() as i64,
+            (*member_name).borrow().get_ref_var("clazz", "Ljava/lang/Class;"),
+        ),
+        MetaData::Method(_) => (-1, Some(member_name.clone())),
+        _ => (-1, None),
+    };
+    let array_class = Jvm::boot_class_loader()
+        .find_or_create("[Ljava/lang/Object;")
+        .unwrap();
+    let mut info = Class::new_array(&array_class, 2);
+    let references = info.mut_references();
+    references[0] = box_parameter(Parameter::Long(vm_index));
+    references[1] = vm_target;
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(Some(Heap::allocate(info)));
+}
+
+/// static native int getConstant(int which);
+/// (I)I
+/// private static native int getNamedCon(int which, Object[] name);
+/// (I[Ljava/lang/Object;)I
+pub fn get_constant(frame: &mut Frame) {
+    // no optional VM support: GC_COUNT_GWT and GC_LAMBDA_SUPPORT are both off
+    frame.operand_stack().expect("stack is none").push_int(0);
+}
+
+/// static native void setCallSiteTargetNormal(CallSite site, MethodHandle target);
+/// static native void setCallSiteTargetVolatile(CallSite site, MethodHandle target);
+/// (Ljava/lang/invoke/CallSite;Ljava/lang/invoke/MethodHandle;)V
+pub fn set_call_site_target(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let call_site = vars.get_ref(0).unwrap();
+    let target = vars.get_ref(1).unwrap();
+
+    (*call_site)
+        .borrow_mut()
+        .set_ref_var("target", "Ljava/lang/invoke/MethodHandle;", target);
+}
+
+/// the VM member behind a java.lang.reflect.Method, Constructor or Field,
+/// copies handed out by ReflectionFactory only keep it on their root,
+/// MetaData::Null if there is none
+fn reflect_meta_data(reference: Rc<RefCell<Object>>) -> MetaData {
+    let mut current = Some(reference);
+    while let Some(object) = current {
+        let borrow = (*object).borrow();
+        if borrow.meta_data.not_null() {
+            return borrow.meta_data.clone();
+        }
+        let class = borrow.class();
+        let root_descriptor = format!("L{};", (*class).borrow().name());
+        current = borrow.get_ref_var("root", root_descriptor.as_str());
+    }
+    return MetaData::Null;
+}
+
+fn method_flags(method: &Rc<Method>) -> i32 {
+    let class = method.class();
+    let (kind, reference_kind) = if method.is_constructor() {
+        (MN_IS_CONSTRUCTOR, REF_INVOKE_SPECIAL)
+    } else if method.is_static() {
+        (MN_IS_METHOD, REF_INVOKE_STATIC)
+    } else if (*class).borrow().is_interface() {
+        (MN_IS_METHOD, REF_INVOKE_INTERFACE)
+    } else if method.is_private() {
+        (MN_IS_METHOD, REF_INVOKE_SPECIAL)
+    } else {
+        (MN_IS_METHOD, REF_INVOKE_VIRTUAL)
+    };
+    return method.access_flags() as i32 | kind | ((reference_kind as i32) << MN_REFERENCE_KIND_SHIFT);
+}
+
+/// signature polymorphic methods of MethodHandle resolve by name alone
+fn look_up_method(class: Rc<RefCell<Class>>, name: &str, descriptor: &str) -> Option<Rc<Method>> {
+    let method = MethodRef::look_up_method(class.clone(), name, descriptor);
+    if method.is_some() {
+        return method;
+    }
+    if !is_signature_polymorphic((*class).borrow().name(), name) {
+        return None;
+    }
+    let borrow = (*class).borrow();
+    for method in borrow.methods() {
+        if method.name() == name {
+            return Some(method.clone());
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::test_classes::{ClassBuilder, TestLoader};
+    use crate::invoke_support::thrown;
+    use crate::native::java::lang::invoke::method_handle_natives::{
+        expand, field_offset, get_members, init_member_name, MN_IS_CONSTRUCTOR, MN_IS_FIELD,
+        MN_IS_METHOD, MN_SEARCH_SUPERCLASSES,
+    };
+    use crate::oops::access_flags::{PUBLIC, STATIC};
+    use crate::oops::class::Class;
+    use crate::oops::method::Method;
+    use crate::oops::object::DataType::{Chars, References};
+    use crate::oops::object::{MetaData, Object};
+    use crate::runtime::frame::Frame;
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// the exception and message of the native run with the references as locals
+    fn thrown_by(
+        native: fn(&mut Frame),
+        locals: Vec<Option<Rc<RefCell<Object>>>>,
+    ) -> (String, Option<String>) {
+        let thread = boxed(JavaThread::new_thread());
+        let method = Rc::new(Method::with_code(1, locals.len(), vec![]));
+        let mut frame = JavaThread::new_frame(thread, method);
+        for (index, local) in locals.into_iter().enumerate() {
+            frame.local_vars().unwrap().set_ref(index, local);
+        }
+        native(&mut frame);
+        let mut thrown = thrown::take();
+        assert_eq!(thrown.len(), 1, "thrown: {:?}", thrown);
+        return thrown.remove(0);
+    }
+
+    fn exception(class_name: &str, msg: &str) -> (String, Option<String>) {
+        return (class_name.to_string(), Some(msg.to_string()));
+    }
+
+    #[test]
+    fn test_unresolved_member_name() {
+        let member_name = || Some(boxed(Object::new(boxed(Class::default()))));
+        // a reflection object without a method or field behind it
+        let loader = TestLoader::new();
+        let mut builder = ClassBuilder::new("a/Reflected");
+        builder.field(0, "root", "La/Reflected;");
+        let reference = Some(boxed(Object::new(loader.define(&builder))));
+        assert_eq!(
+            thrown_by(init_member_name, vec![member_name(), reference]),
+            exception("java/lang/InternalError", "unsupported member reference")
+        );
+        assert_eq!(
+            thrown_by(expand, vec![member_name()]),
+            exception(
+                "java/lang/IllegalArgumentException",
+                "MemberName not resolved"
+            )
+        );
+        assert_eq!(
+            thrown_by(field_offset, vec![member_name()]),
+            exception("java/lang/InternalError", "MemberName is not a field")
+        );
+    }
+
+    /// the count getMembers returns for the class object, `results` holds
+    /// the MemberNames it fills
+    fn get_members_of(
+        class: &Rc<RefCell<Class>>,
+        name: Option<Rc<RefCell<Object>>>,
+        flags: i32,
+        skip: i32,
+        results: &Rc<RefCell<Object>>,
+    ) -> i32 {
+        let thread = boxed(JavaThread::new_thread());
+        let method = Rc::new(Method::with_code(1, 7, vec![]));
+        let mut frame = JavaThread::new_frame(thread, method);
+        let vars = frame.local_vars().unwrap();
+        vars.set_ref(0, (*class).borrow().get_java_class());
+        vars.set_ref(1, name);
+        vars.set_int(3, flags);
+        vars.set_int(5, skip);
+        vars.set_ref(6, Some(results.clone()));
+        get_members(&mut frame);
+        assert!(thrown::take().is_empty());
+        return frame.operand_stack().unwrap().pop_int();
+    }
+
+    fn member_name(member_name: &Option<Rc<RefCell<Object>>>) -> String {
+        return match &(*member_name.clone().unwrap()).borrow().meta_data {
+            MetaData::Method(method) => method.name().to_string(),
+            MetaData::Field(field) => (**field).borrow().name().to_string(),
+            _ => String::new(),
+        };
+    }
+
+    #[test]
+    fn test_get_members() {
+        let loader = TestLoader::new();
+        let mut string = ClassBuilder::new("java/lang/String");
+        string.field(0, "value", "[C");
+        let string = loader.define(&string);
+        let mut builder = ClassBuilder::new("java/lang/invoke/MemberName");
+        builder.field(0, "clazz", "Ljava/lang/Class;");
+        builder.field(0, "flags", "I");
+        let member_name_class = loader.define(&builder);
+        let mut builder = ClassBuilder::new("a/Super");
+        builder.method(PUBLIC, "inherited", "()V");
+        loader.define(&builder);
+        let mut builder = ClassBuilder::new("a/Members");
+        builder.super_class("a/Super");
+        builder.field(0, "count", "I");
+        builder.field(0, "name", "Ljava/lang/String;");
+        builder.method(PUBLIC, "<init>", "()V");
+        builder.method(STATIC, "<clinit>", "()V");
+        builder.method(PUBLIC, "run", "()V");
+        let class = loader.define(&builder);
+
+        let results = |length: usize| {
+            let mut results = Object::new(boxed(Class::default()));
+            let member_names = (0..length)
+                .map(|_| Some(boxed(Object::new(member_name_class.clone()))))
+                .collect();
+            results.data = References(member_names);
+            return boxed(results);
+        };
+        let all = MN_IS_FIELD | MN_IS_METHOD | MN_IS_CONSTRUCTOR;
+        let buf = results(8);
+        assert_eq!(get_members_of(&class, None, all, 0, &buf), 4);
+        let names: Vec<String> = (*buf).borrow().references()[..4]
+            .iter()
+            .map(member_name)
+            .collect();
+        assert_eq!(names, vec!["count", "name", "<init>", "run"]);
+        let first = (*buf).borrow().references()[0].clone().unwrap();
+        assert_ne!((*first).borrow().get_int_var("flags", "I") & MN_IS_FIELD, 0);
+        assert!((*first)
+            .borrow()
+            .get_ref_var("clazz", "Ljava/lang/Class;")
+            .is_some());
+
+        // inherited methods, but no inherited constructors
+        let flags = MN_IS_METHOD | MN_IS_CONSTRUCTOR | MN_SEARCH_SUPERCLASSES;
+        let buf = results(8);
+        assert_eq!(get_members_of(&class, None, flags, 0, &buf), 3);
+        assert_eq!(member_name(&(*buf).borrow().references()[2]), "inherited");
+
+        // matched by name
+        let mut value = Object::new(boxed(Class::default()));
+        value.data = Chars("count".encode_utf16().collect());
+        let mut name = Object::new(string);
+        name.set_ref_var("value", "[C", boxed(value));
+        let buf = results(8);
+        let count = get_members_of(&class, Some(boxed(name)), all, 0, &buf);
+        assert_eq!(count, 1);
+        assert_eq!(member_name(&(*buf).borrow().references()[0]), "count");
+
+        // the count past the skipped ones tells that the array was too small
+        let buf = results(1);
+        assert_eq!(get_members_of(&class, None, all, 1, &buf), 3);
+        assert_eq!(member_name(&(*buf).borrow().references()[0]), "name");
+    }
+}
@@ -0,0 +1,5 @@
+mod method_handle_natives;
+
+pub fn init() {
+    method_handle_natives::init();
+}
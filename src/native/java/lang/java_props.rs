@@ -0,0 +1,238 @@
+use crate::class_path::class_path::PATH_LIST_SEPARATOR;
+use crate::cmd::Cmd;
+use std::env;
+use std::env::consts::{ARCH, OS};
+use std::path::MAIN_SEPARATOR;
+
+// the platform dependent system properties, worked out from the host the
+// way java_props_md.c does it
+
+/// os.name, spelled the way the class library compares it
+pub fn os_name() -> String {
+    return match OS {
+        "linux" => "Linux",
+        "macos" => "Mac OS X",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        "solaris" => "SunOS",
+        os => os,
+    }
+    .to_string();
+}
+
+/// os.arch, x86_64 is amd64 outside of macOS
+pub fn os_arch() -> String {
+    return match (ARCH, OS) {
+        ("x86_64", "macos") => "x86_64",
+        ("x86_64", _) => "amd64",
+        ("powerpc64", _) => "ppc64",
+        (arch, _) => arch,
+    }
+    .to_string();
+}
+
+/// os.version, the release uname reports
+#[cfg(unix)]
+pub fn os_version() -> String {
+    unsafe {
+        let mut name: libc::utsname = std::mem::zeroed();
+        if libc::uname(&mut name) != 0 {
+            return "unknown".to_string();
+        }
+        let release = std::ffi::CStr::from_ptr(name.release.as_ptr());
+        return release.to_string_lossy().into_owned();
+    }
+}
+
+#[cfg(not(unix))]
+pub fn os_version() -> String {
+    return "unknown".to_string();
+}
+
+/// user.name and user.home from the password database, the environment
+/// is the fallback
+#[cfg(unix)]
+pub fn user_name_and_home() -> (String, String) {
+    unsafe {
+        let passwd = libc::getpwuid(libc::getuid());
+        if !passwd.is_null() {
+            let name = std::ffi::CStr::from_ptr((*passwd).pw_name);
+            let home = std::ffi::CStr::from_ptr((*passwd).pw_dir);
+            return (
+                name.to_string_lossy().into_owned(),
+                home.to_string_lossy().into_owned(),
+            );
+        }
+    }
+    return (env_or_unknown("USER"), env_or_unknown("HOME"));
+}
+
+#[cfg(not(unix))]
+pub fn user_name_and_home() -> (String, String) {
+    return (env_or_unknown("USERNAME"), env_or_unknown("USERPROFILE"));
+}
+
+fn env_or_unknown(key: &str) -> String {
+    return env::var(key).unwrap_or_else(|_| "?".to_string());
+}
+
+/// user.dir
+pub fn user_dir() -> String {
+    return match env::current_dir() {
+        Ok(dir) => dir.to_string_lossy().into_owned(),
+        Err(_) => "?".to_string(),
+    };
+}
+
+/// java.io.tmpdir, the class library on linux never looks at TMPDIR
+pub fn tmp_dir() -> String {
+    if OS == "linux" {
+        return "/tmp".to_string();
+    }
+    return env::temp_dir().to_string_lossy().into_owned();
+}
+
+/// java.library.path, the loader's search path followed by the system
+/// library directories
+pub fn library_path() -> String {
+    if OS == "windows" {
+        let path = env::var("PATH").unwrap_or_default();
+        return format!("{}{}.", path, PATH_LIST_SEPARATOR);
+    }
+    let variable = if OS == "macos" {
+        "DYLD_LIBRARY_PATH"
+    } else {
+        "LD_LIBRARY_PATH"
+    };
+    let system = format!(
+        "/usr/java/packages/lib/{}:/lib64:/lib:/usr/lib64:/usr/lib",
+        os_arch()
+    );
+    return match env::var(variable) {
+        Ok(path) if !path.is_empty() => format!("{}:{}", path, system),
+        _ => system,
+    };
+}
+
+/// sun.boot.library.path, where the native libraries of the JRE live
+pub fn boot_library_path(java_home: &str) -> String {
+    if OS == "windows" {
+        return format!("{}{}bin", java_home, MAIN_SEPARATOR);
+    }
+    return format!("{0}{1}lib{1}{2}", java_home, MAIN_SEPARATOR, os_arch());
+}
+
+/// java.ext.dirs
+pub fn ext_dirs(java_home: &str) -> String {
+    let ext = format!("{0}{1}lib{1}ext", java_home, MAIN_SEPARATOR);
+    if OS == "windows" {
+        let root = env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        return format!("{};{}\\Sun\\Java\\lib\\ext", ext, root);
+    }
+    return format!("{}:/usr/java/packages/lib/ext", ext);
+}
+
+/// sun.java.command, the main class or jar followed by the arguments of main
+pub fn java_command(cmd: &Cmd) -> String {
+    let mut command = match cmd.exec_jar_path() {
+        Some(jar) => jar.clone(),
+        None => cmd.class().clone(),
+    };
+    for arg in &cmd.args {
+        command.push(' ');
+        command.push_str(arg);
+    }
+    return command;
+}
+
+/// the locale of the C library, taken from LC_ALL, LC_CTYPE and LANG in
+/// that order
+#[derive(Debug, PartialEq)]
+pub struct Locale {
+    pub language: String,
+    pub country: String,
+    pub variant: String,
+    pub encoding: String,
+}
+
+impl Locale {
+    pub fn from_env() -> Locale {
+        for key in &["LC_ALL", "LC_CTYPE", "LANG"] {
+            if let Ok(value) = env::var(key) {
+                if !value.is_empty() {
+                    return Locale::parse(&value);
+                }
+            }
+        }
+        return Locale::parse("C");
+    }
+
+    /// a POSIX locale name like "en_US.UTF-8@euro", C and POSIX are English
+    pub fn parse(name: &str) -> Locale {
+        let (name, variant) = match name.split_once('@') {
+            Some((name, modifier)) => (name, modifier.to_string()),
+            None => (name, String::new()),
+        };
+        let (name, codeset) = match name.split_once('.') {
+            Some((name, codeset)) => (name, Some(codeset)),
+            None => (name, None),
+        };
+        let (language, country) = match name.split_once('_') {
+            Some((language, country)) => (language, country),
+            None => (name, ""),
+        };
+        let language = match language {
+            "" | "C" | "POSIX" => "en",
+            language => language,
+        };
+        let encoding = match codeset {
+            Some(codeset)
+                if codeset.eq_ignore_ascii_case("utf8")
+                    || codeset.eq_ignore_ascii_case("utf-8") =>
+            {
+                "UTF-8".to_string()
+            }
+            Some(codeset) => codeset.to_string(),
+            // the strings of this VM are UTF-8 whatever the locale says
+            None => "UTF-8".to_string(),
+        };
+        return Locale {
+            language: language.to_string(),
+            country: country.to_string(),
+            variant,
+            encoding,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::java::lang::java_props::{os_arch, os_version, user_dir, Locale};
+
+    #[test]
+    fn test_locale() {
+        assert_eq!(
+            Locale::parse("de_DE.utf8@euro"),
+            Locale {
+                language: "de".to_string(),
+                country: "DE".to_string(),
+                variant: "euro".to_string(),
+                encoding: "UTF-8".to_string(),
+            }
+        );
+        let locale = Locale::parse("C");
+        assert_eq!(locale.language, "en");
+        assert_eq!(locale.country, "");
+        assert_eq!(Locale::parse("ja_JP.eucJP").encoding, "eucJP");
+    }
+
+    #[test]
+    fn test_host() {
+        assert!(!os_version().is_empty());
+        assert!(!os_arch().is_empty());
+        assert_eq!(
+            user_dir(),
+            std::env::current_dir().unwrap().to_str().unwrap()
+        );
+    }
+}
@@ -2,11 +2,14 @@ pub mod class;
 mod class_loader;
 mod double;
 mod float;
+mod invoke;
+mod java_props;
 pub mod object;
 mod runtime;
+mod shutdown;
 mod string;
 pub mod system;
-mod thread;
+pub mod thread;
 pub mod throwable;
 mod package;
 
@@ -21,5 +24,7 @@ pub fn init() {
     thread::init();
     class_loader::init();
     runtime::init();
+    shutdown::init();
     package::init();
+    invoke::init();
 }
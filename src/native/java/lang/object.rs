@@ -1,9 +1,12 @@
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::{object_size, Heap};
+use crate::invoke_support::throw_exception;
 use crate::jvm::Jvm;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::object::Object;
-use crate::utils::boxed;
+use crate::runtime::monitor::WaitResult;
+use crate::runtime::thread::JavaThread;
 use std::ops::Deref;
 
 pub fn init() {
@@ -15,6 +18,9 @@ pub fn init() {
     );
     Registry::register("java/lang/Object", "hashCode", "()I", hash_code);
     Registry::register("java/lang/Object", "clone", "()Ljava/lang/Object;", clone);
+    Registry::register("java/lang/Object", "wait", "(J)V", wait);
+    Registry::register("java/lang/Object", "notify", "()V", notify);
+    Registry::register("java/lang/Object", "notifyAll", "()V", notify_all);
 }
 
 pub fn get_class(frame: &mut Frame) {
@@ -59,12 +65,65 @@ pub fn clone(frame: &mut Frame) {
 
     let borrow = cloneable.borrow();
     if !(*this_class).borrow().is_implements(borrow.deref()) {
-        panic!("java.lang.CloneNotSupportedException");
+        let name = (*this_class).borrow().java_name();
+        drop(borrow);
+        throw_exception(frame, "java/lang/CloneNotSupportedException", Some(name.as_str()));
+        return;
+    }
+    if !Heap::reserve(object_size(&(*this).borrow())) {
+        throw_exception(frame, "java/lang/OutOfMemoryError", Some("Java heap space"));
+        return;
     }
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_ref(Some(boxed((*this).borrow().clone())));
+        .push_ref(Some(Heap::allocate((*this).borrow().clone())));
+}
+
+// public final native void wait(long timeout) throws InterruptedException;
+// (J)V
+pub fn wait(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_this().unwrap();
+    let timeout = vars.get_long(1);
+    if timeout < 0 {
+        throw_exception(
+            frame,
+            "java/lang/IllegalArgumentException",
+            Some("timeout value is negative"),
+        );
+        return;
+    }
+    let monitor = (*this).borrow().monitor();
+    let os_thread = JavaThread::current_thread_object()
+        .and_then(|thread_object| (*thread_object).borrow().os_thread());
+    match monitor.wait(timeout as u64, os_thread.as_deref()) {
+        WaitResult::Resumed => {}
+        WaitResult::Interrupted => throw_exception(frame, "java/lang/InterruptedException", None),
+        WaitResult::NotOwner => {
+            throw_exception(frame, "java/lang/IllegalMonitorStateException", None)
+        }
+    }
+}
+
+// public final native void notify();
+// ()V
+pub fn notify(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_this().unwrap();
+    let monitor = (*this).borrow().monitor();
+    if !monitor.notify() {
+        throw_exception(frame, "java/lang/IllegalMonitorStateException", None);
+    }
+}
+
+// public final native void notifyAll();
+// ()V
+pub fn notify_all(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_this().unwrap();
+    let monitor = (*this).borrow().monitor();
+    if !monitor.notify_all() {
+        throw_exception(frame, "java/lang/IllegalMonitorStateException", None);
+    }
 }
 
 #[cfg(test)]
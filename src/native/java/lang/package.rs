@@ -1,3 +1,4 @@
+use crate::gc::heap::Heap;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::jvm::Jvm;
@@ -7,7 +8,7 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use crate::oops::object::{Object, DataType};
 use crate::oops::array_object::ArrayObject;
-use crate::utils::{boxed, java_str_to_rust_str};
+use crate::utils::java_str_to_rust_str;
 
 pub fn init() {
     Registry::register(
@@ -44,7 +45,7 @@ pub fn get_system_packages0(frame: &mut Frame) {
         data.push(Some(StringPool::java_string(iter.to_string())));
     }
     let packages = ArrayObject::from_data(string_class,DataType::References(data));
-    frame.operand_stack().expect("stack is none").push_ref(Some(boxed(packages)));
+    frame.operand_stack().expect("stack is none").push_ref(Some(Heap::allocate(packages)));
 }
 
 /// private static native String getSystemPackage0(String name);
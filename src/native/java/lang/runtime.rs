@@ -1,3 +1,4 @@
+use crate::gc::heap::Heap;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 
@@ -30,7 +31,7 @@ pub fn free_memory(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_long(100_000);
+        .push_long(Heap::max_size().saturating_sub(Heap::used()) as i64);
 }
 
 /// public native long totalMemory();
@@ -39,7 +40,7 @@ pub fn total_memory(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_long(100_0000);
+        .push_long(Heap::max_size() as i64);
 }
 
 /// public native long maxMemory();
@@ -48,12 +49,14 @@ pub fn max_memory(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_long(100_0000);
+        .push_long(Heap::max_size() as i64);
 }
 
 /// public native void gc();
 /// ()V
-pub fn gc(frame: &mut Frame) {}
+pub fn gc(_frame: &mut Frame) {
+    Heap::gc();
+}
 
 #[cfg(test)]
 mod test {
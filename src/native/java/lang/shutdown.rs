@@ -0,0 +1,59 @@
+use crate::native::registry::Registry;
+use crate::runtime::frame::Frame;
+use std::io::{stderr, stdout, Write};
+use std::process;
+
+pub fn init() {
+    Registry::register("java/lang/Shutdown", "beforeHalt", "()V", before_halt);
+    Registry::register("java/lang/Shutdown", "halt0", "(I)V", halt0);
+}
+
+/// static native void beforeHalt();
+/// ()V
+pub fn before_halt(_frame: &mut Frame) {}
+
+/// static native void halt0(int status);
+/// (I)V
+pub fn halt0(frame: &mut Frame) {
+    let status = frame.local_vars().expect("vars is none").get_int(0);
+    halt(status);
+}
+
+/// ends the process with the status, what was written to the standard
+/// streams is flushed first
+fn halt(status: i32) -> ! {
+    let _ = stdout().flush();
+    let _ = stderr().flush();
+    process::exit(status);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::native::java::lang::shutdown::halt;
+    use std::env;
+    use std::process::Command;
+
+    const HALT_STATUS: &str = "LARK_TEST_HALT_STATUS";
+
+    /// the process the test below starts, it does nothing run on its own
+    #[test]
+    fn halt_child() {
+        if let Ok(status) = env::var(HALT_STATUS) {
+            print!("before halt");
+            halt(status.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_halt() {
+        let output = Command::new(env::current_exe().unwrap())
+            .args(["--exact", "native::java::lang::shutdown::test::halt_child"])
+            .args(["--nocapture", "--test-threads=1"])
+            .env(HALT_STATUS, "3")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("before halt"));
+    }
+}
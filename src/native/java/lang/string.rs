@@ -1,3 +1,4 @@
+use crate::invoke_support::throw_exception;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::string_pool::StringPool;
@@ -9,7 +10,8 @@ pub fn init() {
 pub fn intern(frame: &mut Frame) {
     let this = frame.local_vars().expect("vars is none").get_this();
     if this.is_none() {
-        panic!("java.lang.NullPointerException");
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
     }
     let interned = StringPool::intern_string(this.unwrap());
     frame
@@ -17,3 +19,21 @@ pub fn intern(frame: &mut Frame) {
         .expect("stack is none")
         .push_ref(Some(interned));
 }
+
+#[cfg(test)]
+mod test {
+    use crate::invoke_support::thrown;
+    use crate::native::java::lang::string::intern;
+    use crate::oops::method::Method;
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_intern_null() {
+        let thread = boxed(JavaThread::new_thread());
+        let mut frame = JavaThread::new_frame(thread, Rc::new(Method::with_code(1, 1, vec![])));
+        intern(&mut frame);
+        assert_eq!(thrown::take_class_name(), "java/lang/NullPointerException");
+    }
+}
@@ -1,4 +1,7 @@
+use crate::class_path::class_path::PATH_LIST_SEPARATOR;
 use crate::instructions::base::method_invoke_logic::hack_invoke_method;
+use crate::invoke_support::throw_exception;
+use crate::jvm::Jvm;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::array_object::ArrayObject;
@@ -10,7 +13,9 @@ use crate::utils::java_str_to_rust_str;
 use chrono::Local;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::MAIN_SEPARATOR;
 use std::rc::Rc;
+use crate::native::java::lang::java_props;
 use crate::native::java::lang::object::hash_code;
 
 pub fn init() {
@@ -63,24 +68,32 @@ pub fn init() {
 pub fn array_copy(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
     let src = vars.get_ref(0);
-    let src_pos = vars.get_int(1) as usize;
+    let src_pos = vars.get_int(1);
     let dest = vars.get_ref(2);
-    let dest_pos = vars.get_int(3) as usize;
-    let length = vars.get_int(4) as usize;
+    let dest_pos = vars.get_int(3);
+    let length = vars.get_int(4);
     if src.is_none() || dest.is_none() {
-        panic!("java.lang.NullPointerException");
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
     }
     let src = src.unwrap();
     let dest = dest.unwrap();
     if !check_array_copy(src.clone(), dest.clone()) {
-        panic!("java.lang.ArrayStoreException");
+        throw_exception(frame, "java/lang/ArrayStoreException", Some("arraycopy: type mismatch"));
+        return;
     }
-    if src_pos + length > (*src).borrow().array_length()
-        || dest_pos + length > (*dest).borrow().array_length()
+    let src_len = (*src).borrow().array_length() as i64;
+    let dest_len = (*dest).borrow().array_length() as i64;
+    if src_pos < 0
+        || dest_pos < 0
+        || length < 0
+        || src_pos as i64 + length as i64 > src_len
+        || dest_pos as i64 + length as i64 > dest_len
     {
-        panic!("java.lang.IndexOutOfBoundsException");
+        throw_exception(frame, "java/lang/ArrayIndexOutOfBoundsException", Some("arraycopy: index out of bounds"));
+        return;
     }
-    ArrayObject::array_copy(src, dest, src_pos, dest_pos, length);
+    ArrayObject::array_copy(src, dest, src_pos as usize, dest_pos as usize, length as usize);
 }
 
 fn check_array_copy(src: Rc<RefCell<Object>>, dest: Rc<RefCell<Object>>) -> bool {
@@ -133,41 +146,68 @@ pub fn init_properties(frame: &mut Frame) {
 }
 
 fn _sys_props() -> HashMap<String, String> {
+    let jvm = Jvm::instance().unwrap();
+    let class_path = Jvm::boot_class_loader().class_path();
+    let java_home = class_path.jre_dir().clone();
+    let (user_name, user_home) = java_props::user_name_and_home();
+    let locale = java_props::Locale::from_env();
+
     let mut map = HashMap::new();
     map.insert("java.security.debug".to_owned(), "access".to_owned());
     map.insert("java.security.auth.debug".to_owned(), "access".to_owned());
     map.insert("java.version".to_owned(), "1.8.0".to_owned());
     map.insert("java.vendor".to_owned(), "jvm.rust".to_owned());
-    map.insert("java.home".to_owned(), "D:\\java8\\JDK".to_owned());
     map.insert("java.class.version".to_owned(), "52.0".to_owned());
     map.insert(
         "java.class.path".to_owned(),
-        //        "D:/java8/JDK/lib;D:/java8/JDK/lib/tools.jar;.;D:/workspace/rust-jvm/;".to_owned(),
-        "D:/workspace/rust-jvm/".to_owned(),
+        class_path.class_path_property(),
+    );
+    map.insert(
+        "sun.boot.class.path".to_owned(),
+        class_path.boot_class_path_property(),
+    );
+    map.insert(
+        "sun.boot.library.path".to_owned(),
+        java_props::boot_library_path(&java_home),
     );
+    map.insert("java.ext.dirs".to_owned(), java_props::ext_dirs(&java_home));
+    map.insert("java.library.path".to_owned(), java_props::library_path());
+    map.insert("java.io.tmpdir".to_owned(), java_props::tmp_dir());
+    map.insert(
+        "sun.java.command".to_owned(),
+        java_props::java_command(jvm.cmd()),
+    );
+    map.insert("java.home".to_owned(), java_home);
     //map.insert("sun.misc.URLClassPath.debug".to_owned(), "true".to_owned());
-    //    map.insert(
-    //        "java.library.path".to_owned(),
-    //        "D:\\java8\\JDK\\bin;C:\\WINDOWS\\Sun\\Java\\bin;C:\\WINDOWS\\system32;C:\\WINDOWS;C:\\ProgramData\\Oracle\\Java\\javapath;C:\\Program Files\\Docker\\Docker\\Resources\\bin;C:\\Program Files (x86)\\Intel\\iCLS Client\\;C:\\Program Files\\Intel\\iCLS Client\\;C:\\windows\\system32;C:\\windows;C:\\windows\\System32\\Wbem;C:\\windows\\System32\\WindowsPowerShell\\v1.0\\;C:\\Program Files (x86)\\Intel\\Intel(R) Management Engine Components\\DAL;C:\\Program Files\\Intel\\Intel(R) Management Engine Components\\DAL;C:\\Program Files (x86)\\Intel\\Intel(R) Management Engine Components\\IPT;C:\\Program Files\\Intel\\Intel(R) Management Engine Components\\IPT;C:\\Program Files (x86)\\NVIDIA Corporation\\PhysX\\Common;D:\\MinGw\\bin;D:\\Git\\cmd;D:\\NodeJs\\;C:\\WINDOWS\\system32;C:\\WINDOWS;C:\\WINDOWS\\System32\\Wbem;C:\\WINDOWS\\System32\\WindowsPowerShell\\v1.0\\;D:\\Maven\\apache-maven-3.5.3\\bin;C:\\WINDOWS\\System32\\OpenSSH\\;D:\\androidSDK\\Sdk\\platform-tools;D:\\androidSDK\\Sdk\\tools;D:\\Gradle\\gradle-5.6.3\\bin;D:\\java8\\JDK\\bin;D:\\java8\\JDK\\jre\\bin;D:\\Rust\\bin;C:\\Program Files\\dotnet\\;C:\\Program Files\\Microsoft SQL Server\\130\\Tools\\Binn\\;C:\\Program Files\\Microsoft SQL Server\\Client SDK\\ODBC\\170\\Tools\\Binn\\;C:\\Users\\xuhui\\.cargo\\bin;D:\\Python\\Scripts\\;D:\\Python\\;C:\\Users\\xuhui\\AppData\\Local\\Microsoft\\WindowsApps;C:\\Users\\xuhui\\AppData\\Roaming\\npm;D:\\NodeJs\\node_global;C:\\Users\\xuhui\\AppData\\Local\\BypassRuntm;D:\\VSCode\\Microsoft VS Code\\bin;C:\\Users\\xuhui\\AppData\\Local\\Microsoft\\WindowsApps;;.
-    //".to_owned()
-    //    );
     map.insert(
         "java.awt.graphicsenv".to_owned(),
         "sun.awt.CGraphicsEnvironment".to_owned(),
     );
-    map.insert("os.name".to_owned(), std::env::consts::OS.to_owned());
-    map.insert("os.arch".to_owned(), std::env::consts::ARCH.to_owned());
-    map.insert("os.version".to_owned(), "".to_owned());
-    map.insert("file.separator".to_owned(), "/".to_owned());
-    map.insert("path.separator".to_owned(), ";".to_owned());
-    map.insert("line.separator".to_owned(), "\n".to_owned());
-    map.insert("user.name".to_owned(), "".to_owned());
-    map.insert("user.home".to_owned(), "".to_owned());
-    map.insert("user.dir".to_owned(), "".to_owned());
-    map.insert("user.country".to_owned(), "CN".to_owned());
-    map.insert("file.encoding".to_owned(), "UTF-8".to_owned());
-    map.insert("sun.stdout.encoding".to_owned(), "UTF-8".to_owned());
-    map.insert("sun.stderr.encoding".to_owned(), "UTF-8".to_owned());
+    map.insert("os.name".to_owned(), java_props::os_name());
+    map.insert("os.arch".to_owned(), java_props::os_arch());
+    map.insert("os.version".to_owned(), java_props::os_version());
+    map.insert("file.separator".to_owned(), MAIN_SEPARATOR.to_string());
+    map.insert("path.separator".to_owned(), PATH_LIST_SEPARATOR.to_string());
+    let line_separator = if cfg!(windows) { "\r\n" } else { "\n" };
+    map.insert("line.separator".to_owned(), line_separator.to_owned());
+    map.insert("user.name".to_owned(), user_name);
+    map.insert("user.home".to_owned(), user_home);
+    map.insert("user.dir".to_owned(), java_props::user_dir());
+    map.insert("user.language".to_owned(), locale.language);
+    if !locale.country.is_empty() {
+        map.insert("user.country".to_owned(), locale.country);
+    }
+    if !locale.variant.is_empty() {
+        map.insert("user.variant".to_owned(), locale.variant);
+    }
+    map.insert("file.encoding".to_owned(), locale.encoding.clone());
+    map.insert("sun.jnu.encoding".to_owned(), locale.encoding.clone());
+    map.insert("sun.stdout.encoding".to_owned(), locale.encoding.clone());
+    map.insert("sun.stderr.encoding".to_owned(), locale.encoding);
+    // -D wins over what the VM works out itself
+    for (key, value) in jvm.cmd().system_properties() {
+        map.insert(key.clone(), value.clone());
+    }
 
     return map;
     //    return map[string]string{
@@ -245,3 +285,50 @@ pub fn nano_time(frame: &mut Frame) {
 pub fn identity_hash_code(frame: &mut Frame) {
     hash_code(frame)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::class_loader::test_classes::{ClassBuilder, TestLoader};
+    use crate::invoke_support::thrown;
+    use crate::native::java::lang::system::array_copy;
+    use crate::oops::method::Method;
+    use crate::oops::object::DataType::References;
+    use crate::oops::object::Object;
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Reference = Option<Rc<RefCell<Object>>>;
+
+    /// the exception of System.arraycopy(src, src_pos, dest, 0, length)
+    fn array_copy_thrown(src: Reference, src_pos: i32, dest: Reference, length: i32) -> String {
+        let thread = boxed(JavaThread::new_thread());
+        let mut frame = JavaThread::new_frame(thread, Rc::new(Method::with_code(0, 5, vec![])));
+        let vars = frame.local_vars().unwrap();
+        vars.set_ref(0, src);
+        vars.set_int(1, src_pos);
+        vars.set_ref(2, dest);
+        vars.set_int(3, 0);
+        vars.set_int(4, length);
+        array_copy(&mut frame);
+        return thrown::take_class_name();
+    }
+
+    #[test]
+    fn test_array_copy() {
+        let loader = TestLoader::new();
+        let class = loader.define(&ClassBuilder::new("a/A"));
+        let mut array = Object::new(loader.define(&ClassBuilder::new("[La/A;")));
+        array.data = References(vec![None; 2]);
+        let array = Some(boxed(array));
+        let object = Some(boxed(Object::new(class)));
+
+        let thrown = array_copy_thrown(None, 0, array.clone(), 1);
+        assert_eq!(thrown, "java/lang/NullPointerException");
+        let thrown = array_copy_thrown(object, 0, array.clone(), 1);
+        assert_eq!(thrown, "java/lang/ArrayStoreException");
+        let thrown = array_copy_thrown(array.clone(), 1, array, 2);
+        assert_eq!(thrown, "java/lang/ArrayIndexOutOfBoundsException");
+    }
+}
@@ -1,11 +1,13 @@
-use crate::class_loader::app_class_loader::ClassLoader;
-use crate::jvm::Jvm;
+use crate::invoke_support::throw_exception;
 use crate::native::registry::Registry;
+use crate::oops::method_ref::MethodRef;
+use crate::oops::object::MetaData;
 use crate::runtime::frame::Frame;
-use crate::oops::class::Class;
-use crate::oops::string_pool::StringPool;
-use crate::utils::boxed;
-use std::{thread, time};
+use crate::runtime::os_thread::OsThread;
+use crate::runtime::thread::{JavaThread, THREAD_STATUS_NEW, THREAD_STATUS_RUNNABLE};
+use crate::runtime::vm_lock;
+use std::sync::Arc;
+use std::thread;
 
 pub fn init() {
     Registry::register(
@@ -20,77 +22,152 @@ pub fn init() {
     Registry::register("java/lang/Thread", "sleep", "(J)V", sleep);
     Registry::register("java/lang/Thread", "yield", "()V", java_yield);
     Registry::register("java/lang/Thread", "isInterrupted", "(Z)Z", is_interrupted);
+    Registry::register("java/lang/Thread", "interrupt0", "()V", interrupt0);
+    Registry::register("java/lang/Thread", "holdsLock", "(Ljava/lang/Object;)Z", holds_lock);
 }
 
 pub fn current_thread(frame: &mut Frame) {
-    let class = frame.method().class();
-    let loader = Jvm::boot_class_loader();
-    let thread_class = loader.find_or_create("java/lang/Thread").unwrap();
-    let mut java_thread = Class::new_object(&thread_class);
-    java_thread.set_ref_var(
-        "name",
-        "Ljava/lang/String;",
-        StringPool::java_string("Main".to_string()),
-    );
-
-    let thread_group_class = loader.find_or_create("java/lang/ThreadGroup").unwrap();
-    let mut java_thread_group = Class::new_object(&thread_group_class);
-    java_thread.set_ref_var("group", "Ljava/lang/ThreadGroup;", boxed(java_thread_group));
-    java_thread.set_int_var("priority", "I", 1);
-
+    let java_thread = JavaThread::current_thread_object();
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_ref(Some(boxed(java_thread)));
+        .push_ref(java_thread);
 }
 
 // private native void setPriority0(int newPriority);
 // (I)V
 pub fn set_priority0(frame: &mut Frame) {
-    // vars := frame.LocalVars()
-    // this := vars.GetThis()
-    // newPriority := vars.GetInt(1))
-    // todo
+    let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_this().unwrap();
+    let new_priority = vars.get_int(1);
+    let os_thread = (*this).borrow().os_thread();
+    if let Some(os_thread) = os_thread {
+        os_thread.set_priority(new_priority);
+    }
 }
 
 // public final native boolean isAlive();
 // ()Z
 pub fn is_alive(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_this().unwrap();
+    let alive = (*this).borrow().get_long_var("eetop", "J") != 0;
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_boolean(false);
+        .push_boolean(alive);
 }
 
 // private native void start0();
 // ()V
 pub fn start0(frame: &mut Frame) {
-    // todo
+    let this = frame.local_vars().expect("vars is none").get_this().unwrap();
+    let class = (*this).borrow().class();
+    let run = MethodRef::look_up_method_in_class(class, "run", "()V").unwrap();
+    let (priority, name, daemon) = {
+        let borrow = (*this).borrow();
+        let name = borrow
+            .get_ref_var("name", "[C")
+            .map(|chars| String::from_utf16_lossy((*chars).borrow().chars()))
+            .unwrap_or_default();
+        let daemon = borrow.get_int_var("daemon", "Z") != 0;
+        (borrow.get_int_var("priority", "I"), name, daemon)
+    };
+    let os_thread = Arc::new(OsThread::new(priority));
+    {
+        let mut borrow = (*this).borrow_mut();
+        borrow.set_long_var("eetop", "J", Arc::as_ptr(&os_thread) as i64);
+        borrow.set_int_var("threadStatus", "I", THREAD_STATUS_RUNNABLE);
+        borrow.set_meta_data(MetaData::Thread(os_thread.clone()));
+    }
+    if JavaThread::start(this.clone(), run, os_thread, name, daemon).is_err() {
+        // the thread stays new, Thread.start lets the error through
+        {
+            let mut borrow = (*this).borrow_mut();
+            borrow.set_long_var("eetop", "J", 0);
+            borrow.set_int_var("threadStatus", "I", THREAD_STATUS_NEW);
+            borrow.set_meta_data(MetaData::Null);
+        }
+        throw_exception(
+            frame,
+            "java/lang/OutOfMemoryError",
+            Some("unable to create new native thread"),
+        );
+    }
+}
+
+// private native void interrupt0();
+// ()V
+pub fn interrupt0(frame: &mut Frame) {
+    let this = frame.local_vars().expect("vars is none").get_this().unwrap();
+    let os_thread = (*this).borrow().os_thread();
+    if let Some(os_thread) = os_thread {
+        os_thread.interrupt();
+    }
 }
 
 // public static native void sleep(long millis) throws InterruptedException;
 // (J)V
 pub fn sleep(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("vars is none");
-    let millis = vars.get_long(0);
-    let ten_millis = time::Duration::from_millis(millis as u64);
-    thread::sleep(ten_millis);
+    let millis = frame.local_vars().expect("vars is none").get_long(0);
+    if millis < 0 {
+        throw_exception(
+            frame,
+            "java/lang/IllegalArgumentException",
+            Some("timeout value is negative"),
+        );
+        return;
+    }
+    let os_thread = current_os_thread();
+    if !os_thread.sleep(millis as u64) {
+        throw_exception(
+            frame,
+            "java/lang/InterruptedException",
+            Some("sleep interrupted"),
+        );
+    }
 }
 
 //  public static native void yield();
 // ()V
-pub fn java_yield(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("vars is none");
-    thread::yield_now();
+pub fn java_yield(_frame: &mut Frame) {
+    vm_lock::blocking(thread::yield_now);
 }
 
 // private native boolean isInterrupted(boolean ClearInterrupted);
 // (Z)Z
-/// waiting for impl
 pub fn is_interrupted(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
+    let this = vars.get_this().unwrap();
+    let clear_interrupted = vars.get_boolean(1);
+    let os_thread = (*this).borrow().os_thread();
+    let interrupted = match os_thread {
+        Some(os_thread) => os_thread.is_interrupted(clear_interrupted),
+        None => false,
+    };
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(interrupted);
+}
+
+/// the OsThread of the thread running Java code on this OS thread
+pub fn current_os_thread() -> Arc<OsThread> {
+    let thread_object = JavaThread::current_thread_object().expect("no current thread");
+    let os_thread = (*thread_object).borrow().os_thread();
+    return os_thread.expect("current thread is not started");
+}
+
+// public static native boolean holdsLock(Object obj);
+// (Ljava/lang/Object;)Z
+pub fn holds_lock(frame: &mut Frame) {
+    let object = frame.local_vars().expect("vars is none").get_ref(0);
+    if object.is_none() {
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
+    }
+    let holds = (*object.unwrap()).borrow().monitor().is_owned_by_current_thread();
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_boolean(false);
+        .push_boolean(holds);
 }
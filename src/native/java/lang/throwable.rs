@@ -1,13 +1,19 @@
+use crate::gc::heap::Heap;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
+use crate::oops::method::Method;
 use crate::oops::object::Object;
 use crate::runtime::thread::JavaThread;
 use std::cell::RefCell;
+use std::ops::Deref;
 use std::rc::Rc;
 use crate::jvm::Jvm;
 use crate::oops::string_pool::StringPool;
-use crate::utils::boxed;
+use crate::utils::java_str_to_rust_str;
+
+/// MaxJavaStackTraceDepth, the stack of a StackOverflowError is too deep to record
+const MAX_STACK_TRACE_DEPTH: usize = 1024;
 
 pub fn init() {
     Registry::register(
@@ -37,7 +43,7 @@ pub fn fill_in_stack_trace(frame: &mut Frame) {
         .expect("stack is none")
         .push_ref(this.clone());
     let ptr = this.unwrap();
-    let stes = StackTraceElement::create_stack_trace_elements(ptr.clone(), frame.thread());
+    let stes = StackTraceElement::create_stack_trace_elements(ptr.clone());
     (*ptr).borrow_mut().set_trace(stes);
 }
 
@@ -46,8 +52,7 @@ pub fn fill_in_stack_trace(frame: &mut Frame) {
 pub fn get_stack_trace_depth(frame: &mut Frame) {
     let this = frame.local_vars().expect("vars is none").get_this();
     let ptr = this.unwrap();
-    let depth = (*ptr).borrow().trace().unwrap().len();
-    println!("----depth:{}",depth);
+    let depth = (*ptr).borrow().trace().map_or(0, |trace| trace.len());
     frame
         .operand_stack()
         .expect("stack is none")
@@ -78,11 +83,13 @@ fn create_java_stack_trace_element(element:&StackTraceElement) -> Option<Rc<RefC
         "Ljava/lang/String;",
         StringPool::java_string(element.class_name.clone())
     );
-    object.set_ref_var(
-        "fileName",
-        "Ljava/lang/String;",
-        StringPool::java_string(element.file_name.clone())
-    );
+    if let Some(file_name) = &element.file_name {
+        object.set_ref_var(
+            "fileName",
+            "Ljava/lang/String;",
+            StringPool::java_string(file_name.clone())
+        );
+    }
     object.set_ref_var(
         "methodName",
         "Ljava/lang/String;",
@@ -93,51 +100,61 @@ fn create_java_stack_trace_element(element:&StackTraceElement) -> Option<Rc<RefC
         "I",
         element.line_number
     );
-    Some(boxed(object))
+    Some(Heap::allocate(object))
+}
+
+/// prints an uncaught exception like ThreadGroup.uncaughtException does,
+/// used when that cannot run
+pub fn print_uncaught_exception(thread_name: &str, exception: Rc<RefCell<Object>>) {
+    let borrow = (*exception).borrow();
+    let class = borrow.class();
+    let mut description = (*class).borrow().java_name();
+    if let Some(message) = borrow.get_ref_var("detailMessage", "Ljava/lang/String;") {
+        description.push_str(": ");
+        description.push_str(java_str_to_rust_str(message).as_str());
+    }
+    eprintln!("Exception in thread \"{}\" {}", thread_name, description);
+    for element in borrow.trace().into_iter().flatten() {
+        eprintln!("\tat {}", element.to_string());
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct StackTraceElement {
-    file_name: String,
+    file_name: Option<String>,
     class_name: String,
     method_name: String,
     line_number: i32,
 }
 
 impl StackTraceElement {
-    fn create_stack_trace_elements(
-        object: Rc<RefCell<Object>>,
-        thread: Rc<RefCell<JavaThread>>,
-    ) -> Vec<StackTraceElement> {
-        let skip = StackTraceElement::distance_to_object((*object).borrow().class()) as usize + 2;
-        let thread_borrow = (*thread).borrow();
-        let all_frames = thread_borrow.get_frames();
-        let mut stes = Vec::with_capacity(all_frames.len() - skip);
-        for i in 0..(all_frames.len() - skip) {
-            stes.push(Self::create_stack_trace_element(all_frames[i].clone()));
-        }
-        return stes;
-    }
-
-    fn distance_to_object(class: Rc<RefCell<Class>>) -> i32 {
-        let mut distance = 0;
-        let mut c = (*class).borrow().super_class();
-        while c.is_some() {
-            distance += 1;
-            c = (*c.unwrap()).borrow().super_class();
-        }
-        return distance;
+    /// the Java stack of the current thread, top first, without the frames
+    /// of fillInStackTrace and of the constructors of the throwable, at
+    /// most MAX_STACK_TRACE_DEPTH frames
+    fn create_stack_trace_elements(object: Rc<RefCell<Object>>) -> Vec<StackTraceElement> {
+        let object_class = (*object).borrow().class();
+        let is_own_constructor = |method: &Method| {
+            let class = method.class();
+            method.name() == "<init>"
+                && (class == object_class
+                    || (*object_class).borrow().is_sub_class_of((*class).borrow().deref()))
+        };
+        return JavaThread::stack_trace()
+            .into_iter()
+            .skip_while(|(method, _)| method.name() == "fillInStackTrace")
+            .skip_while(|(method, _)| is_own_constructor(method))
+            .take(MAX_STACK_TRACE_DEPTH)
+            .map(|(method, pc)| Self::create_stack_trace_element(&method, pc))
+            .collect();
     }
 
-    fn create_stack_trace_element(frame: Rc<RefCell<Frame>>) -> StackTraceElement {
-        let frame_borrow = (*frame).borrow();
-        let method = frame_borrow.method();
+    fn create_stack_trace_element(method: &Method, pc: i32) -> StackTraceElement {
         let class = method.class();
         return StackTraceElement {
             file_name: (*class).borrow().source_file(),
             class_name: (*class).borrow().java_name(),
             method_name: method.name().to_string(),
-            line_number: method.get_line_number(frame_borrow.next_pc() - 1),
+            line_number: method.get_line_number(pc),
         };
     }
 }
@@ -149,10 +166,40 @@ impl ToString for StackTraceElement {
         except_str.push('.');
         except_str.push_str(self.method_name.as_str());
         except_str.push('(');
-        except_str.push_str(self.file_name.as_str());
-        except_str.push(':');
-        except_str.push_str(self.line_number.to_string().as_str());
+        match &self.file_name {
+            _ if self.line_number == -2 => except_str.push_str("Native Method"),
+            Some(file_name) if self.line_number >= 0 => {
+                except_str.push_str(file_name.as_str());
+                except_str.push(':');
+                except_str.push_str(self.line_number.to_string().as_str());
+            }
+            Some(file_name) => except_str.push_str(file_name.as_str()),
+            None => except_str.push_str("Unknown Source"),
+        }
         except_str.push(')');
         return except_str;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::native::java::lang::throwable::StackTraceElement;
+
+    fn element(file_name: Option<&str>, line_number: i32) -> String {
+        return StackTraceElement {
+            file_name: file_name.map(|name| name.to_string()),
+            class_name: "testJava.Main".to_string(),
+            method_name: "main".to_string(),
+            line_number,
+        }
+        .to_string();
+    }
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(element(Some("Main.java"), 12), "testJava.Main.main(Main.java:12)");
+        assert_eq!(element(Some("Main.java"), -1), "testJava.Main.main(Main.java)");
+        assert_eq!(element(None, 12), "testJava.Main.main(Unknown Source)");
+        assert_eq!(element(Some("Main.java"), -2), "testJava.Main.main(Native Method)");
+    }
+}
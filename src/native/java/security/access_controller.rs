@@ -1,12 +1,12 @@
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::Heap;
 use crate::instructions::base::method_invoke_logic::invoke_method;
 use crate::invoke_support::parameter::{Parameter, Parameters};
-use crate::invoke_support::{invoke, ReturnType};
+use crate::invoke_support::{invoke, throw_exception, ReturnType};
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
 use crate::oops::object::Object;
-use crate::utils::boxed;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -47,7 +47,8 @@ pub fn init() {
 pub fn do_privileged(frame: &mut Frame) {
     let this = frame.local_vars().expect("vars is none").get_this();
     if this.is_none() {
-        panic!("java.lang.NullPointerException");
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
     }
     let class = (*this.clone().unwrap()).borrow().class();
     let method = Class::get_instance_method(class, "run", "()Ljava/lang/Object;").unwrap();
@@ -58,7 +59,8 @@ pub fn do_privileged(frame: &mut Frame) {
 pub fn run(frame: &mut Frame) {
     let this = frame.local_vars().expect("vars is none").get_this();
     if this.is_none() {
-        panic!("java.lang.NullPointerException");
+        throw_exception(frame, "java/lang/NullPointerException", None);
+        return;
     }
     let class = (*this.clone().unwrap()).borrow().class();
     let method = Class::get_instance_method(class, "run", "()I").unwrap();
@@ -77,11 +79,11 @@ pub fn get_stack_access_control_context(frame: &mut Frame) {
 }
 
 fn create() -> Option<Rc<RefCell<Object>>> {
-    let class = ClassLoader::load_class(None, "[Ljava/security/ProtectionDomain");
-    let args = boxed(Class::new_array(&class, 0));
-    let class = ClassLoader::load_class(None, "java/security/AccessControlContext");
+    let class = ClassLoader::load_class(None, "[Ljava/security/ProtectionDomain").unwrap();
+    let args = Heap::allocate(Class::new_array(&class, 0));
+    let class = ClassLoader::load_class(None, "java/security/AccessControlContext").unwrap();
 
-    let object = boxed(Object::new(class.clone()));
+    let object = Heap::allocate(Object::new(class.clone()));
 
     let method =
         Class::get_instance_method(class, "<init>", "([Ljava/security/ProtectionDomain;)V");
@@ -92,3 +94,26 @@ fn create() -> Option<Rc<RefCell<Object>>> {
     invoke(method.unwrap(), Some(params), ReturnType::Void);
     return Some(object);
 }
+
+#[cfg(test)]
+mod test {
+    use crate::invoke_support::thrown;
+    use crate::native::java::security::access_controller::{do_privileged, run};
+    use crate::oops::method::Method;
+    use crate::runtime::frame::Frame;
+    use crate::runtime::thread::JavaThread;
+    use crate::utils::boxed;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_null_action() {
+        let natives: [fn(&mut Frame); 2] = [do_privileged, run];
+        for native in &natives {
+            let thread = boxed(JavaThread::new_thread());
+            let method = Rc::new(Method::with_code(1, 1, vec![]));
+            let mut frame = JavaThread::new_frame(thread, method);
+            native(&mut frame);
+            assert_eq!(thrown::take_class_name(), "java/lang/NullPointerException");
+        }
+    }
+}
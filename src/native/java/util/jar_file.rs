@@ -1,10 +1,10 @@
+use crate::gc::heap::Heap;
 use crate::jvm::Jvm;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::array_object::ArrayObject;
 use crate::oops::object::DataType;
 use crate::oops::string_pool::StringPool;
-use crate::utils::boxed;
 
 pub fn init() {
     Registry::register(
@@ -40,5 +40,5 @@ pub fn get_meta_inf_entry_names(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_ref(Some(boxed(object)));
+        .push_ref(Some(Heap::allocate(object)));
 }
@@ -1,3 +1,4 @@
+use crate::gc::heap::Heap;
 use crate::jni::JObject;
 use crate::jvm::Jvm;
 use crate::native::java::util::zip_file::zip_file_cache::ZipFile;
@@ -5,7 +6,7 @@ use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::array_object::ArrayObject;
 use crate::oops::object::DataType::Bytes;
-use crate::utils::{boxed, java_str_to_rust_str, jbytes_to_u8s};
+use crate::utils::{java_str_to_rust_str, jbytes_to_u8s};
 use chrono::{DateTime, Utc};
 use podio::ReadPodExt;
 use rc_zip::{Archive, ReadZip, StoredEntry};
@@ -181,7 +182,7 @@ pub fn get_entry_bytes(frame: &mut Frame) {
         frame
             .operand_stack()
             .expect("stack is none")
-            .push_ref(Some(boxed(object)));
+            .push_ref(Some(Heap::allocate(object)));
     }
 }
 
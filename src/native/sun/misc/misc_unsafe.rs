@@ -1,13 +1,34 @@
+use crate::class_loader::app_class_loader::ClassLoader;
 use crate::instructions::base::class_init_logic::init_class;
-use crate::native::registry::Registry;
-use crate::runtime::frame::Frame;
+use crate::instructions::references::athrow::AThrow;
+use crate::instructions::references::new::New;
+use crate::invoke_support::throw_exception;
+use crate::native::java::lang::thread::current_os_thread;
+use crate::native::registry::{NativeMethod, Registry};
 use crate::oops::class::Class;
-use crate::oops::object::DataType::{Ints, StandardObject};
-use crate::utils::numbers::get_power_of_two;
+use crate::oops::object::DataType::{
+    Bytes, Chars, Doubles, Floats, Ints, Longs, References, Shorts,
+};
+use crate::oops::object::Object;
+use crate::oops::slots::Slots;
+use crate::runtime::frame::Frame;
+use crate::runtime::local_vars::LocalVars;
 use std::alloc::Layout;
 use std::cell::RefCell;
 use std::mem::size_of;
+use std::ptr;
 use std::rc::Rc;
+use std::sync::atomic::{fence, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// the alignment of allocateMemory, like the one of malloc
+const MEMORY_ALIGNMENT: usize = 16;
+/// arrayIndexScale of reference arrays
+const REFERENCE_SIZE: usize = 4;
+/// static field offsets start above the instance field offsets of
+/// java.lang.Class, so the fields of a mirror and the static fields of the
+/// class it stands for can be told apart
+const STATIC_FIELD_OFFSET_BASE: i64 = 1 << 20;
 
 pub fn init() {
     Registry::register(
@@ -23,6 +44,7 @@ pub fn init() {
         array_index_scale,
     );
     Registry::register("sun/misc/Unsafe", "addressSize", "()I", address_size);
+    Registry::register("sun/misc/Unsafe", "pageSize", "()I", page_size);
     Registry::register(
         "sun/misc/Unsafe",
         "objectFieldOffset",
@@ -31,53 +53,192 @@ pub fn init() {
     );
     Registry::register(
         "sun/misc/Unsafe",
-        "compareAndSwapObject",
-        "(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z",
-        compare_and_swap_object,
+        "staticFieldOffset",
+        "(Ljava/lang/reflect/Field;)J",
+        static_field_offset,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "staticFieldBase",
+        "(Ljava/lang/reflect/Field;)Ljava/lang/Object;",
+        static_field_base,
+    );
+
+    register_accessors("Boolean", "Z", get::<'Z'>, put::<'Z'>);
+    register_accessors("Byte", "B", get::<'B'>, put::<'B'>);
+    register_accessors("Short", "S", get::<'S'>, put::<'S'>);
+    register_accessors("Char", "C", get::<'C'>, put::<'C'>);
+    register_accessors("Int", "I", get::<'I'>, put::<'I'>);
+    register_accessors("Long", "J", get::<'J'>, put::<'J'>);
+    register_accessors("Float", "F", get::<'F'>, put::<'F'>);
+    register_accessors("Double", "D", get::<'D'>, put::<'D'>);
+    register_accessors("Object", "Ljava/lang/Object;", get::<'L'>, put::<'L'>);
+    register_absolute_accessors("Byte", "B", get_absolute::<'B'>, put_absolute::<'B'>);
+    register_absolute_accessors("Short", "S", get_absolute::<'S'>, put_absolute::<'S'>);
+    register_absolute_accessors("Char", "C", get_absolute::<'C'>, put_absolute::<'C'>);
+    register_absolute_accessors("Int", "I", get_absolute::<'I'>, put_absolute::<'I'>);
+    register_absolute_accessors("Long", "J", get_absolute::<'J'>, put_absolute::<'J'>);
+    register_absolute_accessors("Float", "F", get_absolute::<'F'>, put_absolute::<'F'>);
+    register_absolute_accessors("Double", "D", get_absolute::<'D'>, put_absolute::<'D'>);
+    register_absolute_accessors("Address", "J", get_absolute::<'J'>, put_absolute::<'J'>);
+    Registry::register(
+        "sun/misc/Unsafe",
+        "putOrderedObject",
+        "(Ljava/lang/Object;JLjava/lang/Object;)V",
+        put::<'L'>,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "putOrderedInt",
+        "(Ljava/lang/Object;JI)V",
+        put::<'I'>,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "putOrderedLong",
+        "(Ljava/lang/Object;JJ)V",
+        put::<'J'>,
     );
     Registry::register(
         "sun/misc/Unsafe",
-        "getIntVolatile",
-        "(Ljava/lang/Object;J)I",
-        get_int_volatile,
+        "compareAndSwapObject",
+        "(Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z",
+        compare_and_swap::<'L'>,
     );
     Registry::register(
         "sun/misc/Unsafe",
         "compareAndSwapInt",
         "(Ljava/lang/Object;JII)Z",
-        compare_and_swap_int,
+        compare_and_swap::<'I'>,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "compareAndSwapLong",
+        "(Ljava/lang/Object;JJJ)Z",
+        compare_and_swap::<'J'>,
     );
+    Registry::register("sun/misc/Unsafe", "loadFence", "()V", memory_fence);
+    Registry::register("sun/misc/Unsafe", "storeFence", "()V", memory_fence);
+    Registry::register("sun/misc/Unsafe", "fullFence", "()V", memory_fence);
+
     Registry::register("sun/misc/Unsafe", "allocateMemory", "(J)J", allocate_memory);
-    Registry::register("sun/misc/Unsafe", "putLong", "(JJ)V", put_long);
-    Registry::register("sun/misc/Unsafe", "getByte", "(J)B", get_byte);
+    Registry::register(
+        "sun/misc/Unsafe",
+        "reallocateMemory",
+        "(JJ)J",
+        reallocate_memory,
+    );
     Registry::register("sun/misc/Unsafe", "freeMemory", "(J)V", free_memory);
     Registry::register(
         "sun/misc/Unsafe",
-        "getObjectVolatile",
-        "(Ljava/lang/Object;J)Ljava/lang/Object;",
-        get_object_volatile,
+        "setMemory",
+        "(Ljava/lang/Object;JJB)V",
+        set_memory,
     );
     Registry::register(
         "sun/misc/Unsafe",
-        "compareAndSwapLong",
-        "(Ljava/lang/Object;JJJ)Z",
-        compare_and_swap_long,
+        "copyMemory",
+        "(Ljava/lang/Object;JLjava/lang/Object;JJ)V",
+        copy_memory,
     );
+
     Registry::register(
         "sun/misc/Unsafe",
         "ensureClassInitialized",
         "(Ljava/lang/Class;)V",
         ensure_class_initialized,
     );
-    Registry::register("sun/misc/Unsafe", "getLong", "(J)J", get_long);
+    Registry::register(
+        "sun/misc/Unsafe",
+        "shouldBeInitialized",
+        "(Ljava/lang/Class;)Z",
+        should_be_initialized,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "allocateInstance",
+        "(Ljava/lang/Class;)Ljava/lang/Object;",
+        allocate_instance,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "defineAnonymousClass",
+        "(Ljava/lang/Class;[B[Ljava/lang/Object;)Ljava/lang/Class;",
+        define_anonymous_class,
+    );
+    Registry::register(
+        "sun/misc/Unsafe",
+        "throwException",
+        "(Ljava/lang/Throwable;)V",
+        throw_throwable,
+    );
+    Registry::register("sun/misc/Unsafe", "park", "(ZJ)V", park);
+    Registry::register("sun/misc/Unsafe", "unpark", "(Ljava/lang/Object;)V", unpark);
+}
+
+/// get<Type>(Object, long) and put<Type>(Object, long, <type>) with their
+/// Volatile forms. Java code only runs holding the vm lock, every access is
+/// ordered like a volatile one already.
+fn register_accessors(type_name: &str, descriptor: &str, get: NativeMethod, put: NativeMethod) {
+    let get_descriptor = format!("(Ljava/lang/Object;J){}", descriptor);
+    let put_descriptor = format!("(Ljava/lang/Object;J{})V", descriptor);
+    for suffix in &["", "Volatile"] {
+        let name = format!("get{}{}", type_name, suffix);
+        Registry::register(
+            "sun/misc/Unsafe",
+            name.as_str(),
+            get_descriptor.as_str(),
+            get,
+        );
+        let name = format!("put{}{}", type_name, suffix);
+        Registry::register(
+            "sun/misc/Unsafe",
+            name.as_str(),
+            put_descriptor.as_str(),
+            put,
+        );
+    }
+}
+
+/// get<Type>(long address) and put<Type>(long address, <type>)
+fn register_absolute_accessors(
+    type_name: &str,
+    descriptor: &str,
+    get: NativeMethod,
+    put: NativeMethod,
+) {
+    let get_descriptor = format!("(J){}", descriptor);
+    let put_descriptor = format!("(J{})V", descriptor);
+    let name = format!("get{}", type_name);
+    Registry::register(
+        "sun/misc/Unsafe",
+        name.as_str(),
+        get_descriptor.as_str(),
+        get,
+    );
+    let name = format!("put{}", type_name);
+    Registry::register(
+        "sun/misc/Unsafe",
+        name.as_str(),
+        put_descriptor.as_str(),
+        put,
+    );
 }
 
 pub fn array_base_offset(frame: &mut Frame) {
     frame.operand_stack().expect("stack is none").push_int(0);
 }
 
+/// the offset of an array element is its index times the scale in bytes
 pub fn array_index_scale(frame: &mut Frame) {
-    frame.operand_stack().expect("stack is none").push_int(1);
+    let vars = frame.local_vars().expect("vars is none");
+    let class = vars.get_ref(1).unwrap();
+    let class = (*class).borrow().meta().unwrap();
+    let component = (*class).borrow().name().chars().nth(1).unwrap_or('L');
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_int(size_of_type(component) as i32);
 }
 
 pub fn address_size(frame: &mut Frame) {
@@ -87,6 +248,25 @@ pub fn address_size(frame: &mut Frame) {
         .push_int(size_of::<usize>() as i32);
 }
 
+/// public native int pageSize();
+/// ()I
+pub fn page_size(frame: &mut Frame) {
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_int(os_page_size());
+}
+
+#[cfg(unix)]
+fn os_page_size() -> i32 {
+    return unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as i32;
+}
+
+#[cfg(not(unix))]
+fn os_page_size() -> i32 {
+    return 4096;
+}
+
 // public native long objectFieldOffset(Field field);
 // (Ljava/lang/reflect/Field;)J
 pub fn object_field_offset(frame: &mut Frame) {
@@ -99,88 +279,392 @@ pub fn object_field_offset(frame: &mut Frame) {
     stack.push_long(offset as i64);
 }
 
-// public final native boolean compareAndSwapObject(Object o, long offset, Object expected, Object x)
-// (Ljava/lang/Object;JLjava/lang/Object;Ljava/lang/Object;)Z
-pub fn compare_and_swap_object(frame: &mut Frame) {
-    //    vars := frame.LocalVars()
-    //    obj := vars.GetRef(1)
-    //    fields := obj.Data()
-    //    offset := vars.GetLong(2)
-    //    expected := vars.GetRef(4)
-    //    newVal := vars.GetRef(5)
-    //
-    //    // todo
-    //    if anys, ok := fields.(heap.Slots); ok {
-    //    // object
-    //        swapped := _casObj(obj, anys, offset, expected, newVal)
-    //        frame.OperandStack().PushBoolean(swapped)
-    //    } else if objs, ok := fields.([]*heap.Object); ok {
-    //    // ref[]
-    //        swapped := _casArr(objs, offset, expected, newVal)
-    //        frame.OperandStack().PushBoolean(swapped)
-    //    } else {
-    //    // todo
-    //        panic("todo: compareAndSwapObject!")
-    //    }
+/// public native long staticFieldOffset(Field f);
+/// (Ljava/lang/reflect/Field;)J
+pub fn static_field_offset(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let j_field = vars.get_ref(1).unwrap();
+
+    let slot = (*j_field).borrow().get_int_var("slot", "I");
+
     let stack = frame.operand_stack().expect("stack is none");
-    stack.push_boolean(true);
+    stack.push_long(STATIC_FIELD_OFFSET_BASE + slot as i64);
 }
 
-// public native boolean getInt(Object o, long offset);
-// (Ljava/lang/Object;J)I
-pub fn get_int_volatile(frame: &mut Frame) {
+/// public native Object staticFieldBase(Field f);
+/// (Ljava/lang/reflect/Field;)Ljava/lang/Object;
+pub fn static_field_base(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
-    let object = vars.get_ref(1).unwrap();
-    let borrow = (*object).borrow();
-    let data = borrow.data();
-    let offset = vars.get_long(2) as usize;
+    let j_field = vars.get_ref(1).unwrap();
+
+    // the static fields are accessed through the mirror of the declaring class
+    let class = (*j_field)
+        .borrow()
+        .get_ref_var("clazz", "Ljava/lang/Class;");
 
     let stack = frame.operand_stack().expect("stack is none");
-    match data {
-        StandardObject(inner) => {
-            let slots = inner.as_ref().unwrap();
-            stack.push_int(slots.get_int(offset))
+    stack.push_ref(class);
+}
+
+/// a value the accessors read or write, byte, short, char and boolean are
+/// widened to int
+enum Value {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Reference(Option<Rc<RefCell<Object>>>),
+}
+
+impl Value {
+    /// the argument of the type at index
+    fn from_vars(vars: &LocalVars, index: usize, kind: char) -> Value {
+        return match kind {
+            'J' => Value::Long(vars.get_long(index)),
+            'F' => Value::Float(vars.get_float(index)),
+            'D' => Value::Double(vars.get_double(index)),
+            'L' => Value::Reference(vars.get_ref(index)),
+            _ => Value::Int(vars.get_int(index)),
+        };
+    }
+
+    fn push(self, frame: &mut Frame) {
+        let stack = frame.operand_stack().expect("stack is none");
+        match self {
+            Value::Int(value) => stack.push_int(value),
+            Value::Long(value) => stack.push_long(value),
+            Value::Float(value) => stack.push_float(value),
+            Value::Double(value) => stack.push_double(value),
+            Value::Reference(value) => stack.push_ref(value),
         }
-        Ints(inner) => stack.push_int(inner[offset]),
-        _ => panic!("getInt!"),
-    }
-}
-
-// public final native boolean compareAndSwapInt(Object o, long offset, int expected, int x);
-// (Ljava/lang/Object;JII)Z
-pub fn compare_and_swap_int(frame: &mut Frame) {
-    //    vars := frame.LocalVars()
-    //    fields := vars.GetRef(1).Data()
-    //    offset := vars.GetLong(2)
-    //    expected := vars.GetInt(4)
-    //    newVal := vars.GetInt(5)
-    //
-    //    if slots, ok := fields.(heap.Slots); ok {
-    //    // object
-    //        oldVal := slots.GetInt(uint(offset))
-    //        if oldVal == expected {
-    //            slots.SetInt(uint(offset), newVal)
-    //            frame.OperandStack().PushBoolean(true)
-    //        } else {
-    //            frame.OperandStack().PushBoolean(false)
-    //        }
-    //    } else if ints, ok := fields.([]int32); ok {
-    //    // int[]
-    //        oldVal := ints[offset]
-    //        if oldVal == expected {
-    //            ints[offset] = newVal
-    //            frame.OperandStack().PushBoolean(true)
-    //        } else {
-    //            frame.OperandStack().PushBoolean(false)
-    //        }
-    //    } else {
-    //        // todo
-    //        panic("todo: compareAndSwapInt!")
-    //    }
+    }
+
+    /// what compareAndSwap compares, references by identity
+    fn same_as(&self, other: &Value) -> bool {
+        return match (self, other) {
+            (Value::Int(value), Value::Int(other)) => value == other,
+            (Value::Long(value), Value::Long(other)) => value == other,
+            (Value::Reference(Some(value)), Value::Reference(Some(other))) => {
+                Rc::ptr_eq(value, other)
+            }
+            (Value::Reference(None), Value::Reference(None)) => true,
+            _ => false,
+        };
+    }
+}
+
+/// the bytes a value of the type takes in memory and in arrays
+fn size_of_type(kind: char) -> usize {
+    return match kind {
+        'Z' | 'B' => 1,
+        'S' | 'C' => 2,
+        'I' | 'F' => 4,
+        'J' | 'D' => 8,
+        _ => REFERENCE_SIZE,
+    };
+}
+
+fn slot_count(kind: char) -> usize {
+    return match kind {
+        'J' | 'D' => 2,
+        _ => 1,
+    };
+}
+
+/// where an offset into an object, or an address, points to
+enum Location {
+    /// allocated memory or the elements of a primitive array
+    Memory(*mut u8),
+    /// an element of a reference array
+    Element(Rc<RefCell<Object>>, usize),
+    /// the slot of an instance field
+    Field(Rc<RefCell<Object>>, usize),
+    /// the slot of a static field of the class a mirror stands for
+    Static(Rc<RefCell<Class>>, usize),
+}
+
+impl Location {
+    /// without an object the offset is an address, into an array it is
+    /// the offset of the bytes of an element, into another object it is the
+    /// slot of a field. None for an access that isn't inside the allocated
+    /// memory or the object, which is a fault.
+    fn resolve(object: Option<Rc<RefCell<Object>>>, offset: i64, kind: char) -> Option<Location> {
+        let object = match object {
+            Some(object) => object,
+            None if kind == 'L' => return None,
+            None => return memory_range(None, offset, size_of_type(kind)).map(Location::Memory),
+        };
+        if offset < 0 {
+            return None;
+        }
+        let borrow = (*object).borrow();
+        if let References(elements) = borrow.data() {
+            let index = offset as usize / REFERENCE_SIZE;
+            if kind != 'L'
+                || !(offset as usize).is_multiple_of(REFERENCE_SIZE)
+                || index >= elements.len()
+            {
+                return None;
+            }
+            drop(borrow);
+            return Some(Location::Element(object, index));
+        }
+        if borrow.is_array_object() {
+            drop(borrow);
+            if kind == 'L' {
+                return None;
+            }
+            return memory_range(Some(&object), offset, size_of_type(kind)).map(Location::Memory);
+        }
+        let meta = borrow.meta();
+        drop(borrow);
+        if let Some(class) = meta.filter(|_| offset >= STATIC_FIELD_OFFSET_BASE) {
+            let slot = (offset - STATIC_FIELD_OFFSET_BASE) as usize;
+            let count = (*class).borrow().static_vars().map_or(0, |vars| vars.len());
+            if slot + slot_count(kind) > count {
+                return None;
+            }
+            return Some(Location::Static(class, slot));
+        }
+        let slot = offset as usize;
+        if slot + slot_count(kind) > (*object).borrow().fields_immutable().len() {
+            return None;
+        }
+        return Some(Location::Field(object, slot));
+    }
+
+    fn read(&self, kind: char) -> Value {
+        return match self {
+            Location::Memory(address) => unsafe { read_memory(*address, kind) },
+            Location::Element(array, index) => {
+                Value::Reference((**array).borrow().references()[*index].clone())
+            }
+            Location::Field(object, slot) => {
+                read_slot((**object).borrow().fields_immutable(), *slot, kind)
+            }
+            Location::Static(class, slot) => {
+                read_slot((**class).borrow().static_vars().unwrap(), *slot, kind)
+            }
+        };
+    }
+
+    fn write(&self, kind: char, value: Value) {
+        match self {
+            Location::Memory(address) => unsafe { write_memory(*address, kind, value) },
+            Location::Element(array, index) => {
+                if let Value::Reference(value) = value {
+                    (**array).borrow_mut().mut_references()[*index] = value;
+                }
+            }
+            Location::Field(object, slot) => {
+                write_slot((**object).borrow_mut().fields(), *slot, value)
+            }
+            Location::Static(class, slot) => write_slot(
+                (**class).borrow_mut().mut_static_vars().unwrap(),
+                *slot,
+                value,
+            ),
+        }
+    }
+}
+
+/// the size bytes at offset into a primitive array, or at the address if
+/// there is no array. The pointer stays valid as long as the array is
+/// referenced, arrays never grow. None if the bytes aren't all inside the
+/// array or one allocation of allocateMemory.
+fn memory_range(array: Option<&Rc<RefCell<Object>>>, offset: i64, size: usize) -> Option<*mut u8> {
+    if offset < 0 {
+        return None;
+    }
+    let offset = offset as usize;
+    let array = match array {
+        Some(array) => array,
+        None if memory_size_map::contains(offset, size) => return Some(offset as *mut u8),
+        None => return None,
+    };
+    let mut borrow = (**array).borrow_mut();
+    let (base, len) = match borrow.mut_data() {
+        Bytes(data) => bytes_of(data),
+        Shorts(data) => bytes_of(data),
+        Chars(data) => bytes_of(data),
+        Ints(data) => bytes_of(data),
+        Longs(data) => bytes_of(data),
+        Floats(data) => bytes_of(data),
+        Doubles(data) => bytes_of(data),
+        _ => return None,
+    };
+    if offset.checked_add(size)? > len {
+        return None;
+    }
+    return Some(unsafe { base.add(offset) });
+}
+
+fn bytes_of<T>(data: &mut Vec<T>) -> (*mut u8, usize) {
+    return (data.as_mut_ptr() as *mut u8, data.len() * size_of::<T>());
+}
+
+unsafe fn read_memory(address: *mut u8, kind: char) -> Value {
+    return match kind {
+        'Z' => Value::Int((ptr::read(address) != 0) as i32),
+        'B' => Value::Int(ptr::read(address as *const i8) as i32),
+        'S' => Value::Int(ptr::read_unaligned(address as *const i16) as i32),
+        'C' => Value::Int(ptr::read_unaligned(address as *const u16) as i32),
+        'J' => Value::Long(ptr::read_unaligned(address as *const i64)),
+        'F' => Value::Float(ptr::read_unaligned(address as *const f32)),
+        'D' => Value::Double(ptr::read_unaligned(address as *const f64)),
+        _ => Value::Int(ptr::read_unaligned(address as *const i32)),
+    };
+}
+
+unsafe fn write_memory(address: *mut u8, kind: char, value: Value) {
+    match value {
+        Value::Int(value) => match kind {
+            'Z' | 'B' => ptr::write(address as *mut i8, value as i8),
+            'S' | 'C' => ptr::write_unaligned(address as *mut i16, value as i16),
+            _ => ptr::write_unaligned(address as *mut i32, value),
+        },
+        Value::Long(value) => ptr::write_unaligned(address as *mut i64, value),
+        Value::Float(value) => ptr::write_unaligned(address as *mut f32, value),
+        Value::Double(value) => ptr::write_unaligned(address as *mut f64, value),
+        Value::Reference(_) => panic!("references aren't kept in memory"),
+    }
+}
+
+/// a field slot of a sub-int type holds the value widened to int
+fn read_slot(slots: &Slots, slot: usize, kind: char) -> Value {
+    return match kind {
+        'Z' => Value::Int((slots.get_int(slot) as u8 != 0) as i32),
+        'B' => Value::Int(slots.get_int(slot) as i8 as i32),
+        'S' => Value::Int(slots.get_int(slot) as i16 as i32),
+        'C' => Value::Int(slots.get_int(slot) as u16 as i32),
+        'J' => Value::Long(slots.get_long(slot)),
+        'F' => Value::Float(slots.get_float(slot)),
+        'D' => Value::Double(slots.get_double(slot)),
+        'L' => Value::Reference(slots.get_ref(slot)),
+        _ => Value::Int(slots.get_int(slot)),
+    };
+}
+
+fn write_slot(slots: &mut Slots, slot: usize, value: Value) {
+    match value {
+        Value::Int(value) => slots.set_int(slot, value),
+        Value::Long(value) => slots.set_long(slot, value),
+        Value::Float(value) => slots.set_float(slot, value),
+        Value::Double(value) => slots.set_double(slot, value),
+        Value::Reference(value) => slots.set_ref(slot, value),
+    }
+}
+
+/// HotSpot reports an access outside of memory it knows as InternalError
+fn throw_fault(frame: &mut Frame) {
+    throw_exception(
+        frame,
+        "java/lang/InternalError",
+        Some("a fault occurred in an unsafe memory access operation"),
+    );
+}
+
+/// public native <type> get<Type>(Object o, long offset);
+pub fn get<const KIND: char>(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    match Location::resolve(vars.get_ref(1), vars.get_long(2), KIND) {
+        Some(location) => location.read(KIND).push(frame),
+        None => throw_fault(frame),
+    }
+}
+
+/// public native void put<Type>(Object o, long offset, <type> x);
+pub fn put<const KIND: char>(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let value = Value::from_vars(vars, 4, KIND);
+    match Location::resolve(vars.get_ref(1), vars.get_long(2), KIND) {
+        Some(location) => location.write(KIND, value),
+        None => throw_fault(frame),
+    }
+}
+
+/// public native <type> get<Type>(long address);
+pub fn get_absolute<const KIND: char>(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    match Location::resolve(None, vars.get_long(1), KIND) {
+        Some(location) => location.read(KIND).push(frame),
+        None => throw_fault(frame),
+    }
+}
+
+/// public native void put<Type>(long address, <type> x);
+pub fn put_absolute<const KIND: char>(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let value = Value::from_vars(vars, 3, KIND);
+    match Location::resolve(None, vars.get_long(1), KIND) {
+        Some(location) => location.write(KIND, value),
+        None => throw_fault(frame),
+    }
+}
+
+/// public final native boolean compareAndSwap<Type>(Object o, long offset,
+///                                                   <type> expected, <type> x);
+/// getAndAdd<Type> and getAndSet<Type> are Java loops over these
+pub fn compare_and_swap<const KIND: char>(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let expected = Value::from_vars(vars, 4, KIND);
+    let new_value = Value::from_vars(vars, 4 + slot_count(KIND), KIND);
+    let location = match Location::resolve(vars.get_ref(1), vars.get_long(2), KIND) {
+        Some(location) => location,
+        None => {
+            throw_fault(frame);
+            return;
+        }
+    };
+    let swapped = location.read(KIND).same_as(&expected);
+    if swapped {
+        location.write(KIND, new_value);
+    }
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_boolean(true);
+        .push_boolean(swapped);
+}
+
+/// public native void loadFence(), storeFence() and fullFence();
+/// ()V
+pub fn memory_fence(_frame: &mut Frame) {
+    fence(Ordering::SeqCst);
+}
+
+/// the address of size bytes of new memory, 0 if there isn't enough
+fn allocate(size: usize) -> usize {
+    let layout = match Layout::from_size_align(size, MEMORY_ALIGNMENT) {
+        Ok(layout) => layout,
+        Err(_) => return 0,
+    };
+    let address = unsafe { std::alloc::alloc(layout) } as usize;
+    if address != 0 {
+        memory_size_map::insert(address, size);
+    }
+    return address;
+}
+
+#[inline]
+fn memory_layout(size: usize) -> Layout {
+    return Layout::from_size_align(size, MEMORY_ALIGNMENT).expect("The layout init fail");
+}
+
+/// returns false with an IllegalArgumentException thrown for a negative size
+fn check_size(frame: &mut Frame, bytes: i64) -> bool {
+    if bytes < 0 {
+        throw_exception(frame, "java/lang/IllegalArgumentException", None);
+        return false;
+    }
+    return true;
+}
+
+fn throw_out_of_memory(frame: &mut Frame) {
+    throw_exception(
+        frame,
+        "java/lang/OutOfMemoryError",
+        Some("Unable to allocate memory"),
+    );
 }
 
 /// public native long allocateMemory(long bytes);
@@ -188,45 +672,72 @@ pub fn compare_and_swap_int(frame: &mut Frame) {
 pub fn allocate_memory(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
     // vars.GetRef(0) // this
-    let bytes = vars.get_long(1) as usize;
-    let layout =
-        Layout::from_size_align(bytes, get_power_of_two(bytes)).expect("The layout init fail");
-    unsafe {
-        let ptr = std::alloc::alloc(layout) as usize;
-        let stack = frame.operand_stack().expect("stack is none");
-        memory_size_map::insert(ptr, bytes);
-        stack.push_long(ptr as i64)
+    let bytes = vars.get_long(1);
+    if !check_size(frame, bytes) {
+        return;
     }
-}
-
-/// public native void putLong(long address, long x);
-/// (JJ)V
-pub fn put_long(frame: &mut Frame) {
-    let vars = frame.local_vars().expect("vars is none");
-    // vars.GetRef(0) // this
-    let address = vars.get_long(1);
-    let value = vars.get_long(3);
-
-    let ptr = (address as usize) as *mut u8;
-    unsafe {
-        *(ptr as *mut i64) = value;
+    let address = match bytes {
+        0 => 0,
+        bytes => allocate(bytes as usize),
+    };
+    if bytes != 0 && address == 0 {
+        throw_out_of_memory(frame);
+        return;
     }
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(address as i64);
 }
 
-/// public native byte getByte(long address);
-/// (J)B
-pub fn get_byte(frame: &mut Frame) {
+/// public native long reallocateMemory(long address, long bytes);
+/// (JJ)J
+pub fn reallocate_memory(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
-    // vars.GetRef(0) // this
-    let address = vars.get_long(1);
-    let ptr = (address as usize) as *mut u8;
-    unsafe {
-        let value = *(ptr as *mut i8);
-        frame
-            .operand_stack()
-            .expect("stack is none")
-            .push_int(value as i32);
+    let address = vars.get_long(1) as usize;
+    let bytes = vars.get_long(3);
+    if !check_size(frame, bytes) {
+        return;
     }
+    let size = bytes as usize;
+    let old_size = match address {
+        0 => None,
+        address => match memory_size_map::get(address) {
+            Some(old_size) => Some(old_size),
+            None => {
+                throw_fault(frame);
+                return;
+            }
+        },
+    };
+    let new_address = match old_size {
+        None if size == 0 => 0,
+        None => allocate(size),
+        Some(old_size) if size == 0 => {
+            memory_size_map::remove(address);
+            unsafe { std::alloc::dealloc(address as *mut u8, memory_layout(old_size)) };
+            0
+        }
+        Some(_) if Layout::from_size_align(size, MEMORY_ALIGNMENT).is_err() => 0,
+        Some(old_size) => {
+            let new_address =
+                unsafe { std::alloc::realloc(address as *mut u8, memory_layout(old_size), size) }
+                    as usize;
+            if new_address != 0 {
+                memory_size_map::remove(address);
+                memory_size_map::insert(new_address, size);
+            }
+            new_address
+        }
+    };
+    if size != 0 && new_address == 0 {
+        throw_out_of_memory(frame);
+        return;
+    }
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_long(new_address as i64);
 }
 
 /// public native void freeMemory(long address);
@@ -235,75 +746,51 @@ pub fn free_memory(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
     // vars.GetRef(0) // this
     let address = vars.get_long(1) as usize;
-    let size = memory_size_map::get(address);
-    let layout =
-        Layout::from_size_align(size, get_power_of_two(size)).expect("The layout init fail");
-    unsafe {
-        std::alloc::dealloc(address as *mut u8, layout);
-        memory_size_map::delete(address);
+    if address == 0 {
+        return;
+    }
+    match memory_size_map::remove(address) {
+        Some(size) => unsafe { std::alloc::dealloc(address as *mut u8, memory_layout(size)) },
+        None => throw_fault(frame),
     }
 }
 
-/// public native Object getObjectVolatile(Object o, long offset);
-/// (Ljava/lang/Object;J)Ljava/lang/Object;
-pub fn get_object_volatile(frame: &mut Frame) {
+/// public native void setMemory(Object o, long offset, long bytes, byte value);
+/// (Ljava/lang/Object;JJB)V
+pub fn set_memory(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
-    // vars.GetRef(0) // this
-    let object = vars.get_ref(1).unwrap();
-    let offset = vars.get_long(2) as usize;
-
-    let stack = frame.operand_stack().expect("stack is none");
-    if (*object).borrow().is_class_object() {
-        let meta_class = (*object).borrow().class();
-        let field = Class::get_static_ref_by_slot_id(meta_class, offset);
-        stack.push_ref(field);
-    } else if (*object).borrow().is_array_object() {
-        let element = (*object).borrow().get_references_by_index(offset);
-        stack.push_ref(element);
-    } else {
-        let field = (*object).borrow().get_ref_var_by_slot_id(offset);
-        stack.push_ref(field);
+    let object = vars.get_ref(1);
+    let offset = vars.get_long(2);
+    let bytes = vars.get_long(4);
+    let value = vars.get_int(6) as u8;
+    if !check_size(frame, bytes) || bytes == 0 {
+        return;
+    }
+    match memory_range(object.as_ref(), offset, bytes as usize) {
+        Some(address) => unsafe { ptr::write_bytes(address, value, bytes as usize) },
+        None => throw_fault(frame),
     }
 }
 
-/// public final native boolean compareAndSwapLong(Object o, long offset,
-///                                                   long expected,
-///                                                   long x);
-/// (Ljava/lang/Object;JJJ)Z
-pub fn compare_and_swap_long(frame: &mut Frame) {
+/// public native void copyMemory(Object srcBase, long srcOffset,
+///                               Object destBase, long destOffset, long bytes);
+/// (Ljava/lang/Object;JLjava/lang/Object;JJ)V
+pub fn copy_memory(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
-    // vars.GetRef(0) // this
-    let object = vars.get_ref(1).unwrap();
-    let offset = vars.get_long(2) as usize;
-    let expect = vars.get_long(3);
-    let new_value = vars.get_long(4);
-
-    let stack = frame.operand_stack().expect("stack is none");
-    let mut field = 0i64;
-    let mut raw_class: Option<Rc<RefCell<Class>>> = None;
-    if (*object).borrow().is_class_object() {
-        let meta_class = (*object).borrow().class();
-        field = Class::get_static_long_by_slot_id(meta_class.clone(), offset);
-        raw_class = Some(meta_class);
-    } else if (*object).borrow().is_array_object() {
-        field = (*object).borrow().get_long_by_index(offset);
-    } else {
-        field = (*object).borrow().get_long_var_by_slot_id(offset);
-    }
-
-    if expect == field {
-        if (*object).borrow().is_class_object() {
-            Class::set_static_long_by_slot_id(raw_class.unwrap(), offset, new_value);
-        } else if (*object).borrow().is_array_object() {
-            (*object).borrow_mut().set_long_by_index(offset, new_value);
-        } else {
-            (*object)
-                .borrow_mut()
-                .set_long_var_by_slot_id(offset, new_value);
-        }
-        stack.push_boolean(true);
-    } else {
-        stack.push_boolean(false);
+    let source_base = vars.get_ref(1);
+    let source_offset = vars.get_long(2);
+    let dest_base = vars.get_ref(4);
+    let dest_offset = vars.get_long(5);
+    let bytes = vars.get_long(7);
+    if !check_size(frame, bytes) || bytes == 0 {
+        return;
+    }
+    let source = memory_range(source_base.as_ref(), source_offset, bytes as usize);
+    let dest = memory_range(dest_base.as_ref(), dest_offset, bytes as usize);
+    match (source, dest) {
+        // the ranges may overlap
+        (Some(source), Some(dest)) => unsafe { ptr::copy(source, dest, bytes as usize) },
+        _ => throw_fault(frame),
     }
 }
 
@@ -315,49 +802,172 @@ pub fn ensure_class_initialized(frame: &mut Frame) {
     let object = vars.get_ref(1).unwrap();
     let raw_class = (*object).borrow().meta().unwrap();
     if !(*raw_class).borrow().initialized() {
-        init_class(frame.thread(), raw_class.clone());
+        init_class(raw_class);
     }
 }
 
-/// public native long getLong(long var1);
-/// (J)J
-pub fn get_long(frame: &mut Frame) {
+/// public native boolean shouldBeInitialized(Class<?> c);
+/// (Ljava/lang/Class;)Z
+pub fn should_be_initialized(frame: &mut Frame) {
     let vars = frame.local_vars().expect("vars is none");
-    let address = vars.get_long(1) as usize;
-    let ptr = address as *mut i64;
-    unsafe {
-        let value = *ptr;
-        frame
-            .operand_stack()
-            .expect("stack is none")
-            .push_long(value);
+    let object = vars.get_ref(1).unwrap();
+    let raw_class = (*object).borrow().meta().unwrap();
+    let initialized = (*raw_class).borrow().initialized();
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_boolean(!initialized);
+}
+
+/// public native Object allocateInstance(Class<?> cls) throws InstantiationException;
+/// (Ljava/lang/Class;)Ljava/lang/Object;
+pub fn allocate_instance(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let object = vars.get_ref(1).unwrap();
+    let class = (*object).borrow().meta().unwrap();
+    let (instantiable, name) = {
+        let class = (*class).borrow();
+        let abstract_class = class.is_interface() || class.is_abstract();
+        (
+            !abstract_class && !class.is_array() && !class.is_primitive(),
+            class.java_name(),
+        )
+    };
+    if !instantiable {
+        throw_exception(
+            frame,
+            "java/lang/InstantiationException",
+            Some(name.as_str()),
+        );
+        return;
+    }
+    // no constructor runs, but the class is initialized like by new
+    if !(*class).borrow().initialized() && !init_class(class.clone()) {
+        return;
     }
+    New::allocate(frame, &class);
 }
+
+/// public native Class<?> defineAnonymousClass(Class<?> hostClass, byte[] data, Object[] cpPatches);
+/// (Ljava/lang/Class;[B[Ljava/lang/Object;)Ljava/lang/Class;
+pub fn define_anonymous_class(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let host = vars.get_ref(1);
+    let data = vars.get_ref(2);
+    let patches = vars.get_ref(3);
+    let (host, data) = match (host, data) {
+        (Some(host), Some(data)) => (host, data),
+        _ => {
+            throw_exception(frame, "java/lang/NullPointerException", None);
+            return;
+        }
+    };
+    // the constant pool only holds constants of the class file,
+    // live objects can't be patched in
+    let patched =
+        patches.is_some_and(|patches| (*patches).borrow().references().iter().any(Option::is_some));
+    if patched {
+        throw_exception(
+            frame,
+            "java/lang/UnsupportedOperationException",
+            Some("constant pool patches of anonymous classes"),
+        );
+        return;
+    }
+    let host_class = (*host).borrow().meta().unwrap();
+    let bytes = (*data)
+        .borrow()
+        .bytes()
+        .iter()
+        .map(|byte| *byte as u8)
+        .collect();
+    let class = match ClassLoader::define_anonymous_class(&host_class, bytes) {
+        Some(class) => class,
+        // the ClassFormatError or VerifyError is pending
+        None => return,
+    };
+    let java_class = (*class).borrow().get_java_class();
+    frame
+        .operand_stack()
+        .expect("stack is none")
+        .push_ref(java_class);
+}
+
+/// public native void throwException(Throwable ee);
+/// (Ljava/lang/Throwable;)V
+pub fn throw_throwable(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    match vars.get_ref(1) {
+        Some(exception) => AThrow::throw(frame, exception),
+        None => throw_exception(frame, "java/lang/NullPointerException", None),
+    }
+}
+
+/// public native void park(boolean isAbsolute, long time);
+/// (ZJ)V
+pub fn park(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let is_absolute = vars.get_boolean(1);
+    let time = vars.get_long(2);
+    if time < 0 || (is_absolute && time == 0) {
+        return;
+    }
+    // an absolute time is in milliseconds since the epoch, a relative one
+    // in nanoseconds, 0 parks until unparked
+    let deadline = if is_absolute {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let millis = Duration::from_millis(time as u64);
+        Some(Instant::now() + millis.saturating_sub(since_epoch))
+    } else if time > 0 {
+        Some(Instant::now() + Duration::from_nanos(time as u64))
+    } else {
+        None
+    };
+    current_os_thread().park(deadline);
+}
+
+/// public native void unpark(Object thread);
+/// (Ljava/lang/Object;)V
+pub fn unpark(frame: &mut Frame) {
+    let vars = frame.local_vars().expect("vars is none");
+    let thread = vars.get_ref(1);
+    // a thread that isn't started has no permit yet
+    let os_thread = thread.and_then(|thread| (*thread).borrow().os_thread());
+    if let Some(os_thread) = os_thread {
+        os_thread.unpark();
+    }
+}
+
+/// the allocations of allocateMemory by address, an access through an
+/// address has to stay inside one of them
 mod memory_size_map {
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
 
-    static mut MEMORY_SIZE_MAP: Option<HashMap<usize, usize>> = None;
+    static MEMORY_SIZE_MAP: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
 
-    fn instance() -> &'static mut HashMap<usize, usize> {
-        unsafe {
-            if MEMORY_SIZE_MAP.is_none() {
-                MEMORY_SIZE_MAP = Some(HashMap::new());
-            }
-            return MEMORY_SIZE_MAP.as_mut().unwrap();
-        }
+    pub fn insert(address: usize, size: usize) {
+        MEMORY_SIZE_MAP.lock().unwrap().insert(address, size);
     }
 
-    pub fn insert(key: usize, size: usize) {
-        instance().insert(key, size);
+    pub fn get(address: usize) -> Option<usize> {
+        return MEMORY_SIZE_MAP.lock().unwrap().get(&address).copied();
     }
 
-    pub fn get(key: usize) -> usize {
-        let size = instance().get(&key);
-        return *size.unwrap();
+    pub fn remove(address: usize) -> Option<usize> {
+        return MEMORY_SIZE_MAP.lock().unwrap().remove(&address);
     }
 
-    pub fn delete(key: usize) {
-        instance().remove(&key);
+    /// whether the size bytes at address are inside one allocation
+    pub fn contains(address: usize, size: usize) -> bool {
+        let map = MEMORY_SIZE_MAP.lock().unwrap();
+        let allocation = map.range(..=address).next_back();
+        return match (allocation, address.checked_add(size)) {
+            (Some((start, len)), Some(end)) => end <= start + len,
+            _ => false,
+        };
     }
 }
 
@@ -365,9 +975,14 @@ mod memory_size_map {
 mod java_unsafe {
     use crate::class_path::class_path::ClassPath;
     use crate::cmd::Cmd;
+    use crate::native::sun::misc::misc_unsafe::{
+        allocate, memory_layout, memory_size_map, os_page_size, Location, Value,
+        REFERENCE_SIZE, STATIC_FIELD_OFFSET_BASE,
+    };
     use crate::oops::class::Class;
-    use crate::oops::object::DataType::{Bytes, Ints};
-    use crate::oops::object::Object;
+    use crate::oops::object::DataType::{Bytes, Ints, References, StandardObject};
+    use crate::oops::object::{DataType, Object};
+    use crate::oops::slots::Slots;
     use crate::utils::boxed;
     use crate::utils::numbers::get_power_of_two;
     use std::alloc::Layout;
@@ -514,4 +1129,92 @@ mod java_unsafe {
         }
         println!("size:{}", layout.size());
     }
+
+    #[test]
+    fn test_memory_bounds() {
+        let address = allocate(16);
+        assert!(memory_size_map::contains(address, 16));
+        assert!(memory_size_map::contains(address + 8, 8));
+        assert!(!memory_size_map::contains(address + 8, 9));
+
+        let value = 0x0102_0304_0506_0708i64;
+        let location = Location::resolve(None, address as i64, 'J').unwrap();
+        location.write('J', Value::Long(value));
+        let byte = Location::resolve(None, address as i64 + 7, 'B').unwrap();
+        let expected = value.to_ne_bytes()[7] as i32;
+        assert!(matches!(byte.read('B'), Value::Int(byte) if byte == expected));
+        assert!(Location::resolve(None, address as i64 + 12, 'J').is_none());
+        assert!(Location::resolve(None, address as i64, 'L').is_none());
+
+        let size = memory_size_map::remove(address).unwrap();
+        unsafe { std::alloc::dealloc(address as *mut u8, memory_layout(size)) };
+        assert!(Location::resolve(None, address as i64, 'B').is_none());
+    }
+
+    fn array(data: DataType) -> Rc<RefCell<Object>> {
+        let mut object = Object::new(boxed(Class::default()));
+        object.data = data;
+        return boxed(object);
+    }
+
+    #[test]
+    fn test_array_offsets() {
+        let ints = array(Ints(vec![1, 2, 3]));
+        // the offset of an element is in bytes
+        let location = Location::resolve(Some(ints.clone()), 8, 'I').unwrap();
+        assert!(matches!(location.read('I'), Value::Int(3)));
+        location.write('I', Value::Int(-3));
+        assert_eq!((*ints).borrow().ints()[2], -3);
+        // a long spans two ints
+        let location = Location::resolve(Some(ints.clone()), 0, 'J').unwrap();
+        location.write('J', Value::Long(-1));
+        assert_eq!((*ints).borrow().ints()[..2], [-1, -1]);
+        assert!(Location::resolve(Some(ints.clone()), 4, 'J').is_some());
+        assert!(Location::resolve(Some(ints.clone()), 8, 'J').is_none());
+        assert!(Location::resolve(Some(ints.clone()), -1, 'B').is_none());
+        assert!(Location::resolve(Some(ints.clone()), 0, 'L').is_none());
+
+        let references = array(References(vec![None, Some(ints.clone())]));
+        let offset = REFERENCE_SIZE as i64;
+        let location = Location::resolve(Some(references.clone()), offset, 'L').unwrap();
+        assert!(location.read('L').same_as(&Value::Reference(Some(ints))));
+        assert!(Location::resolve(Some(references.clone()), offset / 2, 'L').is_none());
+        assert!(Location::resolve(Some(references), 0, 'I').is_none());
+    }
+
+    #[test]
+    fn test_field_offsets() {
+        let mut object = Object::new(boxed(Class::default()));
+        object.data = StandardObject(Some(Slots::with_capacity(3)));
+        let object = boxed(object);
+        // the offset of a field is its slot, a long takes two
+        let location = Location::resolve(Some(object.clone()), 1, 'J').unwrap();
+        location.write('J', Value::Long(1 << 40));
+        assert!(matches!(location.read('J'), Value::Long(value) if value == 1 << 40));
+        assert!(Location::resolve(Some(object.clone()), 2, 'J').is_none());
+        // a byte field holds the byte widened to int
+        let location = Location::resolve(Some(object.clone()), 0, 'I').unwrap();
+        location.write('I', Value::Int(0xff));
+        let byte = Location::resolve(Some(object.clone()), 0, 'B').unwrap();
+        assert!(matches!(byte.read('B'), Value::Int(-1)));
+
+        // the static offsets of a mirror are the ones of the class it stands for
+        let mut class = Class::default();
+        class.set_static_vars(Slots::with_capacity(1));
+        let class = boxed(class);
+        (*object).borrow_mut().set_meta(class.clone());
+        let location = Location::resolve(Some(object.clone()), STATIC_FIELD_OFFSET_BASE, 'I');
+        location.unwrap().write('I', Value::Int(7));
+        assert_eq!((*class).borrow().static_vars().unwrap().get_int(0), 7);
+        let location = Location::resolve(Some(object.clone()), 0, 'I').unwrap();
+        assert!(matches!(location.read('I'), Value::Int(0xff)));
+        assert!(Location::resolve(Some(object), STATIC_FIELD_OFFSET_BASE + 1, 'I').is_none());
+    }
+
+    #[test]
+    fn test_page_size() {
+        let page_size = os_page_size();
+        assert!(page_size >= 4096);
+        assert_eq!(page_size & (page_size - 1), 0);
+    }
 }
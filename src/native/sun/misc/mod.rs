@@ -1,5 +1,6 @@
 mod misc_unsafe;
 mod perf;
+mod signal;
 mod url_class_path;
 mod vm;
 
@@ -8,4 +9,5 @@ pub fn init() {
     misc_unsafe::init();
     url_class_path::init();
     perf::init();
+    signal::init();
 }
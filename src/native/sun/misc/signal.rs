@@ -0,0 +1,31 @@
+use crate::native::registry::Registry;
+use crate::runtime::frame::Frame;
+use crate::utils::java_str_to_rust_str;
+
+pub fn init() {
+    Registry::register("sun/misc/Signal", "findSignal", "(Ljava/lang/String;)I", find_signal);
+    Registry::register("sun/misc/Signal", "handle0", "(IJ)J", handle0);
+}
+
+/// private static native int findSignal(String sigName);
+/// (Ljava/lang/String;)I
+pub fn find_signal(frame: &mut Frame) {
+    let name = frame.local_vars().expect("vars is none").get_ref(0);
+    let number = match name.map(java_str_to_rust_str).as_deref() {
+        Some("HUP") => 1,
+        Some("INT") => 2,
+        Some("QUIT") => 3,
+        Some("KILL") => 9,
+        Some("TERM") => 15,
+        _ => -1,
+    };
+    frame.operand_stack().expect("stack is none").push_int(number);
+}
+
+/// private static native long handle0(int sig, long nativeH);
+/// (IJ)J
+/// signals are not forwarded to Java handlers, -1 makes Signal.handle
+/// report the signal as used by the VM
+pub fn handle0(frame: &mut Frame) {
+    frame.operand_stack().expect("stack is none").push_long(-1);
+}
@@ -1,9 +1,9 @@
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::gc::heap::Heap;
 use crate::jvm::Jvm;
 use crate::native::registry::Registry;
 use crate::runtime::frame::Frame;
 use crate::oops::class::Class;
-use crate::utils::boxed;
 
 pub fn init() {
     Registry::register(
@@ -28,5 +28,5 @@ pub fn get_lookup_cache_urls(frame: &mut Frame) {
     frame
         .operand_stack()
         .expect("stack is none")
-        .push_ref(Some(boxed(array)));
+        .push_ref(Some(Heap::allocate(array)));
 }
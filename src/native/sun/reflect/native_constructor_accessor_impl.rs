@@ -1,3 +1,4 @@
+use crate::gc::heap::Heap;
 use crate::instructions::base::class_init_logic::init_class;
 use crate::instructions::base::method_invoke_logic::hack_invoke_method;
 use crate::native::registry::Registry;
@@ -6,7 +7,6 @@ use crate::oops::class::Class;
 use crate::oops::method::Method;
 use crate::oops::object::Object;
 use crate::runtime::operand_stack::OperandStack;
-use crate::utils::boxed;
 use std::cell::RefCell;
 use std::rc::Rc;
 use crate::invoke_support::parameter::{Parameters, Parameter};
@@ -33,12 +33,10 @@ pub fn new_instance0(frame: &mut Frame) {
     let constructor = get_constructor(constructor_obj);
     let class = constructor.class();
 
-    if !(*class).borrow().initialized() {
-        frame.revert_next_pc();
-        init_class(frame.thread(), class);
+    if !(*class).borrow().initialized() && !init_class(class.clone()) {
         return;
     }
-    let obj = Some(boxed(Class::new_object(&class)));
+    let obj = Some(Heap::allocate(Class::new_object(&class)));
     let stack = frame.operand_stack().expect("stack is none");
     stack.push_ref(obj.clone());
 
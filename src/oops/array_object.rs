@@ -3,8 +3,10 @@ use crate::oops::object::DataType::{
     Bytes, Chars, Doubles, Floats, Ints, Longs, References, Shorts,
 };
 use crate::oops::object::{DataType, MetaData, Object};
+use crate::runtime::monitor::Monitor;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 
 pub type ArrayObject = Object;
 
@@ -17,6 +19,7 @@ impl Object {
             meta: None,
             trace: None,
             meta_data: MetaData::Null,
+            monitor: Arc::new(Monitor::new()),
         };
     }
 
@@ -1,4 +1,5 @@
 use lark_classfile::attribute_info::Attribute::RuntimeVisibleAnnotations;
+use lark_classfile::bootstrap_methods_attribute::BootstrapMethod;
 use lark_classfile::class_file::ClassFile;
 use lark_classfile::member_info::MemberInfo;
 use lark_classfile::runtime_visible_annotations_attribute::AnnotationAttribute;
@@ -23,9 +24,28 @@ use std::ops::Deref;
 use std::rc::Rc;
 use crate::oops::string_pool::StringPool;
 use crate::oops::constant_pool::Constant::{FieldReference, ClassReference, MethodReference};
+use crate::runtime::monitor::Monitor;
+use std::sync::Arc;
+use std::thread::ThreadId;
 
 pub type Interfaces = Vec<Rc<RefCell<Class>>>;
 
+/// per implemented interface the methods selected for the interface's
+/// methods, None where the maximally-specific default methods conflict
+pub type Itable = Vec<(Rc<RefCell<Class>>, Vec<Option<Rc<Method>>>)>;
+
+/// how far the initialization of a class got, JVMS §5.5
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitState {
+    /// verified and prepared, <clinit> has not run yet
+    Linked,
+    /// the thread is running <clinit> or initializing the superclasses
+    BeingInitialized(ThreadId),
+    FullyInitialized,
+    /// <clinit> or the initialization of a superclass failed
+    Erroneous,
+}
+
 #[derive(Debug)]
 pub struct Class {
     access_flags: u16,
@@ -35,16 +55,27 @@ pub struct Class {
     constant_pool: ConstantPool,
     fields: Vec<Rc<RefCell<Field>>>,
     methods: Vec<Rc<Method>>,
+    /// the virtual methods selected for this class, built at link time
+    vtable: Vec<Rc<Method>>,
+    itable: Itable,
     loader: Option<Rc<RefCell<ClassLoader>>>,
+    /// the class an anonymous class was defined for, it has the access of it
+    host_class: Option<Rc<RefCell<Class>>>,
     super_class: Option<Rc<RefCell<Class>>>,
     interfaces: Option<Interfaces>,
     instance_slot_count: u32,
     static_slot_count: u32,
     static_vars: Option<Slots>,
-    initialized: bool,
+    init_state: InitState,
+    /// the initialization lock of JVMS §5.5, kept apart from the monitor of
+    /// the java.lang.Class so user code synchronizing on it can't deadlock
+    init_lock: Arc<Monitor>,
     java_class: Option<Rc<RefCell<Object>>>,
     source_file: Option<String>,
     annotations: Option<Vec<AnnotationAttribute>>,
+    bootstrap_methods: Vec<BootstrapMethod>,
+    /// the class file's major version, 0 for array and primitive classes
+    major_version: u16,
 }
 
 impl Class {
@@ -60,16 +91,22 @@ impl Class {
             constant_pool: ConstantPool::new_constant_pool(None, class_file.constant_pool()),
             fields: vec![],
             methods: vec![],
+            vtable: vec![],
+            itable: vec![],
             loader: None,
+            host_class: None,
             super_class: None,
             interfaces: None,
             instance_slot_count: 0,
             static_slot_count: 0,
             static_vars: None,
-            initialized: false,
+            init_state: InitState::Linked,
+            init_lock: Arc::new(Monitor::new()),
             java_class: None,
             source_file: Self::get_source_file(&class_file),
             annotations: Class::copy_annotations(&class_file),
+            bootstrap_methods: Self::copy_bootstrap_methods(&class_file),
+            major_version: class_file.major_version(),
         };
         //        println!("class:{:?}",class.name.as_str());
         let mut point = Rc::new(RefCell::new(class));
@@ -112,6 +149,14 @@ impl Class {
         return None;
     }
 
+    fn copy_bootstrap_methods(class_file: &ClassFile) -> Vec<BootstrapMethod> {
+        let attr = class_file.bootstrap_methods_attribute();
+        if attr.is_some() {
+            return attr.unwrap().bootstrap_methods().clone();
+        }
+        return vec![];
+    }
+
     #[inline]
     pub fn new_array_class(loader: Rc<RefCell<ClassLoader>>, class_name: &str) -> Class {
         let mut interfaces = Vec::new();
@@ -126,6 +171,9 @@ impl Class {
                 .find_or_create("java/io/Serializable")
                 .unwrap(),
         );
+        let object = bootstrap_loader.find_or_create("java/lang/Object").unwrap();
+        // Cloneable and Serializable declare no methods, the itable stays empty
+        let vtable = (*object).borrow().vtable().clone();
         let class = Class {
             access_flags: PUBLIC,
             name: class_name.to_string(),
@@ -134,16 +182,22 @@ impl Class {
             constant_pool: ConstantPool::default(),
             fields: vec![],
             methods: vec![],
+            vtable,
+            itable: vec![],
             loader: Some(loader.clone()),
-            super_class: Some(bootstrap_loader.find_or_create("java/lang/Object").unwrap()),
+            host_class: None,
+            super_class: Some(object),
             interfaces: Some(interfaces),
             instance_slot_count: 0,
             static_slot_count: 0,
             static_vars: None,
-            initialized: true,
+            init_state: InitState::FullyInitialized,
+            init_lock: Arc::new(Monitor::new()),
             java_class: None,
             source_file: None,
             annotations: None,
+            bootstrap_methods: vec![],
+            major_version: 0,
         };
         return class;
     }
@@ -159,16 +213,22 @@ impl Class {
             constant_pool: ConstantPool::default(),
             fields: vec![],
             methods: vec![],
+            vtable: vec![],
+            itable: vec![],
             loader: Some(boot_loader),
+            host_class: None,
             super_class: None,
             interfaces: None,
             instance_slot_count: 0,
             static_slot_count: 0,
             static_vars: None,
-            initialized: true,
+            init_state: InitState::FullyInitialized,
+            init_lock: Arc::new(Monitor::new()),
             java_class: None,
             source_file: None,
             annotations: None,
+            bootstrap_methods: vec![],
+            major_version: 0,
         };
     }
 
@@ -213,7 +273,14 @@ impl Class {
     }
 
     pub fn is_accessible_to(&self, other: &Self) -> bool {
-        return self.is_public() || self.package_name() == other.package_name();
+        if self.is_public() || self.package_name() == other.package_name() {
+            return true;
+        }
+        // an anonymous class has the access of its host class too
+        return match other.host_class() {
+            Some(host) => self.is_accessible_to(&(*host).borrow()),
+            None => false,
+        };
     }
 
     pub fn package_name(&self) -> &str {
@@ -322,6 +389,29 @@ impl Class {
     //        return false;
     //    }
 
+    /// every interface the class implements or the interface extends,
+    /// directly or through its superclasses and superinterfaces
+    pub fn all_interfaces(&self) -> Interfaces {
+        let mut all = vec![];
+        self.collect_interfaces(&mut all);
+        return all;
+    }
+
+    fn collect_interfaces(&self, all: &mut Interfaces) {
+        if let Some(interfaces) = self.interfaces.as_ref() {
+            for interface in interfaces {
+                if all.iter().any(|collected| Rc::ptr_eq(collected, interface)) {
+                    continue;
+                }
+                all.push(interface.clone());
+                (**interface).borrow().collect_interfaces(all);
+            }
+        }
+        if let Some(super_class) = self.super_class.as_ref() {
+            (**super_class).borrow().collect_interfaces(all);
+        }
+    }
+
     ///
     pub fn is_sub_interface_of(&self, other: &Self) -> bool {
         let interfaces = self.interfaces.as_ref();
@@ -476,6 +566,22 @@ impl Class {
         return loader.clone();
     }
 
+    /// None until the class is defined by a class loader
+    #[inline]
+    pub fn defining_loader(&self) -> Option<Rc<RefCell<ClassLoader>>> {
+        return self.loader.clone();
+    }
+
+    #[inline]
+    pub fn host_class(&self) -> Option<Rc<RefCell<Class>>> {
+        return self.host_class.clone();
+    }
+
+    #[inline]
+    pub fn set_host_class(&mut self, host: Rc<RefCell<Class>>) {
+        self.host_class = Some(host);
+    }
+
     #[inline]
     pub fn java_class(&self) -> Option<&Rc<RefCell<Object>>> {
         return self.java_class.as_ref();
@@ -524,6 +630,32 @@ impl Class {
         return &self.methods;
     }
 
+    #[inline]
+    pub fn vtable(&self) -> &Vec<Rc<Method>> {
+        return &self.vtable;
+    }
+
+    #[inline]
+    pub fn set_vtable(&mut self, vtable: Vec<Rc<Method>>) {
+        self.vtable = vtable;
+    }
+
+    /// the methods the class selected for the methods of the interface,
+    /// None if the class doesn't implement it
+    pub fn itable_methods(&self, interface: &Self) -> Option<&Vec<Option<Rc<Method>>>> {
+        for (implemented, methods) in &self.itable {
+            if (**implemented).borrow().deref() == interface {
+                return Some(methods);
+            }
+        }
+        return None;
+    }
+
+    #[inline]
+    pub fn set_itable(&mut self, itable: Itable) {
+        self.itable = itable;
+    }
+
     #[inline]
     pub fn constant_pool(&self) -> &ConstantPool {
         return &self.constant_pool;
@@ -534,14 +666,29 @@ impl Class {
         return &mut self.constant_pool;
     }
 
+    #[inline]
+    pub fn bootstrap_method(&self, index: usize) -> Option<&BootstrapMethod> {
+        return self.bootstrap_methods.get(index);
+    }
+
     #[inline]
     pub fn initialized(&self) -> bool {
-        return self.initialized;
+        return self.init_state == InitState::FullyInitialized;
     }
 
     #[inline]
-    pub fn set_initialized(&mut self) {
-        self.initialized = true;
+    pub fn init_state(&self) -> InitState {
+        return self.init_state;
+    }
+
+    #[inline]
+    pub fn set_init_state(&mut self, state: InitState) {
+        self.init_state = state;
+    }
+
+    #[inline]
+    pub fn init_lock(&self) -> Arc<Monitor> {
+        return self.init_lock.clone();
     }
 
     #[inline]
@@ -561,7 +708,13 @@ impl Class {
 
     #[inline]
     pub fn get_clinit_method(class: Rc<RefCell<Self>>) -> Option<Rc<Method>> {
-        return Self::get_static_method(class, "<clinit>", "()V");
+        // a static initializer is never inherited
+        return (*class)
+            .borrow()
+            .methods()
+            .iter()
+            .find(|method| method.is_clinit())
+            .cloned();
     }
 
     #[inline]
@@ -647,11 +800,13 @@ impl Class {
     }
 
     #[inline]
-    pub fn source_file(&self) -> String {
-        if self.source_file.is_none() {
-            return "Unknown".to_string();
-        }
-        return self.source_file.clone().unwrap();
+    pub fn source_file(&self) -> Option<String> {
+        return self.source_file.clone();
+    }
+
+    #[inline]
+    pub fn major_version(&self) -> u16 {
+        return self.major_version;
     }
 
     #[inline]
@@ -744,7 +899,7 @@ impl Class {
             .unwrap()
             .get_array_class_name(self.name.as_str());
         let class_loader = self.get_class_loader();
-        return ClassLoader::load_class(class_loader, array_class_name.as_str());
+        return ClassLoader::load_class(class_loader, array_class_name.as_str()).unwrap();
     }
 
     pub fn create_array_class(class:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
@@ -753,7 +908,7 @@ impl Class {
             .unwrap()
             .get_array_class_name(class_name.as_str());
         let class_loader = (*class).borrow().get_class_loader();
-        return ClassLoader::load_class(class_loader, array_class_name.as_str());
+        return ClassLoader::load_class(class_loader, array_class_name.as_str()).unwrap();
     }
 
     pub fn component_class(&self) -> Rc<RefCell<Class>> {
@@ -761,7 +916,7 @@ impl Class {
             .unwrap()
             .get_component_class_name(self.name.as_str());
         let class_loader = self.get_class_loader();
-        return ClassLoader::load_class(class_loader, component_class_name.as_str());
+        return ClassLoader::load_class(class_loader, component_class_name.as_str()).unwrap();
     }
 
     pub fn get_class_loader(&self) -> Option<Rc<RefCell<Object>>> {
@@ -842,16 +997,22 @@ impl Default for Class {
             constant_pool: ConstantPool::default(),
             fields: vec![],
             methods: vec![],
+            vtable: vec![],
+            itable: vec![],
             loader: None,
+            host_class: None,
             super_class: None,
             interfaces: None,
             instance_slot_count: 0,
             static_slot_count: 0,
             static_vars: None,
-            initialized: false,
+            init_state: InitState::Linked,
+            init_lock: Arc::new(Monitor::new()),
             java_class: None,
             source_file: None,
             annotations: None,
+            bootstrap_methods: vec![],
+            major_version: 0,
         };
     }
 }
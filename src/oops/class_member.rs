@@ -106,9 +106,17 @@ impl ClassMember {
     }
 
     pub fn is_accessible_to(&self, class: &Class) -> bool {
-        if self.is_public() {
+        if self.is_public() || self.is_accessible_by_rules(class) {
             return true;
         }
+        // an anonymous class has the access of its host class too
+        return match class.host_class() {
+            Some(host) => self.is_accessible_to(&(*host).borrow()),
+            None => false,
+        };
+    }
+
+    fn is_accessible_by_rules(&self, class: &Class) -> bool {
         let o = self.class.clone();
         let other = (*o).borrow();
         if self.is_protected() {
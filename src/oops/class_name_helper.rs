@@ -34,7 +34,7 @@ impl PrimitiveTypes {
         return "[".to_string() + self.to_descriptor(class_name).as_str();
     }
 
-    fn to_descriptor(&self, class_name: &str) -> String {
+    pub fn to_descriptor(&self, class_name: &str) -> String {
         if class_name.starts_with('[') {
             return class_name.to_string();
         }
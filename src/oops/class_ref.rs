@@ -18,7 +18,17 @@ impl ClassRef {
     }
 
     #[inline]
-    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    pub fn class_name(&self) -> &str {
+        return self.symbol_ref.class_name();
+    }
+
+    #[inline]
+    pub fn class(&self) -> Option<&Rc<RefCell<Class>>> {
+        return self.symbol_ref.class();
+    }
+
+    #[inline]
+    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         return self.symbol_ref.resolved_class(holder);
     }
 }
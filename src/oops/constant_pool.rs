@@ -5,6 +5,8 @@ use crate::oops::constant_pool::Constant::*;
 use crate::oops::field::Field;
 use crate::oops::field_ref::FieldRef;
 use crate::oops::interface_method_ref::InterfaceMethodRef;
+use crate::oops::invoke_dynamic_ref::InvokeDynamicRef;
+use crate::oops::method_handle_ref::{MethodHandleRef, MethodTypeRef};
 use crate::oops::method_ref::MethodRef;
 use core::mem;
 use std::cell::RefCell;
@@ -44,6 +46,15 @@ impl ConstantPool {
                 ConstantInfoEnum::InterfaceMethodRef(info) => {
                     InterfaceMethodReference(InterfaceMethodRef::new_method_ref(info))
                 }
+                ConstantInfoEnum::MethodHandle(info) => {
+                    MethodHandleReference(MethodHandleRef::new_method_handle_ref(info))
+                }
+                ConstantInfoEnum::MethodType(info) => {
+                    MethodTypeReference(MethodTypeRef::new_method_type_ref(info))
+                }
+                ConstantInfoEnum::InvokeDynamic(info) => {
+                    InvokeDynamic(InvokeDynamicRef::new_invoke_dynamic_ref(info))
+                }
                 _ => None,
             };
             match constant {
@@ -105,6 +116,35 @@ impl ConstantPool {
         return constant.unwrap();
     }
 
+    /// the class a ClassReference, MethodReference or InterfaceMethodReference
+    /// resolved to, None until it's resolved
+    pub fn resolved_class(&self, index: usize) -> Option<Rc<RefCell<Class>>> {
+        return match self.get_constant_immutable(index) {
+            ClassReference(refe) => refe.class().cloned(),
+            MethodReference(refe) => refe.class().cloned(),
+            InterfaceMethodReference(refe) => refe.class().cloned(),
+            _ => panic!("Unknown constant type"),
+        };
+    }
+
+    /// the field a FieldReference resolved to, None until it's resolved
+    pub fn resolved_field(&self, index: usize) -> Option<Rc<RefCell<Field>>> {
+        return match self.get_constant_immutable(index) {
+            FieldReference(refe) => refe.field().cloned(),
+            _ => panic!("Unknown constant type"),
+        };
+    }
+
+    /// the method a MethodReference or InterfaceMethodReference resolved to,
+    /// None until it's resolved
+    pub fn resolved_method(&self, index: usize) -> Option<Rc<Method>> {
+        return match self.get_constant_immutable(index) {
+            MethodReference(refe) => refe.method().cloned(),
+            InterfaceMethodReference(refe) => refe.method().cloned(),
+            _ => panic!("Unknown constant type"),
+        };
+    }
+
     pub fn class(&self) -> Rc<RefCell<Class>> {
         let class = self.class.as_ref().unwrap();
         return class.clone();
@@ -144,6 +184,9 @@ pub enum Constant {
     FieldReference(FieldRef),
     MethodReference(MethodRef),
     InterfaceMethodReference(InterfaceMethodRef),
+    MethodHandleReference(MethodHandleRef),
+    MethodTypeReference(MethodTypeRef),
+    InvokeDynamic(InvokeDynamicRef),
 }
 
 impl Constant {
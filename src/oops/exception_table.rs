@@ -43,6 +43,11 @@ impl ExceptionTable {
         return Some(class_ref);
     }
 
+    #[inline]
+    pub fn handlers(&self) -> &Vec<ExceptionHandler> {
+        return &self.table;
+    }
+
     pub fn find_exception_handler(
         &self,
         class: Rc<RefCell<Class>>,
@@ -55,7 +60,10 @@ impl ExceptionTable {
                 }
                 let mut class_ref = handler.catch_type.clone().unwrap();
                 let holder = self.holder.as_ref().unwrap().clone();
-                let catch_class = class_ref.resolved_class(holder);
+                let catch_class = match class_ref.resolved_class(holder) {
+                    Some(class) => class,
+                    None => continue,
+                };
                 if catch_class == class
                     || (*catch_class)
                         .borrow()
@@ -84,8 +92,24 @@ pub struct ExceptionHandler {
 }
 
 impl ExceptionHandler {
+    #[inline]
+    pub fn start_pc(&self) -> i32 {
+        return self.start_pc;
+    }
+
+    #[inline]
+    pub fn end_pc(&self) -> i32 {
+        return self.end_pc;
+    }
+
     #[inline]
     pub fn handler_pc(&self) -> i32 {
         return self.handler_pc;
     }
+
+    /// None for a finally handler, which catches everything
+    #[inline]
+    pub fn catch_type(&self) -> Option<&ClassRef> {
+        return self.catch_type.as_ref();
+    }
 }
@@ -117,7 +117,7 @@ impl Field {
             .unwrap()
             .to_class_name(self.descriptor());
         let loader = self.get_class_loader();
-        return ClassLoader::load_class(loader, class_name.as_str());
+        return ClassLoader::load_class(loader, class_name.as_str()).unwrap();
     }
 
     #[inline]
@@ -1,5 +1,6 @@
 use lark_classfile::constant_pool::ConstantFieldRefInfo;
 use crate::oops::class::Class;
+use crate::invoke_support::set_pending_exception;
 use crate::oops::constant_pool::ConstantPool;
 use crate::oops::field::Field;
 use crate::oops::member_ref::MemberRef;
@@ -24,6 +25,12 @@ impl FieldRef {
         return field_ref;
     }
 
+    /// the field resolved so far, None before the first resolution
+    #[inline]
+    pub fn field(&self) -> Option<&Rc<RefCell<Field>>> {
+        return self.field.as_ref();
+    }
+
     pub fn resolved_field(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Field>>> {
         if self.field.is_none() {
             self.resolve_field_ref(holder)
@@ -31,23 +38,34 @@ impl FieldRef {
         return self.field.clone();
     }
 
-    // jvms 5.4.3.2
+    // jvms 5.4.3.2, a failed resolution leaves the error pending
     fn resolve_field_ref(&mut self,holder:Rc<RefCell<Class>>) {
-        let resolved_class = self.member_ref.resolved_class(holder.clone());
+        let resolved_class = match self.member_ref.resolved_class(holder.clone()) {
+            Some(class) => class,
+            None => return,
+        };
         let field = FieldRef::lookup_field(
             &resolved_class,
             self.member_ref.name(),
             self.member_ref.descriptor(),
         );
         if field.is_none() {
-            panic!("java.lang.NoSuchFieldError");
+            set_pending_exception("java/lang/NoSuchFieldError", Some(self.member_ref.name()));
+            return;
         }
         let rc_field = field.unwrap().clone();
         if !(*rc_field)
             .borrow()
             .is_accessible_to((*holder).borrow().deref())
         {
-            panic!("java.lang.IllegalAccessError")
+            let msg = format!(
+                "tried to access field {}.{} from class {}",
+                (*resolved_class).borrow().java_name(),
+                self.member_ref.name(),
+                (*holder).borrow().java_name()
+            );
+            set_pending_exception("java/lang/IllegalAccessError", Some(msg.as_str()));
+            return;
         }
 
         self.field = Some(rc_field);
@@ -84,7 +102,22 @@ impl FieldRef {
         return None;
     }
 
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        return self.member_ref.class_name();
+    }
+
     pub fn name(&self) -> &str {
         return self.member_ref.name();
     }
+
+    #[inline]
+    pub fn descriptor(&self) -> &str {
+        return self.member_ref.descriptor();
+    }
+
+    #[inline]
+    pub fn resolved_class(&mut self, holder: Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
+        return self.member_ref.resolved_class(holder);
+    }
 }
@@ -1,5 +1,6 @@
 use lark_classfile::constant_pool::ConstantInterfaceMethodRefInfo;
 use crate::oops::class::Class;
+use crate::invoke_support::set_pending_exception;
 use crate::oops::constant_pool::ConstantPool;
 use crate::oops::member_ref::MemberRef;
 use crate::oops::method::Method;
@@ -31,10 +32,16 @@ impl InterfaceMethodRef {
         return self.method.clone();
     }
 
+    /// jvms 5.4.3.4, a failed resolution leaves the error pending
     pub fn resolved_interface_method_ref(&mut self,holder:Rc<RefCell<Class>>) {
-        let class = self.member_ref.resolved_class(holder);
+        let class = match self.member_ref.resolved_class(holder) {
+            Some(class) => class,
+            None => return,
+        };
         if !(*class).borrow().is_interface() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            let msg = format!("Found class {}, but interface was expected", (*class).borrow().java_name());
+            set_pending_exception("java/lang/IncompatibleClassChangeError", Some(msg.as_str()));
+            return;
         }
         let method = Self::look_up_interface_method(
             class.clone(),
@@ -42,11 +49,14 @@ impl InterfaceMethodRef {
             self.descriptor(),
         );
         if method.is_none() {
-            panic!("java.lang.NoSuchMethodError");
+            let msg = format!("{}.{}{}", (*class).borrow().java_name(), self.name(), self.descriptor());
+            set_pending_exception("java/lang/NoSuchMethodError", Some(msg.as_str()));
+            return;
         }
         let point = method.clone().unwrap();
         if !(*point).is_accessible_to((*class).borrow().deref()) {
-            panic!("java.lang.IllegalAccessError");
+            set_pending_exception("java/lang/IllegalAccessError", Some(self.name()));
+            return;
         }
         self.method = method;
     }
@@ -63,14 +73,27 @@ impl InterfaceMethodRef {
                 return Some(method.clone());
             }
         }
-        return MethodRef::look_up_method_in_interfaces(
-            (*class).borrow().interfaces().unwrap(),
-            name,
-            desc,
-        );
+        // the public instance methods of Object, the superclass of every interface
+        if let Some(object) = borrow.super_class() {
+            for method in (*object).borrow().methods() {
+                if method.name() == name
+                    && method.descriptor() == desc
+                    && method.is_public()
+                    && !method.is_static()
+                {
+                    return Some(method.clone());
+                }
+            }
+        }
+        return MethodRef::look_up_method_in_super_interfaces(borrow.deref(), name, desc);
     }
 
     #[inline]
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        return self.member_ref.class_name();
+    }
+
     pub fn name(&self) -> &str {
         return self.member_ref.name();
     }
@@ -81,7 +104,18 @@ impl InterfaceMethodRef {
     }
 
     #[inline]
-    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    pub fn class(&self) -> Option<&Rc<RefCell<Class>>> {
+        return self.member_ref.class();
+    }
+
+    /// the method resolved so far, None before the first resolution
+    #[inline]
+    pub fn method(&self) -> Option<&Rc<Method>> {
+        return self.method.as_ref();
+    }
+
+    #[inline]
+    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         return self.member_ref.resolved_class(holder);
     }
 }
@@ -0,0 +1,52 @@
+use lark_classfile::constant_pool::ConstantInvokeDynamicInfo;
+use crate::oops::method_descriptor::MethodDescriptorParser;
+
+/// CONSTANT_InvokeDynamic_info,
+/// the CallSite itself is cached per invokedynamic instruction in `Method`
+#[derive(Debug)]
+pub struct InvokeDynamicRef {
+    bootstrap_method_attr_index: usize,
+    name: String,
+    descriptor: String,
+    arg_slot_count: usize,
+}
+
+impl InvokeDynamicRef {
+    pub fn new_invoke_dynamic_ref(info: &ConstantInvokeDynamicInfo) -> InvokeDynamicRef {
+        let (name, descriptor) = info.name_and_descriptor();
+        let md = MethodDescriptorParser::parse_method_descriptor(descriptor.as_str());
+        let mut arg_slot_count = 0;
+        for parameter_type in md.parameter_types() {
+            arg_slot_count += 1;
+            if parameter_type.as_str() == "J" || parameter_type.as_str() == "D" {
+                arg_slot_count += 1;
+            }
+        }
+        return InvokeDynamicRef {
+            bootstrap_method_attr_index: info.bootstrap_method_attr_index() as usize,
+            name,
+            descriptor,
+            arg_slot_count,
+        };
+    }
+
+    #[inline]
+    pub fn bootstrap_method_attr_index(&self) -> usize {
+        return self.bootstrap_method_attr_index;
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        return self.name.as_str();
+    }
+
+    #[inline]
+    pub fn descriptor(&self) -> &str {
+        return self.descriptor.as_str();
+    }
+
+    #[inline]
+    pub fn arg_slot_count(&self) -> usize {
+        return self.arg_slot_count;
+    }
+}
@@ -30,6 +30,11 @@ impl MemberRef {
         self.descriptor = desc.to_string();
     }
 
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        return self.symbol_ref.class_name();
+    }
+
     #[inline]
     pub fn name(&self) -> &str {
         return self.name.as_str();
@@ -41,7 +46,12 @@ impl MemberRef {
     }
 
     #[inline]
-    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    pub fn class(&self) -> Option<&Rc<RefCell<Class>>> {
+        return self.symbol_ref.class();
+    }
+
+    #[inline]
+    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         return self.symbol_ref.resolved_class(holder);
     }
 }
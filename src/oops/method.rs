@@ -2,16 +2,20 @@ use lark_classfile::attribute_info::Attribute::{Code, Exceptions, RuntimeVisible
 use lark_classfile::exceptions_attribute::ExceptionsAttribute;
 use lark_classfile::line_number_table_attribute::LineNumberTableAttribute;
 use lark_classfile::member_info::MemberInfo;
+use lark_classfile::stack_map_table_attribute::StackMapAttribute;
 use lark_classfile::runtime_visible_annotations_attribute::AnnotationAttribute;
 use crate::class_loader::app_class_loader::ClassLoader;
-use crate::oops::access_flags::NATIVE;
+use crate::instructions::base::instruction_stream::InstructionStream;
+use crate::oops::access_flags::{NATIVE, SYNCHRONIZED, VARARGS};
 use crate::oops::class::Class;
 use crate::oops::class_member::ClassMember;
 use crate::oops::class_name_helper::PrimitiveTypes;
 use crate::oops::constant_pool::Constant::ClassReference;
 use crate::oops::exception_table::ExceptionTable;
+use crate::oops::object::Object;
 use crate::oops::method_descriptor::{MethodDescriptor, MethodDescriptorParser};
-use std::cell::RefCell;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
 use std::ptr;
 use std::rc::Rc;
 use std::ops::Deref;
@@ -23,14 +27,24 @@ pub struct Method {
     max_stack: usize,
     max_locals: usize,
     code: Vec<u8>,
+    instructions: OnceCell<InstructionStream>,
     arg_slot_count: usize,
+    /// the slot in the vtable for a method of a class, the position in the
+    /// itable block for a method of an interface
+    vtable_index: Cell<usize>,
     exception_table: ExceptionTable,
     line_number_table: Option<LineNumberTableAttribute>,
+    stack_map_table: Option<StackMapAttribute>,
     annotations: Option<Vec<AnnotationAttribute>>,
     exceptions: Vec<u16>,
     method_desc: MethodDescriptor,
+    call_sites: RefCell<HashMap<i32, Rc<RefCell<Object>>>>,
+    invokers: RefCell<HashMap<i32, Invoker>>,
 }
 
+/// the invoker MemberName and its appendix linked for a MethodHandle.invoke(Exact) call
+pub type Invoker = (Rc<RefCell<Object>>, Option<Rc<RefCell<Object>>>);
+
 impl Method {
     #[inline]
     pub fn new() -> Method {
@@ -39,12 +53,17 @@ impl Method {
             max_stack: 0,
             max_locals: 0,
             code: vec![],
+            instructions: OnceCell::new(),
             arg_slot_count: 0,
+            vtable_index: Cell::new(0),
             exception_table: ExceptionTable::default(),
             line_number_table: None,
+            stack_map_table: None,
             annotations: None,
             exceptions: vec![],
             method_desc: MethodDescriptor::new(),
+            call_sites: RefCell::new(HashMap::new()),
+            invokers: RefCell::new(HashMap::new()),
         };
     }
 
@@ -80,6 +99,7 @@ impl Method {
                     self.max_stack = attr.max_stack() as usize;
                     self.code = attr.code().clone();
                     self.line_number_table = attr.line_number_table_attribute();
+                    self.stack_map_table = attr.stack_map_table_attribute();
                     self.exception_table = ExceptionTable::new(
                         attr.exception_table(),
                         (*self.class()).borrow().constant_pool(),
@@ -163,6 +183,16 @@ impl Method {
         return self.arg_slot_count;
     }
 
+    #[inline]
+    pub fn vtable_index(&self) -> usize {
+        return self.vtable_index.get();
+    }
+
+    #[inline]
+    pub fn set_vtable_index(&self, index: usize) {
+        self.vtable_index.set(index);
+    }
+
     #[inline]
     pub fn code(&self) -> Vec<u8> {
         unsafe {
@@ -174,6 +204,24 @@ impl Method {
         }
     }
 
+    /// the code decoded on the first call
+    #[inline]
+    pub fn instructions(&self) -> &InstructionStream {
+        return self
+            .instructions
+            .get_or_init(|| InstructionStream::decode(&self.code));
+    }
+
+    #[inline]
+    pub fn exception_table(&self) -> &ExceptionTable {
+        return &self.exception_table;
+    }
+
+    #[inline]
+    pub fn stack_map_table(&self) -> Option<&StackMapAttribute> {
+        return self.stack_map_table.as_ref();
+    }
+
     #[inline]
     pub fn is_accessible_to(&self, class: &Class) -> bool {
         return self.class_member.is_accessible_to(class);
@@ -209,6 +257,16 @@ impl Method {
         return 0 != self.class_member.access_flags() & NATIVE;
     }
 
+    #[inline]
+    pub fn is_synchronized(&self) -> bool {
+        return 0 != self.access_flags() & SYNCHRONIZED;
+    }
+
+    #[inline]
+    pub fn is_varargs(&self) -> bool {
+        return 0 != self.access_flags() & VARARGS;
+    }
+
     pub fn get_line_number(&self, pc: i32) -> i32 {
         if self.is_native() {
             return -2;
@@ -257,6 +315,11 @@ impl Method {
     }
 
     // reflection
+    #[inline]
+    pub fn method_descriptor(&self) -> &MethodDescriptor {
+        return &self.method_desc;
+    }
+
     pub fn parameter_types(&self) -> Option<Vec<Rc<RefCell<Class>>>> {
         if self.arg_slot_count == 0 {
             return None;
@@ -269,7 +332,7 @@ impl Method {
                 .unwrap()
                 .to_class_name(param_type.as_str());
             /// todo
-            let param_type = ClassLoader::load_class(class_loader.clone(),param_class_name.as_str());
+            let param_type = ClassLoader::load_class(class_loader.clone(),param_class_name.as_str()).unwrap();
             param_classes.push(param_type);
         }
 
@@ -288,13 +351,15 @@ impl Method {
 
         for i in 0..self.exceptions.len() {
             let ex_index = self.exceptions[i];
-            ex_classes.push(Self::resolve_class_ref(ex_index as usize,class.clone()));
+            if let Some(ex_class) = Self::resolve_class_ref(ex_index as usize,class.clone()) {
+                ex_classes.push(ex_class);
+            }
         }
 
         return Some(ex_classes);
     }
 
-    fn resolve_class_ref(index:usize,class:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    fn resolve_class_ref(index:usize,class:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         let constant = (*class)
             .borrow_mut()
             .mut_constant_pool()
@@ -324,6 +389,28 @@ impl Method {
         return return_type.expect("The return class not loaded");
     }
 
+    /// the CallSite linked by the invokedynamic instruction at pc
+    #[inline]
+    pub fn call_site(&self, pc: i32) -> Option<Rc<RefCell<Object>>> {
+        return self.call_sites.borrow().get(&pc).cloned();
+    }
+
+    #[inline]
+    pub fn set_call_site(&self, pc: i32, call_site: Rc<RefCell<Object>>) {
+        self.call_sites.borrow_mut().insert(pc, call_site);
+    }
+
+    /// the invoker linked by the signature polymorphic call at pc
+    #[inline]
+    pub fn invoker(&self, pc: i32) -> Option<Invoker> {
+        return self.invokers.borrow().get(&pc).cloned();
+    }
+
+    #[inline]
+    pub fn set_invoker(&self, pc: i32, invoker: Invoker) {
+        self.invokers.borrow_mut().insert(pc, invoker);
+    }
+
     pub fn shim_return_method() -> Method {
         let mut class = Class::default();
         return Method {
@@ -331,12 +418,40 @@ impl Method {
             max_stack: 0,
             max_locals: 0,
             code: vec![0xb1],
+            instructions: OnceCell::new(),
+            arg_slot_count: 0,
+            vtable_index: Cell::new(0),
+            exception_table: ExceptionTable::default(),
+            line_number_table: None,
+            stack_map_table: None,
+            annotations: None,
+            exceptions: vec![],
+            method_desc: MethodDescriptor::new(),
+            call_sites: RefCell::new(HashMap::new()),
+            invokers: RefCell::new(HashMap::new()),
+        };
+    }
+
+    /// a method of a dummy class running `code`
+    #[cfg(test)]
+    pub fn with_code(max_stack: usize, max_locals: usize, code: Vec<u8>) -> Method {
+        let class = Class::default();
+        return Method {
+            class_member: ClassMember::shim(class),
+            max_stack,
+            max_locals,
+            code,
+            instructions: OnceCell::new(),
             arg_slot_count: 0,
+            vtable_index: Cell::new(0),
             exception_table: ExceptionTable::default(),
             line_number_table: None,
+            stack_map_table: None,
             annotations: None,
             exceptions: vec![],
             method_desc: MethodDescriptor::new(),
+            call_sites: RefCell::new(HashMap::new()),
+            invokers: RefCell::new(HashMap::new()),
         };
     }
 }
@@ -0,0 +1,146 @@
+use crate::class_loader::app_class_loader::ClassLoader;
+use crate::invoke_support::method_handle::{link_method_handle_constant, method_type};
+use crate::oops::class::Class;
+use crate::oops::class_name_helper::PrimitiveTypes;
+use crate::oops::constant_pool::Constant::{
+    FieldReference, InterfaceMethodReference, MethodReference,
+};
+use crate::oops::object::Object;
+use lark_classfile::constant_pool::{ConstantMethodHandleInfo, ConstantMethodTypeInfo};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const REF_GET_FIELD: u8 = 1;
+pub const REF_GET_STATIC: u8 = 2;
+pub const REF_PUT_FIELD: u8 = 3;
+pub const REF_PUT_STATIC: u8 = 4;
+pub const REF_INVOKE_VIRTUAL: u8 = 5;
+pub const REF_INVOKE_STATIC: u8 = 6;
+pub const REF_INVOKE_SPECIAL: u8 = 7;
+pub const REF_NEW_INVOKE_SPECIAL: u8 = 8;
+pub const REF_INVOKE_INTERFACE: u8 = 9;
+
+/// CONSTANT_MethodHandle_info,
+/// reference_index points at a FieldReference, MethodReference or InterfaceMethodReference
+#[derive(Debug)]
+pub struct MethodHandleRef {
+    reference_kind: u8,
+    reference_index: usize,
+    method_handle: Option<Rc<RefCell<Object>>>,
+}
+
+impl MethodHandleRef {
+    #[inline]
+    pub fn new_method_handle_ref(info: &ConstantMethodHandleInfo) -> MethodHandleRef {
+        return MethodHandleRef {
+            reference_kind: info.reference_kind(),
+            reference_index: info.reference_index() as usize,
+            method_handle: None,
+        };
+    }
+
+    #[inline]
+    pub fn reference_kind(&self) -> u8 {
+        return self.reference_kind;
+    }
+
+    #[inline]
+    pub fn reference_index(&self) -> usize {
+        return self.reference_index;
+    }
+
+    /// jvms 5.4.3.5, the MethodHandle is created once by
+    /// MethodHandleNatives.linkMethodHandleConstant, which must be initialized
+    /// returns None with the resolution error pending
+    pub fn resolved_method_handle(&mut self, holder: Rc<RefCell<Class>>) -> Option<Rc<RefCell<Object>>> {
+        if self.method_handle.is_none() {
+            self.resolved_method_handle_ref(holder);
+        }
+        return self.method_handle.clone();
+    }
+
+    fn resolved_method_handle_ref(&mut self, holder: Rc<RefCell<Class>>) {
+        let constant = (*holder)
+            .borrow_mut()
+            .mut_constant_pool()
+            .take_constant(self.reference_index);
+        let (class, name, descriptor, constant) = match constant {
+            FieldReference(mut r) => {
+                let class = r.resolved_class(holder.clone());
+                (class, r.name().to_string(), r.descriptor().to_string(), FieldReference(r))
+            }
+            MethodReference(mut r) => {
+                let class = r.resolved_class(holder.clone());
+                (class, r.name().to_string(), r.descriptor().to_string(), MethodReference(r))
+            }
+            InterfaceMethodReference(mut r) => {
+                let class = r.resolved_class(holder.clone());
+                let (name, descriptor) = (r.name().to_string(), r.descriptor().to_string());
+                (class, name, descriptor, InterfaceMethodReference(r))
+            }
+            _ => panic!("Unknown constant type"),
+        };
+        (*holder)
+            .borrow_mut()
+            .mut_constant_pool()
+            .restoration_constant(self.reference_index, constant);
+        let class = match class {
+            Some(class) => class,
+            None => return,
+        };
+
+        let loader = (*holder).borrow().get_class_loader();
+        let type_object = match self.reference_kind <= REF_PUT_STATIC {
+            true => {
+                let class_name = PrimitiveTypes::instance()
+                    .unwrap()
+                    .to_class_name(descriptor.as_str());
+                let field_type = match ClassLoader::load_class(loader, class_name.as_str()) {
+                    Some(class) => class,
+                    None => return,
+                };
+                let java_class = (*field_type).borrow().get_java_class();
+                java_class.unwrap()
+            }
+            false => method_type(descriptor.as_str(), loader),
+        };
+        self.method_handle = link_method_handle_constant(
+            holder,
+            self.reference_kind,
+            class,
+            name.as_str(),
+            type_object,
+        );
+    }
+}
+
+/// CONSTANT_MethodType_info
+#[derive(Debug)]
+pub struct MethodTypeRef {
+    descriptor: String,
+    method_type: Option<Rc<RefCell<Object>>>,
+}
+
+impl MethodTypeRef {
+    #[inline]
+    pub fn new_method_type_ref(info: &ConstantMethodTypeInfo) -> MethodTypeRef {
+        return MethodTypeRef {
+            descriptor: info.descriptor(),
+            method_type: None,
+        };
+    }
+
+    #[inline]
+    pub fn descriptor(&self) -> &str {
+        return self.descriptor.as_str();
+    }
+
+    /// jvms 5.4.3.5, the MethodType is created once in the holder's defining loader
+    pub fn resolved_method_type(&mut self, holder: Rc<RefCell<Class>>) -> Rc<RefCell<Object>> {
+        if self.method_type.is_none() {
+            let loader = (*holder).borrow().get_class_loader();
+            self.method_type = Some(method_type(self.descriptor.as_str(), loader));
+        }
+        return self.method_type.clone().unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 use lark_classfile::constant_pool::ConstantMethodRefInfo;
-use crate::oops::class::{Class, Interfaces};
+use crate::oops::class::Class;
+use crate::invoke_support::set_pending_exception;
 use crate::oops::constant_pool::ConstantPool;
 use crate::oops::member_ref::MemberRef;
 use crate::oops::method::Method;
@@ -25,6 +26,11 @@ impl MethodRef {
         return field_ref;
     }
 
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        return self.member_ref.class_name();
+    }
+
     #[inline]
     pub fn name(&self) -> &str {
         return self.member_ref.name();
@@ -36,7 +42,18 @@ impl MethodRef {
     }
 
     #[inline]
-    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    pub fn class(&self) -> Option<&Rc<RefCell<Class>>> {
+        return self.member_ref.class();
+    }
+
+    /// the method resolved so far, None before the first resolution
+    #[inline]
+    pub fn method(&self) -> Option<&Rc<Method>> {
+        return self.method.as_ref();
+    }
+
+    #[inline]
+    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         return self.member_ref.resolved_class(holder);
     }
 
@@ -47,17 +64,26 @@ impl MethodRef {
         return self.method.clone();
     }
 
+    /// jvms 5.4.3.3, a failed resolution leaves the error pending
     fn resolved_method_ref(&mut self,holder:Rc<RefCell<Class>>) {
-        let class = self.member_ref.resolved_class(holder);
+        let class = match self.member_ref.resolved_class(holder) {
+            Some(class) => class,
+            None => return,
+        };
         if (*class).borrow().is_interface() {
-            panic!("java.lang.IncompatibleClassChangeError");
+            let msg = format!("Found interface {}, but class was expected", (*class).borrow().java_name());
+            set_pending_exception("java/lang/IncompatibleClassChangeError", Some(msg.as_str()));
+            return;
         }
         let method = MethodRef::look_up_method(class.clone(), self.name(), self.descriptor());
         if method.is_none() {
-            panic!("java.lang.NoSuchMethodError");
+            let msg = format!("{}.{}{}", (*class).borrow().java_name(), self.name(), self.descriptor());
+            set_pending_exception("java/lang/NoSuchMethodError", Some(msg.as_str()));
+            return;
         }
         if !(*method.clone().unwrap()).is_accessible_to((*class).borrow().deref()) {
-            panic!("java.lang.IllegalAccessError");
+            set_pending_exception("java/lang/IllegalAccessError", Some(self.name()));
+            return;
         }
         self.method = method;
     }
@@ -65,15 +91,50 @@ impl MethodRef {
     pub fn look_up_method(class: Rc<RefCell<Class>>, name: &str, desc: &str) -> Option<Rc<Method>> {
         let mut method = MethodRef::look_up_method_in_class(class.clone(), name, desc);
         if method.is_none() {
-            method = MethodRef::look_up_method_in_interfaces(
-                (*class).borrow().interfaces().unwrap(),
-                name,
-                desc,
-            );
+            method = MethodRef::look_up_method_in_super_interfaces((*class).borrow().deref(), name, desc);
         }
         return method;
     }
 
+    /// jvms 5.4.3.3 step 3, the one non-abstract maximally-specific method,
+    /// else any of them
+    pub fn look_up_method_in_super_interfaces(class: &Class, name: &str, desc: &str) -> Option<Rc<Method>> {
+        let methods = MethodRef::maximally_specific_methods(class, name, desc);
+        let mut non_abstract = methods.iter().filter(|method| !method.is_abstract());
+        if let (Some(method), None) = (non_abstract.next(), non_abstract.next()) {
+            return Some(method.clone());
+        }
+        return methods.first().cloned();
+    }
+
+    /// jvms 5.4.3.3, the instance methods of the superinterfaces of the class
+    /// that no method of a subinterface among them overrides
+    pub fn maximally_specific_methods(class: &Class, name: &str, desc: &str) -> Vec<Rc<Method>> {
+        let mut candidates = vec![];
+        for interface in class.all_interfaces() {
+            for method in (*interface).borrow().methods() {
+                if method.name() == name
+                    && method.descriptor() == desc
+                    && !method.is_private()
+                    && !method.is_static()
+                {
+                    candidates.push((interface.clone(), method.clone()));
+                }
+            }
+        }
+        let mut methods = vec![];
+        for (interface, method) in &candidates {
+            let interface = (**interface).borrow();
+            let overridden = candidates
+                .iter()
+                .any(|(other, _)| (**other).borrow().is_sub_interface_of(interface.deref()));
+            if !overridden {
+                methods.push(method.clone());
+            }
+        }
+        return methods;
+    }
+
     pub fn look_up_method_in_class(
         class: Rc<RefCell<Class>>,
         name: &str,
@@ -93,29 +154,4 @@ impl MethodRef {
         }
         return None;
     }
-
-    pub fn look_up_method_in_interfaces(
-        interfaces: &Interfaces,
-        name: &str,
-        desc: &str,
-    ) -> Option<Rc<Method>> {
-        for interface in interfaces {
-            let borrow = (**interface).borrow();
-            let methods = borrow.methods();
-            for method in methods {
-                if method.name() == name && method.descriptor() == desc {
-                    return Some(method.clone());
-                }
-            }
-            let method = MethodRef::look_up_method_in_interfaces(
-                (**interface).borrow().interfaces().unwrap(),
-                name,
-                desc,
-            );
-            if method.is_some() {
-                return method;
-            }
-        }
-        return None;
-    }
 }
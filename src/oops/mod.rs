@@ -9,9 +9,11 @@ mod exception_table;
 pub mod field;
 pub mod field_ref;
 pub mod interface_method_ref;
+pub mod invoke_dynamic_ref;
 pub mod member_ref;
 pub mod method;
 pub mod method_descriptor;
+pub mod method_handle_ref;
 pub mod method_ref;
 pub mod object;
 pub mod slots;
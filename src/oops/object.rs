@@ -6,19 +6,21 @@ use crate::oops::method::Method;
 use crate::oops::object::DataType::StandardObject;
 use crate::oops::object::MetaData::{Null};
 use crate::oops::slots::Slots;
+use crate::runtime::monitor::Monitor;
+use crate::runtime::os_thread::OsThread;
 use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::fs::File;
-use crate::utils::boxed;
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Object {
     pub class: Rc<RefCell<Class>>,
     pub data: DataType,
     pub meta: Option<Rc<RefCell<Class>>>,
     pub trace: Option<Vec<StackTraceElement>>,
     pub meta_data: MetaData,
+    pub monitor: Arc<Monitor>,
 }
 
 impl Object {
@@ -30,6 +32,7 @@ impl Object {
             meta: None,
             trace: None,
             meta_data: MetaData::Null,
+            monitor: Arc::new(Monitor::new()),
         };
     }
 
@@ -63,6 +66,12 @@ impl Object {
         self.meta_data = data;
     }
 
+    /// shared so that it can be blocked on without borrowing the object
+    #[inline]
+    pub fn monitor(&self) -> Arc<Monitor> {
+        return self.monitor.clone();
+    }
+
     #[inline]
     pub fn fields(&mut self) -> &mut Slots {
         let fields = &mut self.data;
@@ -111,19 +120,6 @@ impl Object {
             .is_assignable_from(self.class.as_ref().borrow().borrow());
     }
 
-    #[inline]
-    pub fn file(&self) -> Rc<RefCell<File>> {
-        match &self.meta_data {
-            MetaData::File(file) => file.clone(),
-            _ => panic!("The Object isn't file"),
-        }
-    }
-
-    #[inline]
-    pub fn set_file(&mut self, file: File) {
-        self.set_meta_data(MetaData::File(boxed(file)));
-    }
-
     #[inline]
     pub fn get_class_loader(&self) -> Rc<RefCell<ClassLoader>> {
         match &self.meta_data {
@@ -184,6 +180,21 @@ impl Object {
     }
 
     #[inline]
+    pub fn set_long_var(&mut self, name: &str, descriptor: &str, val: i64) {
+        let field = Class::get_field(Some(self.class.clone()), name, descriptor, false);
+        let slots = self.fields();
+        slots.set_long((*field.unwrap()).borrow().slot_id(), val);
+    }
+
+    /// the VM thread of a started java.lang.Thread
+    #[inline]
+    pub fn os_thread(&self) -> Option<Arc<OsThread>> {
+        match &self.meta_data {
+            MetaData::Thread(os_thread) => Some(os_thread.clone()),
+            _ => None,
+        }
+    }
+
     pub fn get_long_var_by_slot_id(&self, slot_id: usize) -> i64 {
         let slots = match &self.data {
             StandardObject(data) => data.as_ref().unwrap(),
@@ -202,6 +213,20 @@ impl Object {
     }
 }
 
+/// a copied object starts out unlocked
+impl Clone for Object {
+    fn clone(&self) -> Self {
+        return Object {
+            class: self.class.clone(),
+            data: self.data.clone(),
+            meta: self.meta.clone(),
+            trace: self.trace.clone(),
+            meta_data: self.meta_data.clone(),
+            monitor: Arc::new(Monitor::new()),
+        };
+    }
+}
+
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         let l = self as *const Object;
@@ -232,7 +257,7 @@ pub enum MetaData {
     Field(Rc<RefCell<Field>>),
     Method(Rc<Method>),
     ClassLoader(Rc<RefCell<ClassLoader>>),
-    File(Rc<RefCell<File>>),
+    Thread(Arc<OsThread>),
 }
 
 impl MetaData {
@@ -60,6 +60,21 @@ impl Slots {
     pub fn get_ref(&self, index: usize) -> Option<Rc<RefCell<Object>>> {
         return self.slots[index].reference.clone();
     }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        return self.slots.len();
+    }
+
+    pub fn references(&self) -> impl Iterator<Item = &Rc<RefCell<Object>>> {
+        return self.slots.iter().filter_map(|slot| slot.reference.as_ref());
+    }
+
+    pub fn clear_references(&mut self) {
+        for slot in &mut self.slots {
+            slot.reference = None;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,9 @@
+use crate::gc::heap::Heap;
 use crate::jvm::Jvm;
 use crate::oops::class::Class;
 use crate::oops::object::DataType::Chars;
 use crate::oops::object::Object;
-use crate::utils::{boxed, java_str_to_rust_str};
+use crate::utils::java_str_to_rust_str;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -47,14 +48,19 @@ impl StringPool {
             Object::from_data(bootstrap_loader.find_or_create("[C").unwrap(), Chars(chars));
         let mut java_string =
             Class::new_object(&bootstrap_loader.find_or_create("java/lang/String").unwrap());
-        java_string.set_ref_var("value", "[C", boxed(java_chars));
-        let target = boxed(java_string);
+        java_string.set_ref_var("value", "[C", Heap::allocate(java_chars));
+        let target = Heap::allocate(java_string);
         StringPool::mut_instance()
             .pool
             .insert(string, target.clone());
         return target;
     }
 
+    /// the interned strings, roots of the garbage collector
+    pub fn strings() -> impl Iterator<Item = &'static Rc<RefCell<Object>>> {
+        return StringPool::instance().pool.values();
+    }
+
     ///java sdk function
     /// string.intern
     pub fn intern_string(string: Rc<RefCell<Object>>) -> Rc<RefCell<Object>> {
@@ -1,8 +1,10 @@
 use lark_classfile::constant_pool::ConstantClassInfo;
 use crate::class_loader::app_class_loader::ClassLoader;
+use crate::invoke_support::set_pending_exception;
 use crate::jvm::Jvm;
 use crate::oops::class::Class;
 use crate::oops::object::Object;
+use crate::runtime::thread::JavaThread;
 use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -30,32 +32,78 @@ impl SymbolRef {
         };
     }
 
+    #[inline]
+    pub fn class_name(&self) -> &str {
+        return self.class_name.as_str();
+    }
+
     #[inline]
     pub fn set_class_name(&mut self, name: String) {
         self.class_name = name;
     }
 
-    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    /// the class resolved so far, None before the first resolution
+    #[inline]
+    pub fn class(&self) -> Option<&Rc<RefCell<Class>>> {
+        return self.class.as_ref();
+    }
+
+    /// returns None with a NoClassDefFoundError or IllegalAccessError pending
+    pub fn resolved_class(&mut self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         if self.class.is_none() {
             self.resolved_class_ref(holder);
         }
-        let class = self.class.as_ref().unwrap();
-        return class.clone();
+        return self.class.clone();
     }
 
     pub fn resolved_class_ref(&mut self, holder:Rc<RefCell<Class>>) {
-        let ref_class = self.resolve_load(holder.clone());
+        let ref_class = match self.resolve_load(holder.clone()) {
+            Some(class) => class,
+            None => {
+                // a LinkageError like VerifyError is thrown as it is,
+                // a ClassNotFoundException from a class loader becomes the linkage error
+                if Self::is_linkage_error_pending() {
+                    return;
+                }
+                JavaThread::take_pending_exception();
+                let msg = self.class_name.replace('/', ".");
+                set_pending_exception("java/lang/NoClassDefFoundError", Some(msg.as_str()));
+                return;
+            }
+        };
         if !(*ref_class)
             .borrow()
             .is_accessible_to((*holder).borrow().deref())
         {
-            panic!("java.lang.IllegalAccessError");
+            let msg = format!(
+                "tried to access class {} from class {}",
+                (*ref_class).borrow().java_name(),
+                (*holder).borrow().java_name()
+            );
+            set_pending_exception("java/lang/IllegalAccessError", Some(msg.as_str()));
+            return;
         }
         self.class = Some(ref_class);
     }
 
-    fn resolve_load(&self,holder:Rc<RefCell<Class>>) -> Rc<RefCell<Class>> {
+    fn is_linkage_error_pending() -> bool {
+        let exception = match JavaThread::take_pending_exception() {
+            Some(exception) => exception,
+            None => return false,
+        };
+        let mut class = Some((*exception).borrow().class());
+        JavaThread::set_pending_exception(exception);
+        while let Some(current) = class {
+            if (*current).borrow().name() == "java/lang/LinkageError" {
+                return true;
+            }
+            class = (*current).borrow().super_class();
+        }
+        return false;
+    }
+
+    fn resolve_load(&self,holder:Rc<RefCell<Class>>) -> Option<Rc<RefCell<Class>>> {
         let class_loader = (*holder).borrow().get_class_loader();
-        return ClassLoader::load_class(class_loader, self.class_name.as_str());
+        return ClassLoader::find_or_load_class(class_loader, self.class_name.as_str());
     }
 }
@@ -1,3 +1,4 @@
+use crate::gc::heap::Heap;
 use crate::invoke_support::parameter::{Parameter, Parameters};
 use crate::invoke_support::{invoke, ReturnType};
 use crate::jni::{JObject, JString};
@@ -5,7 +6,7 @@ use crate::jvm::Jvm;
 use crate::prims::perf_data::{PerfDataManager, Units, Variability};
 use crate::oops::class::Class;
 use crate::oops::object::Object;
-use crate::utils::{boxed, jstr_to_utf_nullable};
+use crate::utils::jstr_to_utf_nullable;
 use std::mem::size_of;
 
 pub struct Perf();
@@ -53,7 +54,7 @@ impl Perf {
             .find_or_create("java/nio/DirectByteBuffer")
             .unwrap();
         let method = Class::get_instance_method(class.clone(), "<init>", "(JI)V");
-        let this = boxed(Class::new_object(&class));
+        let this = Heap::allocate(Class::new_object(&class));
         let param = vec![
             Parameter::Object(Some(this.clone())),
             Parameter::Long(pointer as i64),
@@ -1,4 +1,5 @@
 use crate::oops::method::Method;
+use crate::oops::object::Object;
 use crate::runtime::local_vars::LocalVars;
 use crate::runtime::operand_stack::OperandStack;
 use crate::runtime::thread::JavaThread;
@@ -11,7 +12,9 @@ pub struct Frame {
     thread: Rc<RefCell<JavaThread>>,
     method: Rc<Method>,
     next_pc: i32,
-    frame_type:FrameType
+    frame_type:FrameType,
+    /// the object whose monitor a synchronized method entered
+    monitor: Option<Rc<RefCell<Object>>>,
 }
 
 impl Frame {
@@ -23,7 +26,8 @@ impl Frame {
             thread,
             method,
             next_pc: 0,
-            frame_type: Default::default()
+            frame_type: Default::default(),
+            monitor: None,
         };
     }
 
@@ -39,7 +43,8 @@ impl Frame {
             thread: thread,
             method: Rc::new(Method::new()),
             next_pc: 0,
-            frame_type: Default::default()
+            frame_type: Default::default(),
+            monitor: None,
         };
     }
 
@@ -88,6 +93,14 @@ impl Frame {
         return self.method.clone();
     }
 
+    /// the bottom frame of a call into Java made by the VM,
+    /// an exception unwinding to it is handed back to the caller
+    pub fn new_entry_frame(thread: Rc<RefCell<JavaThread>>, method: Rc<Method>) -> Frame {
+        let mut frame = Frame::new(thread, method);
+        frame.frame_type = FrameType::EntryFrame;
+        return frame;
+    }
+
     pub fn new_shim_frame(thread: Rc<RefCell<JavaThread>>, ops: OperandStack) -> Frame {
         return Frame {
             local_vars: None,
@@ -95,15 +108,47 @@ impl Frame {
             method: Rc::new(Method::shim_return_method()),
             operand_stack: Some(ops),
             next_pc: 0,
-            frame_type: Default::default()
+            frame_type: Default::default(),
+            monitor: None,
         };
     }
 
+    /// enters the monitor of a synchronized method once the arguments are in place,
+    /// the receiver's monitor or the one of the Class object for a static method
+    pub fn lock_if_synchronized(&mut self) {
+        if !self.method.is_synchronized() {
+            return;
+        }
+        let object = match self.method.is_static() {
+            true => (*self.method.class()).borrow().get_java_class(),
+            false => self.immutable_local_vars().expect("vars is none").get_this(),
+        };
+        let object = object.expect("synchronized method without lock object");
+        let monitor = (*object).borrow().monitor();
+        monitor.enter();
+        self.monitor = Some(object);
+    }
+
+    /// leaves the monitor entered by `lock_if_synchronized`,
+    /// on return as well as when an exception unwinds the frame
+    pub fn unlock_if_synchronized(&mut self) {
+        if let Some(object) = self.monitor.take() {
+            (*object).borrow().monitor().exit();
+        }
+    }
+
     #[inline]
     pub fn immutable_local_vars(&self) -> Option<&LocalVars> {
         return self.local_vars.as_ref();
     }
 
+    /// the objects the frame refers to, roots of the garbage collector
+    pub fn references(&self) -> impl Iterator<Item = &Rc<RefCell<Object>>> {
+        let vars = self.local_vars.iter().flat_map(|vars| vars.references());
+        let stack = self.operand_stack.iter().flat_map(|stack| stack.references());
+        return vars.chain(stack).chain(self.monitor.iter());
+    }
+
     #[inline]
     pub fn is_intrinsic_frame(&self) -> bool {
         if let FrameType::IntrinsicFrame = self.frame_type {
@@ -111,11 +156,20 @@ impl Frame {
         }
         false
     }
+
+    #[inline]
+    pub fn is_entry_frame(&self) -> bool {
+        if let FrameType::EntryFrame = self.frame_type {
+            return true;
+        }
+        false
+    }
 }
 
 enum FrameType {
     InterpreterFrame,
-    IntrinsicFrame
+    IntrinsicFrame,
+    EntryFrame,
 }
 
 impl Default for FrameType {
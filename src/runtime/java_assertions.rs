@@ -0,0 +1,179 @@
+/// The -ea, -da, -esa and -dsa switches, consulted by
+/// Class.desiredAssertionStatus0 and handed to the class library by
+/// ClassLoader.retrieveDirectives. Class and package names are kept in the
+/// internal form, "java/lang/Object".
+pub struct JavaAssertions {
+    user_default: bool,
+    system_default: bool,
+    /// the class switches in command line order, the last match wins
+    classes: Vec<(String, bool)>,
+    /// the package switches in command line order, "" is the unnamed package
+    packages: Vec<(String, bool)>,
+}
+
+static mut JAVA_ASSERTIONS: Option<JavaAssertions> = None;
+
+impl JavaAssertions {
+    pub fn new() -> JavaAssertions {
+        return JavaAssertions {
+            user_default: false,
+            system_default: false,
+            classes: Vec::new(),
+            packages: Vec::new(),
+        };
+    }
+
+    pub fn init(options: &[String]) {
+        let mut assertions = JavaAssertions::new();
+        for option in options {
+            assertions.add_option(option);
+        }
+        unsafe {
+            JAVA_ASSERTIONS = Some(assertions);
+        }
+    }
+
+    pub fn instance() -> &'static JavaAssertions {
+        unsafe {
+            if JAVA_ASSERTIONS.is_none() {
+                JAVA_ASSERTIONS = Some(JavaAssertions::new());
+            }
+            return JAVA_ASSERTIONS.as_ref().unwrap();
+        }
+    }
+
+    /// an option the launcher accepted, like "-ea:com.example..." or "-dsa"
+    pub fn add_option(&mut self, option: &str) {
+        let (name, argument) = match option.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (option, None),
+        };
+        let enabled = match name {
+            "-ea" | "-enableassertions" => true,
+            "-da" | "-disableassertions" => false,
+            "-esa" | "-enablesystemassertions" => {
+                self.system_default = true;
+                return;
+            }
+            "-dsa" | "-disablesystemassertions" => {
+                self.system_default = false;
+                return;
+            }
+            _ => return,
+        };
+        match argument {
+            None | Some("") => self.user_default = enabled,
+            Some(argument) => {
+                // "..." is the package marker, not three separators
+                match argument.strip_suffix("...") {
+                    Some(package) => {
+                        let package = package.trim_end_matches('.').replace('.', "/");
+                        self.packages.push((package, enabled));
+                    }
+                    None => self.classes.push((argument.replace('.', "/"), enabled)),
+                }
+            }
+        }
+    }
+
+    /// whether `class_name` runs its assert statements, `system_class` is
+    /// true for the classes of the bootstrap loader
+    pub fn enabled(&self, class_name: &str, system_class: bool) -> bool {
+        if let Some(enabled) = last_match(&self.classes, class_name) {
+            return enabled;
+        }
+        // the innermost package with a switch decides
+        let mut package = match class_name.rfind('/') {
+            Some(index) => &class_name[..index],
+            None => "",
+        };
+        loop {
+            if let Some(enabled) = last_match(&self.packages, package) {
+                return enabled;
+            }
+            match package.rfind('/') {
+                Some(index) => package = &package[..index],
+                None => break,
+            }
+        }
+        return if system_class {
+            self.system_default
+        } else {
+            self.user_default
+        };
+    }
+
+    #[inline]
+    pub fn user_default(&self) -> bool {
+        return self.user_default;
+    }
+
+    #[inline]
+    pub fn classes(&self) -> &Vec<(String, bool)> {
+        return &self.classes;
+    }
+
+    #[inline]
+    pub fn packages(&self) -> &Vec<(String, bool)> {
+        return &self.packages;
+    }
+}
+
+fn last_match(switches: &[(String, bool)], name: &str) -> Option<bool> {
+    return switches
+        .iter()
+        .rev()
+        .find(|(switch, _)| switch == name)
+        .map(|(_, enabled)| *enabled);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::java_assertions::JavaAssertions;
+
+    fn assertions(options: &[&str]) -> JavaAssertions {
+        let mut assertions = JavaAssertions::new();
+        for option in options {
+            assertions.add_option(option);
+        }
+        return assertions;
+    }
+
+    #[test]
+    fn test_defaults() {
+        let none = assertions(&[]);
+        assert!(!none.enabled("app/Main", false));
+        assert!(!none.enabled("java/lang/Object", true));
+
+        let user = assertions(&["-ea"]);
+        assert!(user.enabled("app/Main", false));
+        assert!(!user.enabled("java/lang/Object", true));
+
+        let system = assertions(&["-enablesystemassertions", "-ea", "-da"]);
+        assert!(!system.enabled("app/Main", false));
+        assert!(system.enabled("java/lang/Object", true));
+    }
+
+    #[test]
+    fn test_switches() {
+        let switches = assertions(&[
+            "-ea:app...",
+            "-da:app.internal...",
+            "-ea:app.internal.Checked",
+            "-ea:...",
+            "-da:app.Main",
+            "-ea:app.Main",
+        ]);
+        assert!(switches.enabled("app/Util", false));
+        assert!(switches.enabled("app/sub/Util", false));
+        assert!(!switches.enabled("app/internal/Util", false));
+        assert!(switches.enabled("app/internal/Checked", false));
+        // the last switch for a class wins
+        assert!(switches.enabled("app/Main", false));
+        // "..." alone is the unnamed package
+        assert!(switches.enabled("Main", false));
+        assert!(!switches.enabled("other/Main", false));
+        assert!(!switches.enabled("application/Main", false));
+        assert_eq!(switches.packages()[1], ("app/internal".to_string(), false));
+    }
+}
@@ -95,6 +95,10 @@ impl LocalVars {
     pub fn get_this(&self) -> Option<Rc<RefCell<Object>>> {
         return self.get_ref(0);
     }
+
+    pub fn references(&self) -> impl Iterator<Item = &Rc<RefCell<Object>>> {
+        return self.vars.iter().filter_map(|slot| slot.reference.as_ref());
+    }
 }
 
 #[cfg(test)]
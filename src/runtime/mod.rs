@@ -1,6 +1,10 @@
 pub mod frame;
-mod local_vars;
+pub mod java_assertions;
+pub mod local_vars;
+pub mod monitor;
+pub mod os_thread;
 pub mod operand_stack;
 pub mod slot;
-mod stack;
+pub mod stack;
 pub mod thread;
+pub mod vm_lock;
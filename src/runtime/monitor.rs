@@ -0,0 +1,294 @@
+use crate::runtime::os_thread::OsThread;
+use crate::runtime::vm_lock;
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// The lock every object carries for monitorenter/monitorexit and synchronized methods.
+/// Ownership is per OS thread, so re-entrant calls made by the VM on behalf of a
+/// thread already holding the lock simply count as a recursive enter.
+/// A contended enter and a wait block without the vm lock.
+#[derive(Debug)]
+pub struct Monitor {
+    state: Mutex<MonitorState>,
+    entry: Condvar,
+    waiting: Condvar,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WaitResult {
+    /// notified or timed out
+    Resumed,
+    /// the interrupt status was set before or during the wait, it is cleared
+    Interrupted,
+    /// the current thread does not own the monitor
+    NotOwner,
+}
+
+#[derive(Debug)]
+struct MonitorState {
+    owner: Option<ThreadId>,
+    count: u32,
+    /// threads blocked in enter, granted the monitor in arrival order
+    entry_queue: VecDeque<ThreadId>,
+    /// tickets of the threads in Object.wait, removed by notify
+    wait_set: VecDeque<u64>,
+    next_waiter: u64,
+}
+
+impl Monitor {
+    #[inline]
+    pub fn new() -> Monitor {
+        return Monitor {
+            state: Mutex::new(MonitorState {
+                owner: None,
+                count: 0,
+                entry_queue: VecDeque::new(),
+                wait_set: VecDeque::new(),
+                next_waiter: 0,
+            }),
+            entry: Condvar::new(),
+            waiting: Condvar::new(),
+        };
+    }
+
+    pub fn enter(&self) {
+        if self.try_enter() {
+            return;
+        }
+        vm_lock::blocking(|| {
+            let state = self.state.lock().unwrap();
+            let mut state = self.queue_for_entry(state);
+            state.count = 1;
+        });
+    }
+
+    fn try_enter(&self) -> bool {
+        let current = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner == Some(current) {
+            state.count += 1;
+            return true;
+        }
+        if state.owner.is_none() && state.entry_queue.is_empty() {
+            state.owner = Some(current);
+            state.count = 1;
+            return true;
+        }
+        return false;
+    }
+
+    /// blocks in the entry queue until the monitor is handed to the current thread
+    fn queue_for_entry<'a>(
+        &self,
+        mut state: MutexGuard<'a, MonitorState>,
+    ) -> MutexGuard<'a, MonitorState> {
+        let current = thread::current().id();
+        state.entry_queue.push_back(current);
+        while state.owner.is_some() || state.entry_queue.front() != Some(&current) {
+            state = self.entry.wait(state).unwrap();
+        }
+        state.entry_queue.pop_front();
+        state.owner = Some(current);
+        return state;
+    }
+
+    /// returns false if the current thread is not the owner,
+    /// the caller has to throw IllegalMonitorStateException then
+    pub fn exit(&self) -> bool {
+        let current = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(current) {
+            return false;
+        }
+        state.count -= 1;
+        if state.count == 0 {
+            state.owner = None;
+            self.entry.notify_all();
+        }
+        return true;
+    }
+
+    /// Object.wait, a zero timeout waits until notified. The waiting thread
+    /// can be interrupted through `os_thread`, which is woken up by
+    /// `OsThread::interrupt` while it sits in the wait set.
+    pub fn wait(self: &Arc<Self>, millis: u64, os_thread: Option<&OsThread>) -> WaitResult {
+        if !self.is_owned_by_current_thread() {
+            return WaitResult::NotOwner;
+        }
+        let interrupted = || os_thread.map_or(false, |thread| thread.is_interrupted(false));
+        if interrupted() {
+            os_thread.unwrap().is_interrupted(true);
+            return WaitResult::Interrupted;
+        }
+        if let Some(thread) = os_thread {
+            thread.set_waiting_on(Some(self.clone()));
+        }
+        let result = vm_lock::blocking(|| {
+            let mut state = self.state.lock().unwrap();
+            let count = state.count;
+            state.owner = None;
+            state.count = 0;
+            self.entry.notify_all();
+
+            let ticket = state.next_waiter;
+            state.next_waiter += 1;
+            state.wait_set.push_back(ticket);
+            let deadline = match millis {
+                0 => None,
+                _ => Some(Instant::now() + Duration::from_millis(millis)),
+            };
+            let mut result = WaitResult::Resumed;
+            while state.wait_set.contains(&ticket) {
+                if interrupted() {
+                    state.wait_set.retain(|waiter| *waiter != ticket);
+                    result = WaitResult::Interrupted;
+                    break;
+                }
+                match deadline {
+                    None => state = self.waiting.wait(state).unwrap(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            state.wait_set.retain(|waiter| *waiter != ticket);
+                            break;
+                        }
+                        state = self.waiting.wait_timeout(state, deadline - now).unwrap().0;
+                    }
+                }
+            }
+            let mut state = self.queue_for_entry(state);
+            state.count = count;
+            return result;
+        });
+        if let Some(thread) = os_thread {
+            thread.set_waiting_on(None);
+            if result == WaitResult::Interrupted {
+                thread.is_interrupted(true);
+            }
+        }
+        return result;
+    }
+
+    /// wakes the waiters so an interrupted one can leave the wait set
+    pub fn wake_waiters(&self) {
+        let _state = self.state.lock().unwrap();
+        self.waiting.notify_all();
+    }
+
+    /// Object.notify, returns false if the current thread is not the owner
+    pub fn notify(&self) -> bool {
+        let current = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(current) {
+            return false;
+        }
+        state.wait_set.pop_front();
+        self.waiting.notify_all();
+        return true;
+    }
+
+    /// Object.notifyAll, returns false if the current thread is not the owner
+    pub fn notify_all(&self) -> bool {
+        let current = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(current) {
+            return false;
+        }
+        state.wait_set.clear();
+        self.waiting.notify_all();
+        return true;
+    }
+
+    pub fn is_owned_by_current_thread(&self) -> bool {
+        let current = thread::current().id();
+        return self.state.lock().unwrap().owner == Some(current);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::runtime::monitor::{Monitor, WaitResult};
+    use crate::runtime::os_thread::OsThread;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_recursive_enter() {
+        let monitor = Monitor::new();
+        monitor.enter();
+        monitor.enter();
+        assert!(monitor.exit());
+        assert!(monitor.is_owned_by_current_thread());
+        assert!(monitor.exit());
+        assert!(!monitor.is_owned_by_current_thread());
+        assert!(!monitor.exit());
+    }
+
+    #[test]
+    fn test_exclusion() {
+        let monitor = Arc::new(Monitor::new());
+        monitor.enter();
+        let other = monitor.clone();
+        let handle = thread::spawn(move || {
+            assert!(!other.exit());
+            other.enter();
+            assert!(other.is_owned_by_current_thread());
+            assert!(other.exit());
+        });
+        thread::sleep(std::time::Duration::from_millis(20));
+        assert!(monitor.is_owned_by_current_thread());
+        assert!(monitor.exit());
+        handle.join().unwrap();
+        assert!(!monitor.is_owned_by_current_thread());
+    }
+
+    #[test]
+    fn test_wait_notify() {
+        let monitor = Arc::new(Monitor::new());
+        assert_eq!(monitor.wait(0, None), WaitResult::NotOwner);
+        assert!(!monitor.notify());
+        let other = monitor.clone();
+        monitor.enter();
+        monitor.enter();
+        let handle = thread::spawn(move || {
+            other.enter();
+            assert!(other.notify_all());
+            assert!(other.exit());
+        });
+        assert_eq!(monitor.wait(0, None), WaitResult::Resumed);
+        assert!(monitor.exit());
+        assert!(monitor.is_owned_by_current_thread());
+        assert!(monitor.exit());
+        handle.join().unwrap();
+
+        monitor.enter();
+        assert_eq!(monitor.wait(10, None), WaitResult::Resumed);
+        assert!(monitor.exit());
+    }
+
+    #[test]
+    fn test_interrupt_wait() {
+        let monitor = Arc::new(Monitor::new());
+        let os_thread = Arc::new(OsThread::new(5));
+        let (other_monitor, other_thread) = (monitor.clone(), os_thread.clone());
+        let handle = thread::spawn(move || {
+            other_monitor.enter();
+            let result = other_monitor.wait(0, Some(&other_thread));
+            assert!(other_monitor.is_owned_by_current_thread());
+            assert!(other_monitor.exit());
+            return result;
+        });
+        thread::sleep(std::time::Duration::from_millis(20));
+        os_thread.interrupt();
+        assert_eq!(handle.join().unwrap(), WaitResult::Interrupted);
+        assert!(!os_thread.is_interrupted(false));
+
+        os_thread.interrupt();
+        monitor.enter();
+        assert_eq!(monitor.wait(0, Some(&os_thread)), WaitResult::Interrupted);
+        assert!(monitor.exit());
+    }
+}
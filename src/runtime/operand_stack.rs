@@ -136,4 +136,8 @@ impl OperandStack {
         self.size = 0;
         self.slots.clear();
     }
+
+    pub fn references(&self) -> impl Iterator<Item = &Rc<RefCell<Object>>> {
+        return self.slots.iter().filter_map(|slot| slot.reference.as_ref());
+    }
 }
@@ -0,0 +1,241 @@
+use crate::runtime::monitor::Monitor;
+use crate::runtime::vm_lock;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// The VM side of a started java.lang.Thread, kept in the Thread object's meta data.
+/// It is shared with other OS threads, which interrupt it or change its priority.
+#[derive(Debug)]
+pub struct OsThread {
+    interrupted: Mutex<bool>,
+    interrupt_event: Condvar,
+    /// the monitor the thread is in Object.wait on
+    waiting_on: Mutex<Option<Arc<Monitor>>>,
+    priority: AtomicI32,
+    /// the kernel thread id, known once the thread runs
+    tid: AtomicI32,
+    /// the permit of LockSupport.park
+    permit: Mutex<bool>,
+    park_event: Condvar,
+}
+
+impl OsThread {
+    #[inline]
+    pub fn new(priority: i32) -> OsThread {
+        return OsThread {
+            interrupted: Mutex::new(false),
+            interrupt_event: Condvar::new(),
+            waiting_on: Mutex::new(None),
+            priority: AtomicI32::new(priority),
+            tid: AtomicI32::new(0),
+            permit: Mutex::new(false),
+            park_event: Condvar::new(),
+        };
+    }
+
+    pub fn interrupt(&self) {
+        *self.interrupted.lock().unwrap() = true;
+        self.interrupt_event.notify_all();
+        // like HotSpot, a parked thread wakes up on the permit
+        self.unpark();
+        let waiting_on = self.waiting_on.lock().unwrap().clone();
+        if let Some(monitor) = waiting_on {
+            monitor.wake_waiters();
+        }
+    }
+
+    #[inline]
+    pub fn set_waiting_on(&self, monitor: Option<Arc<Monitor>>) {
+        *self.waiting_on.lock().unwrap() = monitor;
+    }
+
+    pub fn is_interrupted(&self, clear_interrupted: bool) -> bool {
+        let mut interrupted = self.interrupted.lock().unwrap();
+        let value = *interrupted;
+        if clear_interrupted {
+            *interrupted = false;
+        }
+        return value;
+    }
+
+    /// Thread.sleep, returns false and clears the interrupt status
+    /// if the thread was interrupted before or while sleeping
+    pub fn sleep(&self, millis: u64) -> bool {
+        return vm_lock::blocking(|| {
+            let deadline = Instant::now() + Duration::from_millis(millis);
+            let mut interrupted = self.interrupted.lock().unwrap();
+            loop {
+                if *interrupted {
+                    *interrupted = false;
+                    return false;
+                }
+                let now = Instant::now();
+                if now >= deadline {
+                    return true;
+                }
+                interrupted = self
+                    .interrupt_event
+                    .wait_timeout(interrupted, deadline - now)
+                    .unwrap()
+                    .0;
+            }
+        });
+    }
+
+    /// Unsafe.park, returns once the permit is available and takes it, or
+    /// when the thread is interrupted or the deadline passed
+    pub fn park(&self, deadline: Option<Instant>) {
+        {
+            let mut permit = self.permit.lock().unwrap();
+            if *permit {
+                *permit = false;
+                return;
+            }
+        }
+        if self.is_interrupted(false) {
+            return;
+        }
+        vm_lock::blocking(|| {
+            let mut permit = self.permit.lock().unwrap();
+            loop {
+                if *permit {
+                    *permit = false;
+                    return;
+                }
+                permit = match deadline {
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            return;
+                        }
+                        self.park_event
+                            .wait_timeout(permit, deadline - now)
+                            .unwrap()
+                            .0
+                    }
+                    None => self.park_event.wait(permit).unwrap(),
+                };
+            }
+        });
+    }
+
+    /// makes the permit available, a permit isn't counted
+    pub fn unpark(&self) {
+        *self.permit.lock().unwrap() = true;
+        self.park_event.notify_all();
+    }
+
+    #[inline]
+    pub fn priority(&self) -> i32 {
+        return self.priority.load(Ordering::Relaxed);
+    }
+
+    /// records the Java priority and hands it to the OS once the thread runs
+    pub fn set_priority(&self, priority: i32) {
+        self.priority.store(priority, Ordering::Relaxed);
+        self.apply_priority();
+    }
+
+    /// called on the new OS thread before it executes Java code
+    pub fn attach(&self) {
+        self.tid.store(current_tid(), Ordering::Relaxed);
+        self.apply_priority();
+    }
+
+    fn apply_priority(&self) {
+        let tid = self.tid.load(Ordering::Relaxed);
+        if tid != 0 {
+            set_native_priority(tid, self.priority());
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn current_tid() -> i32 {
+    return unsafe { libc::gettid() };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn current_tid() -> i32 {
+    return 0;
+}
+
+/// Java priorities 1..10 map to nice values like HotSpot's table on linux,
+/// raising the priority above normal needs privileges and is silently refused
+#[cfg(target_os = "linux")]
+fn set_native_priority(tid: i32, priority: i32) {
+    const NICE: [i32; 11] = [19, 4, 3, 2, 1, 0, -1, -2, -3, -4, -5];
+    let nice = NICE[priority.clamp(0, 10) as usize];
+    unsafe {
+        libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_native_priority(_tid: i32, _priority: i32) {}
+
+#[cfg(test)]
+mod test {
+    use crate::runtime::os_thread::{current_tid, OsThread};
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_interrupt_sleep() {
+        let os_thread = Arc::new(OsThread::new(5));
+        let other = os_thread.clone();
+        let start = Instant::now();
+        let handle = thread::spawn(move || other.sleep(10_000));
+        thread::sleep(Duration::from_millis(20));
+        os_thread.interrupt();
+        assert!(!handle.join().unwrap());
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(!os_thread.is_interrupted(false));
+        assert!(os_thread.sleep(1));
+    }
+
+    #[test]
+    fn test_park_unpark() {
+        let os_thread = Arc::new(OsThread::new(5));
+        // the permit isn't counted, the second park times out
+        os_thread.unpark();
+        os_thread.unpark();
+        os_thread.park(None);
+        let start = Instant::now();
+        os_thread.park(Some(start + Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+
+        let other = os_thread.clone();
+        let handle = thread::spawn(move || other.park(None));
+        thread::sleep(Duration::from_millis(20));
+        os_thread.unpark();
+        handle.join().unwrap();
+
+        let other = os_thread.clone();
+        let handle = thread::spawn(move || other.park(None));
+        thread::sleep(Duration::from_millis(20));
+        os_thread.interrupt();
+        handle.join().unwrap();
+        // an interrupted thread doesn't park
+        os_thread.park(None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_attach() {
+        let os_thread = Arc::new(OsThread::new(5));
+        let other = os_thread.clone();
+        let tid = thread::spawn(move || {
+            other.attach();
+            return current_tid();
+        })
+        .join()
+        .unwrap();
+        assert_ne!(tid, 0);
+        assert_ne!(tid, current_tid());
+        assert_eq!(os_thread.tid.load(Ordering::Relaxed), tid);
+    }
+}
@@ -1,29 +1,125 @@
+use crate::oops::method::Method;
 use crate::runtime::frame::Frame;
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::utils::boxed;
 
+/// the stack size of a thread without -Xss or a stack size of its own, in bytes
+pub const DEFAULT_STACK_SIZE: usize = 1024 * 1024;
+/// the part at the end of the stack kept free for constructing and throwing
+/// the StackOverflowError
+pub const RESERVED_ZONE_SIZE: usize = 32 * 1024;
+pub const MIN_STACK_SIZE: usize = 4 * RESERVED_ZONE_SIZE;
+/// a local variable or operand stack slot
+const SLOT_SIZE: usize = 8;
+/// what a frame takes besides its slots, the saved pc, method and links
+const FRAME_OVERHEAD: usize = 6 * SLOT_SIZE;
+
+/// the stack size of new threads, -Xss
+static THREAD_STACK_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_STACK_SIZE);
+
+thread_local! {
+    /// the Java stack of this OS thread, the stacks of the JavaThreads made
+    /// for its re-entrant calls into Java are all part of it
+    static USAGE: RefCell<StackUsage> = RefCell::new(StackUsage {
+        size: THREAD_STACK_SIZE.load(Ordering::Relaxed),
+        used: 0,
+        reserved_zone_opened_at: None,
+    });
+}
+
+struct StackUsage {
+    size: usize,
+    used: usize,
+    /// the bytes used when a frame didn't fit, the reserved zone can be used
+    /// until the stack unwound below that
+    reserved_zone_opened_at: Option<usize>,
+}
+
 pub struct Stack {
-    max_size: usize,
     size: usize,
     frames: VecDeque<Rc<RefCell<Frame>>>,
+    /// the methods of the frames, readable while the top frame is borrowed
+    /// for executing an instruction
+    methods: VecDeque<Rc<Method>>,
 }
 
 impl Stack {
     #[inline]
-    pub fn new(max_size: usize) -> Stack {
+    pub fn new() -> Stack {
         return Stack {
-            max_size,
             size: 0,
             frames: Default::default(),
+            methods: Default::default(),
         };
     }
 
-    pub fn push(&mut self, frame: Frame) {
-        if self.frames.len() >= self.max_size {
+    /// the stack size of the threads started from now on
+    pub fn set_default_size(size: usize) {
+        THREAD_STACK_SIZE.store(size.max(MIN_STACK_SIZE), Ordering::Relaxed);
+    }
+
+    /// sizes the stack of this OS thread, 0 takes the default size
+    pub fn set_thread_stack_size(size: usize) {
+        let size = match size {
+            0 => THREAD_STACK_SIZE.load(Ordering::Relaxed),
+            size => size.max(MIN_STACK_SIZE),
+        };
+        USAGE.with(|usage| usage.borrow_mut().size = size);
+    }
+
+    /// the bytes a frame of the method takes
+    #[inline]
+    pub fn frame_size(method: &Method) -> usize {
+        return (method.max_locals() + method.max_stack()) * SLOT_SIZE + FRAME_OVERHEAD;
+    }
+
+    /// whether a frame of the method fits on the stack of this OS thread.
+    /// The first frame that doesn't fit opens the reserved zone, the
+    /// StackOverflowError is constructed in there. Overflowing the reserved
+    /// zone too is fatal.
+    #[inline]
+    pub fn has_room_for(method: &Method) -> bool {
+        return Self::has_room(Self::frame_size(method));
+    }
+
+    /// whether a call of the method made by the VM fits, the entry frame
+    /// below the frame of the method takes as much room
+    #[inline]
+    pub fn has_room_for_call(method: &Method) -> bool {
+        return Self::has_room(2 * Self::frame_size(method));
+    }
+
+    fn has_room(bytes: usize) -> bool {
+        return USAGE.with(|usage| {
+            let mut usage = usage.borrow_mut();
+            let needed = usage.used + bytes;
+            if needed <= usage.size - RESERVED_ZONE_SIZE {
+                return true;
+            }
+            if usage.reserved_zone_opened_at.is_none() {
+                usage.reserved_zone_opened_at = Some(usage.used);
+                return false;
+            }
+            if needed <= usage.size {
+                return true;
+            }
             panic!("java.lang.StackOverflowError");
-        }
+        });
+    }
+
+    /// the bytes of the stack of this OS thread in use
+    #[cfg(test)]
+    pub fn used() -> usize {
+        return USAGE.with(|usage| usage.borrow().used);
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        let method = frame.method_ptr();
+        USAGE.with(|usage| usage.borrow_mut().used += Self::frame_size(&method));
+        self.methods.push_back(method);
         self.frames.push_back(boxed(frame));
         self.size += 1;
     }
@@ -33,10 +129,28 @@ impl Stack {
             panic!("jvm stack is empty!");
         }
         let frame = self.frames.pop_back().unwrap();
+        let method = self.methods.pop_back().unwrap();
+        Self::release(Self::frame_size(&method));
         self.size -= 1;
         return frame;
     }
 
+    /// gives the bytes of popped frames back, the reserved zone closes once
+    /// the stack unwound to where it was opened, so a StackOverflowError
+    /// caught by the frame that overflowed leaves it closed
+    fn release(bytes: usize) {
+        // the thread local is gone when a stack is dropped while the thread exits
+        let _ = USAGE.try_with(|usage| {
+            let mut usage = usage.borrow_mut();
+            usage.used -= bytes;
+            if let Some(opened_at) = usage.reserved_zone_opened_at {
+                if usage.used <= opened_at {
+                    usage.reserved_zone_opened_at = None;
+                }
+            }
+        });
+    }
+
     pub fn top(&self) -> Rc<RefCell<Frame>> {
         if self.frames.is_empty() {
             panic!("jvm stack is empty!");
@@ -66,4 +180,109 @@ impl Stack {
     pub fn get_frames(&self) -> &VecDeque<Rc<RefCell<Frame>>> {
         return &self.frames;
     }
+
+    #[inline]
+    pub fn get_methods(&self) -> &VecDeque<Rc<Method>> {
+        return &self.methods;
+    }
+}
+
+impl Drop for Stack {
+    /// a thread dropped with frames left, like one a test ran, gives their bytes back
+    fn drop(&mut self) {
+        let bytes = self.methods.iter().map(|method| Self::frame_size(method)).sum();
+        Self::release(bytes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::oops::method::Method;
+    use crate::runtime::stack::{Stack, MIN_STACK_SIZE, RESERVED_ZONE_SIZE};
+    use crate::runtime::thread::JavaThread;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// pushes frames of a method with 10 slots until one doesn't fit
+    fn fill() -> (Stack, Rc<Method>) {
+        Stack::set_thread_stack_size(MIN_STACK_SIZE);
+        let thread = Rc::new(RefCell::new(JavaThread::new_thread()));
+        let method = Rc::new(Method::with_code(6, 4, vec![0xb1]));
+        let mut stack = Stack::new();
+        while Stack::has_room_for(&method) {
+            stack.push(JavaThread::new_frame(thread.clone(), method.clone()));
+        }
+        return (stack, method);
+    }
+
+    #[test]
+    fn test_reserved_zone() {
+        let (mut stack, method) = fill();
+        let thread = Rc::new(RefCell::new(JavaThread::new_thread()));
+        let frame_size = Stack::frame_size(&method);
+        assert_eq!(frame_size, 128);
+        assert_eq!(stack.size(), (MIN_STACK_SIZE - RESERVED_ZONE_SIZE) / frame_size);
+        assert_eq!(Stack::used(), stack.size() * frame_size);
+
+        // the reserved zone is open for the StackOverflowError
+        for _ in 0..RESERVED_ZONE_SIZE / frame_size {
+            assert!(Stack::has_room_for(&method));
+            stack.push(JavaThread::new_frame(thread.clone(), method.clone()));
+        }
+        for _ in 0..RESERVED_ZONE_SIZE / frame_size {
+            stack.pop();
+        }
+        // it closes once the stack unwound to where it was opened, the next
+        // frame that doesn't fit opens it again
+        assert!(!Stack::has_room_for(&method));
+        stack.pop();
+        assert!(Stack::has_room_for(&method));
+        stack.push(JavaThread::new_frame(thread, method.clone()));
+        assert!(!Stack::has_room_for(&method));
+
+        drop(stack);
+        assert_eq!(Stack::used(), 0);
+    }
+
+    #[test]
+    fn test_overflow_caught_in_overflowing_frame() {
+        let (mut stack, method) = fill();
+        let thread = Rc::new(RefCell::new(JavaThread::new_thread()));
+        let depth = stack.size();
+        for _ in 0..2 {
+            // the StackOverflowError is constructed in the reserved zone and
+            // caught by the frame whose call didn't fit, which calls again
+            assert!(Stack::has_room_for_call(&method));
+            stack.push(JavaThread::new_frame(thread.clone(), method.clone()));
+            stack.push(JavaThread::new_frame(thread.clone(), method.clone()));
+            stack.pop();
+            stack.pop();
+            assert!(!Stack::has_room_for(&method));
+            assert_eq!(stack.size(), depth);
+        }
+    }
+
+    #[test]
+    fn test_call_counts_entry_frame() {
+        let (mut stack, method) = fill();
+        let frame_size = Stack::frame_size(&method);
+        stack.pop();
+        assert_eq!(Stack::used() + frame_size, MIN_STACK_SIZE - RESERVED_ZONE_SIZE);
+        // one frame still fits, the frame and the entry frame of a call don't
+        assert!(Stack::has_room_for(&method));
+        assert!(!Stack::has_room_for_call(&method));
+        stack.pop();
+        assert!(Stack::has_room_for_call(&method));
+    }
+
+    #[test]
+    #[should_panic(expected = "java.lang.StackOverflowError")]
+    fn test_reserved_zone_overflow() {
+        let (mut stack, method) = fill();
+        let thread = Rc::new(RefCell::new(JavaThread::new_thread()));
+        loop {
+            assert!(Stack::has_room_for(&method));
+            stack.push(JavaThread::new_frame(thread.clone(), method.clone()));
+        }
+    }
 }
@@ -1,12 +1,51 @@
+use crate::gc::heap::Heap;
+use crate::interpreter::run;
+use crate::invoke_support::parameter::{Parameter, Parameters};
+use crate::invoke_support::{invoke, ReturnType};
+use crate::jvm::Jvm;
+use crate::native::java::lang::throwable::print_uncaught_exception;
 use crate::runtime::frame::Frame;
+use crate::oops::class::Class;
 use crate::oops::method::Method;
+use crate::oops::object::Object;
+use crate::runtime::os_thread::OsThread;
 use crate::runtime::stack::Stack;
+use crate::runtime::vm_lock;
+use crate::runtime::vm_lock::Unshared;
+use crate::utils::boxed;
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::io;
 use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::thread::{Builder, Thread};
 
+/// java.lang.Thread.threadStatus of a thread not started yet
+pub const THREAD_STATUS_NEW: i32 = 0;
+/// java.lang.Thread.threadStatus, JVMTI_THREAD_STATE_ALIVE | JVMTI_THREAD_STATE_RUNNABLE
+pub const THREAD_STATUS_RUNNABLE: i32 = 0x0005;
+/// JVMTI_THREAD_STATE_TERMINATED
+pub const THREAD_STATUS_TERMINATED: i32 = 0x0002;
+pub const NORM_PRIORITY: i32 = 5;
+
+/// the started non-daemon threads that have not ended yet, the VM exits
+/// once the main method returned and this dropped to zero
+static NON_DAEMON_THREADS: Mutex<usize> = Mutex::new(0);
+static NON_DAEMON_THREAD_ENDED: Condvar = Condvar::new();
+
+thread_local! {
+    /// the java.lang.Thread running on this OS thread, it is shared by the
+    /// JavaThreads the VM creates for its re-entrant calls into Java
+    static THREAD_OBJECT: RefCell<Option<Rc<RefCell<Object>>>> = RefCell::new(None);
+    /// an exception raised where it cannot be thrown right away, the
+    /// interpreter throws it after the current instruction
+    static PENDING_EXCEPTION: RefCell<Option<Rc<RefCell<Object>>>> = RefCell::new(None);
+    /// the JavaThreads executing on this OS thread, the innermost re-entrant
+    /// call last, together they make up the Java stack of the thread
+    static ACTIVE_THREADS: RefCell<Vec<Rc<RefCell<JavaThread>>>> = RefCell::new(Vec::new());
+}
+
 pub struct JavaThread {
     pc: i32,
     stack: Stack,
@@ -17,7 +56,7 @@ impl JavaThread {
     pub fn new_thread() -> JavaThread {
         return JavaThread {
             pc: 0,
-            stack: Stack::new(1024),
+            stack: Stack::new(),
             //            thread: Some(thread::Builder::new())
         };
     }
@@ -25,7 +64,7 @@ impl JavaThread {
     pub fn new_main_thread() -> JavaThread {
         return JavaThread {
             pc: 0,
-            stack: Stack::new(1024),
+            stack: Stack::new(),
             //            thread: Some(thread::Builder::new().name("Main".to_string()))
         };
     }
@@ -83,15 +122,210 @@ impl JavaThread {
     //        return self.thread.take().unwrap();
     //    }
 
-    pub fn current() -> JavaThread {
-        unimplemented!()
+    #[inline]
+    pub fn current_thread_object() -> Option<Rc<RefCell<Object>>> {
+        return THREAD_OBJECT.with(|object| object.borrow().clone());
+    }
+
+    #[inline]
+    pub fn set_current_thread_object(thread_object: Option<Rc<RefCell<Object>>>) {
+        THREAD_OBJECT.with(|object| *object.borrow_mut() = thread_object);
+    }
+
+    #[inline]
+    pub fn enter(thread: Rc<RefCell<JavaThread>>) {
+        ACTIVE_THREADS.with(|threads| threads.borrow_mut().push(thread));
+    }
+
+    #[inline]
+    pub fn leave() {
+        ACTIVE_THREADS.with(|threads| threads.borrow_mut().pop());
+    }
+
+    /// the method and pc of every frame on this OS thread, the top frame first;
+    /// the frames the VM pushes to call into Java are left out
+    pub fn stack_trace() -> Vec<(Rc<Method>, i32)> {
+        let threads = ACTIVE_THREADS.with(|threads| threads.borrow().clone());
+        let mut trace = Vec::new();
+        for thread in threads.iter().rev() {
+            let borrow = (**thread).borrow();
+            let frames = borrow.stack.get_frames();
+            let methods = borrow.stack.get_methods();
+            for index in (0..frames.len()).rev() {
+                // the frame executing an instruction is borrowed, its pc is the thread's
+                let pc = match frames[index].try_borrow() {
+                    Ok(frame) if frame.is_entry_frame() => continue,
+                    Ok(frame) => (frame.next_pc() - 1).max(0),
+                    Err(_) => borrow.get_pc(),
+                };
+                trace.push((methods[index].clone(), pc));
+            }
+        }
+        return trace;
+    }
+
+    #[inline]
+    pub fn set_pending_exception(exception: Rc<RefCell<Object>>) {
+        PENDING_EXCEPTION.with(|pending| *pending.borrow_mut() = Some(exception));
+    }
+
+    #[inline]
+    pub fn take_pending_exception() -> Option<Rc<RefCell<Object>>> {
+        return PENDING_EXCEPTION.with(|pending| pending.borrow_mut().take());
+    }
+
+    #[inline]
+    pub fn has_pending_exception() -> bool {
+        return PENDING_EXCEPTION.with(|pending| pending.borrow().is_some());
+    }
+
+    /// Thread.start0, runs `run` on the thread object in a new OS thread.
+    /// Fails if the OS thread can't be created
+    pub fn start(
+        thread_object: Rc<RefCell<Object>>,
+        run: Rc<Method>,
+        os_thread: Arc<OsThread>,
+        name: String,
+        daemon: bool,
+    ) -> io::Result<()> {
+        // the new thread only unpacks them after vm_lock::acquire, this one
+        // holds the lock whenever it runs Java code
+        let payload = unsafe { Unshared::new((thread_object, run)) };
+        let non_daemon = match daemon {
+            true => None,
+            false => Some(NonDaemonThread::start()),
+        };
+        let spawned = Builder::new().name(name).spawn(move || {
+            let _non_daemon = non_daemon;
+            vm_lock::acquire();
+            let _held = HeldUntilExit;
+            os_thread.attach();
+            JavaThread::run_thread_object(payload);
+        });
+        return spawned.map(|_| ());
+    }
+
+    fn run_thread_object(payload: Unshared<(Rc<RefCell<Object>>, Rc<Method>)>) {
+        let (thread_object, run_method) = payload.into_inner();
+        // the stackSize of the Thread constructor, 0 if none was given
+        let stack_size = (*thread_object).borrow().get_long_var("stackSize", "J");
+        Stack::set_thread_stack_size(stack_size.max(0) as usize);
+        JavaThread::set_current_thread_object(Some(thread_object.clone()));
+        let thread = boxed(JavaThread::new_thread());
+        Heap::register_thread(&thread);
+        let mut frame = JavaThread::new_frame(thread.clone(), run_method);
+        frame
+            .local_vars()
+            .expect("vars is none")
+            .set_ref(0, Some(thread_object.clone()));
+        (*thread).borrow_mut().push_frame(frame);
+        run(thread);
+        if let Some(exception) = JavaThread::take_pending_exception() {
+            JavaThread::dispatch_uncaught_exception(Some(thread_object.clone()), exception);
+        }
+        JavaThread::exit(thread_object);
+        JavaThread::set_current_thread_object(None);
+    }
+
+    /// DestroyJavaVM, blocks until all non-daemon threads have ended
+    pub fn wait_for_non_daemon_threads() {
+        vm_lock::blocking(|| {
+            let mut count = NON_DAEMON_THREADS.lock().unwrap();
+            while *count > 0 {
+                count = NON_DAEMON_THREAD_ENDED.wait(count).unwrap();
+            }
+        });
+    }
+
+    /// Thread.dispatchUncaughtException hands an exception that ended the
+    /// thread to its handler, it is printed directly if that fails too
+    pub fn dispatch_uncaught_exception(
+        thread_object: Option<Rc<RefCell<Object>>>,
+        exception: Rc<RefCell<Object>>,
+    ) {
+        if let Some(thread_object) = &thread_object {
+            let thread_class = Jvm::boot_class_loader()
+                .find_or_create("java/lang/Thread")
+                .unwrap();
+            let dispatch = Class::get_instance_method(
+                thread_class,
+                "dispatchUncaughtException",
+                "(Ljava/lang/Throwable;)V",
+            )
+            .unwrap();
+            let parameters = vec![
+                Parameter::Object(Some(thread_object.clone())),
+                Parameter::Object(Some(exception.clone())),
+            ];
+            invoke(dispatch, Some(Parameters::with_parameters(parameters)), ReturnType::Void);
+            if JavaThread::take_pending_exception().is_none() {
+                return;
+            }
+        }
+        let name = thread_object
+            .and_then(|object| (*object).borrow().get_ref_var("name", "[C"))
+            .map(|chars| String::from_utf16_lossy((*chars).borrow().chars()))
+            .unwrap_or_else(|| "main".to_string());
+        print_uncaught_exception(name.as_str(), exception);
+    }
+
+    /// Thread.exit() takes the thread out of its group, then the
+    /// threads joining it are woken up
+    fn exit(thread_object: Rc<RefCell<Object>>) {
+        let thread_class = Jvm::boot_class_loader()
+            .find_or_create("java/lang/Thread")
+            .unwrap();
+        let exit = Class::get_instance_method(thread_class, "exit", "()V").unwrap();
+        let parameters = vec![Parameter::Object(Some(thread_object.clone()))];
+        invoke(exit, Some(Parameters::with_parameters(parameters)), ReturnType::Void);
+
+        let monitor = (*thread_object).borrow().monitor();
+        monitor.enter();
+        {
+            let mut borrow = (*thread_object).borrow_mut();
+            borrow.set_int_var("threadStatus", "I", THREAD_STATUS_TERMINATED);
+            borrow.set_long_var("eetop", "J", 0);
+        }
+        monitor.notify_all();
+        monitor.exit();
+    }
+}
+
+/// counts a running non-daemon thread until it ends, also if it panicked
+struct NonDaemonThread;
+
+impl NonDaemonThread {
+    fn start() -> NonDaemonThread {
+        *NON_DAEMON_THREADS.lock().unwrap() += 1;
+        return NonDaemonThread;
+    }
+}
+
+impl Drop for NonDaemonThread {
+    fn drop(&mut self) {
+        *NON_DAEMON_THREADS.lock().unwrap() -= 1;
+        NON_DAEMON_THREAD_ENDED.notify_all();
+    }
+}
+
+/// gives up the vm lock when a Java thread ends, also if it panicked
+struct HeldUntilExit;
+
+impl Drop for HeldUntilExit {
+    fn drop(&mut self) {
+        if vm_lock::is_held() {
+            vm_lock::release();
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::runtime::thread::{JavaThread, NonDaemonThread};
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::{Arc, Mutex};
     use std::thread;
+    use std::time::Duration;
 
     #[test]
     fn test_mutex() {
@@ -117,4 +351,28 @@ mod test {
         println!("main thread");
         handle.join().expect("thread::spawn failed");
     }
+
+    #[test]
+    fn test_wait_for_non_daemon_threads() {
+        let ended = Arc::new(AtomicUsize::new(0));
+        let mut handles = vec![];
+        for i in 0..3 {
+            let non_daemon = NonDaemonThread::start();
+            let ended = ended.clone();
+            handles.push(thread::spawn(move || {
+                let _non_daemon = non_daemon;
+                thread::sleep(Duration::from_millis(20 * (i + 1)));
+                ended.fetch_add(1, Ordering::SeqCst);
+                // a thread that panics still counts as ended
+                if i == 1 {
+                    panic!("thread {} failed", i);
+                }
+            }));
+        }
+        JavaThread::wait_for_non_daemon_threads();
+        assert_eq!(ended.load(Ordering::SeqCst), 3);
+        for handle in handles {
+            let _ = handle.join();
+        }
+    }
 }
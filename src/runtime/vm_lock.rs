@@ -0,0 +1,139 @@
+//! The global VM lock. Every Java thread runs on its own OS thread, but
+//! bytecode is executed by one thread at a time: the heap is made of
+//! `Rc<RefCell<..>>` and the registries are `static mut`, so they are only
+//! ever touched by the thread holding this lock. The lock is handed over at
+//! instruction boundaries (`safepoint`) and released around everything that
+//! may block (`blocking`), in ticket order.
+//!
+//! This stands in for making the heap and the registries thread safe, which
+//! would mean replacing every `Rc<RefCell<..>>` with atomically counted and
+//! locked types. Threads get real blocking I/O, sleeps and monitors, but no
+//! parallel execution of bytecode. Whatever crosses to another OS thread goes
+//! through `Unshared`, which is only unpacked with the lock held.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+struct Tickets {
+    next: u64,
+    serving: u64,
+}
+
+static TICKETS: Mutex<Tickets> = Mutex::new(Tickets {
+    next: 0,
+    serving: 0,
+});
+static TURN: Condvar = Condvar::new();
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static HELD: Cell<bool> = Cell::new(false);
+}
+
+pub fn acquire() {
+    WAITING.fetch_add(1, Ordering::SeqCst);
+    let mut tickets = TICKETS.lock().unwrap();
+    let ticket = tickets.next;
+    tickets.next += 1;
+    while tickets.serving != ticket {
+        tickets = TURN.wait(tickets).unwrap();
+    }
+    WAITING.fetch_sub(1, Ordering::SeqCst);
+    HELD.with(|held| held.set(true));
+}
+
+pub fn release() {
+    HELD.with(|held| held.set(false));
+    let mut tickets = TICKETS.lock().unwrap();
+    tickets.serving += 1;
+    TURN.notify_all();
+}
+
+#[inline]
+pub fn is_held() -> bool {
+    return HELD.with(|held| held.get());
+}
+
+/// lets a waiting thread run, called between two instructions
+#[inline]
+pub fn safepoint() {
+    if WAITING.load(Ordering::Relaxed) > 0 && is_held() {
+        release();
+        acquire();
+    }
+}
+
+/// runs `f` without the lock, `f` must not touch the heap
+pub fn blocking<T, F: FnOnce() -> T>(f: F) -> T {
+    if !is_held() {
+        return f();
+    }
+    release();
+    let value = f();
+    acquire();
+    return value;
+}
+
+/// moves heap references into a new OS thread, which only
+/// uses them once it holds the lock
+pub struct Unshared<T>(T);
+
+unsafe impl<T> Send for Unshared<T> {}
+
+impl<T> Unshared<T> {
+    /// # Safety
+    ///
+    /// `value` and everything it shares, like the other clones of an `Rc`,
+    /// may only be used by a thread holding the vm lock from now on, on the
+    /// sending thread as well as on the receiving one. The receiving thread
+    /// has to drop the value before it releases the lock.
+    pub unsafe fn new(value: T) -> Unshared<T> {
+        return Unshared(value);
+    }
+
+    /// unpacks the value, the current thread has to hold the lock
+    pub fn into_inner(self) -> T {
+        assert!(is_held(), "the vm lock is not held");
+        return self.0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::runtime::vm_lock::{acquire, blocking, is_held, release, safepoint, Unshared};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_exclusive() {
+        let inside = Arc::new(AtomicUsize::new(0));
+        let mut handles = vec![];
+        for _ in 0..4 {
+            let inside = inside.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..100 {
+                    acquire();
+                    assert_eq!(inside.fetch_add(1, Ordering::SeqCst), 0);
+                    thread::yield_now();
+                    assert_eq!(inside.fetch_sub(1, Ordering::SeqCst), 1);
+                    safepoint();
+                    blocking(|| assert!(!is_held()));
+                    assert!(is_held());
+                    release();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the vm lock is not held")]
+    fn test_unshared_without_lock() {
+        let payload = unsafe { Unshared::new(0) };
+        payload.into_inner();
+    }
+}
@@ -0,0 +1,33 @@
+package testJava;
+
+public class AssertionTest {
+
+    static class Inner {
+        static boolean check() {
+            try {
+                assert false : "inner";
+                return false;
+            } catch (AssertionError e) {
+                return true;
+            }
+        }
+    }
+
+    // run with: -ea:testJava... -da:testJava.AssertionTest$Inner, javac makes a
+    // nested class ask its top-level class, so Inner keeps its assertions
+    public static void main(String[] args) {
+        System.out.println("desired " + AssertionTest.class.desiredAssertionStatus());
+        try {
+            assert args.length > 100 : "too few arguments";
+            System.out.println("assertions disabled");
+        } catch (AssertionError e) {
+            System.out.println("caught " + e.getMessage());
+        }
+        System.out.println("inner enabled " + Inner.check());
+        System.out.println("Object desired " + Object.class.desiredAssertionStatus());
+
+        ClassLoader loader = AssertionTest.class.getClassLoader();
+        loader.setClassAssertionStatus("testJava.AssertionTest$Inner", true);
+        System.out.println("after setClassAssertionStatus " + Inner.class.desiredAssertionStatus());
+    }
+}
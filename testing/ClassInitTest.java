@@ -0,0 +1,90 @@
+package testJava;
+
+public class ClassInitTest {
+
+    static StringBuilder order = new StringBuilder();
+
+    static class Failing {
+        static int value = fail();
+
+        static int fail() {
+            throw new IllegalStateException("static initializer failed");
+        }
+    }
+
+    static class Base {
+        static {
+            order.append("Base ");
+        }
+    }
+
+    interface WithDefault {
+        int MARK = mark("WithDefault ");
+
+        default void hello() {
+        }
+    }
+
+    interface WithoutDefault {
+        int MARK = mark("WithoutDefault ");
+    }
+
+    static class Derived extends Base implements WithoutDefault, WithDefault {
+        static {
+            order.append("Derived ");
+        }
+    }
+
+    static class Recursive {
+        static Recursive instance = new Recursive();
+        static int counter = 7;
+        final int seen;
+
+        Recursive() {
+            // the initializing thread sees the class before counter is set
+            seen = counter;
+        }
+    }
+
+    static class Slow {
+        static int value;
+
+        static {
+            try {
+                Thread.sleep(200);
+            } catch (InterruptedException e) {
+            }
+            value = 42;
+        }
+    }
+
+    static int mark(String name) {
+        order.append(name);
+        return 1;
+    }
+
+    public static void main(String[] args) throws Exception {
+        try {
+            System.out.println(Failing.value);
+        } catch (ExceptionInInitializerError e) {
+            System.out.println("first: " + e.getCause());
+        }
+        try {
+            System.out.println(Failing.value);
+        } catch (NoClassDefFoundError e) {
+            System.out.println("second: " + e.getMessage());
+        }
+
+        new Derived();
+        System.out.println("order: " + order.toString().trim());
+
+        System.out.println("recursive seen " + Recursive.instance.seen + ", counter " + Recursive.counter);
+
+        final int[] other = new int[1];
+        Thread thread = new Thread(() -> other[0] = Slow.value);
+        thread.start();
+        int mine = Slow.value;
+        thread.join();
+        System.out.println("slow " + mine + " " + other[0]);
+    }
+}
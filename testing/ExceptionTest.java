@@ -11,6 +11,45 @@ public class ExceptionTest {
             var3.printStackTrace();
         }
 
+        var1.vmExceptions();
+    }
+
+    public void vmExceptions() {
+        try {
+            String s = null;
+            s.length();
+        } catch (NullPointerException e) {
+            System.out.println("caught " + e);
+        }
+        try {
+            int[] array = new int[2];
+            array[2] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.out.println("caught " + e);
+        }
+        try {
+            int zero = 0;
+            System.out.println(1 / zero);
+        } catch (ArithmeticException e) {
+            System.out.println("caught " + e);
+        }
+        try {
+            Object o = "string";
+            Integer i = (Integer) o;
+        } catch (ClassCastException e) {
+            System.out.println("caught " + e);
+        }
+        try {
+            Object[] objects = new String[1];
+            objects[0] = 1;
+        } catch (ArrayStoreException e) {
+            System.out.println("caught " + e);
+        }
+        try {
+            int[] negative = new int[-1];
+        } catch (NegativeArraySizeException e) {
+            System.out.println("caught " + e);
+        }
     }
 
     public void first() {
@@ -0,0 +1,54 @@
+package testJava;
+
+import java.io.File;
+import java.io.FileInputStream;
+import java.io.FileNotFoundException;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.io.RandomAccessFile;
+
+public class FileIOTest {
+
+    public static void main(String[] args) throws IOException {
+        File file = new File(System.getProperty("java.io.tmpdir"), "lark-io-test.txt");
+        try (FileOutputStream out = new FileOutputStream(file)) {
+            out.write("hello".getBytes());
+            out.write(' ');
+        }
+        try (FileOutputStream out = new FileOutputStream(file, true)) {
+            out.write("world".getBytes());
+        }
+
+        try (FileInputStream in = new FileInputStream(file)) {
+            System.out.println("available " + in.available());
+            System.out.println("skip " + in.skip(6) + ", read " + (char) in.read());
+            byte[] buf = new byte[16];
+            int n = in.read(buf, 0, buf.length);
+            System.out.println("readBytes " + new String(buf, 0, n) + ", eof " + in.read());
+        }
+
+        try (RandomAccessFile raf = new RandomAccessFile(file, "rw")) {
+            System.out.println("length " + raf.length());
+            raf.seek(6);
+            raf.write("WORLD".getBytes());
+            raf.seek(0);
+            System.out.println("readLine " + raf.readLine() + ", pointer " + raf.getFilePointer());
+            raf.setLength(5);
+            System.out.println("setLength " + raf.length() + ", pointer " + raf.getFilePointer());
+        }
+
+        FileInputStream closed = new FileInputStream(file);
+        closed.close();
+        try {
+            closed.read();
+        } catch (IOException e) {
+            System.out.println("closed: " + e.getMessage());
+        }
+        try {
+            new FileInputStream(new File(file.getParentFile(), "lark-missing.txt"));
+        } catch (FileNotFoundException e) {
+            System.out.println("missing: " + e.getClass().getName());
+        }
+        System.out.println("delete " + file.delete());
+    }
+}
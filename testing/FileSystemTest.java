@@ -0,0 +1,24 @@
+package testJava;
+
+import java.io.File;
+import java.io.IOException;
+import java.util.Arrays;
+
+public class FileSystemTest {
+
+    public static void main(String[] args) throws IOException {
+        File dir = new File(System.getProperty("java.io.tmpdir"), "lark-fs-test");
+        System.out.println("mkdir " + dir.mkdir() + ", isDirectory " + dir.isDirectory());
+        File file = new File(dir, "a.txt");
+        System.out.println("create " + file.createNewFile() + ", again " + file.createNewFile());
+        System.out.println("exists " + file.exists() + ", length " + file.length()
+                + ", lastModified > 0 " + (file.lastModified() > 0));
+        System.out.println("canonical " + new File(dir, "./x/../a.txt").getCanonicalPath());
+        System.out.println("setWritable " + file.setWritable(false) + ", " + file.setWritable(true));
+        File renamed = new File(dir, "b.txt");
+        System.out.println("rename " + file.renameTo(renamed));
+        System.out.println("list " + Arrays.toString(dir.list()));
+        System.out.println("usable space > 0 " + (dir.getUsableSpace() > 0));
+        System.out.println("delete " + renamed.delete() + ", " + dir.delete() + ", exists " + dir.exists());
+    }
+}
@@ -0,0 +1,27 @@
+package testJava;
+public class GcTest {
+
+    static class Node {
+        Node parent;
+        Node child;
+        int[] payload = new int[1024];
+    }
+
+    public static void main(String[] args) {
+        // run with -Xmx16m, every tree is a cycle that only the collector frees
+        for (int i = 0; i < 10000; i++) {
+            Node parent = new Node();
+            parent.child = new Node();
+            parent.child.parent = parent;
+        }
+        Runtime runtime = Runtime.getRuntime();
+        System.gc();
+        System.out.println(runtime.freeMemory() <= runtime.maxMemory());
+        try {
+            long[] huge = new long[Integer.MAX_VALUE - 8];
+            System.out.println(huge.length);
+        } catch (OutOfMemoryError e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,18 @@
+package testJava;
+public class ShutdownTest {
+
+    public static void main(String[] args) {
+        Runtime.getRuntime().addShutdownHook(new Thread(() -> System.out.println("hook ran")));
+        Thread worker = new Thread(() -> {
+            try {
+                Thread.sleep(100);
+            } catch (InterruptedException e) {
+                return;
+            }
+            System.out.println("worker ends, exit 3");
+            System.exit(3);
+        });
+        worker.start();
+        System.out.println("main returns");
+    }
+}
@@ -4,11 +4,20 @@ public class SyncTest {
     private int count = 0;
 
     public static void main(String[] args) {
-
+        SyncTest test = new SyncTest();
+        System.out.println(test.random());
+        test.test();
+        test.println();
+        System.out.println(test.count);
+        try {
+            test.throwInSync();
+        } catch (RuntimeException e) {
+            System.out.println(Thread.holdsLock(test));
+        }
     }
 
     public synchronized int random() {
-        return 99;
+        return Thread.holdsLock(this) ? 99 : -1;
     }
 
     public void test() {
@@ -22,4 +31,8 @@ public class SyncTest {
             System.out.println(666);
         }
     }
-}
\ No newline at end of file
+
+    public synchronized void throwInSync() {
+        throw new RuntimeException("unwind");
+    }
+}
@@ -0,0 +1,44 @@
+package testJava;
+public class ThreadTest {
+
+    private static int count = 0;
+
+    public static void main(String[] args) throws InterruptedException {
+        System.out.println(Thread.currentThread().getName());
+        System.out.println(Thread.currentThread() == Thread.currentThread());
+        Thread[] workers = new Thread[4];
+        for (int i = 0; i < workers.length; i++) {
+            workers[i] = new Thread(new Runnable() {
+                public void run() {
+                    for (int j = 0; j < 1000; j++) {
+                        increment();
+                    }
+                }
+            }, "worker-" + i);
+            workers[i].setPriority(Thread.NORM_PRIORITY + 1);
+            workers[i].start();
+        }
+        for (Thread worker : workers) {
+            worker.join();
+            System.out.println(worker.getName() + " alive:" + worker.isAlive());
+        }
+        System.out.println(count);
+
+        Thread sleeper = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    Thread.sleep(60000);
+                } catch (InterruptedException e) {
+                    System.out.println("interrupted");
+                }
+            }
+        });
+        sleeper.start();
+        sleeper.interrupt();
+        sleeper.join();
+    }
+
+    private static synchronized void increment() {
+        count++;
+    }
+}
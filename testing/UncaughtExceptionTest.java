@@ -0,0 +1,22 @@
+package testJava;
+public class UncaughtExceptionTest {
+
+    public static void main(String[] args) throws InterruptedException {
+        Thread worker = new Thread(() -> {
+            throw new IllegalStateException("worker failed");
+        });
+        worker.start();
+        worker.join();
+        System.out.println("worker done");
+        first();
+    }
+
+    static void first() {
+        second();
+    }
+
+    static void second() {
+        int[] array = new int[1];
+        System.out.println(array[1]);
+    }
+}
@@ -0,0 +1,68 @@
+package testJava;
+import java.util.LinkedList;
+
+public class WaitNotifyTest {
+
+    private final LinkedList<Integer> queue = new LinkedList<Integer>();
+
+    public static void main(String[] args) throws InterruptedException {
+        final WaitNotifyTest test = new WaitNotifyTest();
+        Thread consumer = new Thread(new Runnable() {
+            public void run() {
+                int sum = 0;
+                try {
+                    for (int i = 0; i < 100; i++) {
+                        sum += test.take();
+                    }
+                } catch (InterruptedException e) {
+                    System.out.println("unexpected interrupt");
+                }
+                System.out.println(sum);
+            }
+        });
+        consumer.start();
+        for (int i = 0; i < 100; i++) {
+            test.put(i);
+        }
+        consumer.join();
+
+        final Object lock = new Object();
+        Thread waiter = new Thread(new Runnable() {
+            public void run() {
+                synchronized (lock) {
+                    try {
+                        lock.wait();
+                    } catch (InterruptedException e) {
+                        System.out.println("interrupted " + Thread.currentThread().isInterrupted());
+                    }
+                }
+            }
+        });
+        waiter.start();
+        waiter.interrupt();
+        waiter.join();
+
+        synchronized (lock) {
+            long start = System.currentTimeMillis();
+            lock.wait(50);
+            System.out.println(System.currentTimeMillis() - start >= 50);
+        }
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println("not owner");
+        }
+    }
+
+    public synchronized void put(int value) {
+        queue.addLast(value);
+        notifyAll();
+    }
+
+    public synchronized int take() throws InterruptedException {
+        while (queue.isEmpty()) {
+            wait();
+        }
+        return queue.removeFirst();
+    }
+}